@@ -13,6 +13,7 @@ fn main() {
         .flag("-fno-stack-protector")
         .file("src/c/dlmalloc.c")
         .file("src/c/fcntl.c")
+        .file("src/c/ioctl.c")
         .file("src/c/stack_chk.c")
         .file("src/c/stdio.c")
         .file("src/c/unistd.c")
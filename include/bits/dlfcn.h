@@ -0,0 +1,6 @@
+#ifndef _BITS_DLFCN_H
+#define _BITS_DLFCN_H
+
+#define RTLD_DEFAULT ((void*) 0)
+
+#endif
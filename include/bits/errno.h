@@ -1,7 +1,6 @@
 #ifndef _BITS_ERRNO_H
 #define _BITS_ERRNO_H
 
-//TODO extern __thread int errno;
-extern int errno;
+extern __thread int errno;
 
 #endif /* _BITS_ERRNO_H */
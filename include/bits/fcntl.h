@@ -2,6 +2,7 @@
 #define _BITS_FCNTL_H
 
 int open(const char* filename, int flags, ...);
+int openat(int dirfd, const char* filename, int flags, ...);
 int fcntl(int fildes, int cmd, ...);
 
 #endif
@@ -0,0 +1,8 @@
+#ifndef _BITS_LIBGEN_H
+#define _BITS_LIBGEN_H
+
+// The GNU basename in string.h doesn't modify its argument, so the POSIX one goes by another name
+#undef basename
+#define basename __xpg_basename
+
+#endif
@@ -0,0 +1,9 @@
+#ifndef _BITS_PTHREAD_H
+#define _BITS_PTHREAD_H
+
+#define PTHREAD_COND_INITIALIZER {0}
+#define PTHREAD_MUTEX_INITIALIZER {0}
+#define PTHREAD_ONCE_INIT 0
+#define PTHREAD_RWLOCK_INITIALIZER {0}
+
+#endif /* _BITS_PTHREAD_H */
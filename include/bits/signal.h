@@ -1,3 +1,14 @@
 #define SIG_ERR ((void (*)(int)) -1)
 #define SIG_DFL ((void (*)(int)) 0)
 #define SIG_IGN ((void (*)(int)) 1)
+
+typedef struct {
+    int si_signo;
+    int si_errno;
+    int si_code;
+    int _pad0;
+    pid_t si_pid;
+    uid_t si_uid;
+    int si_status;
+    int _pad[25];
+} siginfo_t;
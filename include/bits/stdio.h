@@ -3,6 +3,7 @@
 
 #define EOF (-1)
 #define BUFSIZ 1024
+#define P_tmpdir "/tmp"
 
 int fprintf(FILE * stream, const char * fmt, ...);
 int printf(const char * fmt, ...);
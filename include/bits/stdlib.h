@@ -0,0 +1,9 @@
+#ifndef _BITS_STDLIB_H
+#define _BITS_STDLIB_H
+
+// long double can't be produced from Rust, so it is parsed with the precision of a double
+static inline long double strtold(const char *nptr, char **endptr) {
+    return strtod(nptr, endptr);
+}
+
+#endif /* _BITS_STDLIB_H */
@@ -0,0 +1,9 @@
+#ifndef _BITS_STRING_H
+#define _BITS_STRING_H
+
+// libgen.h replaces basename with the POSIX version
+#if defined(_GNU_SOURCE) && !defined(basename)
+char *basename(const char *path);
+#endif
+
+#endif
@@ -1,3 +1,6 @@
+#ifndef _BITS_SYS_IOCTL_H
+#define _BITS_SYS_IOCTL_H
+
 // Shamelessly copy-pasted from musl
 
 #define _IOC(a,b,c,d) ( ((a)<<30) | ((b)<<8) | (c) | ((d)<<16) )
@@ -9,3 +12,7 @@
 #define _IOW(a,b,c) _IOC(_IOC_WRITE,(a),(b),sizeof(c))
 #define _IOR(a,b,c) _IOC(_IOC_READ,(a),(b),sizeof(c))
 #define _IOWR(a,b,c) _IOC(_IOC_READ|_IOC_WRITE,(a),(b),sizeof(c))
+
+int ioctl(int fd, unsigned long request, ...);
+
+#endif
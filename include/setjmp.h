@@ -1,7 +1,7 @@
 #ifndef _SETJMP_H
 #define _SETJMP_H
 
-#ifdef __arch64__
+#ifdef __aarch64__
 typedef unsigned long jmp_buf[22];
 #endif
 
@@ -61,7 +61,16 @@ typedef unsigned long long jmp_buf[8];
 typedef unsigned long jmp_buf[8];
 #endif
 
+typedef struct {
+    jmp_buf __jb;
+    unsigned long __fl;
+    unsigned long __ss[128 / sizeof(long)];
+} sigjmp_buf[1];
+
 int setjmp(jmp_buf buf);
 void longjmp(jmp_buf buf, int value);
 
+int sigsetjmp(sigjmp_buf buf, int savemask);
+void siglongjmp(sigjmp_buf buf, int value);
+
 #endif /* _SETJMP_H */
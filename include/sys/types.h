@@ -19,6 +19,8 @@ typedef long clock_t;
 typedef int clockid_t;
 typedef void* timer_t;
 typedef unsigned long int blkcnt_t;
+typedef unsigned long fsblkcnt_t;
+typedef unsigned long fsfilcnt_t;
 
 typedef unsigned char u_char, uchar;
 typedef unsigned short u_short, ushort;
@@ -27,11 +29,4 @@ typedef unsigned long u_long, ulong;
 typedef long long quad_t;
 typedef unsigned long long u_quad_t;
 
-#ifdef __linux__
-#define _SC_PAGE_SIZE 30
-#endif
-#ifdef __redox__
-#define _SC_PAGE_SIZE 8
-#endif
-
 #endif /* _SYS_TYPES_H */
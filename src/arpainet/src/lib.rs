@@ -11,12 +11,11 @@ extern crate netinet;
 extern crate platform;
 extern crate sys_socket;
 
-use core::str::FromStr;
-use core::{mem, ptr, slice, str};
+use core::{ptr, slice};
 use errno::*;
-use netinet::in_h::in_addr;
-use platform::c_str;
+use netinet::in_h::{in_addr, in_addr_t, INADDR_NONE};
 use platform::types::*;
+use platform::{c_str, inet};
 
 #[no_mangle]
 pub extern "C" fn htonl(hostlong: u32) -> u32 {
@@ -42,8 +41,13 @@ static mut NTOA_ADDR: [c_char; 16] = [0; 16];
 
 #[no_mangle]
 pub unsafe extern "C" fn inet_aton(cp: *const c_char, inp: *mut in_addr) -> c_int {
-    // TODO: octal/hex
-    inet_pton(AF_INET, cp, inp as *mut c_void)
+    match inet::parse_ipv4_legacy(c_str(cp)) {
+        Some(addr) => {
+            (*inp).s_addr = addr.to_be();
+            1
+        }
+        None => 0,
+    }
 }
 
 #[no_mangle]
@@ -58,26 +62,24 @@ pub unsafe extern "C" fn inet_ntoa(addr: in_addr) -> *const c_char {
 
 #[no_mangle]
 pub unsafe extern "C" fn inet_pton(domain: c_int, src: *const c_char, dest: *mut c_void) -> c_int {
-    if domain != AF_INET {
-        platform::errno = EAFNOSUPPORT;
-        -1
-    } else {
-        let mut s_addr = slice::from_raw_parts_mut(
-            &mut (*(dest as *mut in_addr)).s_addr as *mut _ as *mut u8,
-            4,
-        );
-        let mut octets = str::from_utf8_unchecked(c_str(src)).split('.');
-        for i in 0..4 {
-            if let Some(n) = octets.next().and_then(|x| u8::from_str(x).ok()) {
-                s_addr[i] = n;
-            } else {
-                return 0;
+    match domain {
+        AF_INET => match inet::parse_ipv4(c_str(src)) {
+            Some(addr) => {
+                ptr::copy_nonoverlapping(addr.as_ptr(), dest as *mut u8, addr.len());
+                1
             }
-        }
-        if octets.next() == None {
-            1 // Success
-        } else {
-            0
+            None => 0,
+        },
+        AF_INET6 => match inet::parse_ipv6(c_str(src)) {
+            Some(addr) => {
+                ptr::copy_nonoverlapping(addr.as_ptr(), dest as *mut u8, addr.len());
+                1
+            }
+            None => 0,
+        },
+        _ => {
+            platform::errno = EAFNOSUPPORT;
+            -1
         }
     }
 }
@@ -89,26 +91,32 @@ pub unsafe extern "C" fn inet_ntop(
     dest: *mut c_char,
     size: socklen_t,
 ) -> *const c_char {
-    if domain != AF_INET {
-        platform::errno = EAFNOSUPPORT;
-        ptr::null()
-    } else if size < 16 {
+    let addr = match domain {
+        AF_INET => {
+            let s_addr = slice::from_raw_parts(src as *const u8, 4);
+            format!("{}.{}.{}.{}", s_addr[0], s_addr[1], s_addr[2], s_addr[3])
+        }
+        AF_INET6 => format!("{}", inet::Ipv6Display(&*(src as *const [u8; 16]))),
+        _ => {
+            platform::errno = EAFNOSUPPORT;
+            return ptr::null();
+        }
+    };
+    if addr.len() >= size as usize {
         platform::errno = ENOSPC;
-        ptr::null()
-    } else {
-        let s_addr = slice::from_raw_parts(
-            &(*(src as *const in_addr)).s_addr as *const _ as *const u8,
-            4,
-        );
-        let addr = format!("{}.{}.{}.{}\0", s_addr[0], s_addr[1], s_addr[2], s_addr[3]);
-        ptr::copy(addr.as_ptr() as *const c_char, dest, addr.len());
-        dest
+        return ptr::null();
     }
+    ptr::copy_nonoverlapping(addr.as_ptr() as *const c_char, dest, addr.len());
+    *dest.offset(addr.len() as isize) = 0;
+    dest
 }
 
 #[no_mangle]
-pub extern "C" fn inet_addr(cp: *const c_char) -> in_addr_t {
-    unimplemented!();
+pub unsafe extern "C" fn inet_addr(cp: *const c_char) -> in_addr_t {
+    match inet::parse_ipv4_legacy(c_str(cp)) {
+        Some(addr) => addr.to_be(),
+        None => INADDR_NONE,
+    }
 }
 
 #[no_mangle]
@@ -127,6 +135,6 @@ pub extern "C" fn inet_netof(_in: in_addr) -> in_addr_t {
 }
 
 #[no_mangle]
-pub extern "C" fn inet_network(cp: *const c_char) -> in_addr_t {
-    unimplemented!();
+pub unsafe extern "C" fn inet_network(cp: *const c_char) -> in_addr_t {
+    inet::parse_ipv4_network(c_str(cp)).unwrap_or(INADDR_NONE)
 }
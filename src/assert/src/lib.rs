@@ -0,0 +1,36 @@
+//! assert implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/assert.h.html
+
+#![no_std]
+
+extern crate platform;
+extern crate stdio;
+extern crate stdlib;
+
+use core::fmt::Write;
+use core::str;
+
+use platform::types::*;
+
+/// Called by the assert macro when an assertion fails. Uses the same message as glibc.
+#[no_mangle]
+pub unsafe extern "C" fn __assert_fail(
+    assertion: *const c_char,
+    file: *const c_char,
+    line: c_uint,
+    function: *const c_char,
+) -> ! {
+    let assertion = str::from_utf8_unchecked(platform::c_str(assertion));
+    let file = str::from_utf8_unchecked(platform::c_str(file));
+    let function = str::from_utf8_unchecked(platform::c_str(function));
+
+    {
+        let mut w = (*stdio::stderr).lock();
+        let _ = write!(
+            w,
+            "{}:{}: {}: Assertion `{}' failed.\n",
+            file, line, function, assertion
+        );
+    }
+
+    stdlib::abort();
+}
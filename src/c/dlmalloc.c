@@ -533,6 +533,8 @@ MAX_RELEASE_CHECK_RATE   default: 4095 unless not HAVE_MMAP
 #define LACKS_UNISTD_H
 #define NO_MALLOC_STATS 1
 #define USE_DL_PREFIX 1
+#define USE_LOCKS 1
+#define LACKS_SCHED_H
 
 #define malloc_getpagesize ((size_t)4096U)
 
@@ -541,7 +543,7 @@ MAX_RELEASE_CHECK_RATE   default: 4095 unless not HAVE_MMAP
 #define ENOMEM 12
 #define EINVAL 22
 
-extern int errno;
+extern __thread int errno;
 
 void abort(void);
 void *memcpy(void *dest, const void *src, size_t n);
@@ -0,0 +1,13 @@
+#include <stdarg.h>
+
+int sys_ioctl(int fd, unsigned long request, void* out);
+
+int ioctl(int fd, unsigned long request, ...) {
+    // Every request relibc knows about takes a pointer, if anything at all
+    void* out = 0;
+    va_list ap;
+    va_start(ap, request);
+    out = va_arg(ap, void*);
+    va_end(ap);
+    return sys_ioctl(fd, request, out);
+}
@@ -56,6 +56,16 @@ impl Stack {
     fn envp(&self) -> *const *const c_char {
         unsafe { self.argv().offset(self.argc() + 1) }
     }
+
+    fn auxv(&self) -> *const usize {
+        unsafe {
+            let mut envp = self.envp();
+            while !(*envp).is_null() {
+                envp = envp.offset(1);
+            }
+            envp.offset(1) as *const usize
+        }
+    }
 }
 
 #[inline(never)]
@@ -63,8 +73,12 @@ impl Stack {
 pub unsafe extern "C" fn _start_rust(sp: &'static Stack) -> ! {
     extern "C" {
         fn main(argc: isize, argv: *const *const c_char, envp: *const *const c_char) -> c_int;
+        fn exit(status: c_int) -> !;
     }
 
+    // Nothing can use errno or any other thread local before this
+    platform::init_tls(sp.auxv());
+
     let argc = sp.argc();
     let argv = sp.argv();
 
@@ -95,7 +109,9 @@ pub unsafe extern "C" fn _start_rust(sp: &'static Stack) -> ! {
     stdio::stdout = stdio::default_stdout.get();
     stdio::stderr = stdio::default_stderr.get();
 
-    platform::exit(main(
+    // Returning from main is the same as calling exit, which also runs the
+    // atexit handlers and flushes stdio
+    exit(main(
         argc,
         argv,
         // not envp, because programs like bash try to modify this *const*
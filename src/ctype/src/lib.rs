@@ -53,12 +53,13 @@ pub extern "C" fn isprint(c: c_int) -> c_int {
 
 #[no_mangle]
 pub extern "C" fn ispunct(c: c_int) -> c_int {
-    (isgraph(c) != 0 && !isalnum(c) != 0) as c_int
+    (isgraph(c) != 0 && isalnum(c) == 0) as c_int
 }
 
 #[no_mangle]
 pub extern "C" fn isspace(c: c_int) -> c_int {
-    (c == 0x20) as c_int
+    // ' ' and '\t' through '\r'
+    (c == 0x20 || ((c - 0x09) as c_uint) < 5) as c_int
 }
 
 #[no_mangle]
@@ -68,7 +69,7 @@ pub extern "C" fn isupper(c: c_int) -> c_int {
 
 #[no_mangle]
 pub extern "C" fn isxdigit(c: c_int) -> c_int {
-    (isdigit(c) != 0 || ((c as c_int) | 32) - ('a' as c_int) < 6) as c_int
+    (isdigit(c) != 0 || (((c | 32) - 'a' as c_int) as c_uint) < 6) as c_int
 }
 
 #[no_mangle]
@@ -8,9 +8,12 @@ extern crate errno;
 extern crate fcntl;
 extern crate platform;
 extern crate stdio;
+extern crate string;
+extern crate sys_stat;
 extern crate unistd;
 
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::{mem, ptr};
 use platform::types::*;
 
@@ -39,6 +42,15 @@ pub struct dirent {
     pub d_name: [c_char; 256],
 }
 
+pub const DT_UNKNOWN: c_uchar = 0;
+pub const DT_FIFO: c_uchar = 1;
+pub const DT_CHR: c_uchar = 2;
+pub const DT_DIR: c_uchar = 4;
+pub const DT_BLK: c_uchar = 6;
+pub const DT_REG: c_uchar = 8;
+pub const DT_LNK: c_uchar = 10;
+pub const DT_SOCK: c_uchar = 12;
+
 #[no_mangle]
 pub extern "C" fn opendir(path: *const c_char) -> *mut DIR {
     let fd = platform::open(
@@ -51,6 +63,22 @@ pub extern "C" fn opendir(path: *const c_char) -> *mut DIR {
         return ptr::null_mut();
     }
 
+    fdopendir(fd)
+}
+
+#[no_mangle]
+pub extern "C" fn fdopendir(fd: c_int) -> *mut DIR {
+    let mut stat = unsafe { mem::zeroed::<platform::types::stat>() };
+    if platform::fstat(fd, &mut stat) < 0 {
+        return ptr::null_mut();
+    }
+    if stat.st_mode & sys_stat::S_IFMT != sys_stat::S_IFDIR {
+        unsafe {
+            platform::errno = errno::ENOTDIR;
+        }
+        return ptr::null_mut();
+    }
+
     Box::into_raw(Box::new(DIR {
         fd,
         buf: [0; DIR_BUF_SIZE],
@@ -87,20 +115,7 @@ pub unsafe extern "C" fn readdir(dir: *mut DIR) -> *mut dirent {
     }
 
     let ptr = (*dir).buf.as_mut_ptr().offset((*dir).index as isize) as *mut dirent;
-
-    #[cfg(target_os = "redox")]
-    {
-        if (*dir).index != 0 || (*dir).offset != 0 {
-            // This should happen every time but the first, making the offset
-            // point to the current element and not the next
-            (*dir).offset += mem::size_of::<dirent>();
-        }
-        (*ptr).d_off = (*dir).offset as off_t;
-    }
-    #[cfg(not(target_os = "redox"))]
-    {
-        (*dir).offset = (*ptr).d_off as usize;
-    }
+    (*dir).offset = (*ptr).d_off as usize;
 
     (*dir).index += (*ptr).d_reclen as usize;
     ptr
@@ -129,3 +144,80 @@ pub unsafe extern "C" fn seekdir(dir: *mut DIR, off: c_long) {
 pub unsafe extern "C" fn rewinddir(dir: *mut DIR) {
     seekdir(dir, 0)
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn alphasort(first: *mut *const dirent, second: *mut *const dirent) -> c_int {
+    string::strcoll((**first).d_name.as_ptr(), (**second).d_name.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn versionsort(first: *mut *const dirent, second: *mut *const dirent) -> c_int {
+    string::strverscmp((**first).d_name.as_ptr(), (**second).d_name.as_ptr())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn scandir(
+    dirp: *const c_char,
+    namelist: *mut *mut *mut dirent,
+    filter: Option<extern "C" fn(_: *const dirent) -> c_int>,
+    compare: Option<extern "C" fn(_: *mut *const dirent, _: *mut *const dirent) -> c_int>,
+) -> c_int {
+    let dir = opendir(dirp);
+    if dir.is_null() {
+        return -1;
+    }
+
+    let old_errno = platform::errno;
+    platform::errno = 0;
+
+    let mut entries: Vec<*mut dirent> = Vec::new();
+    loop {
+        let entry = readdir(dir);
+        if entry.is_null() {
+            break;
+        }
+        if let Some(filter) = filter {
+            if filter(entry) == 0 {
+                continue;
+            }
+        }
+
+        let copy = platform::alloc(mem::size_of::<dirent>()) as *mut dirent;
+        if copy.is_null() {
+            platform::errno = errno::ENOMEM;
+            break;
+        }
+        ptr::copy_nonoverlapping(entry, copy, 1);
+        entries.push(copy);
+    }
+    closedir(dir);
+
+    let list = if platform::errno == 0 {
+        platform::alloc(entries.len().max(1) * mem::size_of::<*mut dirent>()) as *mut *mut dirent
+    } else {
+        ptr::null_mut()
+    };
+    if list.is_null() {
+        if platform::errno == 0 {
+            platform::errno = errno::ENOMEM;
+        }
+        for entry in entries {
+            platform::free(entry as *mut c_void);
+        }
+        return -1;
+    }
+    platform::errno = old_errno;
+
+    if let Some(compare) = compare {
+        entries.sort_by(|first, second| {
+            compare(
+                first as *const *mut dirent as *mut *const dirent,
+                second as *const *mut dirent as *mut *const dirent,
+            ).cmp(&0)
+        });
+    }
+
+    ptr::copy_nonoverlapping(entries.as_ptr(), list, entries.len());
+    *namelist = list;
+    entries.len() as c_int
+}
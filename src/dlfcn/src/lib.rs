@@ -0,0 +1,242 @@
+//! dlfcn implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/dlfcn.h.html
+//!
+//! Programs are linked statically and there is no dynamic linker to hand shared objects to, so
+//! only the handle of the program itself can be opened. The mode flags are checked for validity
+//! but otherwise ignored: with nothing to load, there's nothing to bind lazily or make global.
+//!
+//! None of the program's symbols are loaded into memory, so dlsym reads the symbol table from the
+//! executable instead. A stripped program has nothing left to find.
+
+#![no_std]
+#![feature(alloc)]
+
+#[macro_use]
+extern crate alloc;
+extern crate fcntl;
+extern crate platform;
+
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::{mem, ptr, str};
+use platform::types::*;
+use platform::RawFile;
+
+pub const RTLD_LAZY: c_int = 0x0001;
+pub const RTLD_NOW: c_int = 0x0002;
+pub const RTLD_GLOBAL: c_int = 0x0100;
+pub const RTLD_LOCAL: c_int = 0x0000;
+
+// What dlopen(NULL, ...) hands out. Its address is all that matters.
+static MAIN_PROGRAM: u8 = 0;
+
+static mut ERROR: [u8; 256] = [0; 256];
+static mut ERROR_SET: bool = false;
+
+fn set_error(args: core::fmt::Arguments) {
+    unsafe {
+        ERROR[0] = 0;
+        let _ = platform::StringWriter(ERROR.as_mut_ptr(), ERROR.len()).write_fmt(args);
+        ERROR_SET = true;
+    }
+}
+
+fn main_handle() -> *mut c_void {
+    &MAIN_PROGRAM as *const u8 as *mut c_void
+}
+
+#[cfg(target_os = "linux")]
+fn open_program() -> Option<RawFile> {
+    RawFile::open(b"/proc/self/exe\0".as_ptr() as *const c_char, fcntl::O_RDONLY, 0).ok()
+}
+
+#[cfg(target_os = "redox")]
+fn open_program() -> Option<RawFile> {
+    // sys:exe holds the path of the running program
+    let exe = RawFile::open(b"sys:exe\0".as_ptr() as *const c_char, fcntl::O_RDONLY, 0).ok()?;
+    let mut path = [0; 4096];
+    let len = platform::read(*exe, &mut path[..4095]);
+    if len <= 0 {
+        return None;
+    }
+    RawFile::open(path.as_ptr() as *const c_char, fcntl::O_RDONLY, 0).ok()
+}
+
+fn read_at(file: &RawFile, offset: u64, len: u64) -> Option<Vec<u8>> {
+    let mut buf = vec![0; len as usize];
+    let mut done = 0;
+    while done < buf.len() {
+        let n = platform::pread(**file, &mut buf[done..], (offset + done as u64) as off_t);
+        if n <= 0 {
+            return None;
+        }
+        done += n as usize;
+    }
+    Some(buf)
+}
+
+/// Reads a field of an ELF structure, in the byte order the program runs in
+fn field<T: Copy>(buf: &[u8], offset: usize) -> Option<T> {
+    if offset + mem::size_of::<T>() > buf.len() {
+        return None;
+    }
+    Some(unsafe { ptr::read_unaligned(buf[offset..].as_ptr() as *const T) })
+}
+
+const SHT_SYMTAB: u32 = 2;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const STB_GLOBAL: u8 = 1;
+const STB_WEAK: u8 = 2;
+
+/// The ELF64 symbol table of the program, along with the names it refers to
+struct SymbolTable {
+    symbols: Vec<u8>,
+    names: Vec<u8>,
+}
+
+impl SymbolTable {
+    fn load() -> Option<SymbolTable> {
+        let file = open_program()?;
+        let header = read_at(&file, 0, 64)?;
+        if &header[..4] != b"\x7fELF" || header[4] != 2 {
+            return None;
+        }
+        let shoff: u64 = field(&header, 0x28)?;
+        let shentsize: u16 = field(&header, 0x3a)?;
+        let shnum: u16 = field(&header, 0x3c)?;
+        let sections = read_at(&file, shoff, shentsize as u64 * shnum as u64)?;
+
+        for i in 0..shnum as usize {
+            let section = &sections[i * shentsize as usize..(i + 1) * shentsize as usize];
+            if field::<u32>(section, 0x04)? != SHT_SYMTAB {
+                continue;
+            }
+            let link: u32 = field(section, 0x28)?;
+            if link >= shnum as u32 {
+                return None;
+            }
+            let strtab = &sections[link as usize * shentsize as usize..];
+            return Some(SymbolTable {
+                symbols: read_at(&file, field(section, 0x18)?, field(section, 0x20)?)?,
+                names: read_at(&file, field(strtab, 0x18)?, field(strtab, 0x20)?)?,
+            });
+        }
+        None
+    }
+
+    /// The address `name` has in the executable, if it's a global function or object
+    fn find(&self, name: &[u8]) -> Option<u64> {
+        for symbol in self.symbols.chunks(24) {
+            let info: u8 = field(symbol, 0x04)?;
+            let shndx: u16 = field(symbol, 0x06)?;
+            let kind = info & 0xf;
+            let bind = info >> 4;
+            if shndx == 0
+                || (kind != STT_OBJECT && kind != STT_FUNC)
+                || (bind != STB_GLOBAL && bind != STB_WEAK)
+            {
+                continue;
+            }
+
+            let start = field::<u32>(symbol, 0x00)? as usize;
+            let matches = self.names.get(start..start + name.len() + 1).map_or(false, |found| {
+                &found[..name.len()] == name && found[name.len()] == 0
+            });
+            if matches {
+                return field(symbol, 0x08);
+            }
+        }
+        None
+    }
+}
+
+// The program's symbol table along with how far the program was moved when loaded, read the
+// first time dlsym needs it. None once loading it failed, as it's not going to work the next time.
+static SYMBOLS_LOCK: AtomicBool = AtomicBool::new(false);
+static mut SYMBOLS: Option<Option<(SymbolTable, usize)>> = None;
+
+fn load_symbols() -> Option<(SymbolTable, usize)> {
+    let table = SymbolTable::load()?;
+    let bias = (dlsym as usize).wrapping_sub(table.find(b"dlsym")? as usize);
+    Some((table, bias))
+}
+
+/// Where `name` is in the running program, which is wherever the executable says plus however
+/// far it was moved when loaded
+fn lookup(name: &[u8]) -> Option<*mut c_void> {
+    while SYMBOLS_LOCK.compare_and_swap(false, true, Ordering::Acquire) {}
+    let symbols = unsafe { SYMBOLS.get_or_insert_with(load_symbols) };
+    let addr = symbols.as_ref().and_then(|&(ref table, bias)| {
+        table
+            .find(name)
+            .map(|value| (value as usize).wrapping_add(bias) as *mut c_void)
+    });
+    SYMBOLS_LOCK.store(false, Ordering::Release);
+    addr
+}
+
+unsafe fn c_str_lossy<'a>(s: *const c_char) -> &'a str {
+    str::from_utf8(platform::c_str(s)).unwrap_or("<invalid utf-8>")
+}
+
+#[no_mangle]
+pub extern "C" fn dlclose(handle: *mut c_void) -> c_int {
+    if handle != main_handle() {
+        set_error(format_args!("invalid handle {:p}", handle));
+        return -1;
+    }
+    0
+}
+
+/// Returns a description of the last error since this was last called, or NULL if there was
+/// none
+#[no_mangle]
+pub extern "C" fn dlerror() -> *mut c_char {
+    unsafe {
+        if !ERROR_SET {
+            return ptr::null_mut();
+        }
+        ERROR_SET = false;
+        ERROR.as_mut_ptr() as *mut c_char
+    }
+}
+
+/// Opening NULL gives a handle to the program itself. Anything else fails, as there is no
+/// dynamic linker to load it.
+#[no_mangle]
+pub extern "C" fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void {
+    if flag & (RTLD_LAZY | RTLD_NOW) == 0 || flag & !(RTLD_LAZY | RTLD_NOW | RTLD_GLOBAL) != 0 {
+        set_error(format_args!("invalid mode {:#x}", flag));
+        return ptr::null_mut();
+    }
+    if filename.is_null() {
+        return main_handle();
+    }
+
+    set_error(format_args!(
+        "{}: cannot load shared objects into a statically linked program",
+        unsafe { c_str_lossy(filename) }
+    ));
+    ptr::null_mut()
+}
+
+/// Looks `symbol` up among the program's own, which is all there is, whether `handle` is
+/// RTLD_DEFAULT or what dlopen(NULL) gave
+#[no_mangle]
+pub extern "C" fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void {
+    if !handle.is_null() && handle != main_handle() {
+        set_error(format_args!("invalid handle {:p}", handle));
+        return ptr::null_mut();
+    }
+
+    match lookup(unsafe { platform::c_str(symbol) }) {
+        Some(addr) => addr,
+        None => {
+            set_error(format_args!("{}: symbol not found", unsafe {
+                c_str_lossy(symbol)
+            }));
+            ptr::null_mut()
+        }
+    }
+}
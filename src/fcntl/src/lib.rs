@@ -24,20 +24,35 @@ pub const F_SETFL: c_int = 4;
 pub const F_GETLK: c_int = 5;
 pub const F_SETLK: c_int = 6;
 pub const F_SETLKW: c_int = 7;
+pub const F_DUPFD_CLOEXEC: c_int = 1030;
 
-pub const FD_CLOEXEC: c_int = 0x0100_0000;
+pub const FD_CLOEXEC: c_int = 1;
 
 pub const F_RDLCK: c_int = 0;
 pub const F_WRLCK: c_int = 1;
 pub const F_UNLCK: c_int = 2;
 
+pub const AT_FDCWD: c_int = -100;
+pub const AT_SYMLINK_NOFOLLOW: c_int = 0x100;
+pub const AT_REMOVEDIR: c_int = 0x200;
+pub const AT_EACCESS: c_int = 0x200;
+
+#[repr(C)]
+pub struct flock {
+    pub l_type: c_short,
+    pub l_whence: c_short,
+    pub l_start: off_t,
+    pub l_len: off_t,
+    pub l_pid: pid_t,
+}
+
 #[no_mangle]
 pub extern "C" fn creat(path: *const c_char, mode: mode_t) -> c_int {
     sys_open(path, O_WRONLY | O_CREAT | O_TRUNC, mode)
 }
 
 #[no_mangle]
-pub extern "C" fn sys_fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int {
+pub extern "C" fn sys_fcntl(fildes: c_int, cmd: c_int, arg: c_ulong) -> c_int {
     platform::fcntl(fildes, cmd, arg)
 }
 
@@ -46,6 +61,16 @@ pub extern "C" fn sys_open(path: *const c_char, oflag: c_int, mode: mode_t) -> c
     platform::open(path, oflag, mode)
 }
 
+#[no_mangle]
+pub extern "C" fn sys_openat(
+    fd: c_int,
+    path: *const c_char,
+    oflag: c_int,
+    mode: mode_t,
+) -> c_int {
+    platform::openat(fd, path, oflag, mode)
+}
+
 /*
 #[no_mangle]
 pub extern "C" fn func(args) -> c_int {
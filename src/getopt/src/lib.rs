@@ -0,0 +1,204 @@
+//! getopt implementation for Redox, following https://www.gnu.org/software/libc/manual/html_node/Getopt-Long-Options.html
+
+#![no_std]
+#![allow(non_camel_case_types, non_upper_case_globals)]
+
+extern crate platform;
+extern crate stdio;
+extern crate string;
+extern crate unistd;
+
+use core::ptr;
+
+use platform::types::*;
+use unistd::{getopt, optarg, optind, opterr, optopt};
+
+pub const no_argument: c_int = 0;
+pub const required_argument: c_int = 1;
+pub const optional_argument: c_int = 2;
+
+#[repr(C)]
+pub struct option {
+    pub name: *const c_char,
+    pub has_arg: c_int,
+    pub flag: *mut c_int,
+    pub val: c_int,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getopt_long(
+    argc: c_int,
+    argv: *const *mut c_char,
+    optstring: *const c_char,
+    longopts: *const option,
+    longindex: *mut c_int,
+) -> c_int {
+    // a leading '+' or '-' asks for the POSIX behaviour of stopping at the first non-option
+    let permute = *optstring != b'+' as c_char && *optstring != b'-' as c_char;
+    let optstring = if permute {
+        optstring
+    } else {
+        optstring.offset(1)
+    };
+
+    if optind >= argc || (*argv.offset(optind as isize)).is_null() {
+        return -1;
+    }
+
+    // skip over non-options, they are moved behind the parsed options afterwards
+    let skipped = optind;
+    if permute {
+        let mut i = optind;
+        loop {
+            if i >= argc || (*argv.offset(i as isize)).is_null() {
+                return -1;
+            }
+            let arg = *argv.offset(i as isize);
+            if *arg == b'-' as c_char && *arg.offset(1) != 0 {
+                break;
+            }
+            i += 1;
+        }
+        optind = i;
+    }
+
+    let resumed = optind;
+    let ret = getopt_long_inner(argc, argv, optstring, longopts, longindex);
+    if resumed > skipped {
+        let argv = argv as *mut *mut c_char;
+        let count = optind - resumed;
+        for _ in 0..count {
+            permute_arg(argv, skipped, optind - 1);
+        }
+        optind = skipped + count;
+    }
+
+    ret
+}
+
+/// Moves argv[src] to argv[dest], shifting everything in between one to the right
+unsafe fn permute_arg(argv: *mut *mut c_char, dest: c_int, src: c_int) {
+    let arg = *argv.offset(src as isize);
+    let mut i = src;
+    while i > dest {
+        *argv.offset(i as isize) = *argv.offset(i as isize - 1);
+        i -= 1;
+    }
+    *argv.offset(dest as isize) = arg;
+}
+
+unsafe fn getopt_long_inner(
+    argc: c_int,
+    argv: *const *mut c_char,
+    optstring: *const c_char,
+    longopts: *const option,
+    longindex: *mut c_int,
+) -> c_int {
+    let current_arg = *argv.offset(optind as isize);
+    // "--" on its own and anything without two dashes is handled by getopt
+    if longopts.is_null()
+        || *current_arg != b'-' as c_char
+        || *current_arg.offset(1) != b'-' as c_char
+        || *current_arg.offset(2) == 0
+    {
+        return getopt(argc, argv, optstring);
+    }
+
+    let colon = *optstring == b':' as c_char;
+    let name = current_arg.offset(2);
+    let (i, rest) = match find_long_option(name, longopts) {
+        Ok(found) => found,
+        Err(desc) => {
+            if opterr != 0 && !colon {
+                print_error(argv, desc, current_arg);
+            }
+            optopt = 0;
+            optind += 1;
+            return b'?' as c_int;
+        }
+    };
+    let opt = &*longopts.offset(i as isize);
+
+    optind += 1;
+    optarg = ptr::null_mut();
+    if *rest == b'=' as c_char {
+        if opt.has_arg == no_argument {
+            if opterr != 0 && !colon {
+                print_error(argv, b": option doesn't allow an argument -- \0", current_arg);
+            }
+            optopt = opt.val;
+            return b'?' as c_int;
+        }
+        optarg = rest.offset(1);
+    } else if opt.has_arg == required_argument {
+        if optind >= argc {
+            optopt = opt.val;
+            if colon {
+                return b':' as c_int;
+            }
+            if opterr != 0 {
+                print_error(argv, b": option requires an argument -- \0", current_arg);
+            }
+            return b'?' as c_int;
+        }
+        optarg = *argv.offset(optind as isize);
+        optind += 1;
+    }
+
+    if !longindex.is_null() {
+        *longindex = i as c_int;
+    }
+    if opt.flag.is_null() {
+        opt.val
+    } else {
+        *opt.flag = opt.val;
+        0
+    }
+}
+
+/// Finds the option named by `name` (up to an '='), allowing unambiguous abbreviations. Returns
+/// its index and a pointer to whatever follows the name.
+unsafe fn find_long_option(
+    name: *const c_char,
+    longopts: *const option,
+) -> Result<(usize, *const c_char), &'static [u8]> {
+    let mut len = 0;
+    while *name.offset(len) != 0 && *name.offset(len) != b'=' as c_char {
+        len += 1;
+    }
+
+    let mut found = None;
+    let mut ambiguous = false;
+    let mut i = 0;
+    loop {
+        let opt = &*longopts.offset(i as isize);
+        if opt.name.is_null() {
+            break;
+        }
+        if string::strncmp(opt.name, name, len as size_t) == 0 {
+            if *opt.name.offset(len) == 0 {
+                // an exact match always wins
+                return Ok((i, name.offset(len)));
+            } else if found.is_none() {
+                found = Some(i);
+            } else {
+                ambiguous = true;
+            }
+        }
+        i += 1;
+    }
+
+    match found {
+        Some(_) if ambiguous => Err(&b": option is ambiguous -- \0"[..]),
+        Some(i) => Ok((i, name.offset(len))),
+        None => Err(&b": unrecognized option -- \0"[..]),
+    }
+}
+
+unsafe fn print_error(argv: *const *mut c_char, desc: &[u8], arg: *const c_char) {
+    // NOTE: we don't use fprintf to get around the usage of va_list
+    stdio::fputs(*argv as _, &mut *stdio::stderr);
+    stdio::fputs(desc.as_ptr() as _, &mut *stdio::stderr);
+    stdio::fputs(arg, &mut *stdio::stderr);
+    stdio::fputc(b'\n' as _, &mut *stdio::stderr);
+}
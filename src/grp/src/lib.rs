@@ -1,10 +1,20 @@
 //! grp implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/grp.h.html
 
 #![no_std]
+#![feature(alloc)]
 
+#[macro_use]
+extern crate alloc;
+extern crate errno;
+extern crate fcntl;
 extern crate platform;
+extern crate stdio;
 
+use alloc::vec::Vec;
+use core::{mem, ptr, slice, str};
 use platform::types::*;
+use platform::{RawFile, RawLines};
+use stdio::FILE;
 
 #[repr(C)]
 pub struct group {
@@ -14,17 +24,205 @@ pub struct group {
     pub gr_mem: *mut *mut c_char,
 }
 
-// #[no_mangle]
+static mut GROUP_BUF: *mut c_char = ptr::null_mut();
+static mut GROUP: group = group {
+    gr_name: ptr::null_mut(),
+    gr_passwd: ptr::null_mut(),
+    gr_gid: 0,
+    gr_mem: ptr::null_mut(),
+};
+
+// The database getgrent() is walking through, opened on first use
+static mut GROUP_DB: Option<RawLines> = None;
+
+enum OptionGroup {
+    Error,
+    NotFound,
+    Found(*mut c_char),
+}
+
+/// Opens /etc/group to be read one line at a time
+fn open_db() -> Result<RawLines, ()> {
+    let file = RawFile::open(
+        "/etc/group\0".as_ptr() as *const c_char,
+        fcntl::O_RDONLY,
+        0,
+    )?;
+    Ok(RawLines::new(file))
+}
+
+fn parse_gid(part: &[u8]) -> Option<gid_t> {
+    str::from_utf8(part).ok().and_then(|part| part.parse().ok())
+}
+
+/// Splits an entry into its four fields, or returns None if it is malformed
+fn split(line: &[u8]) -> Option<[&[u8]; 4]> {
+    let mut parts: [&[u8]; 4] = [&[]; 4];
+    let mut count = 0;
+    for (i, part) in line.splitn(4, |b| *b == b':').enumerate() {
+        parts[i] = part;
+        count += 1;
+    }
+
+    if count < 4 || parse_gid(parts[2]).is_none() {
+        return None;
+    }
+    Some(parts)
+}
+
+/// Copies an entry into `out`. The strings and the NULL-terminated gr_mem array all live in
+/// the same buffer, which is allocated here unless the caller gave one.
+fn fill(parts: &[&[u8]; 4], out: *mut group, alloc: Option<(*mut c_char, size_t)>) -> OptionGroup {
+    let members: Vec<&[u8]> = if parts[3].is_empty() {
+        Vec::new()
+    } else {
+        parts[3].split(|b| *b == b',').collect()
+    };
+
+    // The array of members goes first, so only the start might need padding
+    let align = mem::align_of::<*mut c_char>();
+    let pad = |buf: *mut c_char| (align - buf as usize % align) % align;
+    let array_len = (members.len() + 1) * mem::size_of::<*mut c_char>();
+    let strings_len: usize = parts[0].len() + 1
+        + parts[1].len() + 1
+        + members.iter().map(|member| member.len() + 1).sum::<usize>();
+
+    let buf = match alloc {
+        Some((buf, size)) => {
+            if pad(buf) + array_len + strings_len > size {
+                unsafe {
+                    platform::errno = errno::ERANGE;
+                }
+                return OptionGroup::Error;
+            }
+            buf
+        }
+        None => unsafe { platform::alloc(align - 1 + array_len + strings_len) as *mut c_char },
+    };
+    if buf.is_null() {
+        unsafe {
+            platform::errno = errno::ENOMEM;
+        }
+        return OptionGroup::Error;
+    }
+
+    unsafe {
+        let array = buf.add(pad(buf)) as *mut *mut c_char;
+        let mut dst = (array as *mut c_char).add(array_len);
+        let mut copy = |src: &[u8]| {
+            let start = dst;
+            ptr::copy_nonoverlapping(src.as_ptr() as *const c_char, dst, src.len());
+            *dst.add(src.len()) = 0;
+            dst = dst.add(src.len() + 1);
+            start
+        };
+
+        (*out).gr_name = copy(parts[0]);
+        (*out).gr_passwd = copy(parts[1]);
+        (*out).gr_gid = parse_gid(parts[2]).unwrap_or(0);
+        for (i, member) in members.iter().enumerate() {
+            *array.add(i) = copy(*member);
+        }
+        *array.add(members.len()) = ptr::null_mut();
+        (*out).gr_mem = array;
+    }
+
+    OptionGroup::Found(buf)
+}
+
+/// Reads on from where `db` is until `callback` accepts an entry, skipping malformed lines
+fn grp_search<F>(
+    db: &mut RawLines,
+    out: *mut group,
+    alloc: Option<(*mut c_char, size_t)>,
+    mut callback: F,
+) -> OptionGroup
+where
+    F: FnMut(&[&[u8]; 4]) -> bool,
+{
+    loop {
+        let line = match db.next_line() {
+            Ok(Some(line)) => line,
+            Ok(None) => return OptionGroup::NotFound,
+            Err(()) => return OptionGroup::Error,
+        };
+
+        if let Some(parts) = split(&line) {
+            if callback(&parts) {
+                return fill(&parts, out, alloc);
+            }
+        }
+    }
+}
+
+fn grp_lookup<F>(out: *mut group, alloc: Option<(*mut c_char, size_t)>, callback: F) -> OptionGroup
+where
+    F: FnMut(&[&[u8]; 4]) -> bool,
+{
+    match open_db() {
+        Ok(mut db) => grp_search(&mut db, out, alloc, callback),
+        Err(()) => OptionGroup::Error,
+    }
+}
+
+fn name_matches(parts: &[&[u8]; 4], name: *const c_char) -> bool {
+    unsafe { platform::c_str(name) == parts[0] }
+}
+
+fn gid_matches(parts: &[&[u8]; 4], gid: gid_t) -> bool {
+    parse_gid(parts[2]) == Some(gid)
+}
+
+/// Hands out the reentrant result, returning the error number like POSIX wants
+fn reentrant(res: OptionGroup, out: *mut group, result: *mut *mut group) -> c_int {
+    match res {
+        OptionGroup::Error => unsafe {
+            *result = ptr::null_mut();
+            platform::errno
+        },
+        OptionGroup::NotFound => unsafe {
+            *result = ptr::null_mut();
+            0
+        },
+        OptionGroup::Found(_) => unsafe {
+            *result = out;
+            0
+        },
+    }
+}
+
+/// Hands out the static result, freeing the buffer of the last one
+unsafe fn static_group(res: OptionGroup) -> *mut group {
+    match res {
+        OptionGroup::Error => ptr::null_mut(),
+        OptionGroup::NotFound => ptr::null_mut(),
+        OptionGroup::Found(buf) => {
+            if GROUP_BUF != ptr::null_mut() {
+                platform::free(GROUP_BUF as *mut c_void);
+            }
+            GROUP_BUF = buf;
+            &mut GROUP
+        }
+    }
+}
+
+#[no_mangle]
 pub extern "C" fn getgrgid(gid: gid_t) -> *mut group {
-    unimplemented!();
+    unsafe {
+        let res = grp_lookup(&mut GROUP, None, |parts| gid_matches(parts, gid));
+        static_group(res)
+    }
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn getgrnam(name: *const c_char) -> *mut group {
-    unimplemented!();
+    unsafe {
+        let res = grp_lookup(&mut GROUP, None, |parts| name_matches(parts, name));
+        static_group(res)
+    }
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn getgrgid_r(
     gid: gid_t,
     grp: *mut group,
@@ -32,10 +230,11 @@ pub extern "C" fn getgrgid_r(
     bufsize: usize,
     result: *mut *mut group,
 ) -> c_int {
-    unimplemented!();
+    let res = grp_lookup(grp, Some((buffer, bufsize)), |parts| gid_matches(parts, gid));
+    reentrant(res, grp, result)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn getgrnam_r(
     name: *const c_char,
     grp: *mut group,
@@ -43,27 +242,96 @@ pub extern "C" fn getgrnam_r(
     bufsize: usize,
     result: *mut *mut group,
 ) -> c_int {
-    unimplemented!();
+    let res = grp_lookup(grp, Some((buffer, bufsize)), |parts| name_matches(parts, name));
+    reentrant(res, grp, result)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn getgrent() -> *mut group {
-    unimplemented!();
+    unsafe {
+        if GROUP_DB.is_none() {
+            match open_db() {
+                Ok(db) => GROUP_DB = Some(db),
+                Err(()) => return ptr::null_mut(),
+            }
+        }
+        let db = GROUP_DB.as_mut().unwrap();
+        let res = grp_search(db, &mut GROUP, None, |_| true);
+        static_group(res)
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn endgrent() {
-    unimplemented!();
+/// Reads the next entry from `stream`, which is laid out like /etc/group
+#[no_mangle]
+pub unsafe extern "C" fn fgetgrent(stream: *mut FILE) -> *mut group {
+    let mut line: *mut c_char = ptr::null_mut();
+    let mut n: size_t = 0;
+    let res = loop {
+        let len = stdio::getline(&mut line, &mut n, &mut *stream);
+        if len < 0 {
+            break OptionGroup::NotFound;
+        }
+
+        let mut bytes = slice::from_raw_parts(line as *const u8, len as usize);
+        if bytes.last() == Some(&b'\n') {
+            bytes = &bytes[..bytes.len() - 1];
+        }
+        if let Some(parts) = split(bytes) {
+            break fill(&parts, &mut GROUP, None);
+        }
+    };
+    if !line.is_null() {
+        platform::free(line as *mut c_void);
+    }
+    static_group(res)
 }
 
-// #[no_mangle]
-pub extern "C" fn setgrent() {
-    unimplemented!();
+/// Sets the supplementary group ids to the `size` ones in `list`. Only root may do this.
+#[no_mangle]
+pub extern "C" fn setgroups(size: size_t, list: *const gid_t) -> c_int {
+    platform::setgroups(size, list)
 }
 
-/*
+/// Sets the supplementary groups to `group` and every group in /etc/group that lists `user`
+/// as a member
 #[no_mangle]
-pub extern "C" fn func(args) -> c_int {
-    unimplemented!();
+pub extern "C" fn initgroups(user: *const c_char, group: gid_t) -> c_int {
+    let user = unsafe { platform::c_str(user) };
+    let mut groups = vec![group];
+
+    let mut db = match open_db() {
+        Ok(db) => db,
+        Err(()) => return -1,
+    };
+    loop {
+        let line = match db.next_line() {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(()) => return -1,
+        };
+
+        if let Some(parts) = split(&line) {
+            let gid = parse_gid(parts[2]).unwrap_or(0);
+            if !groups.contains(&gid) && parts[3].split(|b| *b == b',').any(|member| member == user) {
+                groups.push(gid);
+            }
+        }
+    }
+
+    setgroups(groups.len(), groups.as_ptr())
+}
+
+#[no_mangle]
+pub extern "C" fn endgrent() {
+    unsafe {
+        GROUP_DB = None;
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn setgrent() {
+    // Reopening the file is the easiest way to start over
+    unsafe {
+        GROUP_DB = None;
+    }
 }
-*/
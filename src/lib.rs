@@ -7,16 +7,23 @@
 extern crate platform;
 
 pub extern crate arpainet;
+pub extern crate assert;
 pub extern crate ctype;
 pub extern crate dirent;
+pub extern crate dlfcn;
 pub extern crate errno;
 pub extern crate fcntl;
 pub extern crate fenv;
 pub extern crate float;
 pub extern crate fnmatch;
+pub extern crate getopt;
 pub extern crate grp;
+pub extern crate libgen;
 pub extern crate locale;
+pub extern crate netdb;
 pub extern crate netinet;
+pub extern crate poll;
+pub extern crate pthread;
 pub extern crate pwd;
 pub extern crate semaphore;
 pub extern crate setjmp;
@@ -31,8 +38,10 @@ pub extern crate sys_resource;
 pub extern crate sys_select;
 pub extern crate sys_socket;
 pub extern crate sys_stat;
+pub extern crate sys_statvfs;
 pub extern crate sys_time;
 pub extern crate sys_times;
+pub extern crate sys_uio;
 pub extern crate sys_un;
 pub extern crate sys_utsname;
 pub extern crate sys_wait;
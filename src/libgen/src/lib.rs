@@ -0,0 +1,62 @@
+//! libgen implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/libgen.h.html
+
+#![no_std]
+
+extern crate platform;
+
+use platform::types::*;
+
+/// Returns the last component of `path`, removing any trailing slashes from it
+#[no_mangle]
+pub unsafe extern "C" fn __xpg_basename(path: *mut c_char) -> *mut c_char {
+    if path.is_null() || *path == 0 {
+        return ".\0".as_ptr() as *mut c_char;
+    }
+
+    let mut i = platform::c_str(path).len() - 1;
+    while i > 0 && *path.add(i) == b'/' as c_char {
+        *path.add(i) = 0;
+        i -= 1;
+    }
+    // only slashes were left
+    if i == 0 {
+        return path;
+    }
+
+    while i > 0 && *path.add(i - 1) != b'/' as c_char {
+        i -= 1;
+    }
+    path.add(i)
+}
+
+/// Returns everything in `path` before its last component
+#[no_mangle]
+pub unsafe extern "C" fn dirname(path: *mut c_char) -> *mut c_char {
+    if path.is_null() || *path == 0 {
+        return ".\0".as_ptr() as *mut c_char;
+    }
+
+    let mut i = platform::c_str(path).len() - 1;
+    // trailing slashes, the last component and the slashes in front of it
+    while *path.add(i) == b'/' as c_char {
+        if i == 0 {
+            return "/\0".as_ptr() as *mut c_char;
+        }
+        i -= 1;
+    }
+    while *path.add(i) != b'/' as c_char {
+        if i == 0 {
+            return ".\0".as_ptr() as *mut c_char;
+        }
+        i -= 1;
+    }
+    while *path.add(i) == b'/' as c_char {
+        if i == 0 {
+            return "/\0".as_ptr() as *mut c_char;
+        }
+        i -= 1;
+    }
+
+    *path.add(i + 1) = 0;
+    path
+}
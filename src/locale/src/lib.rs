@@ -9,64 +9,231 @@ use core::ptr;
 use platform::types::*;
 
 const EMPTY_PTR: *const c_char = "\0" as *const _ as *const c_char;
-// Can't use &str because of the mutability
-static mut C_LOCALE: [c_char; 2] = [b'C' as c_char, 0];
+
+// These have to match bits/locale.h
+const LC_ALL: c_int = 0;
+const LC_TIME: c_int = 6;
+
+// The names of the categories, indexed by category - 1 like CATEGORIES
+const CATEGORY_NAMES: [&[u8]; 6] = [
+    b"LC_COLLATE\0",
+    b"LC_CTYPE\0",
+    b"LC_MESSAGES\0",
+    b"LC_MONETARY\0",
+    b"LC_NUMERIC\0",
+    b"LC_TIME\0",
+];
+
+/// The locales there are. C.UTF-8 is the C locale, except that characters are UTF-8.
+#[derive(Clone, Copy, PartialEq)]
+enum Locale {
+    C,
+    Utf8,
+}
+
+impl Locale {
+    fn from_name(name: &[u8]) -> Option<Locale> {
+        match name {
+            b"C" | b"POSIX" => Some(Locale::C),
+            b"C.UTF-8" | b"C.utf8" => Some(Locale::Utf8),
+            _ => None,
+        }
+    }
+
+    /// The name setlocale() hands out, with a NUL
+    fn name(&self) -> &'static [u8] {
+        match *self {
+            Locale::C => b"C\0",
+            Locale::Utf8 => b"C.UTF-8\0",
+        }
+    }
+}
+
+// The locale every category is in, indexed by category - 1
+static mut CATEGORIES: [Locale; 6] = [Locale::C; 6];
+
+// Where setlocale(LC_ALL, NULL) puts the name of a mix of locales
+static mut COMPOSITE_NAME: [u8; 256] = [0; 256];
 
 #[repr(C)]
 #[no_mangle]
 pub struct lconv {
-    currency_symbol: *const c_char,
-    decimal_point: *const c_char,
-    frac_digits: c_char,
-    grouping: *const c_char,
-    int_curr_symbol: *const c_char,
-    int_frac_digits: c_char,
-    mon_decimal_point: *const c_char,
-    mon_grouping: *const c_char,
-    mon_thousands_sep: *const c_char,
-    negative_sign: *const c_char,
-    n_cs_precedes: c_char,
-    n_sep_by_space: c_char,
-    n_sign_posn: c_char,
-    positive_sign: *const c_char,
-    p_cs_precedes: c_char,
-    p_sep_by_space: c_char,
-    p_sign_posn: c_char,
-    thousands_sep: *const c_char,
+    pub decimal_point: *const c_char,
+    pub thousands_sep: *const c_char,
+    pub grouping: *const c_char,
+    pub int_curr_symbol: *const c_char,
+    pub currency_symbol: *const c_char,
+    pub mon_decimal_point: *const c_char,
+    pub mon_thousands_sep: *const c_char,
+    pub mon_grouping: *const c_char,
+    pub positive_sign: *const c_char,
+    pub negative_sign: *const c_char,
+    pub int_frac_digits: c_char,
+    pub frac_digits: c_char,
+    pub p_cs_precedes: c_char,
+    pub p_sep_by_space: c_char,
+    pub n_cs_precedes: c_char,
+    pub n_sep_by_space: c_char,
+    pub p_sign_posn: c_char,
+    pub n_sign_posn: c_char,
+    pub int_p_cs_precedes: c_char,
+    pub int_p_sep_by_space: c_char,
+    pub int_n_cs_precedes: c_char,
+    pub int_n_sep_by_space: c_char,
+    pub int_p_sign_posn: c_char,
+    pub int_n_sign_posn: c_char,
 }
 unsafe impl Sync for lconv {}
 
-static CURRENT_LOCALE: lconv = lconv {
-    currency_symbol: EMPTY_PTR,
+// The conventions of the C locale, which C.UTF-8 shares. CHAR_MAX and "" mean a value isn't
+// available.
+static C_LCONV: lconv = lconv {
     decimal_point: ".\0" as *const _ as *const c_char,
-    frac_digits: c_char::max_value(),
+    thousands_sep: EMPTY_PTR,
     grouping: EMPTY_PTR,
     int_curr_symbol: EMPTY_PTR,
-    int_frac_digits: c_char::max_value(),
+    currency_symbol: EMPTY_PTR,
     mon_decimal_point: EMPTY_PTR,
-    mon_grouping: EMPTY_PTR,
     mon_thousands_sep: EMPTY_PTR,
-    negative_sign: EMPTY_PTR,
-    n_cs_precedes: c_char::max_value(),
-    n_sep_by_space: c_char::max_value(),
-    n_sign_posn: c_char::max_value(),
+    mon_grouping: EMPTY_PTR,
     positive_sign: EMPTY_PTR,
+    negative_sign: EMPTY_PTR,
+    int_frac_digits: c_char::max_value(),
+    frac_digits: c_char::max_value(),
     p_cs_precedes: c_char::max_value(),
     p_sep_by_space: c_char::max_value(),
+    n_cs_precedes: c_char::max_value(),
+    n_sep_by_space: c_char::max_value(),
     p_sign_posn: c_char::max_value(),
-    thousands_sep: EMPTY_PTR,
+    n_sign_posn: c_char::max_value(),
+    int_p_cs_precedes: c_char::max_value(),
+    int_p_sep_by_space: c_char::max_value(),
+    int_n_cs_precedes: c_char::max_value(),
+    int_n_sep_by_space: c_char::max_value(),
+    int_p_sign_posn: c_char::max_value(),
+    int_n_sign_posn: c_char::max_value(),
 };
 
-#[no_mangle]
-pub extern "C" fn localeconv() -> *const lconv {
-    &CURRENT_LOCALE as *const _
+/// The locale "" stands for: the first of `$LC_ALL`, `$LC_<category>` and `$LANG` that is set,
+/// or C if none are
+unsafe fn env_locale(category: usize) -> Option<Locale> {
+    let vars: [&[u8]; 3] = [b"LC_ALL\0", CATEGORY_NAMES[category], b"LANG\0"];
+    for var in vars.iter() {
+        let value = platform::getenv(var.as_ptr() as *const c_char);
+        if !value.is_null() && *value != 0 {
+            return Locale::from_name(platform::c_str(value));
+        }
+    }
+    Some(Locale::C)
+}
+
+unsafe fn find_locale(category: usize, name: &[u8]) -> Option<Locale> {
+    if name.is_empty() {
+        env_locale(category)
+    } else {
+        Locale::from_name(name)
+    }
+}
+
+/// Reads a name like "LC_COLLATE=C;LC_CTYPE=C.UTF-8;..." into `locales`
+unsafe fn parse_composite(name: &[u8], locales: &mut [Locale; 6]) -> bool {
+    for part in name.split(|b| *b == b';') {
+        let mut split = part.splitn(2, |b| *b == b'=');
+        let category = split.next().unwrap_or(&[]);
+        let locale = split.next().unwrap_or(&[]);
+
+        let index = match CATEGORY_NAMES
+            .iter()
+            .position(|name| &name[..name.len() - 1] == category)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+        match find_locale(index, locale) {
+            Some(locale) => locales[index] = locale,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// The name of the locale `category` is in. If the categories of LC_ALL are in different
+/// locales, this lists all of them in the format `parse_composite` reads.
+unsafe fn locale_name(category: c_int) -> *mut c_char {
+    if category != LC_ALL {
+        return CATEGORIES[category as usize - 1].name().as_ptr() as *mut c_char;
+    }
+
+    let first = CATEGORIES[0];
+    if CATEGORIES.iter().all(|locale| *locale == first) {
+        return first.name().as_ptr() as *mut c_char;
+    }
+
+    let mut len = 0;
+    for (i, locale) in CATEGORIES.iter().enumerate() {
+        let category = CATEGORY_NAMES[i];
+        let locale = locale.name();
+        let parts: [&[u8]; 4] = [
+            b";",
+            &category[..category.len() - 1],
+            b"=",
+            &locale[..locale.len() - 1],
+        ];
+        // No separator in front of the first one
+        let parts = if i == 0 { &parts[1..] } else { &parts[..] };
+        for part in parts {
+            COMPOSITE_NAME[len..len + part.len()].copy_from_slice(part);
+            len += part.len();
+        }
+    }
+    COMPOSITE_NAME[len] = 0;
+    COMPOSITE_NAME.as_mut_ptr() as *mut c_char
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn setlocale(_option: c_int, val: *const c_char) -> *mut c_char {
-    if val.is_null() {
-        return C_LOCALE.as_mut_ptr() as *mut c_char;
+pub unsafe extern "C" fn setlocale(category: c_int, locale: *const c_char) -> *mut c_char {
+    if category < LC_ALL || category > LC_TIME {
+        return ptr::null_mut();
     }
-    // TODO actually implement
-    ptr::null_mut()
+
+    if !locale.is_null() {
+        // Nothing changes unless every category can be set
+        let mut locales = CATEGORIES;
+        let name = platform::c_str(locale);
+
+        let found = if category != LC_ALL {
+            match find_locale(category as usize - 1, name) {
+                Some(locale) => {
+                    locales[category as usize - 1] = locale;
+                    true
+                }
+                None => false,
+            }
+        } else if name.contains(&b'=') {
+            parse_composite(name, &mut locales)
+        } else {
+            let mut found = true;
+            for i in 0..locales.len() {
+                match find_locale(i, name) {
+                    Some(locale) => locales[i] = locale,
+                    None => found = false,
+                }
+            }
+            found
+        };
+
+        if !found {
+            return ptr::null_mut();
+        }
+        CATEGORIES = locales;
+    }
+
+    locale_name(category)
+}
+
+/// The numeric and monetary conventions of the current locale, which the caller must not change
+#[no_mangle]
+pub unsafe extern "C" fn localeconv() -> *mut lconv {
+    // LC_NUMERIC and LC_MONETARY can only be in C or C.UTF-8, which format things the same way
+    &C_LCONV as *const lconv as *mut lconv
 }
@@ -0,0 +1,325 @@
+//! netdb implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xns/netdb.h.html
+
+#![no_std]
+#![feature(alloc)]
+
+#[macro_use]
+extern crate alloc;
+
+extern crate fcntl;
+extern crate netinet;
+extern crate platform;
+extern crate sys_socket;
+
+use alloc::vec::Vec;
+use core::{mem, ptr, str};
+use netinet::in_h::{in_addr, sockaddr_in, INADDR_ANY, INADDR_LOOPBACK, IPPROTO_TCP, IPPROTO_UDP};
+use platform::types::*;
+use platform::{c_str, inet, RawFile};
+use sys_socket::{sa_family_t, sockaddr, socklen_t};
+
+#[repr(C)]
+pub struct addrinfo {
+    pub ai_flags: c_int,
+    pub ai_family: c_int,
+    pub ai_socktype: c_int,
+    pub ai_protocol: c_int,
+    pub ai_addrlen: socklen_t,
+    pub ai_addr: *mut sockaddr,
+    pub ai_canonname: *mut c_char,
+    pub ai_next: *mut addrinfo,
+}
+
+pub const AI_PASSIVE: c_int = 0x0001;
+pub const AI_CANONNAME: c_int = 0x0002;
+pub const AI_NUMERICHOST: c_int = 0x0004;
+pub const AI_V4MAPPED: c_int = 0x0008;
+pub const AI_ALL: c_int = 0x0010;
+pub const AI_ADDRCONFIG: c_int = 0x0020;
+pub const AI_NUMERICSERV: c_int = 0x0400;
+
+pub const EAI_BADFLAGS: c_int = -1;
+pub const EAI_NONAME: c_int = -2;
+pub const EAI_AGAIN: c_int = -3;
+pub const EAI_FAIL: c_int = -4;
+pub const EAI_FAMILY: c_int = -6;
+pub const EAI_SOCKTYPE: c_int = -7;
+pub const EAI_SERVICE: c_int = -8;
+pub const EAI_MEMORY: c_int = -10;
+pub const EAI_SYSTEM: c_int = -11;
+pub const EAI_OVERFLOW: c_int = -12;
+
+fn read_file(path: &[u8]) -> Option<Vec<u8>> {
+    let file = RawFile::open(path.as_ptr() as *const c_char, fcntl::O_RDONLY, 0).ok()?;
+
+    let mut data = Vec::new();
+    let mut buf = [0; 1024];
+    loop {
+        match platform::read(*file, &mut buf) {
+            0 => return Some(data),
+            n if n < 0 => return None,
+            n => data.extend_from_slice(&buf[..n as usize]),
+        }
+    }
+}
+
+// Splits a hosts(5) or services(5) style database into the whitespace
+// separated fields of each line, skipping comments and blank lines
+fn entries<'a>(data: &'a [u8]) -> impl Iterator<Item = Vec<&'a [u8]>> + 'a {
+    data.split(|b| *b == b'\n')
+        .map(|line| {
+            line.split(|b| *b == b'#')
+                .next()
+                .unwrap_or(&[])
+                .split(|b| *b == b' ' || *b == b'\t')
+                .filter(|field| !field.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|fields| !fields.is_empty())
+}
+
+fn parse_in_addr(s: &[u8]) -> Option<in_addr> {
+    inet::parse_ipv4(s).map(|octets| in_addr {
+        s_addr: unsafe { mem::transmute(octets) },
+    })
+}
+
+fn lookup_host(name: &[u8]) -> Result<Vec<in_addr>, c_int> {
+    let mut addrs = Vec::new();
+
+    if let Some(hosts) = read_file(b"/etc/hosts\0") {
+        for fields in entries(&hosts) {
+            if fields[1..].iter().any(|alias| alias.eq_ignore_ascii_case(name)) {
+                if let Some(addr) = parse_in_addr(fields[0]) {
+                    addrs.push(addr);
+                }
+            }
+        }
+    }
+    if !addrs.is_empty() {
+        return Ok(addrs);
+    }
+
+    // Reading dns:name yields the resolved IPv4 addresses, 4 bytes each in
+    // network byte order
+    let mut path = Vec::with_capacity(name.len() + 5);
+    path.extend_from_slice(b"dns:");
+    path.extend_from_slice(name);
+    path.push(0);
+
+    let data = read_file(&path).ok_or(EAI_NONAME)?;
+    for chunk in data.chunks(4) {
+        if chunk.len() == 4 {
+            let host = (chunk[0] as u32) << 24
+                | (chunk[1] as u32) << 16
+                | (chunk[2] as u32) << 8
+                | chunk[3] as u32;
+            addrs.push(in_addr {
+                s_addr: host.to_be(),
+            });
+        }
+    }
+    if addrs.is_empty() {
+        Err(EAI_NONAME)
+    } else {
+        Ok(addrs)
+    }
+}
+
+fn lookup_service(name: &[u8], socktype: c_int, flags: c_int) -> Result<u16, c_int> {
+    if let Some(port) = str::from_utf8(name).ok().and_then(|s| s.parse().ok()) {
+        return Ok(port);
+    }
+    if flags & AI_NUMERICSERV == AI_NUMERICSERV {
+        return Err(EAI_NONAME);
+    }
+
+    let services = read_file(b"/etc/services\0").ok_or(EAI_SERVICE)?;
+    for fields in entries(&services) {
+        if fields.len() < 2 || (fields[0] != name && !fields[2..].contains(&name)) {
+            continue;
+        }
+
+        let mut parts = fields[1].splitn(2, |b| *b == b'/');
+        let port = parts.next().unwrap_or(&[]);
+        let proto = parts.next().unwrap_or(&[]);
+        let matches = match socktype {
+            SOCK_STREAM => proto == b"tcp",
+            SOCK_DGRAM => proto == b"udp",
+            _ => true,
+        };
+        if matches {
+            if let Some(port) = str::from_utf8(port).ok().and_then(|s| s.parse().ok()) {
+                return Ok(port);
+            }
+        }
+    }
+    Err(EAI_SERVICE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn freeaddrinfo(mut res: *mut addrinfo) {
+    while !res.is_null() {
+        let next = (*res).ai_next;
+        // platform::free doesn't take null on every platform
+        if !(*res).ai_addr.is_null() {
+            platform::free((*res).ai_addr as *mut c_void);
+        }
+        if !(*res).ai_canonname.is_null() {
+            platform::free((*res).ai_canonname as *mut c_void);
+        }
+        platform::free(res as *mut c_void);
+        res = next;
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn gai_strerror(errcode: c_int) -> *const c_char {
+    match errcode {
+        EAI_BADFLAGS => "Invalid flags\0",
+        EAI_NONAME => "Name does not resolve\0",
+        EAI_AGAIN => "Try again\0",
+        EAI_FAIL => "Non-recoverable error\0",
+        EAI_FAMILY => "Unrecognized address family or invalid length\0",
+        EAI_SOCKTYPE => "Unrecognized socket type\0",
+        EAI_SERVICE => "Unrecognized service\0",
+        EAI_MEMORY => "Out of memory\0",
+        EAI_SYSTEM => "System error\0",
+        EAI_OVERFLOW => "Overflow\0",
+        _ => "Unknown error\0",
+    }.as_ptr() as *const c_char
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getaddrinfo(
+    node: *const c_char,
+    service: *const c_char,
+    hints: *const addrinfo,
+    res: *mut *mut addrinfo,
+) -> c_int {
+    let (flags, family, socktype, protocol) = if hints.is_null() {
+        (0, AF_UNSPEC, 0, 0)
+    } else {
+        (
+            (*hints).ai_flags,
+            (*hints).ai_family,
+            (*hints).ai_socktype,
+            (*hints).ai_protocol,
+        )
+    };
+
+    if flags
+        & !(AI_PASSIVE | AI_CANONNAME | AI_NUMERICHOST | AI_V4MAPPED | AI_ALL | AI_ADDRCONFIG
+            | AI_NUMERICSERV) != 0
+    {
+        return EAI_BADFLAGS;
+    }
+    // TODO: AF_INET6
+    if family != AF_UNSPEC && family != AF_INET {
+        return EAI_FAMILY;
+    }
+    let socktypes: &[(c_int, c_int)] = match socktype {
+        0 => &[
+            (SOCK_STREAM, IPPROTO_TCP as c_int),
+            (SOCK_DGRAM, IPPROTO_UDP as c_int),
+        ],
+        SOCK_STREAM => &[(SOCK_STREAM, IPPROTO_TCP as c_int)],
+        SOCK_DGRAM => &[(SOCK_DGRAM, IPPROTO_UDP as c_int)],
+        _ => return EAI_SOCKTYPE,
+    };
+    if node.is_null() && service.is_null() {
+        return EAI_NONAME;
+    }
+
+    let port = if service.is_null() {
+        0
+    } else {
+        match lookup_service(c_str(service), socktype, flags) {
+            Ok(port) => port,
+            Err(err) => return err,
+        }
+    };
+
+    let addrs = if node.is_null() {
+        let addr = if flags & AI_PASSIVE == AI_PASSIVE {
+            INADDR_ANY
+        } else {
+            INADDR_LOOPBACK
+        };
+        vec![in_addr {
+            s_addr: addr.to_be(),
+        }]
+    } else if let Some(addr) = parse_in_addr(c_str(node)) {
+        vec![addr]
+    } else if flags & AI_NUMERICHOST == AI_NUMERICHOST {
+        return EAI_NONAME;
+    } else {
+        match lookup_host(c_str(node)) {
+            Ok(addrs) => addrs,
+            Err(err) => return err,
+        }
+    };
+
+    let mut first: *mut addrinfo = ptr::null_mut();
+    let mut next: *mut *mut addrinfo = &mut first;
+    for addr in &addrs {
+        for &(kind, proto) in socktypes {
+            if protocol != 0 && protocol != proto {
+                continue;
+            }
+
+            let ai = platform::alloc(mem::size_of::<addrinfo>()) as *mut addrinfo;
+            let sa = platform::alloc(mem::size_of::<sockaddr>()) as *mut sockaddr;
+            if ai.is_null() || sa.is_null() {
+                if !ai.is_null() {
+                    platform::free(ai as *mut c_void);
+                }
+                if !sa.is_null() {
+                    platform::free(sa as *mut c_void);
+                }
+                freeaddrinfo(first);
+                return EAI_MEMORY;
+            }
+
+            ptr::write_bytes(sa as *mut u8, 0, mem::size_of::<sockaddr>());
+            let sin = sa as *mut sockaddr_in;
+            (*sin).sin_family = AF_INET as sa_family_t;
+            (*sin).sin_port = port.to_be();
+            (*sin).sin_addr = *addr;
+
+            ptr::write(
+                ai,
+                addrinfo {
+                    ai_flags: flags,
+                    ai_family: AF_INET,
+                    ai_socktype: kind,
+                    ai_protocol: proto,
+                    ai_addrlen: mem::size_of::<sockaddr>() as socklen_t,
+                    ai_addr: sa,
+                    ai_canonname: ptr::null_mut(),
+                    ai_next: ptr::null_mut(),
+                },
+            );
+            *next = ai;
+            next = &mut (*ai).ai_next;
+        }
+    }
+    if first.is_null() {
+        return EAI_SOCKTYPE;
+    }
+
+    if flags & AI_CANONNAME == AI_CANONNAME && !node.is_null() {
+        let name = c_str(node);
+        let canonname = platform::alloc(name.len() + 1) as *mut c_char;
+        if canonname.is_null() {
+            freeaddrinfo(first);
+            return EAI_MEMORY;
+        }
+        ptr::copy_nonoverlapping(name.as_ptr() as *const c_char, canonname, name.len());
+        *canonname.offset(name.len() as isize) = 0;
+        (*first).ai_canonname = canonname;
+    }
+
+    *res = first;
+    0
+}
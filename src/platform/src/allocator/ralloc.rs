@@ -1,48 +1,59 @@
 extern crate ralloc;
 
+use core::ptr;
+
 pub use ralloc::Allocator;
 
-unsafe fn alloc_inner(size: usize, offset: usize, align: usize) -> *mut c_void {
-    let ptr = ralloc::alloc(size + offset, align);
+// The block starts with `align` bytes of padding, so that the returned pointer stays aligned. The
+// last 16 of those hold the size of the whole block and its alignment, which free and realloc
+// need to find the start of it again.
+unsafe fn alloc_inner(size: usize, align: usize) -> *mut c_void {
+    let total = match size.checked_add(align) {
+        Some(total) => total,
+        None => return ptr::null_mut(),
+    };
+    let ptr = ralloc::alloc(total, align);
     if !ptr.is_null() {
-        *(ptr as *mut u64) = (size + offset) as u64;
-        *(ptr as *mut u64).offset(1) = align as u64;
-        ptr.offset(offset as isize) as *mut c_void
+        let ptr = ptr.offset(align as isize);
+        *(ptr as *mut u64).offset(-2) = total as u64;
+        *(ptr as *mut u64).offset(-1) = align as u64;
+        ptr as *mut c_void
     } else {
         ptr as *mut c_void
     }
 }
 
 pub unsafe fn alloc(size: usize) -> *mut c_void {
-    alloc_inner(size, 16, 8)
+    // Aligned for any type, like long double
+    alloc_inner(size, 16)
 }
 
 pub unsafe fn alloc_align(size: usize, alignment: usize) -> *mut c_void {
-    let mut align = 32;
-    while align <= alignment {
-        align *= 2;
-    }
-
-    alloc_inner(size, align / 2, align)
+    alloc_inner(size, alignment.max(16))
 }
 
 pub unsafe fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
-    let old_ptr = (ptr as *mut u8).offset(-16);
-    let old_size = *(old_ptr as *mut u64);
-    let align = *(old_ptr as *mut u64).offset(1);
-    let ptr = ralloc::realloc(old_ptr, old_size as usize, size + 16, align as usize);
+    let old_size = *(ptr as *mut u64).offset(-2);
+    let align = *(ptr as *mut u64).offset(-1) as usize;
+    let total = match size.checked_add(align) {
+        Some(total) => total,
+        // Like any failed realloc, this leaves the old block alone
+        None => return ptr::null_mut(),
+    };
+    let old_ptr = (ptr as *mut u8).offset(-(align as isize));
+    let ptr = ralloc::realloc(old_ptr, old_size as usize, total, align);
     if !ptr.is_null() {
-        *(ptr as *mut u64) = (size + 16) as u64;
-        *(ptr as *mut u64).offset(1) = align;
-        ptr.offset(16) as *mut c_void
+        let ptr = ptr.offset(align as isize);
+        *(ptr as *mut u64).offset(-2) = total as u64;
+        *(ptr as *mut u64).offset(-1) = align as u64;
+        ptr as *mut c_void
     } else {
         ptr as *mut c_void
     }
 }
 
 pub unsafe fn free(ptr: *mut c_void) {
-    let ptr = (ptr as *mut u8).offset(-16);
-    let size = *(ptr as *mut u64);
-    let _align = *(ptr as *mut u64).offset(1);
-    ralloc::free(ptr, size as usize);
+    let size = *(ptr as *mut u64).offset(-2);
+    let align = *(ptr as *mut u64).offset(-1) as usize;
+    ralloc::free((ptr as *mut u8).offset(-(align as isize)), size as usize);
 }
@@ -0,0 +1,219 @@
+//! Textual address formatting shared between the platform backends and arpa/inet
+
+use core::fmt;
+
+/// Formats an IPv6 address in its canonical form, as described by RFC 5952:
+/// lowercase hex groups without leading zeros, with the longest run of two or
+/// more zero groups compressed to `::`.
+pub struct Ipv6Display<'a>(pub &'a [u8; 16]);
+
+impl<'a> fmt::Display for Ipv6Display<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut groups = [0u16; 8];
+        for (i, group) in groups.iter_mut().enumerate() {
+            *group = (self.0[i * 2] as u16) << 8 | self.0[i * 2 + 1] as u16;
+        }
+
+        // IPv4-mapped addresses keep the dotted quad at the end
+        if groups[..5].iter().all(|g| *g == 0) && groups[5] == 0xffff {
+            return write!(
+                f,
+                "::ffff:{}.{}.{}.{}",
+                self.0[12], self.0[13], self.0[14], self.0[15]
+            );
+        }
+
+        // Find the first longest run of zero groups
+        let (mut best_start, mut best_len) = (0, 0);
+        let mut i = 0;
+        while i < groups.len() {
+            if groups[i] == 0 {
+                let start = i;
+                while i < groups.len() && groups[i] == 0 {
+                    i += 1;
+                }
+                if i - start > best_len {
+                    best_start = start;
+                    best_len = i - start;
+                }
+            } else {
+                i += 1;
+            }
+        }
+        if best_len < 2 {
+            best_len = 0;
+        }
+
+        let mut i = 0;
+        while i < groups.len() {
+            if best_len > 0 && i == best_start {
+                f.write_str("::")?;
+                i += best_len;
+                continue;
+            }
+            if i > 0 && !(best_len > 0 && i == best_start + best_len) {
+                f.write_str(":")?;
+            }
+            write!(f, "{:x}", groups[i])?;
+            i += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a dotted-quad IPv4 address in the strict form accepted by
+/// inet_pton: exactly four decimal octets without leading zeros.
+pub fn parse_ipv4(s: &[u8]) -> Option<[u8; 4]> {
+    let mut addr = [0; 4];
+    let mut parts = s.split(|c| *c == b'.');
+    for octet in addr.iter_mut() {
+        let part = parts.next()?;
+        if part.is_empty()
+            || part.len() > 3
+            || (part.len() > 1 && part[0] == b'0')
+            || !part.iter().all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let n = part.iter().fold(0u16, |n, c| n * 10 + (c - b'0') as u16);
+        if n > 255 {
+            return None;
+        }
+        *octet = n as u8;
+    }
+    if parts.next().is_some() {
+        None
+    } else {
+        Some(addr)
+    }
+}
+
+// Parses one part of a legacy dotted address, which may be decimal, octal
+// with a leading 0 or hex with a leading 0x
+fn parse_legacy_part(s: &[u8]) -> Option<u32> {
+    let (digits, radix) = if s.len() > 2 && (s[..2] == *b"0x" || s[..2] == *b"0X") {
+        (&s[2..], 16)
+    } else if s.len() > 1 && s[0] == b'0' {
+        (&s[1..], 8)
+    } else {
+        (s, 10)
+    };
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut n = 0u32;
+    for c in digits {
+        let digit = (*c as char).to_digit(radix)?;
+        n = n.checked_mul(radix)?.checked_add(digit)?;
+    }
+    Some(n)
+}
+
+// Splits a legacy dotted address into between one and four parts
+fn parse_legacy_parts(s: &[u8], parts: &mut [u32; 4]) -> Option<usize> {
+    let mut len = 0;
+    for part in s.split(|c| *c == b'.') {
+        if len >= parts.len() {
+            return None;
+        }
+        parts[len] = parse_legacy_part(part)?;
+        len += 1;
+    }
+    Some(len)
+}
+
+/// Parses an IPv4 address in any of the forms accepted by inet_aton:
+/// `a.b.c.d`, `a.b.c`, `a.b` or `a`, where each part may be decimal, octal or
+/// hex, and the last part fills all of the remaining bytes. The result is in
+/// host byte order.
+pub fn parse_ipv4_legacy(s: &[u8]) -> Option<u32> {
+    let mut parts = [0; 4];
+    let len = parse_legacy_parts(s, &mut parts)?;
+    let (last, init) = parts[..len].split_last()?;
+    if init.iter().any(|part| *part > 0xFF) || *last > !0 >> (8 * init.len()) {
+        return None;
+    }
+    Some(
+        init.iter()
+            .enumerate()
+            .fold(*last, |addr, (i, part)| addr | part << (24 - 8 * i)),
+    )
+}
+
+/// Parses a network number as accepted by inet_network, where each part is a
+/// single byte and the parts are right aligned. The result is in host byte
+/// order.
+pub fn parse_ipv4_network(s: &[u8]) -> Option<u32> {
+    let mut parts = [0; 4];
+    let len = parse_legacy_parts(s, &mut parts)?;
+    if parts[..len].iter().any(|part| *part > 0xFF) {
+        return None;
+    }
+    Some(parts[..len].iter().fold(0, |addr, part| addr << 8 | part))
+}
+
+// Parses colon separated hex groups into `out`, returning the number of bytes
+// written. The last group may be a dotted quad if `last` is set.
+fn parse_ipv6_groups(s: &[u8], out: &mut [u8; 16], last: bool) -> Option<usize> {
+    if s.is_empty() {
+        return Some(0);
+    }
+
+    let mut len = 0;
+    let mut parts = s.split(|c| *c == b':').peekable();
+    while let Some(part) = parts.next() {
+        if last && parts.peek().is_none() && part.contains(&b'.') {
+            if len > 12 {
+                return None;
+            }
+            out[len..len + 4].copy_from_slice(&parse_ipv4(part)?);
+            len += 4;
+        } else {
+            if part.is_empty() || part.len() > 4 || len >= 16 {
+                return None;
+            }
+            let mut n = 0u16;
+            for c in part {
+                n = n << 4 | (*c as char).to_digit(16)? as u16;
+            }
+            out[len] = (n >> 8) as u8;
+            out[len + 1] = n as u8;
+            len += 2;
+        }
+    }
+    Some(len)
+}
+
+/// Parses an IPv6 address in any of the textual forms described by RFC 4291,
+/// including `::` compression and a trailing dotted quad.
+pub fn parse_ipv6(s: &[u8]) -> Option<[u8; 16]> {
+    let split = s.windows(2).position(|w| w == b"::");
+    let (head, tail) = match split {
+        Some(i) => (&s[..i], Some(&s[i + 2..])),
+        None => (s, None),
+    };
+
+    let mut addr = [0; 16];
+    let mut head_buf = [0; 16];
+    let head_len = parse_ipv6_groups(head, &mut head_buf, tail.is_none())?;
+    match tail {
+        None => {
+            if head_len != 16 {
+                return None;
+            }
+            addr = head_buf;
+        }
+        Some(tail) => {
+            let mut tail_buf = [0; 16];
+            let tail_len = parse_ipv6_groups(tail, &mut tail_buf, true)?;
+            // :: has to stand for at least one group of zeros
+            if head_len + tail_len > 14 {
+                return None;
+            }
+            addr[..head_len].copy_from_slice(&head_buf[..head_len]);
+            addr[16 - tail_len..].copy_from_slice(&tail_buf[..tail_len]);
+        }
+    }
+    Some(addr)
+}
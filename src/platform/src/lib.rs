@@ -1,7 +1,6 @@
 #![no_std]
 #![allow(non_camel_case_types)]
-#![feature(alloc, allocator_api, const_vec_new)]
-#![cfg_attr(target_os = "redox", feature(thread_local))]
+#![feature(alloc, allocator_api, asm, const_vec_new, thread_local)]
 
 #[cfg_attr(target_os = "redox", macro_use)]
 extern crate alloc;
@@ -36,10 +35,11 @@ mod sys;
 #[path = "redox/mod.rs"]
 mod sys;
 
+pub mod inet;
 pub mod rawfile;
 pub mod types;
 
-pub use rawfile::RawFile;
+pub use rawfile::{RawFile, RawLines};
 
 use alloc::vec::Vec;
 use core::{fmt, ptr};
@@ -49,7 +49,7 @@ use types::*;
 #[global_allocator]
 static ALLOCATOR: Allocator = Allocator;
 
-//TODO #[thread_local]
+#[thread_local]
 #[allow(non_upper_case_globals)]
 #[no_mangle]
 pub static mut errno: c_int = 0;
@@ -60,6 +60,25 @@ pub static mut environ: *mut *mut c_char = ptr::null_mut();
 #[allow(non_upper_case_globals)]
 pub static mut inner_environ: Vec<*mut c_char> = Vec::new();
 
+/// Whether the permission bits of `st` grant everything in `mode` (a combination of R_OK, W_OK and
+/// X_OK) to `uid` and `gid`. Root can read and write anything, and execute anything that someone
+/// is allowed to execute.
+pub fn check_access(st: &stat, uid: uid_t, gid: gid_t, mode: c_int) -> bool {
+    let wanted = mode & (R_OK | W_OK | X_OK);
+    if uid == 0 {
+        return wanted & X_OK == 0 || st.st_mode & 0o111 != 0;
+    }
+
+    let perms = if st.st_uid == uid {
+        st.st_mode >> 6
+    } else if st.st_gid == gid {
+        st.st_mode >> 3
+    } else {
+        st.st_mode
+    };
+    perms & wanted == wanted
+}
+
 pub unsafe fn c_str_mut<'a>(s: *mut c_char) -> &'a mut [u8] {
     use core::usize;
 
@@ -107,17 +126,29 @@ pub unsafe fn cstr_from_bytes_with_nul_unchecked(bytes: &[u8]) -> *const c_char
     bytes.as_ptr() as *const c_char
 }
 
-// NOTE: defined here rather than in string because memcpy() is useful in multiple crates
-pub unsafe fn memcpy(s1: *mut c_void, s2: *const c_void, n: usize) -> *mut c_void {
-    let mut i = 0;
-    while i + 7 < n {
-        *(s1.offset(i as isize) as *mut u64) = *(s2.offset(i as isize) as *const u64);
-        i += 8;
+// NOTE: defined here rather than in stdlib so that crates stdlib depends on can look at the
+// environment too
+pub unsafe fn getenv(name: *const c_char) -> *mut c_char {
+    let name = c_str(name);
+    if environ.is_null() {
+        return ptr::null_mut();
     }
-    while i < n {
-        *(s1 as *mut u8).offset(i as isize) = *(s2 as *const u8).offset(i as isize);
-        i += 1;
+
+    let mut var = environ;
+    while !(*var).is_null() {
+        let item = c_str(*var);
+        if item.len() > name.len() && item.starts_with(name) && item[name.len()] == b'=' {
+            return (*var).offset(name.len() as isize + 1);
+        }
+        var = var.offset(1);
     }
+    ptr::null_mut()
+}
+
+// NOTE: defined here rather than in string because memcpy() is useful in multiple crates
+pub unsafe fn memcpy(s1: *mut c_void, s2: *const c_void, n: usize) -> *mut c_void {
+    // This ends up calling the memcpy from string
+    ptr::copy_nonoverlapping(s2 as *const u8, s1 as *mut u8, n);
     s1
 }
 
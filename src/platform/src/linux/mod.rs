@@ -1,20 +1,110 @@
-use core::{mem, ptr};
+use core::fmt::Write;
+use core::{cmp, mem, ptr, slice};
 
+use check_access;
 use errno;
 use types::*;
+use StringWriter;
+use {alloc_align, free};
 
+const EACCES: c_int = 13;
 const EINVAL: c_int = 22;
 
 const SIGCHLD: usize = 17;
 
 const TCGETS: c_ulong = 0x5401;
 const TCSETS: c_ulong = 0x5402;
-const TIOCGWINSZ: c_ulong = 0x5413;
 
-const AT_FDCWD: c_int = -100;
 const AT_EMPTY_PATH: c_int = 0x1000;
-const AT_REMOVEDIR: c_int = 0x200;
-const AT_SYMLINK_NOFOLLOW: c_int = 0x100;
+
+const ST_VALID: c_ulong = 0x20;
+
+const CLONE_VM: usize = 0x100;
+const CLONE_FS: usize = 0x200;
+const CLONE_FILES: usize = 0x400;
+const CLONE_SIGHAND: usize = 0x800;
+const CLONE_THREAD: usize = 0x10000;
+const CLONE_SYSVSEM: usize = 0x40000;
+const CLONE_SETTLS: usize = 0x80000;
+const CLONE_PARENT_SETTID: usize = 0x100000;
+const CLONE_CHILD_CLEARTID: usize = 0x200000;
+
+const FUTEX_WAIT: c_int = 0;
+const FUTEX_WAKE: c_int = 1;
+
+const AT_NULL: usize = 0;
+const AT_PHDR: usize = 3;
+const AT_PHENT: usize = 4;
+const AT_PHNUM: usize = 5;
+
+const PT_PHDR: u32 = 6;
+const PT_TLS: u32 = 7;
+
+#[cfg(target_arch = "x86_64")]
+const ARCH_SET_FS: c_int = 0x1002;
+
+// Room for the thread control block the thread pointer points at. On x86_64 it comes after the TLS
+// block and starts with a pointer to itself, on aarch64 it comes before the TLS block.
+#[cfg(target_arch = "x86_64")]
+const TCB_SIZE: usize = 64;
+#[cfg(target_arch = "aarch64")]
+const TCB_SIZE: usize = 16;
+
+// The TLS image from the PT_TLS program header, which every thread starts out with a copy of
+static mut TLS_IMAGE: &'static [u8] = &[];
+static mut TLS_SIZE: usize = 0;
+static mut TLS_ALIGN: usize = 1;
+
+#[repr(C)]
+struct program_header {
+    p_type: u32,
+    p_flags: u32,
+    p_offset: u64,
+    p_vaddr: u64,
+    p_paddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    p_align: u64,
+}
+
+// What the kernel fills in for statfs(), which statvfs is built from
+#[repr(C)]
+struct linux_statfs {
+    f_type: c_long,
+    f_bsize: c_long,
+    f_blocks: fsblkcnt_t,
+    f_bfree: fsblkcnt_t,
+    f_bavail: fsblkcnt_t,
+    f_files: fsfilcnt_t,
+    f_ffree: fsfilcnt_t,
+    f_fsid: [c_int; 2],
+    f_namelen: c_long,
+    f_frsize: c_long,
+    f_flags: c_long,
+    f_spare: [c_long; 4],
+}
+
+impl<'a> From<&'a linux_statfs> for statvfs {
+    fn from(buf: &linux_statfs) -> statvfs {
+        statvfs {
+            f_bsize: buf.f_bsize as c_ulong,
+            f_frsize: if buf.f_frsize != 0 {
+                buf.f_frsize as c_ulong
+            } else {
+                buf.f_bsize as c_ulong
+            },
+            f_blocks: buf.f_blocks,
+            f_bfree: buf.f_bfree,
+            f_bavail: buf.f_bavail,
+            f_files: buf.f_files,
+            f_ffree: buf.f_ffree,
+            f_favail: buf.f_ffree,
+            f_fsid: buf.f_fsid[0] as u32 as c_ulong | (buf.f_fsid[1] as u32 as c_ulong) << 32,
+            f_flag: buf.f_flags as c_ulong & !ST_VALID,
+            f_namemax: buf.f_namelen as c_ulong,
+        }
+    }
+}
 
 fn e(sys: usize) -> usize {
     if (sys as isize) < 0 && (sys as isize) >= -256 {
@@ -31,10 +121,45 @@ pub unsafe fn accept(socket: c_int, address: *mut sockaddr, address_len: *mut so
     e(syscall!(ACCEPT, socket, address, address_len)) as c_int
 }
 
+pub unsafe fn accept4(
+    socket: c_int,
+    address: *mut sockaddr,
+    address_len: *mut socklen_t,
+    flags: c_int,
+) -> c_int {
+    e(syscall!(ACCEPT4, socket, address, address_len, flags)) as c_int
+}
+
 pub fn access(path: *const c_char, mode: c_int) -> c_int {
     e(unsafe { syscall!(ACCESS, path, mode) }) as c_int
 }
 
+pub fn faccessat(dirfd: c_int, path: *const c_char, mode: c_int, flags: c_int) -> c_int {
+    if flags & !AT_EACCESS != 0 {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    // The kernel always checks against the real ids, which is only right for AT_EACCESS if they
+    // are the same as the effective ones
+    if flags & AT_EACCESS == 0 || (getuid() == geteuid() && getgid() == getegid()) {
+        return e(unsafe { syscall!(FACCESSAT, dirfd, path, mode) }) as c_int;
+    }
+
+    let mut buf: stat = unsafe { mem::zeroed() };
+    if e(unsafe { syscall!(NEWFSTATAT, dirfd, path, &mut buf as *mut stat, 0) }) == !0 {
+        return -1;
+    }
+    if mode != F_OK && !check_access(&buf, geteuid(), getegid(), mode) {
+        unsafe {
+            errno = EACCES;
+        }
+        return -1;
+    }
+    0
+}
+
 pub unsafe fn bind(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
     e(syscall!(BIND, socket, address, address_len)) as c_int
 }
@@ -55,6 +180,57 @@ pub fn chown(path: *const c_char, owner: uid_t, group: gid_t) -> c_int {
     e(unsafe { syscall!(FCHOWNAT, AT_FDCWD, path, owner as u32, group as u32) }) as c_int
 }
 
+/// Starts a thread that shares everything with this one, except for its stack and the TLS block
+/// `tls` from tls_new. `stack` has to point to the function the thread runs, followed by the
+/// argument to pass it, and be 16-byte aligned. The thread's ID is written to `tid` before this
+/// returns, and cleared once the thread is gone for good.
+#[inline(never)]
+pub unsafe fn clone_thread(stack: *mut usize, tls: *mut c_void, tid: *mut pid_t) -> pid_t {
+    let flags = CLONE_VM
+        | CLONE_FS
+        | CLONE_FILES
+        | CLONE_SIGHAND
+        | CLONE_THREAD
+        | CLONE_SYSVSEM
+        | CLONE_SETTLS
+        | CLONE_PARENT_SETTID
+        | CLONE_CHILD_CLEARTID;
+    let pid: usize;
+
+    #[cfg(target_arch = "x86_64")]
+    asm!("syscall
+        test rax, rax
+        jnz .Lclone_thread_parent
+        xor rbp, rbp
+        pop rax
+        pop rdi
+        call rax
+        ud2
+    .Lclone_thread_parent:"
+        : "={rax}"(pid)
+        : "{rax}"(::sc::nr::CLONE), "{rdi}"(flags), "{rsi}"(stack), "{rdx}"(tid), "{r10}"(tid),
+          "{r8}"(tls)
+        : "rcx", "r11", "memory"
+        : "intel", "volatile"
+    );
+    #[cfg(target_arch = "aarch64")]
+    asm!("svc 0
+        cbnz x0, .Lclone_thread_parent
+        mov x29, xzr
+        ldp x1, x0, [sp], #16
+        blr x1
+        brk #0
+    .Lclone_thread_parent:"
+        : "={x0}"(pid)
+        : "{x8}"(::sc::nr::CLONE), "{x0}"(flags), "{x1}"(stack), "{x2}"(tid), "{x3}"(tls),
+          "{x4}"(tid)
+        : "memory"
+        : "volatile"
+    );
+
+    e(pid) as pid_t
+}
+
 pub fn close(fildes: c_int) -> c_int {
     e(unsafe { syscall!(CLOSE, fildes) }) as c_int
 }
@@ -71,13 +247,25 @@ pub fn dup2(fildes: c_int, fildes2: c_int) -> c_int {
     e(unsafe { syscall!(DUP3, fildes, fildes2, 0) }) as c_int
 }
 
+pub fn dup3(fildes: c_int, fildes2: c_int, flags: c_int) -> c_int {
+    e(unsafe { syscall!(DUP3, fildes, fildes2, flags) }) as c_int
+}
+
 pub fn execve(path: *const c_char, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
     e(unsafe { syscall!(EXECVE, path, argv, envp) }) as c_int
 }
 
 pub fn exit(status: c_int) -> ! {
     unsafe {
-        syscall!(EXIT, status);
+        syscall!(EXIT_GROUP, status);
+    }
+    loop {}
+}
+
+/// Ends the calling thread, leaving the rest of the process running
+pub fn exit_thread() -> ! {
+    unsafe {
+        syscall!(EXIT, 0);
     }
     loop {}
 }
@@ -98,12 +286,23 @@ pub fn flock(fd: c_int, operation: c_int) -> c_int {
     e(unsafe { syscall!(FLOCK, fd, operation) }) as c_int
 }
 
+pub fn fpath(fildes: c_int, buf: &mut [u8]) -> ssize_t {
+    // The kernel keeps a link to every open file in /proc
+    let mut proc_path = [0u8; 32];
+    let _ = write!(
+        StringWriter(proc_path.as_mut_ptr(), proc_path.len()),
+        "/proc/self/fd/{}",
+        fildes
+    );
+    readlink(proc_path.as_ptr() as *const c_char, buf)
+}
+
 pub fn fstat(fildes: c_int, buf: *mut stat) -> c_int {
     let empty_cstr: *const c_char = unsafe { ::cstr_from_bytes_with_nul_unchecked(b"\0") };
     e(unsafe { syscall!(NEWFSTATAT, fildes, empty_cstr, buf, AT_EMPTY_PATH) }) as c_int
 }
 
-pub fn fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int {
+pub fn fcntl(fildes: c_int, cmd: c_int, arg: c_ulong) -> c_int {
     e(unsafe { syscall!(FCNTL, fildes, cmd, arg) }) as c_int
 }
 
@@ -111,6 +310,21 @@ pub fn fork() -> pid_t {
     e(unsafe { syscall!(CLONE, SIGCHLD, 0) }) as pid_t
 }
 
+pub fn fstatvfs(fildes: c_int, buf: *mut statvfs) -> c_int {
+    let mut kbuf: linux_statfs = unsafe { mem::zeroed() };
+    let res = e(unsafe { syscall!(FSTATFS, fildes, &mut kbuf as *mut linux_statfs) });
+    if res != !0 {
+        unsafe {
+            *buf = statvfs::from(&kbuf);
+        }
+    }
+    res as c_int
+}
+
+pub fn fstatat(dirfd: c_int, path: *const c_char, buf: *mut stat, flags: c_int) -> c_int {
+    e(unsafe { syscall!(NEWFSTATAT, dirfd, path, buf, flags) }) as c_int
+}
+
 pub fn fsync(fildes: c_int) -> c_int {
     e(unsafe { syscall!(FSYNC, fildes) }) as c_int
 }
@@ -123,10 +337,31 @@ pub fn futimens(fd: c_int, times: *const timespec) -> c_int {
     e(unsafe { syscall!(UTIMENSAT, fd, ptr::null::<c_char>(), times, 0) }) as c_int
 }
 
+/// Sleeps until futex_wake is called on `addr`, as long as it still holds `val`. A non-null
+/// `timeout` is how long to wait at most.
+pub fn futex_wait(addr: *mut c_int, val: c_int, timeout: *const timespec) -> c_int {
+    e(unsafe { syscall!(FUTEX, addr, FUTEX_WAIT, val, timeout) }) as c_int
+}
+
+/// Wakes up at most `count` threads waiting on `addr`, and returns how many there were
+pub fn futex_wake(addr: *mut c_int, count: c_int) -> c_int {
+    e(unsafe { syscall!(FUTEX, addr, FUTEX_WAKE, count) }) as c_int
+}
+
 pub fn utimens(path: *const c_char, times: *const timespec) -> c_int {
     e(unsafe { syscall!(UTIMENSAT, AT_FDCWD, path, times, 0) }) as c_int
 }
 
+pub fn get_nprocs() -> c_int {
+    // Every CPU this process may run on has a bit set in its affinity mask
+    let mut set = [0u64; 16];
+    let res = unsafe { syscall!(SCHED_GETAFFINITY, 0, mem::size_of_val(&set), set.as_mut_ptr()) };
+    if (res as isize) < 0 {
+        return 1;
+    }
+    set.iter().map(|word| word.count_ones() as c_int).sum()
+}
+
 pub fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
     if e(unsafe { syscall!(GETCWD, buf, size) }) == !0 {
         ptr::null_mut()
@@ -151,36 +386,25 @@ pub fn getgid() -> gid_t {
     e(unsafe { syscall!(GETGID) }) as gid_t
 }
 
-pub fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
-    e(unsafe { syscall!(GETRUSAGE, who, r_usage) }) as c_int
+pub fn getgroups(size: c_int, list: *mut gid_t) -> c_int {
+    e(unsafe { syscall!(GETGROUPS, size, list) }) as c_int
 }
 
-pub unsafe fn gethostname(mut name: *mut c_char, len: size_t) -> c_int {
-    // len only needs to be mutable on linux
-    let mut len = len;
-
-    let mut uts = mem::uninitialized();
-    let err = uname(&mut uts);
-    if err < 0 {
-        mem::forget(uts);
-        return err;
+pub fn getpriority(which: c_int, who: id_t) -> c_int {
+    // The kernel returns 20 - nice, so that it never looks like an error
+    let res = e(unsafe { syscall!(GETPRIORITY, which, who) });
+    if res == !0 {
+        return -1;
     }
-    for c in uts.nodename.iter() {
-        if len == 0 {
-            break;
-        }
-        len -= 1;
-
-        *name = *c;
+    20 - res as c_int
+}
 
-        if *name == 0 {
-            // We do want to copy the zero also, so we check this after the copying.
-            break;
-        }
+pub fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int {
+    e(unsafe { syscall!(GETRLIMIT, resource, rlim) }) as c_int
+}
 
-        name = name.offset(1);
-    }
-    0
+pub fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
+    e(unsafe { syscall!(GETRUSAGE, who, r_usage) }) as c_int
 }
 
 pub fn getitimer(which: c_int, out: *mut itimerval) -> c_int {
@@ -199,6 +423,10 @@ pub fn getpgid(pid: pid_t) -> pid_t {
     e(unsafe { syscall!(GETPGID, pid) }) as pid_t
 }
 
+pub fn getrandom(buf: &mut [u8]) -> ssize_t {
+    e(unsafe { syscall!(GETRANDOM, buf.as_mut_ptr(), buf.len(), 0) }) as ssize_t
+}
+
 pub fn getpid() -> pid_t {
     e(unsafe { syscall!(GETPID) }) as pid_t
 }
@@ -242,6 +470,59 @@ pub fn getuid() -> uid_t {
     e(unsafe { syscall!(GETUID) }) as uid_t
 }
 
+/// Finds the TLS image in the program headers that `auxv` points to, and sets up the calling
+/// thread's copy of it. This has to happen before anything touches a thread local, errno included.
+pub unsafe fn init_tls(auxv: *const usize) {
+    let mut phdr = 0;
+    let mut phent = 0;
+    let mut phnum = 0;
+    let mut aux = auxv;
+    while *aux != AT_NULL {
+        match *aux {
+            AT_PHDR => phdr = *aux.offset(1),
+            AT_PHENT => phent = *aux.offset(1),
+            AT_PHNUM => phnum = *aux.offset(1),
+            _ => (),
+        }
+        aux = aux.offset(2);
+    }
+
+    // Position independent executables are loaded somewhere else than their headers say
+    let mut base = 0;
+    let mut tls = None;
+    for i in 0..phnum {
+        let header = &*((phdr + i * phent) as *const program_header);
+        match header.p_type {
+            PT_PHDR => base = phdr - header.p_vaddr as usize,
+            PT_TLS => tls = Some(header),
+            _ => (),
+        }
+    }
+    if let Some(header) = tls {
+        TLS_IMAGE = slice::from_raw_parts(
+            (base + header.p_vaddr as usize) as *const u8,
+            header.p_filesz as usize,
+        );
+        TLS_SIZE = header.p_memsz as usize;
+        TLS_ALIGN = cmp::max(header.p_align as usize, 1);
+    }
+
+    match tls_new() {
+        Ok(tp) => set_thread_pointer(tp),
+        Err(()) => exit(1),
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn set_thread_pointer(tp: *mut c_void) {
+    syscall!(ARCH_PRCTL, ARCH_SET_FS, tp);
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn set_thread_pointer(tp: *mut c_void) {
+    asm!("msr tpidr_el0, $0" : : "r"(tp) : : "volatile");
+}
+
 pub fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) -> c_int {
     // TODO: Somehow support varargs to syscall??
     e(unsafe { syscall!(IOCTL, fd, request, out) }) as c_int
@@ -273,11 +554,15 @@ pub fn lseek(fildes: c_int, offset: off_t, whence: c_int) -> off_t {
 }
 
 pub fn lstat(file: *const c_char, buf: *mut stat) -> c_int {
-    e(unsafe { syscall!(NEWFSTATAT, AT_FDCWD, file, buf, AT_SYMLINK_NOFOLLOW) }) as c_int
+    fstatat(AT_FDCWD, file, buf, AT_SYMLINK_NOFOLLOW)
 }
 
 pub fn mkdir(path: *const c_char, mode: mode_t) -> c_int {
-    e(unsafe { syscall!(MKDIRAT, AT_FDCWD, path, mode) }) as c_int
+    mkdirat(AT_FDCWD, path, mode)
+}
+
+pub fn mkdirat(dirfd: c_int, path: *const c_char, mode: mode_t) -> c_int {
+    e(unsafe { syscall!(MKDIRAT, dirfd, path, mode) }) as c_int
 }
 
 pub fn mkfifo(path: *const c_char, mode: mode_t) -> c_int {
@@ -295,6 +580,10 @@ pub unsafe fn mmap(
     e(syscall!(MMAP, addr, len, prot, flags, fildes, off)) as *mut c_void
 }
 
+pub unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
+    e(syscall!(MPROTECT, addr, len, prot)) as c_int
+}
+
 pub unsafe fn munmap(addr: *mut c_void, len: usize) -> c_int {
     e(syscall!(MUNMAP, addr, len)) as c_int
 }
@@ -304,11 +593,23 @@ pub fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
 }
 
 pub fn open(path: *const c_char, oflag: c_int, mode: mode_t) -> c_int {
-    e(unsafe { syscall!(OPENAT, AT_FDCWD, path, oflag, mode) }) as c_int
+    openat(AT_FDCWD, path, oflag, mode)
+}
+
+pub fn openat(dirfd: c_int, path: *const c_char, oflag: c_int, mode: mode_t) -> c_int {
+    e(unsafe { syscall!(OPENAT, dirfd, path, oflag, mode) }) as c_int
 }
 
 pub fn pipe(fildes: &mut [c_int]) -> c_int {
-    e(unsafe { syscall!(PIPE2, fildes.as_mut_ptr(), 0) }) as c_int
+    pipe2(fildes, 0)
+}
+
+pub fn pipe2(fildes: &mut [c_int], flags: c_int) -> c_int {
+    e(unsafe { syscall!(PIPE2, fildes.as_mut_ptr(), flags) }) as c_int
+}
+
+pub fn poll(fds: *mut pollfd, nfds: nfds_t, timeout: c_int) -> c_int {
+    e(unsafe { syscall!(POLL, fds, nfds, timeout) }) as c_int
 }
 
 pub fn raise(sig: c_int) -> c_int {
@@ -322,10 +623,34 @@ pub fn raise(sig: c_int) -> c_int {
     ret
 }
 
+pub fn pread(fildes: c_int, buf: &mut [u8], offset: off_t) -> ssize_t {
+    e(unsafe { syscall!(PREAD64, fildes, buf.as_mut_ptr(), buf.len(), offset) }) as ssize_t
+}
+
+pub fn pwrite(fildes: c_int, buf: &[u8], offset: off_t) -> ssize_t {
+    e(unsafe { syscall!(PWRITE64, fildes, buf.as_ptr(), buf.len(), offset) }) as ssize_t
+}
+
 pub fn read(fildes: c_int, buf: &mut [u8]) -> ssize_t {
     e(unsafe { syscall!(READ, fildes, buf.as_mut_ptr(), buf.len()) }) as ssize_t
 }
 
+pub fn readlink(path: *const c_char, buf: &mut [u8]) -> ssize_t {
+    readlinkat(AT_FDCWD, path, buf)
+}
+
+pub fn readlinkat(dirfd: c_int, path: *const c_char, buf: &mut [u8]) -> ssize_t {
+    e(unsafe { syscall!(READLINKAT, dirfd, path, buf.as_mut_ptr(), buf.len()) }) as ssize_t
+}
+
+pub fn readv(fildes: c_int, iov: &[iovec]) -> ssize_t {
+    e(unsafe { syscall!(READV, fildes, iov.as_ptr(), iov.len()) }) as ssize_t
+}
+
+pub unsafe fn recv(socket: c_int, buf: *mut c_void, len: size_t, flags: c_int) -> ssize_t {
+    recvfrom(socket, buf, len, flags, ptr::null_mut(), ptr::null_mut())
+}
+
 pub unsafe fn recvfrom(
     socket: c_int,
     buf: *mut c_void,
@@ -346,21 +671,19 @@ pub unsafe fn recvfrom(
 }
 
 pub fn rename(old: *const c_char, new: *const c_char) -> c_int {
-    e(unsafe { syscall!(RENAMEAT, AT_FDCWD, old, AT_FDCWD, new) }) as c_int
+    renameat(AT_FDCWD, old, AT_FDCWD, new)
+}
+
+pub fn renameat(olddirfd: c_int, old: *const c_char, newdirfd: c_int, new: *const c_char) -> c_int {
+    e(unsafe { syscall!(RENAMEAT, olddirfd, old, newdirfd, new) }) as c_int
 }
 
 pub fn rmdir(path: *const c_char) -> c_int {
-    e(unsafe { syscall!(UNLINKAT, AT_FDCWD, path, AT_REMOVEDIR) }) as c_int
+    unlinkat(AT_FDCWD, path, AT_REMOVEDIR)
 }
 
-pub fn select(
-    nfds: c_int,
-    readfds: *mut fd_set,
-    writefds: *mut fd_set,
-    exceptfds: *mut fd_set,
-    timeout: *mut timeval,
-) -> c_int {
-    e(unsafe { syscall!(SELECT, nfds, readfds, writefds, exceptfds, timeout) }) as c_int
+pub unsafe fn send(socket: c_int, buf: *const c_void, len: size_t, flags: c_int) -> ssize_t {
+    sendto(socket, buf, len, flags, ptr::null(), 0)
 }
 
 pub unsafe fn sendto(
@@ -380,6 +703,34 @@ pub fn setitimer(which: c_int, new: *const itimerval, old: *mut itimerval) -> c_
     e(unsafe { syscall!(SETITIMER, which, new, old) }) as c_int
 }
 
+pub fn setpriority(which: c_int, who: id_t, prio: c_int) -> c_int {
+    e(unsafe { syscall!(SETPRIORITY, which, who, prio) }) as c_int
+}
+
+pub fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+    e(unsafe { syscall!(SETRLIMIT, resource, rlim) }) as c_int
+}
+
+pub fn setegid(egid: gid_t) -> c_int {
+    e(unsafe { syscall!(SETRESGID, -1i32 as gid_t, egid, -1i32 as gid_t) }) as c_int
+}
+
+pub fn seteuid(euid: uid_t) -> c_int {
+    e(unsafe { syscall!(SETRESUID, -1i32 as uid_t, euid, -1i32 as uid_t) }) as c_int
+}
+
+pub fn setgid(gid: gid_t) -> c_int {
+    e(unsafe { syscall!(SETGID, gid) }) as c_int
+}
+
+pub fn setgroups(size: size_t, list: *const gid_t) -> c_int {
+    e(unsafe { syscall!(SETGROUPS, size, list) }) as c_int
+}
+
+pub fn sethostname(name: *const c_char, len: size_t) -> c_int {
+    e(unsafe { syscall!(SETHOSTNAME, name, len) }) as c_int
+}
+
 pub fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
     e(unsafe { syscall!(SETPGID, pid, pgid) }) as c_int
 }
@@ -392,6 +743,10 @@ pub fn setreuid(ruid: uid_t, euid: uid_t) -> c_int {
     e(unsafe { syscall!(SETREUID, ruid, euid) }) as c_int
 }
 
+pub fn setuid(uid: uid_t) -> c_int {
+    e(unsafe { syscall!(SETUID, uid) }) as c_int
+}
+
 pub fn setsockopt(
     socket: c_int,
     level: c_int,
@@ -429,10 +784,25 @@ pub fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_i
     e(unsafe { syscall!(RT_SIGPROCMASK, how, set, oset, mem::size_of::<sigset_t>()) }) as c_int
 }
 
+pub fn sigsuspend(mask: *const sigset_t) -> c_int {
+    e(unsafe { syscall!(RT_SIGSUSPEND, mask, mem::size_of::<sigset_t>()) }) as c_int
+}
+
 pub fn stat(file: *const c_char, buf: *mut stat) -> c_int {
     e(unsafe { syscall!(NEWFSTATAT, AT_FDCWD, file, buf, 0) }) as c_int
 }
 
+pub fn statvfs(file: *const c_char, buf: *mut statvfs) -> c_int {
+    let mut kbuf: linux_statfs = unsafe { mem::zeroed() };
+    let res = e(unsafe { syscall!(STATFS, file, &mut kbuf as *mut linux_statfs) });
+    if res != !0 {
+        unsafe {
+            *buf = statvfs::from(&kbuf);
+        }
+    }
+    res as c_int
+}
+
 pub fn socket(domain: c_int, kind: c_int, protocol: c_int) -> c_int {
     e(unsafe { syscall!(SOCKET, domain, kind, protocol) }) as c_int
 }
@@ -441,12 +811,20 @@ pub fn socketpair(domain: c_int, kind: c_int, protocol: c_int, socket_vector: *m
     e(unsafe { syscall!(SOCKETPAIR, domain, kind, protocol, socket_vector) }) as c_int
 }
 
+pub fn symlink(target: *const c_char, linkpath: *const c_char) -> c_int {
+    symlinkat(target, AT_FDCWD, linkpath)
+}
+
+pub fn symlinkat(target: *const c_char, dirfd: c_int, linkpath: *const c_char) -> c_int {
+    e(unsafe { syscall!(SYMLINKAT, target, dirfd, linkpath) }) as c_int
+}
+
 pub fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
     ioctl(fd, TCGETS, out as *mut c_void)
 }
 
 pub fn tcsetattr(fd: c_int, act: c_int, value: *const termios) -> c_int {
-    if act < 0 || act > 2 {
+    if act < TCSANOW || act > TCSAFLUSH {
         unsafe {
             errno = EINVAL;
         }
@@ -456,10 +834,51 @@ pub fn tcsetattr(fd: c_int, act: c_int, value: *const termios) -> c_int {
     ioctl(fd, TCSETS + act as c_ulong, value as *mut c_void)
 }
 
+/// Where the TLS block and the thread pointer go in an allocation, and how big it is
+#[cfg(target_arch = "x86_64")]
+unsafe fn tls_layout() -> (usize, usize, usize) {
+    let tp = (TLS_SIZE + TLS_ALIGN - 1) & !(TLS_ALIGN - 1);
+    (0, tp, tp + TCB_SIZE)
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn tls_layout() -> (usize, usize, usize) {
+    let data = (TCB_SIZE + TLS_ALIGN - 1) & !(TLS_ALIGN - 1);
+    (data, 0, data + TLS_SIZE)
+}
+
+/// Makes a fresh copy of the TLS image for a new thread, and returns the thread pointer to give
+/// it. Doesn't touch errno, as the calling thread might not have a TLS block yet.
+pub unsafe fn tls_new() -> Result<*mut c_void, ()> {
+    let (data, tp, size) = tls_layout();
+    let block = alloc_align(size, cmp::max(TLS_ALIGN, 16)) as *mut u8;
+    if block.is_null() {
+        return Err(());
+    }
+
+    ptr::write_bytes(block, 0, size);
+    ptr::copy_nonoverlapping(TLS_IMAGE.as_ptr(), block.add(data), TLS_IMAGE.len());
+    let tp = block.add(tp);
+    if cfg!(target_arch = "x86_64") {
+        *(tp as *mut *mut u8) = tp;
+    }
+    Ok(tp as *mut c_void)
+}
+
+/// Frees what tls_new returned, once the thread using it is gone
+pub unsafe fn tls_free(tp: *mut c_void) {
+    let (_, offset, _) = tls_layout();
+    free((tp as *mut u8).sub(offset) as *mut c_void);
+}
+
 pub fn times(out: *mut tms) -> clock_t {
     unsafe { syscall!(TIMES, out) as clock_t }
 }
 
+pub fn truncate(path: *const c_char, length: off_t) -> c_int {
+    e(unsafe { syscall!(TRUNCATE, path, length) }) as c_int
+}
+
 pub fn umask(mask: mode_t) -> mode_t {
     unsafe { syscall!(UMASK, mask) as mode_t }
 }
@@ -469,7 +888,26 @@ pub fn uname(utsname: *mut utsname) -> c_int {
 }
 
 pub fn unlink(path: *const c_char) -> c_int {
-    e(unsafe { syscall!(UNLINKAT, AT_FDCWD, path, 0) }) as c_int
+    unlinkat(AT_FDCWD, path, 0)
+}
+
+pub fn unlinkat(dirfd: c_int, path: *const c_char, flags: c_int) -> c_int {
+    e(unsafe { syscall!(UNLINKAT, dirfd, path, flags) }) as c_int
+}
+
+/// Whether a thread started by clone_thread is gone for good, waiting for it to be if `block` is
+/// set. The kernel clears `tid` and wakes up anyone waiting on it at that point.
+pub fn wait_thread(tid: *mut pid_t, block: bool) -> bool {
+    loop {
+        let current = unsafe { ptr::read_volatile(tid) };
+        if current == 0 {
+            return true;
+        }
+        if !block {
+            return false;
+        }
+        futex_wait(tid, current, ptr::null());
+    }
 }
 
 pub fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
@@ -480,6 +918,10 @@ pub fn write(fildes: c_int, buf: &[u8]) -> ssize_t {
     e(unsafe { syscall!(WRITE, fildes, buf.as_ptr(), buf.len()) }) as ssize_t
 }
 
+pub fn writev(fildes: c_int, iov: &[iovec]) -> ssize_t {
+    e(unsafe { syscall!(WRITEV, fildes, iov.as_ptr(), iov.len()) }) as ssize_t
+}
+
 pub fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int {
     e(unsafe { syscall!(CLOCK_GETTIME, clk_id, tp) }) as c_int
 }
@@ -1,4 +1,6 @@
-use super::{close, dup, open, types::*};
+use super::{close, dup, open, read, types::*};
+use alloc::vec::Vec;
+use core::mem;
 use core::ops::Deref;
 
 pub struct RawFile(c_int);
@@ -44,3 +46,60 @@ impl Deref for RawFile {
         &self.0
     }
 }
+
+/// A file read one line at a time, like the databases in /etc
+pub struct RawLines {
+    file: RawFile,
+    buf: Vec<u8>,
+}
+
+impl RawLines {
+    pub fn new(file: RawFile) -> Self {
+        RawLines {
+            file: file,
+            buf: Vec::new(),
+        }
+    }
+
+    /// The next line without its newline, or None at the end of the file
+    pub fn next_line(&mut self) -> Result<Option<Vec<u8>>, ()> {
+        loop {
+            if let Some(newline) = self.buf.iter().position(|b| *b == b'\n') {
+                let mut line: Vec<u8> = self.buf.drain(..newline + 1).collect();
+                line.pop();
+                return Ok(Some(line));
+            }
+
+            let len = self.buf.len();
+
+            if len >= self.buf.capacity() {
+                self.buf.reserve(1024);
+            }
+
+            unsafe {
+                let capacity = self.buf.capacity();
+                self.buf.set_len(capacity);
+            }
+
+            let read = read(*self.file, &mut self.buf[len..]);
+
+            if read < 0 {
+                unsafe {
+                    self.buf.set_len(len);
+                }
+                return Err(());
+            }
+            unsafe {
+                self.buf.set_len(len + read as usize);
+            }
+
+            if read == 0 {
+                // The last line doesn't have to end with a newline
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+                return Ok(Some(mem::replace(&mut self.buf, Vec::new())));
+            }
+        }
+    }
+}
@@ -1,10 +1,14 @@
 //! sys/socket implementation, following http://pubs.opengroup.org/onlinepubs/009696699/basedefs/sys/socket.h.html
 
 use alloc::btree_map::BTreeMap;
+use alloc::btree_set::BTreeSet;
+use alloc::Vec;
 use core::fmt::Write;
-use core::{mem, ptr, slice};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{cmp, mem, ptr, slice, str};
 use spin::{Once, Mutex, MutexGuard};
 use syscall::data::Stat as redox_stat;
+use syscall::data::StatVfs as redox_statvfs;
 use syscall::data::TimeSpec as redox_timespec;
 use syscall::flag::*;
 use syscall::{self, Result};
@@ -13,10 +17,72 @@ use types::*;
 use *;
 
 const EINVAL: c_int = 22;
-const MAP_ANON: c_int = 1;
-
-#[thread_local]
-static mut SIG_HANDLER: Option<extern "C" fn(c_int)> = None;
+const MAP_ANON: c_int = 0x20;
+const MAP_FIXED: c_int = 0x10;
+const PROT_READ: c_int = 0x1;
+const PROT_WRITE: c_int = 0x2;
+const PROT_EXEC: c_int = 0x4;
+const SA_SIGINFO: c_ulong = 4;
+const SIG_DFL: usize = 0;
+const SIG_IGN: usize = 1;
+
+// The id time.h gives the CPU time of the process
+const CLOCK_PROCESS_CPUTIME_ID: clockid_t = 2;
+
+// Has to match sys/time.h
+const ITIMER_REAL: c_int = 0;
+
+// The clock ticks times() counts in, which has to match _SC_CLK_TCK
+const CLK_TCK: clock_t = 100;
+
+// The kernel limits every context to this many files
+const OPEN_MAX: usize = 65536;
+
+// The main thread's stack is always this big
+const STACK_SIZE: rlim_t = 1024 * 1024;
+
+// The kernel has no resource limits, so they're only kept track of here. Only the ones that
+// describe what the kernel does anyway are anything other than unlimited.
+static mut RLIMITS: [rlimit; RLIM_NLIMITS as usize] = [
+    // RLIMIT_CPU
+    rlimit { rlim_cur: RLIM_INFINITY, rlim_max: RLIM_INFINITY },
+    // RLIMIT_FSIZE
+    rlimit { rlim_cur: RLIM_INFINITY, rlim_max: RLIM_INFINITY },
+    // RLIMIT_DATA
+    rlimit { rlim_cur: RLIM_INFINITY, rlim_max: RLIM_INFINITY },
+    // RLIMIT_STACK
+    rlimit { rlim_cur: STACK_SIZE, rlim_max: STACK_SIZE },
+    // RLIMIT_CORE, as nothing ever dumps core
+    rlimit { rlim_cur: 0, rlim_max: RLIM_INFINITY },
+    // RLIMIT_RSS
+    rlimit { rlim_cur: RLIM_INFINITY, rlim_max: RLIM_INFINITY },
+    // RLIMIT_NPROC
+    rlimit { rlim_cur: RLIM_INFINITY, rlim_max: RLIM_INFINITY },
+    // RLIMIT_NOFILE
+    rlimit { rlim_cur: OPEN_MAX as rlim_t, rlim_max: OPEN_MAX as rlim_t },
+    // RLIMIT_MEMLOCK
+    rlimit { rlim_cur: RLIM_INFINITY, rlim_max: RLIM_INFINITY },
+    // RLIMIT_AS
+    rlimit { rlim_cur: RLIM_INFINITY, rlim_max: RLIM_INFINITY },
+];
+
+// The kernel has no supplementary groups, so they're only kept track of here
+static mut GROUPS: Vec<gid_t> = Vec::new();
+const NGROUPS_MAX: usize = 65536;
+
+// The scheduler has no priorities, so the nice value is only kept track of here
+static mut NICE: c_int = 0;
+
+// The kernel only passes the signal number to its handlers, so sig_handler looks up what the
+// program actually installed and calls it the way sigaction() was asked to
+static mut SIG_HANDLERS: [Option<extern "C" fn(c_int)>; 64] = [None; 64];
+static mut SIG_FLAGS: [c_ulong; 64] = [0; 64];
+
+// How many signals sig_handler has been called for, so sigsuspend can tell when to stop waiting
+static SIGNALS_CAUGHT: AtomicUsize = AtomicUsize::new(0);
+
+// The kernel has no notion of a file creation mask, so it's applied here before every create
+static mut UMASK: mode_t = 0o022;
 
 static ANONYMOUS_MAPS: Once<Mutex<BTreeMap<usize, usize>>> = Once::new();
 
@@ -24,10 +90,171 @@ fn anonymous_maps() -> MutexGuard<'static, BTreeMap<usize, usize>> {
     ANONYMOUS_MAPS.call_once(|| Mutex::new(BTreeMap::new())).lock()
 }
 
+// The contexts clone_thread started, which are children as far as the kernel is concerned, but
+// aren't for waitpid to hand out
+static THREAD_PIDS: Once<Mutex<BTreeSet<usize>>> = Once::new();
+
+fn thread_pids() -> MutexGuard<'static, BTreeSet<usize>> {
+    THREAD_PIDS.call_once(|| Mutex::new(BTreeSet::new())).lock()
+}
+
+// Descriptors that have been made close-on-exec at some point. The flag itself lives in the kernel,
+// so execve checks these again instead of asking about every descriptor there could be.
+static CLOEXEC_FDS: Once<Mutex<BTreeSet<usize>>> = Once::new();
+
+fn cloexec_fds() -> MutexGuard<'static, BTreeSet<usize>> {
+    CLOEXEC_FDS.call_once(|| Mutex::new(BTreeSet::new())).lock()
+}
+
+/// Remembers `fd` for execve if it was opened close-on-exec
+fn track_cloexec(fd: usize, flags: usize) {
+    if fd != !0 && flags & O_CLOEXEC == O_CLOEXEC {
+        cloexec_fds().insert(fd);
+    }
+}
+
+// Where exit_thread has to say that the calling thread is gone, like CLONE_CHILD_CLEARTID on Linux
+#[thread_local]
+static mut CLEAR_TID: *mut pid_t = ptr::null_mut();
+
+// Socket state that the network schemes don't keep track of for us
+#[derive(Default)]
+struct SocketOptions {
+    error: c_int,
+    reuseaddr: bool,
+}
+
+static SOCKET_OPTIONS: Once<Mutex<BTreeMap<c_int, SocketOptions>>> = Once::new();
+
+fn socket_options() -> MutexGuard<'static, BTreeMap<c_int, SocketOptions>> {
+    SOCKET_OPTIONS.call_once(|| Mutex::new(BTreeMap::new())).lock()
+}
+
 extern "C" fn sig_handler(sig: usize) {
-    if let Some(ref callback) = unsafe { SIG_HANDLER } {
-        callback(sig as c_int);
+    SIGNALS_CAUGHT.fetch_add(1, Ordering::SeqCst);
+    let (handler, flags) = unsafe { (SIG_HANDLERS[sig], SIG_FLAGS[sig]) };
+    if let Some(handler) = handler {
+        if flags & SA_SIGINFO == SA_SIGINFO {
+            let handler: extern "C" fn(c_int, *mut siginfo_t, *mut c_void) =
+                unsafe { mem::transmute(handler) };
+            let mut info = siginfo_t::default();
+            info.si_signo = sig as c_int;
+            handler(sig as c_int, &mut info, ptr::null_mut());
+        } else {
+            handler(sig as c_int);
+        }
+    }
+}
+
+/// Resolves `path` relative to the directory `dirfd` instead of the current one, unless it's
+/// absolute
+fn at_path(dirfd: c_int, path: *const c_char) -> Result<Vec<u8>> {
+    let path = unsafe { c_str(path) };
+    let absolute = path.first() == Some(&b'/')
+        || path.iter().take_while(|&&c| c != b'/').any(|&c| c == b':');
+    if dirfd == AT_FDCWD || absolute {
+        return Ok(path.to_vec());
+    }
+
+    let mut buf = [0; 4096];
+    let len = syscall::fpath(dirfd as usize, &mut buf)?;
+    let mut full = buf[..len].to_vec();
+    if full.last() != Some(&b'/') {
+        full.push(b'/');
+    }
+    full.extend_from_slice(path);
+    Ok(full)
+}
+
+fn open_at(dirfd: c_int, path: *const c_char, flags: usize) -> Result<usize> {
+    syscall::open(&at_path(dirfd, path)?, flags)
+}
+
+/// Runs `f` with `fd` positioned at `offset`, always putting the old position back afterwards.
+/// There is no positioned I/O in the kernel, so unlike on Linux this isn't atomic.
+fn at_offset<F>(fd: c_int, offset: off_t, f: F) -> Result<usize>
+where
+    F: FnOnce() -> Result<usize>,
+{
+    let old = syscall::lseek(fd as usize, 0, SEEK_CUR)?;
+    syscall::lseek(fd as usize, offset as isize, SEEK_SET)?;
+    let res = f();
+    let _ = syscall::lseek(fd as usize, old as isize, SEEK_SET);
+    res
+}
+
+/// Transfers each of `iov` in turn, as there is no vectored I/O in the kernel. Like a real readv()
+/// or writev(), this stops at the first short transfer and only fails if nothing was transferred.
+fn vectored<F>(iov: &[iovec], mut f: F) -> Result<usize>
+where
+    F: FnMut(&iovec) -> Result<usize>,
+{
+    let mut total = 0;
+    for iov in iov {
+        match f(iov) {
+            Ok(count) => {
+                total += count;
+                if count < iov.iov_len {
+                    break;
+                }
+            }
+            Err(_) if total > 0 => break,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(total)
+}
+
+/// Whether `which` and `who` name the calling process, which is the only one whose nice value is
+/// known. Sets errno if they don't.
+fn is_priority_self(which: c_int, who: id_t) -> bool {
+    let own = match which {
+        PRIO_PROCESS => getpid() as id_t,
+        PRIO_PGRP => getpgid(0) as id_t,
+        PRIO_USER => getuid() as id_t,
+        _ => {
+            unsafe {
+                errno = EINVAL;
+            }
+            return false;
+        }
+    };
+    if who != 0 && who != own {
+        unsafe {
+            errno = syscall::ESRCH;
+        }
+        return false;
     }
+    true
+}
+
+/// Opens the settings of the terminal `fd`, failing with ENOTTY if it isn't one
+fn tty_fd(fd: c_int, name: &[u8]) -> Result<usize> {
+    // Only terminals can be duplicated into their termios settings or window size
+    syscall::dup(fd as usize, name).map_err(|err| {
+        if err.errno == syscall::EBADF {
+            err
+        } else {
+            syscall::Error::new(syscall::ENOTTY)
+        }
+    })
+}
+
+fn termios_fd(fd: c_int) -> Result<usize> {
+    tty_fd(fd, b"termios")
+}
+
+/// Reads as much of the small file at `path` as fits in `buf`
+fn read_file(path: &str, buf: &mut [u8]) -> Result<usize> {
+    let fd = syscall::open(path, O_RDONLY | O_CLOEXEC)?;
+    let res = syscall::read(fd, buf);
+    let _ = syscall::close(fd);
+    res
+}
+
+/// The permission bits a new file created with `mode` should get
+fn creation_mode(mode: mode_t) -> usize {
+    (mode & 0o777 & !unsafe { UMASK }) as usize
 }
 
 fn e(sys: Result<usize>) -> usize {
@@ -50,18 +277,32 @@ macro_rules! bind_or_connect {
         $path
     };
     ($mode:ident $socket:expr, $address:expr, $address_len:expr) => {{
-        if (*$address).sa_family as c_int != AF_INET {
-            errno = syscall::EAFNOSUPPORT;
-            return -1;
-        }
-        if ($address_len as usize) < mem::size_of::<sockaddr>() {
-            errno = syscall::EINVAL;
-            return -1;
-        }
-        let data = &*($address as *const sockaddr_in);
-        let addr = &data.sin_addr.s_addr;
-        let port = in_port_t::from_be(data.sin_port); // This is transmuted from bytes in BigEndian order
-        let path = format!(bind_or_connect!($mode "{}.{}.{}.{}:{}"), addr[0], addr[1], addr[2], addr[3], port);
+        let path = match (*$address).sa_family as c_int {
+            AF_INET => {
+                if ($address_len as usize) < mem::size_of::<sockaddr>() {
+                    errno = syscall::EINVAL;
+                    return -1;
+                }
+                let data = &*($address as *const sockaddr_in);
+                let addr = &data.sin_addr.s_addr;
+                let port = in_port_t::from_be(data.sin_port); // This is transmuted from bytes in BigEndian order
+                format!(bind_or_connect!($mode "{}.{}.{}.{}:{}"), addr[0], addr[1], addr[2], addr[3], port)
+            }
+            AF_INET6 => {
+                if ($address_len as usize) < mem::size_of::<sockaddr_in6>() {
+                    errno = syscall::EINVAL;
+                    return -1;
+                }
+                let data = &*($address as *const sockaddr_in6);
+                let addr = inet::Ipv6Display(&data.sin6_addr.s6_addr);
+                let port = in_port_t::from_be(data.sin6_port);
+                format!(bind_or_connect!($mode "[{}]:{}"), addr, port)
+            }
+            _ => {
+                errno = syscall::EAFNOSUPPORT;
+                return -1;
+            }
+        };
 
         // Duplicate the socket, and then duplicate the copy back to the original fd
         let fd = e(syscall::dup($socket as usize, path.as_bytes()));
@@ -78,10 +319,44 @@ macro_rules! bind_or_connect {
 }
 
 pub unsafe fn accept(socket: c_int, address: *mut sockaddr, address_len: *mut socklen_t) -> c_int {
+    accept4(socket, address, address_len, 0)
+}
+
+pub unsafe fn accept4(
+    socket: c_int,
+    address: *mut sockaddr,
+    address_len: *mut socklen_t,
+    flags: c_int,
+) -> c_int {
+    if flags & !(SOCK_NONBLOCK | SOCK_CLOEXEC) != 0 {
+        errno = syscall::EINVAL;
+        return -1;
+    }
+
+    // Whether the socket is listening is for the scheme to say, as it may have been set up through
+    // another fd or by another process
     let stream = e(syscall::dup(socket as usize, b"listen")) as c_int;
     if stream < 0 {
         return -1;
     }
+    socket_options().insert(stream, SocketOptions::default());
+
+    // The new stream doesn't take these from the listening socket, so they're set afterwards
+    let mut res = Ok(0);
+    if flags & SOCK_NONBLOCK == SOCK_NONBLOCK {
+        res = syscall::fcntl(stream as usize, F_GETFL, 0)
+            .and_then(|status| syscall::fcntl(stream as usize, F_SETFL, status | O_NONBLOCK));
+    }
+    if res.is_ok() && flags & SOCK_CLOEXEC == SOCK_CLOEXEC {
+        res = syscall::fcntl(stream as usize, F_SETFD, O_CLOEXEC);
+        if res.is_ok() {
+            track_cloexec(stream as usize, O_CLOEXEC);
+        }
+    }
+    if let Err(err) = res {
+        close(stream);
+        return e(Err(err)) as c_int;
+    }
     if address != ptr::null_mut()
         && address_len != ptr::null_mut()
         && getpeername(stream, address, address_len) < 0
@@ -92,48 +367,7 @@ pub unsafe fn accept(socket: c_int, address: *mut sockaddr, address_len: *mut so
 }
 
 pub fn access(path: *const c_char, mode: c_int) -> c_int {
-    let fd = match RawFile::open(path, 0, 0) {
-        Ok(fd) => fd,
-        Err(_) => return -1
-    };
-    if mode == F_OK {
-        return 0;
-    }
-
-    let mut stat = syscall::Stat::default();
-
-    if e(syscall::fstat(*fd as usize, &mut stat)) == !0 {
-        return -1;
-    }
-
-    let uid = e(syscall::getuid());
-    if uid == !0 {
-        return -1;
-    }
-    let gid = e(syscall::getgid());
-    if gid == !0 {
-        return -1;
-    }
-
-    let perms = if stat.st_uid as usize == uid {
-        // octal has max 7 characters, binary has max two. And we're interested
-        // in the 3rd digit
-        stat.st_mode >> ((7 / 2) * 2 & 0o7)
-    } else if stat.st_gid as usize == gid {
-        stat.st_mode >> ((7 / 2) & 0o7)
-    } else {
-        stat.st_mode & 0o7
-    };
-    if (mode & R_OK == R_OK && perms & 0o4 != 0o4)
-            || (mode & W_OK == W_OK && perms & 0o2 != 0o2)
-            || (mode & X_OK == X_OK && perms & 0o1 != 0o1) {
-        unsafe {
-            errno = EINVAL;
-        }
-        return -1;
-    }
-
-    0
+    faccessat(AT_FDCWD, path, mode, 0)
 }
 
 pub unsafe fn bind(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
@@ -173,20 +407,100 @@ pub fn chown(path: *const c_char, owner: uid_t, group: gid_t) -> c_int {
     }
 }
 
+/// Starts a thread that shares everything with this one but its stack, which has to point to the
+/// function the thread runs, followed by the argument to pass it, and be 16-byte aligned. The
+/// kernel gives every context its own copy of the TLS image, so `tls` isn't used. The thread writes
+/// its ID to `tid` when it starts, and clears it in exit_thread, so `tid` has to hold something
+/// other than 0 until then.
+#[inline(never)]
+pub unsafe fn clone_thread(stack: *mut usize, _tls: *mut c_void, tid: *mut pid_t) -> pid_t {
+    // Held until the pid is in there, so that a waitpid can't take the thread for a child first
+    let mut pids = thread_pids();
+    reap_threads(&mut pids);
+
+    let pid = e(syscall::clone(CLONE_VM | CLONE_FS | CLONE_FILES | CLONE_SIGHAND));
+    if pid == 0 {
+        // Only the thread writes this, so that it can't overwrite the 0 from exit_thread
+        CLEAR_TID = tid;
+        ptr::write_volatile(tid, syscall::getpid().unwrap_or(!0) as pid_t);
+
+        // The new thread starts out on a copy of this stack, so it moves over to its own
+        asm!("mov rsp, rdi
+            xor rbp, rbp
+            pop rax
+            pop rdi
+            call rax
+            ud2"
+            :
+            : "{rdi}"(stack)
+            :
+            : "intel", "volatile"
+        );
+    }
+    if pid != !0 {
+        pids.insert(pid);
+    }
+    pid as pid_t
+}
+
 pub fn close(fd: c_int) -> c_int {
+    // Whatever gets this fd number next shouldn't inherit the options
+    socket_options().remove(&fd);
     e(syscall::close(fd as usize)) as c_int
 }
 
-pub unsafe fn connect(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
+unsafe fn inner_connect(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
     bind_or_connect!(connect socket, address, address_len)
 }
 
+pub unsafe fn connect(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
+    let ret = inner_connect(socket, address, address_len);
+    if ret < 0 {
+        // A non-blocking connect reports its failure through SO_ERROR. One that's still in
+        // progress hasn't failed, and nothing tells us later whether it does.
+        let flags = syscall::fcntl(socket as usize, F_GETFL, 0).unwrap_or(0);
+        if flags & O_NONBLOCK == O_NONBLOCK {
+            if let Some(options) = socket_options().get_mut(&socket) {
+                options.error = if errno == syscall::EINPROGRESS { 0 } else { errno };
+            }
+        }
+    }
+    ret
+}
+
 pub fn dup(fd: c_int) -> c_int {
     e(syscall::dup(fd as usize, &[])) as c_int
 }
 
 pub fn dup2(fd1: c_int, fd2: c_int) -> c_int {
-    e(syscall::dup2(fd1 as usize, fd2 as usize, &[])) as c_int
+    if fd1 != fd2 {
+        socket_options().remove(&fd2);
+    }
+    let fd = e(syscall::dup2(fd1 as usize, fd2 as usize, &[]));
+    if fd != !0 && fd1 != fd2 {
+        // Whatever fd2 was is closed, and the new one isn't close-on-exec
+        cloexec_fds().remove(&fd);
+    }
+    fd as c_int
+}
+
+pub fn dup3(fd1: c_int, fd2: c_int, flags: c_int) -> c_int {
+    if fd1 == fd2 || flags & !(O_CLOEXEC as c_int) != 0 {
+        unsafe {
+            errno = syscall::EINVAL;
+        }
+        return -1;
+    }
+
+    let fd = dup2(fd1, fd2);
+    if fd >= 0 && flags & O_CLOEXEC as c_int == O_CLOEXEC as c_int {
+        if let Err(err) = syscall::fcntl(fd as usize, F_SETFD, O_CLOEXEC) {
+            close(fd);
+            return e(Err(err)) as c_int;
+        }
+        track_cloexec(fd as usize, O_CLOEXEC);
+    }
+    fd
 }
 
 pub fn exit(status: c_int) -> ! {
@@ -194,12 +508,41 @@ pub fn exit(status: c_int) -> ! {
     loop {}
 }
 
+/// Ends the calling thread, leaving the rest of the process running
+pub fn exit_thread() -> ! {
+    unsafe {
+        let tid = CLEAR_TID;
+        if !tid.is_null() {
+            // As soon as the tid is cleared, the stack can be unmapped under this thread, so waking
+            // up whoever waits for it and exiting can't touch it
+            asm!("mov dword ptr [rbx], 0
+                mov rax, r12
+                mov rcx, r13
+                mov rdx, 0x7fffffff
+                xor rsi, rsi
+                xor rdi, rdi
+                int 0x80
+                mov rax, r14
+                xor rbx, rbx
+                int 0x80
+                ud2"
+                :
+                : "{rbx}"(tid), "{r12}"(syscall::number::SYS_FUTEX), "{r13}"(FUTEX_WAKE),
+                  "{r14}"(syscall::number::SYS_EXIT)
+                :
+                : "intel", "volatile"
+            );
+        }
+    }
+    let _ = syscall::exit(0);
+    loop {}
+}
+
 pub unsafe extern "C" fn execve(
     path: *const c_char,
     mut argv: *const *mut c_char,
     mut envp: *const *mut c_char,
 ) -> c_int {
-    use alloc::Vec;
 
     let fd = match RawFile::open(path, O_RDONLY as c_int, 0) {
         Ok(fd) => fd,
@@ -240,9 +583,71 @@ pub unsafe extern "C" fn execve(
         envp = envp.offset(1);
     }
 
+    // Closing the close-on-exec descriptors can't be undone, so whatever would make fexec fail
+    // gets found out first
+    let mut st: stat = mem::zeroed();
+    if fstat(*fd, &mut st) < 0 {
+        return -1;
+    }
+    if st.st_mode as u16 & MODE_TYPE != MODE_FILE
+        || !check_access(&st, geteuid(), getegid(), X_OK)
+    {
+        errno = syscall::EACCES;
+        return -1;
+    }
+    let mut magic = [0; 4];
+    let read = syscall::read(*fd as usize, &mut magic).unwrap_or(0);
+    if syscall::lseek(*fd as usize, 0, SEEK_SET).is_err()
+        || !(read == 4 && &magic == b"\x7fELF" || read >= 2 && &magic[..2] == b"#!")
+    {
+        errno = syscall::ENOEXEC;
+        return -1;
+    }
+
+    for &fd in cloexec_fds().iter() {
+        if let Ok(flags) = syscall::fcntl(fd, F_GETFD, 0) {
+            if flags & O_CLOEXEC == O_CLOEXEC {
+                let _ = syscall::close(fd);
+            }
+        }
+    }
+
     e(syscall::fexec(*fd as usize, &args, &envs)) as c_int
 }
 
+pub fn faccessat(dirfd: c_int, path: *const c_char, mode: c_int, flags: c_int) -> c_int {
+    if flags & !AT_EACCESS != 0 || mode & !(R_OK | W_OK | X_OK) != 0 {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+
+    let fd = e(open_at(dirfd, path, O_STAT | O_CLOEXEC));
+    if fd == !0 {
+        return -1;
+    }
+    let mut buf: stat = unsafe { mem::zeroed() };
+    let res = fstat(fd as c_int, &mut buf);
+    let _ = syscall::close(fd);
+    if res < 0 {
+        return -1;
+    }
+
+    let (uid, gid) = if flags & AT_EACCESS == AT_EACCESS {
+        (geteuid(), getegid())
+    } else {
+        (getuid(), getgid())
+    };
+    if mode != F_OK && !check_access(&buf, uid, gid, mode) {
+        unsafe {
+            errno = syscall::EACCES;
+        }
+        return -1;
+    }
+    0
+}
+
 pub fn fchdir(fd: c_int) -> c_int {
     let path: &mut [u8] = &mut [0; 4096];
     if e(syscall::fpath(fd as usize, path)) == !0 {
@@ -260,8 +665,166 @@ pub fn fchown(fd: c_int, owner: uid_t, group: gid_t) -> c_int {
     e(syscall::fchown(fd as usize, owner as u32, group as u32)) as c_int
 }
 
-pub fn fcntl(fd: c_int, cmd: c_int, args: c_int) -> c_int {
-    e(syscall::fcntl(fd as usize, cmd as usize, args as usize)) as c_int
+pub fn fcntl(fd: c_int, cmd: c_int, args: c_ulong) -> c_int {
+    // Status flags that F_SETFL may change. The access mode, creation flags
+    // and O_CLOEXEC share the same word and must be left alone.
+    const SETFL_MASK: usize = O_NONBLOCK | O_APPEND | O_ASYNC | O_FSYNC;
+
+    match cmd as usize {
+        F_GETFD => e(syscall::fcntl(fd as usize, F_GETFD, 0).map(|flags| {
+            if flags & O_CLOEXEC == O_CLOEXEC {
+                FD_CLOEXEC as usize
+            } else {
+                0
+            }
+        })) as c_int,
+        F_SETFD => {
+            let flags = if args as c_int & FD_CLOEXEC == FD_CLOEXEC {
+                O_CLOEXEC
+            } else {
+                0
+            };
+            track_cloexec(fd as usize, flags);
+            e(syscall::fcntl(fd as usize, F_SETFD, flags)) as c_int
+        }
+        F_GETFL => e(syscall::fcntl(fd as usize, F_GETFL, 0).map(|flags| flags & !O_CLOEXEC)) as c_int,
+        F_SETFL => {
+            let flags = match syscall::fcntl(fd as usize, F_GETFL, 0) {
+                Ok(flags) => flags,
+                Err(err) => return e(Err(err)) as c_int,
+            };
+            let flags = flags & !SETFL_MASK | args as usize & SETFL_MASK;
+            e(syscall::fcntl(fd as usize, F_SETFL, flags)) as c_int
+        }
+        F_DUPFD => fcntl_dupfd(fd, args as c_int, false),
+        _ if cmd == F_DUPFD_CLOEXEC => fcntl_dupfd(fd, args as c_int, true),
+        _ if cmd == F_GETLK || cmd == F_SETLK || cmd == F_SETLKW => unsafe {
+            fcntl_lock(fd, cmd, &mut *(args as *mut flock))
+        },
+        _ => e(syscall::fcntl(fd as usize, cmd as usize, args as usize)) as c_int,
+    }
+}
+
+fn fcntl_dupfd(fd: c_int, min: c_int, cloexec: bool) -> c_int {
+    if min < 0 || min as usize >= OPEN_MAX {
+        unsafe {
+            errno = syscall::EINVAL;
+        }
+        return -1;
+    }
+
+    for new in min as usize..OPEN_MAX {
+        match syscall::fcntl(new, F_GETFD, 0) {
+            Ok(_) => continue,
+            Err(ref err) if err.errno == syscall::EBADF => (),
+            Err(err) => return e(Err(err)) as c_int,
+        }
+
+        let new = match syscall::dup2(fd as usize, new, &[]) {
+            Ok(new) => new,
+            Err(err) => return e(Err(err)) as c_int,
+        };
+        if cloexec {
+            if let Err(err) = syscall::fcntl(new, F_SETFD, O_CLOEXEC) {
+                let _ = syscall::close(new);
+                return e(Err(err)) as c_int;
+            }
+            track_cloexec(new, O_CLOEXEC);
+        }
+        return new as c_int;
+    }
+
+    unsafe {
+        errno = syscall::EMFILE;
+    }
+    -1
+}
+
+// Record locks are handled by the scheme: writing "<type> <start> <len>" to
+// the fd's lock file takes or releases a lock, blocking until it can be
+// taken unless the file is non-blocking. Writing the same to the getlk file
+// and reading it back yields either "unlock" or the first conflicting lock as
+// "<type> <start> <len> <pid>".
+unsafe fn fcntl_lock(fd: c_int, cmd: c_int, lock: &mut flock) -> c_int {
+    let base = match lock.l_whence as usize {
+        SEEK_SET => 0,
+        SEEK_CUR => match syscall::lseek(fd as usize, 0, SEEK_CUR) {
+            Ok(pos) => pos as off_t,
+            Err(err) => return e(Err(err)) as c_int,
+        },
+        SEEK_END => {
+            let mut stat = redox_stat::default();
+            if let Err(err) = syscall::fstat(fd as usize, &mut stat) {
+                return e(Err(err)) as c_int;
+            }
+            stat.st_size as off_t
+        }
+        _ => {
+            errno = syscall::EINVAL;
+            return -1;
+        }
+    };
+    let (start, len) = if lock.l_len < 0 {
+        (base + lock.l_start + lock.l_len, -lock.l_len)
+    } else {
+        (base + lock.l_start, lock.l_len)
+    };
+    if start < 0 {
+        errno = syscall::EINVAL;
+        return -1;
+    }
+
+    let kind = match lock.l_type as c_int {
+        F_RDLCK => "read",
+        F_WRLCK => "write",
+        F_UNLCK if cmd != F_GETLK => "unlock",
+        _ => {
+            errno = syscall::EINVAL;
+            return -1;
+        }
+    };
+
+    let path: &[u8] = if cmd == F_GETLK { b"getlk" } else { b"lock" };
+    let lock_fd = match syscall::dup(fd as usize, path) {
+        Ok(lock_fd) => RawFile::from_raw_fd(lock_fd as c_int),
+        Err(err) => return e(Err(err)) as c_int,
+    };
+    if cmd == F_SETLK {
+        if let Err(err) = syscall::fcntl(*lock_fd as usize, F_SETFL, O_NONBLOCK) {
+            return e(Err(err)) as c_int;
+        }
+    }
+
+    let request = format!("{} {} {}", kind, start, len);
+    if let Err(err) = syscall::write(*lock_fd as usize, request.as_bytes()) {
+        return e(Err(err)) as c_int;
+    }
+    if cmd != F_GETLK {
+        return 0;
+    }
+
+    let mut buf = [0; 64];
+    let len = match syscall::read(*lock_fd as usize, &mut buf) {
+        Ok(len) => len,
+        Err(err) => return e(Err(err)) as c_int,
+    };
+    let mut fields = buf[..len]
+        .split(|c| *c == b' ')
+        .filter_map(|field| str::from_utf8(field).ok());
+    let l_type = match fields.next() {
+        Some("read") => F_RDLCK,
+        Some("write") => F_WRLCK,
+        _ => {
+            lock.l_type = F_UNLCK as c_short;
+            return 0;
+        }
+    };
+    lock.l_type = l_type as c_short;
+    lock.l_whence = SEEK_SET as c_short;
+    lock.l_start = fields.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    lock.l_len = fields.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    lock.l_pid = fields.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    0
 }
 
 pub fn flock(_fd: c_int, _operation: c_int) -> c_int {
@@ -273,6 +836,10 @@ pub fn fork() -> pid_t {
     e(unsafe { syscall::clone(0) }) as pid_t
 }
 
+pub fn fpath(fd: c_int, buf: &mut [u8]) -> ssize_t {
+    e(syscall::fpath(fd as usize, buf)) as ssize_t
+}
+
 pub fn fstat(fildes: c_int, buf: *mut stat) -> c_int {
     let mut redox_buf: redox_stat = redox_stat::default();
     match e(syscall::fstat(fildes as usize, &mut redox_buf)) {
@@ -309,33 +876,154 @@ pub fn fstat(fildes: c_int, buf: *mut stat) -> c_int {
     }
 }
 
-pub fn fsync(fd: c_int) -> c_int {
-    e(syscall::fsync(fd as usize)) as c_int
-}
-
-pub fn ftruncate(fd: c_int, len: off_t) -> c_int {
-    e(syscall::ftruncate(fd as usize, len as usize)) as c_int
-}
+pub fn fstatvfs(fd: c_int, buf: *mut statvfs) -> c_int {
+    let mut redox_buf = redox_statvfs::default();
+    if e(syscall::fstatvfs(fd as usize, &mut redox_buf)) == !0 {
+        return -1;
+    }
+    // The filesystem's device number is the best id there is for it
+    let mut stat = redox_stat::default();
+    if e(syscall::fstat(fd as usize, &mut stat)) == !0 {
+        return -1;
+    }
 
-pub fn futimens(fd: c_int, times: *const timespec) -> c_int {
-    let times = [unsafe { redox_timespec::from(&*times) }, unsafe {
-        redox_timespec::from(&*times.offset(1))
-    }];
-    e(syscall::futimens(fd as usize, &times)) as c_int
+    // Redox doesn't report inode counts or the longest name allowed, so those are !0, which no
+    // filesystem could have, rather than made up. Mount flags aren't reported either, but any
+    // bits set there would claim a flag, so they stay 0.
+    unsafe {
+        *buf = statvfs {
+            f_bsize: redox_buf.f_bsize as c_ulong,
+            f_frsize: redox_buf.f_bsize as c_ulong,
+            f_blocks: redox_buf.f_blocks,
+            f_bfree: redox_buf.f_bfree,
+            f_bavail: redox_buf.f_bavail,
+            f_files: !0,
+            f_ffree: !0,
+            f_favail: !0,
+            f_fsid: stat.st_dev,
+            f_flag: 0,
+            f_namemax: !0,
+        };
+    }
+    0
 }
 
-pub fn utimens(path: *const c_char, times: *const timespec) -> c_int {
-    let path = unsafe { c_str(path) };
-    match syscall::open(path, O_STAT) {
-        Err(err) => e(Err(err)) as c_int,
-        Ok(fd) => {
-            let res = futimens(fd as c_int, times);
-            let _ = syscall::close(fd);
-            res
+pub fn fstatat(dirfd: c_int, path: *const c_char, buf: *mut stat, flags: c_int) -> c_int {
+    if flags & !AT_SYMLINK_NOFOLLOW != 0 {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    let mut oflags = O_STAT | O_CLOEXEC;
+    if flags & AT_SYMLINK_NOFOLLOW == AT_SYMLINK_NOFOLLOW {
+        // O_STAT with O_NOFOLLOW opens a symlink itself, so its mode has MODE_SYMLINK set
+        oflags |= O_NOFOLLOW;
+    }
+    match open_at(dirfd, path, oflags) {
+        Err(err) => e(Err(err)) as c_int,
+        Ok(fd) => {
+            let res = fstat(fd as c_int, buf);
+            let _ = syscall::close(fd);
+            res
+        }
+    }
+}
+
+pub fn fsync(fd: c_int) -> c_int {
+    e(syscall::fsync(fd as usize)) as c_int
+}
+
+pub fn ftruncate(fd: c_int, len: off_t) -> c_int {
+    e(syscall::ftruncate(fd as usize, len as usize)) as c_int
+}
+
+pub fn futimens(fd: c_int, times: *const timespec) -> c_int {
+    // The kernel only takes actual times, so a null times, UTIME_NOW and UTIME_OMIT are all
+    // resolved here
+    let mut now = redox_timespec::default();
+    if e(syscall::clock_gettime(syscall::CLOCK_REALTIME, &mut now)) == !0 {
+        return -1;
+    }
+    let mut old = redox_stat::default();
+    if e(syscall::fstat(fd as usize, &mut old)) == !0 {
+        return -1;
+    }
+    let old = [
+        redox_timespec {
+            tv_sec: old.st_atime as i64,
+            tv_nsec: old.st_atime_nsec as i32,
+        },
+        redox_timespec {
+            tv_sec: old.st_mtime as i64,
+            tv_nsec: old.st_mtime_nsec as i32,
+        },
+    ];
+
+    let mut new = [now, now];
+    if !times.is_null() {
+        for i in 0..2 {
+            let time = unsafe { &*times.offset(i as isize) };
+            new[i] = match time.tv_nsec {
+                UTIME_NOW => now,
+                UTIME_OMIT => old[i],
+                0...999_999_999 => redox_timespec::from(time),
+                _ => {
+                    unsafe {
+                        errno = EINVAL;
+                    }
+                    return -1;
+                }
+            };
+        }
+    }
+    e(syscall::futimens(fd as usize, &new)) as c_int
+}
+
+/// Sleeps until futex_wake is called on `addr`, as long as it still holds `val`. A non-null
+/// `timeout` is how long to wait at most.
+pub fn futex_wait(addr: *mut c_int, val: c_int, timeout: *const timespec) -> c_int {
+    let redox_timeout = if timeout.is_null() {
+        None
+    } else {
+        Some(unsafe { redox_timespec::from(&*timeout) })
+    };
+    let timeout_ptr = match redox_timeout {
+        Some(ref timeout) => timeout as *const redox_timespec as usize,
+        None => 0,
+    };
+    e(unsafe { syscall::futex(addr as *mut i32, FUTEX_WAIT, val, timeout_ptr, ptr::null_mut()) })
+        as c_int
+}
+
+/// Wakes up at most `count` threads waiting on `addr`, and returns how many there were
+pub fn futex_wake(addr: *mut c_int, count: c_int) -> c_int {
+    e(unsafe { syscall::futex(addr as *mut i32, FUTEX_WAKE, count, 0, ptr::null_mut()) }) as c_int
+}
+
+pub fn utimens(path: *const c_char, times: *const timespec) -> c_int {
+    let path = unsafe { c_str(path) };
+    match syscall::open(path, O_STAT | O_CLOEXEC) {
+        Err(err) => e(Err(err)) as c_int,
+        Ok(fd) => {
+            let res = futimens(fd as c_int, times);
+            let _ = syscall::close(fd);
+            res
         }
     }
 }
 
+pub fn get_nprocs() -> c_int {
+    // sys:cpu starts with a line of the form "CPUs: <count>"
+    let mut buf = [0; 256];
+    let len = read_file("sys:cpu", &mut buf).unwrap_or(0);
+    str::from_utf8(&buf[..len])
+        .ok()
+        .and_then(|text| text.lines().next())
+        .and_then(|line| line.trim_left_matches("CPUs:").trim().parse().ok())
+        .unwrap_or(1)
+}
+
 pub fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
     let buf_slice = unsafe { slice::from_raw_parts_mut(buf as *mut u8, size as usize - 1) };
     let read = e(syscall::getcwd(buf_slice));
@@ -343,56 +1031,78 @@ pub fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
         ptr::null_mut()
     } else {
         unsafe {
-            *buf.offset(read as isize + 1) = 0;
+            *buf.offset(read as isize) = 0;
         }
         buf
     }
 }
 
 pub fn getdents(fd: c_int, mut dirents: *mut dirent, mut bytes: usize) -> c_int {
+    // Directories are read as newline separated names. Entries that don't fit
+    // are given back by seeking to the start of their name, so that d_off can
+    // be used with lseek like on other systems.
+    let mut pos = match syscall::lseek(fd as usize, 0, SEEK_CUR) {
+        Ok(pos) => pos,
+        Err(err) => return -err.errno,
+    };
     let mut amount = 0;
 
     let mut buf = [0; 1024];
-    let mut bindex = 0;
-    let mut blen = 0;
-
     let mut name = [0; 256];
     let mut nindex = 0;
 
     loop {
-        if bindex >= blen {
-            bindex = 0;
-            blen = match syscall::read(fd as usize, &mut buf) {
-                Ok(0) => return amount,
-                Ok(n) => n,
-                Err(err) => return -err.errno,
-            };
+        let read = match syscall::read(fd as usize, &mut buf) {
+            Ok(n) => n,
+            Err(err) => return -err.errno,
+        };
+
+        // A last name without a trailing newline is still an entry
+        let eof = read == 0;
+        if eof && nindex == 0 {
+            return amount;
         }
 
-        if buf[bindex] == b'\n' {
+        for i in 0..read.max(1) {
+            if !eof && buf[i] != b'\n' {
+                if nindex < name.len() {
+                    name[nindex] = buf[i] as c_char;
+                }
+                nindex += 1;
+                continue;
+            }
+
+            if bytes < mem::size_of::<dirent>() {
+                if amount == 0 {
+                    return -syscall::EINVAL;
+                }
+                if let Err(err) = syscall::lseek(fd as usize, pos as isize, SEEK_SET) {
+                    return -err.errno;
+                }
+                return amount;
+            }
+
+            pos += nindex + if eof { 0 } else { 1 };
             // Put a NUL byte either at the end, or if it's too big, at where it's truncated.
             name[nindex.min(name.len() - 1)] = 0;
             unsafe {
                 *dirents = dirent {
                     d_ino: 0,
-                    d_off: 0,
+                    d_off: pos as off_t,
                     d_reclen: mem::size_of::<dirent>() as c_ushort,
-                    d_type: 0,
+                    d_type: DT_UNKNOWN,
                     d_name: name,
                 };
                 dirents = dirents.offset(1);
             }
-            amount += 1;
-            if bytes <= mem::size_of::<dirent>() {
-                return amount;
-            }
+            amount += mem::size_of::<dirent>() as c_int;
             bytes -= mem::size_of::<dirent>();
-        } else {
-            if nindex < name.len() {
-                name[nindex] = buf[bindex] as c_char;
+            name = [0; 256];
+            nindex = 0;
+
+            if eof {
+                return amount;
             }
-            nindex += 1;
-            bindex += 1;
         }
     }
 }
@@ -409,34 +1119,76 @@ pub fn getgid() -> gid_t {
     e(syscall::getgid()) as gid_t
 }
 
-pub fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
-    let _ = writeln!(
-        FileWriter(2),
-        "unimplemented: getrusage({}, {:p})",
-        who,
-        r_usage
-    );
-    -1
+pub fn getgroups(size: c_int, list: *mut gid_t) -> c_int {
+    let groups = unsafe { &GROUPS };
+    if size == 0 {
+        return groups.len() as c_int;
+    }
+    if size < 0 || (size as usize) < groups.len() {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    unsafe {
+        ptr::copy_nonoverlapping(groups.as_ptr(), list, groups.len());
+    }
+    groups.len() as c_int
 }
 
-pub unsafe fn gethostname(mut name: *mut c_char, len: size_t) -> c_int {
-    let fd = e(syscall::open("/etc/hostname", O_RDONLY)) as i32;
-    if fd < 0 {
-        return fd;
+pub fn getpriority(which: c_int, who: id_t) -> c_int {
+    if !is_priority_self(which, who) {
+        return -1;
     }
-    let mut reader = FileReader(fd);
-    for _ in 0..len {
-        match reader.read_u8() {
-            Ok(Some(b)) => {
-                *name = b as c_char;
-                name = name.offset(1);
-            },
-            Ok(None) => {
-                *name = 0;
-                break;
-            },
-            Err(()) => return -1
+    unsafe { NICE }
+}
+
+pub fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int {
+    if resource < 0 || resource >= RLIM_NLIMITS {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    unsafe {
+        *rlim = RLIMITS[resource as usize];
+    }
+    0
+}
+
+/// The CPU time the process has used, from the same clock clock() reads. Where the kernel doesn't
+/// keep that, this is 0.
+fn cpu_time() -> timespec {
+    let mut ts = timespec::default();
+    let errno_backup = unsafe { errno };
+    if clock_gettime(CLOCK_PROCESS_CPUTIME_ID, &mut ts) < 0 {
+        ts = timespec::default();
+    }
+    unsafe {
+        errno = errno_backup;
+    }
+    ts
+}
+
+pub fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
+    if who != RUSAGE_SELF && who != RUSAGE_CHILDREN {
+        unsafe {
+            errno = EINVAL;
         }
+        return -1;
+    }
+
+    // The kernel doesn't count page faults, context switches or the like, so those stay 0
+    let mut usage: rusage = unsafe { mem::zeroed() };
+
+    if who == RUSAGE_SELF {
+        let ts = cpu_time();
+        usage.ru_utime.tv_sec = ts.tv_sec;
+        usage.ru_utime.tv_usec = (ts.tv_nsec / 1000) as suseconds_t;
+    }
+
+    unsafe {
+        *r_usage = usage;
     }
     0
 }
@@ -476,19 +1228,176 @@ unsafe fn inner_get_name(
 }
 
 pub fn getitimer(which: c_int, out: *mut itimerval) -> c_int {
-    let _ = writeln!(
-        FileWriter(2),
-        "unimplemented: getitimer({}, {:p})",
-        which,
-        out
-    );
+    if which != ITIMER_REAL {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
 
     unsafe {
-        *out = itimerval::default();
+        *out = real_timer_left(&timer_state().timer);
+    }
+    0
+}
+
+/// The ITIMER_REAL timer. A thread sends SIGALRM at the right times, which shares the file
+/// descriptors of the process rather than keeping copies of them open the way a forked process
+/// would.
+struct RealTimer {
+    // The process the timer is for. A forked child doesn't get the timer, but does get a copy of
+    // this.
+    owner: pid_t,
+    // When the timer goes off next, on the monotonic clock, in nanoseconds
+    deadline: u64,
+    interval: u64,
+}
+
+struct TimerState {
+    timer: Option<RealTimer>,
+    // The process the timer thread was last started for, and whether it's still running. It
+    // stops once there's nothing left for it to do.
+    thread_owner: pid_t,
+    thread_running: bool,
+}
+
+static TIMER_STATE: Once<Mutex<TimerState>> = Once::new();
+
+fn timer_state() -> MutexGuard<'static, TimerState> {
+    TIMER_STATE
+        .call_once(|| {
+            Mutex::new(TimerState {
+                timer: None,
+                thread_owner: 0,
+                thread_running: false,
+            })
+        })
+        .lock()
+}
+
+// Bumped whenever the timer changes, which wakes up the timer thread to look at it again
+static mut TIMER_SEQ: c_int = 0;
+static mut TIMER_TID: pid_t = 0;
+// Only one timer thread runs at a time, so they can all use the same stack
+static mut TIMER_STACK: [usize; 8192] = [0; 8192];
+
+const NSEC_PER_SEC: u64 = 1_000_000_000;
+
+fn monotonic_ns() -> u64 {
+    let mut ts = redox_timespec::default();
+    let _ = syscall::clock_gettime(syscall::CLOCK_MONOTONIC, &mut ts);
+    ts.tv_sec as u64 * NSEC_PER_SEC + ts.tv_nsec as u64
+}
+
+fn valid_timeval(tv: &timeval) -> bool {
+    tv.tv_sec >= 0 && tv.tv_usec >= 0 && tv.tv_usec < 1_000_000
+}
+
+fn timeval_ns(tv: &timeval) -> u64 {
+    tv.tv_sec as u64 * NSEC_PER_SEC + tv.tv_usec as u64 * 1000
+}
+
+fn ns_timeval(ns: u64) -> timeval {
+    // Rounded up, so a timer that's about to go off isn't reported as disabled
+    let us = (ns + 999) / 1000;
+    timeval {
+        tv_sec: (us / 1_000_000) as time_t,
+        tv_usec: (us % 1_000_000) as suseconds_t,
+    }
+}
+
+fn real_timer_left(timer: &Option<RealTimer>) -> itimerval {
+    match *timer {
+        Some(ref timer) if timer.owner == getpid() => {
+            let now = monotonic_ns();
+            let left = if now < timer.deadline {
+                timer.deadline - now
+            } else if timer.interval > 0 {
+                timer.interval - (now - timer.deadline) % timer.interval
+            } else {
+                0
+            };
+            itimerval {
+                it_interval: ns_timeval(timer.interval),
+                it_value: ns_timeval(left),
+            }
+        }
+        _ => itimerval::default(),
+    }
+}
+
+/// Starts the timer thread for `owner`. The state has to be locked, so that the thread doesn't
+/// look at it before it's set up.
+unsafe fn spawn_timer(state: &mut TimerState, owner: pid_t) -> c_int {
+    // The last thread may still be on its way out, and has to be off the stack first. One that was
+    // started before a fork never runs here, so there's nothing to wait for then.
+    if state.thread_owner == owner {
+        wait_thread(&mut TIMER_TID, true);
+    }
+
+    let top = ((TIMER_STACK.as_mut_ptr() as usize + mem::size_of_val(&TIMER_STACK)) & !15)
+        as *mut usize;
+    let sp = top.offset(-2);
+    *sp = timer_thread as usize;
+    *sp.offset(1) = owner as usize;
+
+    TIMER_TID = -1;
+    if clone_thread(sp, ptr::null_mut(), &mut TIMER_TID) < 0 {
+        TIMER_TID = 0;
+        return -1;
     }
+    state.thread_owner = owner;
+    state.thread_running = true;
     0
 }
 
+/// What the timer thread does: send SIGALRM at every deadline, until the timer is done or the
+/// process it's for is gone
+extern "C" fn timer_thread(owner: usize) -> ! {
+    let owner = owner as pid_t;
+    loop {
+        let seq = unsafe { ptr::read_volatile(&TIMER_SEQ) };
+        {
+            let mut state = timer_state();
+            let now = monotonic_ns();
+            let left = match state.timer {
+                Some(ref mut timer) if timer.owner == owner => {
+                    if now < timer.deadline {
+                        Some(timer.deadline - now)
+                    } else if syscall::kill(owner as usize, syscall::SIGALRM).is_ok()
+                        && timer.interval > 0
+                    {
+                        // Ticks that were missed are skipped rather than made up for
+                        timer.deadline +=
+                            ((now - timer.deadline) / timer.interval + 1) * timer.interval;
+                        Some(timer.deadline - now)
+                    } else {
+                        None
+                    }
+                }
+                _ => None,
+            };
+
+            match left {
+                Some(left) => {
+                    drop(state);
+                    let timeout = timespec {
+                        tv_sec: (left / NSEC_PER_SEC) as time_t,
+                        tv_nsec: (left % NSEC_PER_SEC) as c_long,
+                    };
+                    futex_wait(unsafe { &mut TIMER_SEQ }, seq, &timeout);
+                }
+                None => {
+                    state.timer = None;
+                    state.thread_running = false;
+                    drop(state);
+                    exit_thread();
+                }
+            }
+        }
+    }
+}
+
 pub unsafe fn getpeername(
     socket: c_int,
     address: *mut sockaddr,
@@ -501,6 +1410,16 @@ pub fn getpgid(pid: pid_t) -> pid_t {
     e(syscall::getpgid(pid as usize)) as pid_t
 }
 
+pub fn getrandom(buf: &mut [u8]) -> ssize_t {
+    let fd = e(syscall::open("rand:", O_RDONLY | O_CLOEXEC));
+    if fd == !0 {
+        return -1;
+    }
+    let res = e(syscall::read(fd, buf));
+    let _ = syscall::close(fd);
+    res as ssize_t
+}
+
 pub fn getpid() -> pid_t {
     e(syscall::getpid()) as pid_t
 }
@@ -517,6 +1436,62 @@ pub unsafe fn getsockname(
     e(inner_get_name(true, socket, address, address_len)) as c_int
 }
 
+fn socket_kind(socket: c_int) -> Result<c_int> {
+    let mut buf = [0; 4];
+    let len = syscall::fpath(socket as usize, &mut buf)?;
+    match &buf[..len] {
+        b"tcp:" => Ok(SOCK_STREAM),
+        b"udp:" => Ok(SOCK_DGRAM),
+        _ => Err(syscall::Error::new(syscall::ENOTSOCK)),
+    }
+}
+
+// The tcp: and udp: schemes expose their timeouts as files that hold a TimeSpec,
+// or nothing at all if there is no timeout
+fn socket_timeout_path(option_name: c_int) -> Option<&'static [u8]> {
+    match option_name {
+        SO_RCVTIMEO => Some(b"read_timeout"),
+        SO_SNDTIMEO => Some(b"write_timeout"),
+        _ => None,
+    }
+}
+
+fn get_socket_timeout(socket: c_int, path: &[u8], value: &mut timeval) -> Result<()> {
+    let fd = syscall::dup(socket as usize, path)?;
+    let mut timeout = redox_timespec::default();
+    let res = syscall::read(fd, &mut timeout);
+    let _ = syscall::close(fd);
+
+    if res? < mem::size_of::<redox_timespec>() {
+        *value = timeval::default();
+    } else {
+        value.tv_sec = timeout.tv_sec;
+        value.tv_usec = (timeout.tv_nsec / 1000) as suseconds_t;
+    }
+    Ok(())
+}
+
+fn set_socket_timeout(socket: c_int, path: &[u8], value: &timeval) -> Result<()> {
+    // Anything else would overflow tv_nsec
+    if value.tv_usec < 0 || value.tv_usec >= 1_000_000 {
+        return Err(syscall::Error::new(syscall::EINVAL));
+    }
+    let fd = syscall::dup(socket as usize, path)?;
+    let res = if value.tv_sec == 0 && value.tv_usec == 0 {
+        syscall::write(fd, &[])
+    } else {
+        syscall::write(
+            fd,
+            &redox_timespec {
+                tv_sec: value.tv_sec,
+                tv_nsec: value.tv_usec * 1000,
+            },
+        )
+    };
+    let _ = syscall::close(fd);
+    res.and(Ok(()))
+}
+
 pub fn getsockopt(
     socket: c_int,
     level: c_int,
@@ -524,30 +1499,77 @@ pub fn getsockopt(
     option_value: *mut c_void,
     option_len: *mut socklen_t,
 ) -> c_int {
-    let _ = writeln!(
-        FileWriter(2),
-        "unimplemented: getsockopt({}, {}, {}, {:p}, {:p})",
-        socket,
-        level,
-        option_name,
-        option_value,
-        option_len
-    );
-    -1
+    if level != SOL_SOCKET {
+        unsafe {
+            errno = syscall::ENOPROTOOPT;
+        }
+        return -1;
+    }
+
+    let kind = match socket_kind(socket) {
+        Ok(kind) => kind,
+        Err(err) => return e(Err(err)) as c_int,
+    };
+
+    if let Some(path) = socket_timeout_path(option_name) {
+        unsafe {
+            if option_value.is_null()
+                || option_len.is_null()
+                || (*option_len as usize) < mem::size_of::<timeval>()
+            {
+                errno = syscall::EINVAL;
+                return -1;
+            }
+            if e(get_socket_timeout(socket, path, &mut *(option_value as *mut timeval))) == !0 {
+                return -1;
+            }
+            *option_len = mem::size_of::<timeval>() as socklen_t;
+        }
+        return 0;
+    }
+
+    let value = match option_name {
+        SO_ERROR => socket_options()
+            .get_mut(&socket)
+            .map(|options| mem::replace(&mut options.error, 0))
+            .unwrap_or(0),
+        SO_TYPE => kind,
+        SO_REUSEADDR => socket_options()
+            .get(&socket)
+            .map(|options| options.reuseaddr as c_int)
+            .unwrap_or(0),
+        _ => {
+            unsafe {
+                errno = syscall::ENOPROTOOPT;
+            }
+            return -1;
+        }
+    };
+
+    unsafe {
+        if option_value.is_null()
+            || option_len.is_null()
+            || (*option_len as usize) < mem::size_of::<c_int>()
+        {
+            errno = syscall::EINVAL;
+            return -1;
+        }
+        *(option_value as *mut c_int) = value;
+        *option_len = mem::size_of::<c_int>() as socklen_t;
+    }
+    0
 }
 
 pub fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int {
-    let mut redox_tp = redox_timespec::default();
-    let err = e(syscall::clock_gettime(
-        syscall::CLOCK_REALTIME,
-        &mut redox_tp,
-    )) as c_int;
-    if err < 0 {
-        return err;
+    let mut ts = timespec::default();
+    if clock_gettime(CLOCK_REALTIME as clockid_t, &mut ts) < 0 {
+        return -1;
     }
     unsafe {
-        (*tp).tv_sec = redox_tp.tv_sec as time_t;
-        (*tp).tv_usec = (redox_tp.tv_nsec / 1000) as suseconds_t;
+        if !tp.is_null() {
+            (*tp).tv_sec = ts.tv_sec;
+            (*tp).tv_usec = (ts.tv_nsec / 1000) as suseconds_t;
+        }
 
         if !tzp.is_null() {
             (*tzp).tz_minuteswest = 0;
@@ -561,13 +1583,102 @@ pub fn getuid() -> uid_t {
     e(syscall::getuid()) as pid_t
 }
 
+/// The kernel sets up TLS for every context itself
+pub unsafe fn init_tls(_auxv: *const usize) {}
+
+pub fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) -> c_int {
+    match request {
+        TIOCGWINSZ | TIOCSWINSZ => {
+            let dup = e(tty_fd(fd, b"winsize"));
+            if dup == !0 {
+                return -1;
+            }
+
+            // The terminal only knows about rows and columns, not pixels
+            let mut size = [0u16; 2];
+            let res = if request == TIOCGWINSZ {
+                syscall::read(dup, unsafe { slice::from_raw_parts_mut(
+                    size.as_mut_ptr() as *mut u8,
+                    mem::size_of_val(&size)
+                ) }).map(|_| unsafe {
+                    let out = out as *mut winsize;
+                    (*out).ws_row = size[0];
+                    (*out).ws_col = size[1];
+                    (*out).ws_xpixel = 0;
+                    (*out).ws_ypixel = 0;
+                })
+            } else {
+                unsafe {
+                    let out = out as *const winsize;
+                    size = [(*out).ws_row, (*out).ws_col];
+                }
+                syscall::write(dup, unsafe { slice::from_raw_parts(
+                    size.as_ptr() as *const u8,
+                    mem::size_of_val(&size)
+                ) }).map(|_| ())
+            };
+            let _ = syscall::close(dup);
+
+            e(res.map(|()| 0)) as c_int
+        }
+        FIONBIO => {
+            let flags = e(syscall::fcntl(fd as usize, F_GETFL, 0));
+            if flags == !0 {
+                return -1;
+            }
+            let flags = if unsafe { *(out as *const c_int) } != 0 {
+                flags | O_NONBLOCK
+            } else {
+                flags & !O_NONBLOCK
+            };
+            e(syscall::fcntl(fd as usize, F_SETFL, flags)) as c_int
+        }
+        FIONREAD => {
+            // Only regular files can tell how much is left to read. The
+            // schemes have no way to report what's queued up in a pipe,
+            // socket or anything else, so those always have 0 bytes, and
+            // poll is what says whether there's something to read.
+            let mut redox_buf = redox_stat::default();
+            if e(syscall::fstat(fd as usize, &mut redox_buf)) == !0 {
+                return -1;
+            }
+            if redox_buf.st_mode & MODE_TYPE != MODE_FILE {
+                unsafe {
+                    *(out as *mut c_int) = 0;
+                }
+                return 0;
+            }
+            let pos = e(syscall::lseek(fd as usize, 0, SEEK_CUR));
+            if pos == !0 {
+                return -1;
+            }
+            unsafe {
+                *(out as *mut c_int) = (redox_buf.st_size as usize).saturating_sub(pos) as c_int;
+            }
+            0
+        }
+        _ => {
+            unsafe {
+                errno = syscall::ENOTTY;
+            }
+            -1
+        }
+    }
+}
+
 pub fn isatty(fd: c_int) -> c_int {
-    syscall::dup(fd as usize, b"termios")
-        .map(|fd| {
+    match termios_fd(fd) {
+        Ok(fd) => {
             let _ = syscall::close(fd);
             1
-        })
-        .unwrap_or(0)
+        }
+        Err(err) => {
+            unsafe {
+                errno = err.errno;
+            }
+            0
+        }
+    }
 }
 
 pub fn kill(pid: pid_t, sig: c_int) -> c_int {
@@ -584,8 +1695,44 @@ pub fn link(path1: *const c_char, path2: *const c_char) -> c_int {
     e(unsafe { syscall::link(path1.as_ptr(), path2.as_ptr()) }) as c_int
 }
 
-pub fn listen(_socket: c_int, _backlog: c_int) -> c_int {
-    // TODO
+fn socket_is_bound_tcp(socket: c_int) -> Result<bool> {
+    // Format: tcp:remote/local, where the local port is 0 until bound
+    let mut buf = [0; 256];
+    let len = syscall::fpath(socket as usize, &mut buf)?;
+    let buf = &buf[..len];
+    if len < 4 || &buf[..4] != b"tcp:" {
+        return Ok(false);
+    }
+
+    let local = buf[4..].split(|c| *c == b'/').nth(1).unwrap_or(&[]);
+    let port = local.rsplit(|c| *c == b':').next().unwrap_or(&[]);
+    Ok(!port.is_empty() && port != b"0")
+}
+
+pub fn listen(socket: c_int, backlog: c_int) -> c_int {
+    match socket_is_bound_tcp(socket) {
+        Ok(true) => (),
+        Ok(false) => {
+            unsafe {
+                errno = syscall::EOPNOTSUPP;
+            }
+            return -1;
+        }
+        Err(err) => return e(Err(err)) as c_int,
+    }
+
+    // A backlog of 0 still has to allow one pending connection
+    let backlog = backlog.max(1);
+
+    let fd = e(syscall::dup(socket as usize, b"backlog"));
+    if fd == !0 {
+        return -1;
+    }
+    let res = syscall::write(fd, format!("{}", backlog).as_bytes());
+    let _ = syscall::close(fd);
+    if e(res) == !0 {
+        return -1;
+    }
     0
 }
 
@@ -598,21 +1745,16 @@ pub fn lseek(fd: c_int, offset: off_t, whence: c_int) -> off_t {
 }
 
 pub fn lstat(path: *const c_char, buf: *mut stat) -> c_int {
-    let path = unsafe { c_str(path) };
-    match syscall::open(path, O_STAT | O_NOFOLLOW) {
-        Err(err) => e(Err(err)) as c_int,
-        Ok(fd) => {
-            let res = fstat(fd as i32, buf);
-            let _ = syscall::close(fd);
-            res
-        }
-    }
+    fstatat(AT_FDCWD, path, buf, AT_SYMLINK_NOFOLLOW)
 }
 
 pub fn mkdir(path: *const c_char, mode: mode_t) -> c_int {
-    let flags = O_CREAT | O_EXCL | O_CLOEXEC | O_DIRECTORY | mode as usize & 0o777;
-    let path = unsafe { c_str(path) };
-    match syscall::open(path, flags) {
+    mkdirat(AT_FDCWD, path, mode)
+}
+
+pub fn mkdirat(dirfd: c_int, path: *const c_char, mode: mode_t) -> c_int {
+    let flags = O_CREAT | O_EXCL | O_CLOEXEC | O_DIRECTORY | creation_mode(mode);
+    match open_at(dirfd, path, flags) {
         Ok(fd) => {
             let _ = syscall::close(fd);
             0
@@ -622,7 +1764,7 @@ pub fn mkdir(path: *const c_char, mode: mode_t) -> c_int {
 }
 
 pub fn mkfifo(path: *const c_char, mode: mode_t) -> c_int {
-    let flags = O_CREAT | MODE_FIFO as usize | mode as usize & 0o777;
+    let flags = O_CREAT | MODE_FIFO as usize | creation_mode(mode);
     let path = unsafe { c_str(path) };
     match syscall::open(path, flags) {
         Ok(fd) => {
@@ -641,13 +1783,19 @@ pub unsafe fn mmap(
     fildes: c_int,
     off: off_t,
 ) -> *mut c_void {
+    // The kernel picks the address itself, so there's no way to honour a fixed one
+    if flags & MAP_FIXED == MAP_FIXED {
+        errno = EINVAL;
+        return !0 as *mut c_void;
+    }
+
     if flags & MAP_ANON == MAP_ANON {
         let fd = e(syscall::open("memory:", 0)); // flags don't matter currently
         if fd == !0 {
             return !0 as *mut c_void;
         }
 
-        let addr = e(syscall::fmap(fd, off as usize, len as usize));
+        let addr = e(syscall::fmap(fd, 0, len as usize));
         if addr == !0 {
             let _ = syscall::close(fd);
             return !0 as *mut c_void;
@@ -660,6 +1808,20 @@ pub unsafe fn mmap(
     }
 }
 
+pub unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
+    let mut flags = 0;
+    if prot & PROT_READ == PROT_READ {
+        flags |= syscall::PROT_READ;
+    }
+    if prot & PROT_WRITE == PROT_WRITE {
+        flags |= syscall::PROT_WRITE;
+    }
+    if prot & PROT_EXEC == PROT_EXEC {
+        flags |= syscall::PROT_EXEC;
+    }
+    e(syscall::mprotect(addr as usize, len, flags)) as c_int
+}
+
 pub unsafe fn munmap(addr: *mut c_void, _len: usize) -> c_int {
     if e(syscall::funmap(addr as usize)) == !0 {
         return !0;
@@ -672,142 +1834,111 @@ pub unsafe fn munmap(addr: *mut c_void, _len: usize) -> c_int {
 
 pub fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
     let redox_rqtp = unsafe { redox_timespec::from(&*rqtp) };
-    let mut redox_rmtp: redox_timespec;
-    if rmtp.is_null() {
-        redox_rmtp = redox_timespec::default();
-    } else {
-        redox_rmtp = unsafe { redox_timespec::from(&*rmtp) };
-    }
-    match e(syscall::nanosleep(&redox_rqtp, &mut redox_rmtp)) as c_int {
-        -1 => -1,
-        _ => {
-            unsafe {
-                if !rmtp.is_null() {
+    let mut redox_rmtp = redox_timespec::default();
+    match syscall::nanosleep(&redox_rqtp, &mut redox_rmtp) {
+        Ok(_) => 0,
+        Err(err) => {
+            // the remaining time is only meaningful if the sleep was interrupted
+            if err.errno == syscall::EINTR && !rmtp.is_null() {
+                unsafe {
                     (*rmtp).tv_sec = redox_rmtp.tv_sec;
                     (*rmtp).tv_nsec = redox_rmtp.tv_nsec as i64;
                 }
             }
-            0
+            unsafe {
+                errno = err.errno as c_int;
+            }
+            -1
         }
     }
 }
 
 pub fn open(path: *const c_char, oflag: c_int, mode: mode_t) -> c_int {
-    let path = unsafe { c_str(path) };
-    e(syscall::open(path, (oflag as usize) | (mode as usize))) as c_int
-}
-
-pub fn pipe(fds: &mut [c_int]) -> c_int {
-    let mut usize_fds: [usize; 2] = [0; 2];
-    let res = e(syscall::pipe2(&mut usize_fds, 0));
-    fds[0] = usize_fds[0] as c_int;
-    fds[1] = usize_fds[1] as c_int;
-    res as c_int
-}
-
-pub fn raise(sig: c_int) -> c_int {
-    kill(getpid(), sig)
+    openat(AT_FDCWD, path, oflag, mode)
 }
 
-pub fn read(fd: c_int, buf: &mut [u8]) -> ssize_t {
-    e(syscall::read(fd as usize, buf)) as ssize_t
+pub fn openat(dirfd: c_int, path: *const c_char, oflag: c_int, mode: mode_t) -> c_int {
+    let mode = if oflag as usize & O_CREAT == O_CREAT {
+        creation_mode(mode)
+    } else {
+        0
+    };
+    let fd = e(open_at(dirfd, path, oflag as usize | mode));
+    track_cloexec(fd, oflag as usize);
+    fd as c_int
 }
 
-pub unsafe fn recvfrom(
-    socket: c_int,
-    buf: *mut c_void,
-    len: size_t,
-    flags: c_int,
-    address: *mut sockaddr,
-    address_len: *mut socklen_t,
-) -> ssize_t {
-    if flags != 0 {
-        errno = syscall::EOPNOTSUPP;
-        return -1;
-    }
-    if address != ptr::null_mut()
-        && address_len != ptr::null_mut()
-        && getpeername(socket, address, address_len) < 0
-    {
-        return -1;
-    }
-    read(socket, slice::from_raw_parts_mut(buf as *mut u8, len))
+pub fn pipe(fds: &mut [c_int]) -> c_int {
+    pipe2(fds, 0)
 }
 
-pub fn rename(oldpath: *const c_char, newpath: *const c_char) -> c_int {
-    let (oldpath, newpath) = unsafe { (c_str(oldpath), c_str(newpath)) };
-    match syscall::open(oldpath, O_WRONLY) {
-        Ok(fd) => {
-            let retval = syscall::frename(fd, newpath);
-            let _ = syscall::close(fd);
-            e(retval) as c_int
-        }
-        err => e(err) as c_int,
+pub fn pipe2(fds: &mut [c_int], flags: c_int) -> c_int {
+    let mut usize_fds: [usize; 2] = [0; 2];
+    let res = e(syscall::pipe2(&mut usize_fds, flags as usize));
+    if res != !0 {
+        track_cloexec(usize_fds[0], flags as usize);
+        track_cloexec(usize_fds[1], flags as usize);
     }
+    fds[0] = usize_fds[0] as c_int;
+    fds[1] = usize_fds[1] as c_int;
+    res as c_int
 }
 
-pub fn rmdir(path: *const c_char) -> c_int {
-    let path = unsafe { c_str(path) };
-    e(syscall::rmdir(path)) as c_int
-}
+pub fn poll(fds: *mut pollfd, nfds: nfds_t, timeout: c_int) -> c_int {
+    let fds = unsafe { slice::from_raw_parts_mut(fds, nfds as usize) };
 
-pub fn select(
-    nfds: c_int,
-    readfds: *mut fd_set,
-    writefds: *mut fd_set,
-    exceptfds: *mut fd_set,
-    timeout: *mut timeval,
-) -> c_int {
-    fn isset(set: *mut fd_set, fd: usize) -> bool {
-        if set.is_null() {
-            return false;
+    let mut invalid = false;
+    for pfd in fds.iter_mut() {
+        pfd.revents = 0;
+        if pfd.fd >= 0 && syscall::fcntl(pfd.fd as usize, F_GETFL, 0).is_err() {
+            pfd.revents = POLLNVAL;
+            invalid = true;
         }
-
-        let mask = 1 << (fd & (8 * mem::size_of::<c_ulong>() - 1));
-        unsafe { (*set).fds_bits[fd / (8 * mem::size_of::<c_ulong>())] & mask == mask }
     }
 
-    let event_file = match RawFile::open("event:\0".as_ptr() as *const c_char, 0, 0) {
+    // Never block if there is nothing to wait for, or if we already have
+    // something to report
+    let block = timeout != 0 && !invalid;
+    let event_flags = if block { 0 } else { O_NONBLOCK };
+    let event_file = match RawFile::open(
+        "event:\0".as_ptr() as *const c_char,
+        event_flags as c_int,
+        0,
+    ) {
         Ok(file) => file,
         Err(_) => return -1,
     };
 
-    let mut total = 0;
-
-    for fd in 0..nfds as usize {
-        macro_rules! register {
-            ($fd:expr, $flags:expr) => {
-                if write(
-                    *event_file,
-                    &syscall::Event {
-                        id: $fd,
-                        flags: $flags,
-                        data: 0,
-                    },
-                ) < 0
-                {
-                    return -1;
-                }
-            };
+    for pfd in fds.iter() {
+        if pfd.fd < 0 || pfd.revents != 0 {
+            continue;
+        }
+        let mut flags = 0;
+        if pfd.events & POLLIN == POLLIN {
+            flags |= syscall::EVENT_READ;
         }
-        if isset(readfds, fd) {
-            register!(fd, syscall::EVENT_READ);
-            total += 1;
+        if pfd.events & POLLOUT == POLLOUT {
+            flags |= syscall::EVENT_WRITE;
         }
-        if isset(writefds, fd) {
-            register!(fd, syscall::EVENT_WRITE);
-            total += 1;
+        if flags == 0 {
+            continue;
         }
-        if isset(exceptfds, fd) {
-            total += 1;
+        if write(
+            *event_file,
+            &syscall::Event {
+                id: pfd.fd as usize,
+                flags: flags,
+                data: 0,
+            },
+        ) < 0
+        {
+            return -1;
         }
     }
 
     const TIMEOUT_TOKEN: usize = 1;
 
-    let timeout_file = if timeout.is_null() {
-        None
-    } else {
+    let _timeout_file = if block && timeout > 0 {
         let timeout_file = match RawFile::open(
             format!("time:{}\0", syscall::CLOCK_MONOTONIC).as_ptr() as *const c_char,
             0,
@@ -816,15 +1947,19 @@ pub fn select(
             Ok(file) => file,
             Err(_) => return -1,
         };
-        let timeout = unsafe { &*timeout };
-        if write(
-            *timeout_file,
-            &syscall::TimeSpec {
-                tv_sec: timeout.tv_sec,
-                tv_nsec: timeout.tv_usec * 1000,
-            },
-        ) < 0
-        {
+
+        // The time scheme wants an absolute deadline
+        let mut time = redox_timespec::default();
+        if read(*timeout_file, &mut time) < 0 {
+            return -1;
+        }
+        time.tv_sec += (timeout / 1000) as i64;
+        time.tv_nsec += (timeout % 1000) * 1_000_000;
+        if time.tv_nsec >= 1_000_000_000 {
+            time.tv_sec += 1;
+            time.tv_nsec -= 1_000_000_000;
+        }
+        if write(*timeout_file, &time) < 0 {
             return -1;
         }
         if write(
@@ -840,20 +1975,190 @@ pub fn select(
         }
 
         Some(timeout_file)
+    } else {
+        None
+    };
+
+    let mut events = [syscall::Event::default(); 32];
+    let count = {
+        let buf = unsafe {
+            slice::from_raw_parts_mut(events.as_mut_ptr() as *mut u8, mem::size_of_val(&events))
+        };
+        match syscall::read(*event_file as usize, buf) {
+            Ok(bytes) => bytes / mem::size_of::<syscall::Event>(),
+            Err(ref err) if err.errno == syscall::EAGAIN => 0,
+            Err(err) => return e(Err(err)) as c_int,
+        }
+    };
+
+    for event in &events[..count] {
+        if event.data == TIMEOUT_TOKEN {
+            continue;
+        }
+        for pfd in fds.iter_mut() {
+            if pfd.fd < 0 || pfd.fd as usize != event.id {
+                continue;
+            }
+            if event.flags & syscall::EVENT_READ == syscall::EVENT_READ {
+                pfd.revents |= pfd.events & POLLIN;
+            }
+            if event.flags & syscall::EVENT_WRITE == syscall::EVENT_WRITE {
+                pfd.revents |= pfd.events & POLLOUT;
+            }
+        }
+    }
+
+    fds.iter().filter(|pfd| pfd.revents != 0).count() as c_int
+}
+
+pub fn raise(sig: c_int) -> c_int {
+    kill(getpid(), sig)
+}
+
+pub fn pread(fd: c_int, buf: &mut [u8], offset: off_t) -> ssize_t {
+    e(at_offset(fd, offset, || syscall::read(fd as usize, buf))) as ssize_t
+}
+
+pub fn pwrite(fd: c_int, buf: &[u8], offset: off_t) -> ssize_t {
+    e(at_offset(fd, offset, || syscall::write(fd as usize, buf))) as ssize_t
+}
+
+pub fn read(fd: c_int, buf: &mut [u8]) -> ssize_t {
+    e(syscall::read(fd as usize, buf)) as ssize_t
+}
+
+pub fn readlink(path: *const c_char, buf: &mut [u8]) -> ssize_t {
+    readlinkat(AT_FDCWD, path, buf)
+}
+
+pub fn readlinkat(dirfd: c_int, path: *const c_char, buf: &mut [u8]) -> ssize_t {
+    // Opening with O_SYMLINK gives us the link itself, whose contents are the target
+    let fd = e(open_at(dirfd, path, O_RDONLY | O_SYMLINK | O_CLOEXEC));
+    if fd == !0 {
+        return -1;
+    }
+    let mut stat = redox_stat::default();
+    let count = match syscall::fstat(fd, &mut stat) {
+        Ok(_) if stat.st_mode & MODE_TYPE != MODE_SYMLINK => Err(syscall::Error::new(EINVAL)),
+        Ok(_) => syscall::read(fd, buf),
+        Err(err) => Err(err),
     };
+    let _ = syscall::close(fd);
+    e(count) as ssize_t
+}
+
+pub fn readv(fd: c_int, iov: &[iovec]) -> ssize_t {
+    e(vectored(iov, |iov| {
+        let buf = unsafe { slice::from_raw_parts_mut(iov.iov_base as *mut u8, iov.iov_len) };
+        syscall::read(fd as usize, buf)
+    })) as ssize_t
+}
+
+// Runs a socket transfer, emulating MSG_DONTWAIT by making the socket
+// non-blocking for the duration of the call
+fn with_msg_flags<F>(socket: c_int, flags: c_int, f: F) -> ssize_t
+where
+    F: FnOnce() -> Result<usize>,
+{
+    if flags & MSG_DONTWAIT == MSG_DONTWAIT {
+        let old = match syscall::fcntl(socket as usize, F_GETFL, 0) {
+            Ok(old) => old,
+            Err(err) => return e(Err(err)) as ssize_t,
+        };
+        if old & O_NONBLOCK != O_NONBLOCK {
+            if let Err(err) = syscall::fcntl(socket as usize, F_SETFL, old | O_NONBLOCK) {
+                return e(Err(err)) as ssize_t;
+            }
+            let res = f();
+            let _ = syscall::fcntl(socket as usize, F_SETFL, old);
+            return e(res) as ssize_t;
+        }
+    }
+    e(f()) as ssize_t
+}
+
+pub unsafe fn recv(socket: c_int, buf: *mut c_void, len: size_t, flags: c_int) -> ssize_t {
+    match socket_is_connected(socket) {
+        Ok(true) => (),
+        Ok(false) => {
+            errno = syscall::ENOTCONN;
+            return -1;
+        }
+        Err(err) => return e(Err(err)) as ssize_t,
+    }
+    recvfrom(socket, buf, len, flags, ptr::null_mut(), ptr::null_mut())
+}
 
-    let mut event = syscall::Event::default();
-    if read(*event_file, &mut event) < 0 {
+pub unsafe fn recvfrom(
+    socket: c_int,
+    buf: *mut c_void,
+    len: size_t,
+    flags: c_int,
+    address: *mut sockaddr,
+    address_len: *mut socklen_t,
+) -> ssize_t {
+    if flags & !(MSG_PEEK | MSG_DONTWAIT) != 0 {
+        errno = syscall::EOPNOTSUPP;
+        return -1;
+    }
+    if address != ptr::null_mut()
+        && address_len != ptr::null_mut()
+        && getpeername(socket, address, address_len) < 0
+    {
         return -1;
     }
+    let buf = slice::from_raw_parts_mut(buf as *mut u8, len);
+    with_msg_flags(socket, flags, || {
+        if flags & MSG_PEEK == MSG_PEEK {
+            // The peek file reads without consuming the data
+            let fd = syscall::dup(socket as usize, b"peek")?;
+            let res = syscall::read(fd, buf);
+            let _ = syscall::close(fd);
+            res
+        } else {
+            syscall::read(socket as usize, buf)
+        }
+    })
+}
+
+pub fn rename(oldpath: *const c_char, newpath: *const c_char) -> c_int {
+    renameat(AT_FDCWD, oldpath, AT_FDCWD, newpath)
+}
+
+pub fn renameat(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+) -> c_int {
+    let newpath = match at_path(newdirfd, newpath) {
+        Ok(newpath) => newpath,
+        Err(err) => return e(Err(err)) as c_int,
+    };
+    match open_at(olddirfd, oldpath, O_WRONLY) {
+        Ok(fd) => {
+            let retval = syscall::frename(fd, &newpath);
+            let _ = syscall::close(fd);
+            e(retval) as c_int
+        }
+        err => e(err) as c_int,
+    }
+}
 
-    if timeout_file.is_some() && event.data == TIMEOUT_TOKEN {
-        return 0;
-    }
+pub fn rmdir(path: *const c_char) -> c_int {
+    unlinkat(AT_FDCWD, path, AT_REMOVEDIR)
+}
 
-    // I really don't get why, but select wants me to return the total number
-    // of file descriptors that was inputted. I'm confused.
-    total
+pub unsafe fn send(socket: c_int, buf: *const c_void, len: size_t, flags: c_int) -> ssize_t {
+    match socket_is_connected(socket) {
+        Ok(true) => (),
+        Ok(false) => {
+            errno = syscall::ENOTCONN;
+            return -1;
+        }
+        Err(err) => return e(Err(err)) as ssize_t,
+    }
+    sendto(socket, buf, len, flags, ptr::null(), 0)
 }
 
 pub unsafe fn sendto(
@@ -868,28 +2173,186 @@ pub unsafe fn sendto(
         errno = syscall::EISCONN;
         return -1;
     }
-    if flags != 0 {
+    if flags & !MSG_DONTWAIT != 0 {
         errno = syscall::EOPNOTSUPP;
         return -1;
     }
-    write(socket, slice::from_raw_parts(buf as *const u8, len))
+    let buf = slice::from_raw_parts(buf as *const u8, len);
+    with_msg_flags(socket, flags, || syscall::write(socket as usize, buf))
 }
 
+/// Only ITIMER_REAL is there, as the kernel doesn't keep track of the CPU time the other timers
+/// need. The process behind it keeps running across exec(), like POSIX wants, but the new
+/// program can't see or change it.
 pub fn setitimer(which: c_int, new: *const itimerval, old: *mut itimerval) -> c_int {
-    let _ = writeln!(
-        FileWriter(2),
-        "unimplemented: setitimer({}, {:p}, {:p})",
-        which,
-        new,
-        old
-    );
+    let new = unsafe { &*new };
+    if which != ITIMER_REAL || !valid_timeval(&new.it_value) || !valid_timeval(&new.it_interval) {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+
+    let pid = getpid();
+    let value = timeval_ns(&new.it_value);
+    let mut state = timer_state();
+    if !old.is_null() {
+        unsafe {
+            *old = real_timer_left(&state.timer);
+        }
+    }
+
+    // Starting the thread is all that can fail, and the old timer is left alone if it does. The
+    // thread only looks at the timer with the state locked, so the old one can't go off once the
+    // new one is in.
+    if value > 0
+        && !(state.thread_running && state.thread_owner == pid)
+        && unsafe { spawn_timer(&mut state, pid) } < 0
+    {
+        return -1;
+    }
+    state.timer = if value == 0 {
+        None
+    } else {
+        Some(RealTimer {
+            owner: pid,
+            deadline: monotonic_ns() + value,
+            interval: timeval_ns(&new.it_interval),
+        })
+    };
+
+    unsafe {
+        ptr::write_volatile(&mut TIMER_SEQ, TIMER_SEQ.wrapping_add(1));
+        futex_wake(&mut TIMER_SEQ, 1);
+    }
+    0
+}
+
+pub fn setpriority(which: c_int, who: id_t, prio: c_int) -> c_int {
+    if !is_priority_self(which, who) {
+        return -1;
+    }
+    let prio = cmp::max(-20, cmp::min(prio, 19));
+    // Only root may make itself less nice
+    if prio < unsafe { NICE } && geteuid() != 0 {
+        unsafe {
+            errno = syscall::EACCES;
+        }
+        return -1;
+    }
+    unsafe {
+        NICE = prio;
+    }
+    0
+}
+
+pub fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+    let new = unsafe { *rlim };
+    if resource < 0 || resource >= RLIM_NLIMITS || new.rlim_cur > new.rlim_max {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    let old = unsafe { RLIMITS[resource as usize] };
+    // Only root may raise a hard limit, and nobody can go past what the kernel allows
+    if (new.rlim_max > old.rlim_max && geteuid() != 0)
+        || (resource == RLIMIT_NOFILE && new.rlim_max > OPEN_MAX as rlim_t)
+        || (resource == RLIMIT_STACK && new.rlim_max > STACK_SIZE)
+    {
+        unsafe {
+            errno = syscall::EPERM;
+        }
+        return -1;
+    }
+    unsafe {
+        RLIMITS[resource as usize] = new;
+    }
+    0
+}
+
+// The kernel has no saved set-user-ID or set-group-ID, so only the real and effective ids are
+// allowed here. Root changing both gives up root for good.
+
+pub fn setegid(egid: gid_t) -> c_int {
+    let (rgid, cur_egid) = (e(syscall::getgid()), e(syscall::getegid()));
+    if e(syscall::geteuid()) != 0 && egid as usize != rgid && egid as usize != cur_egid {
+        unsafe {
+            errno = syscall::EPERM;
+        }
+        return -1;
+    }
+    e(syscall::setregid(!0, egid as usize)) as c_int
+}
+
+pub fn seteuid(euid: uid_t) -> c_int {
+    let (ruid, cur_euid) = (e(syscall::getuid()), e(syscall::geteuid()));
+    if cur_euid != 0 && euid as usize != ruid && euid as usize != cur_euid {
+        unsafe {
+            errno = syscall::EPERM;
+        }
+        return -1;
+    }
+    e(syscall::setreuid(!0, euid as usize)) as c_int
+}
 
+pub fn setgid(gid: gid_t) -> c_int {
+    if e(syscall::geteuid()) == 0 {
+        return e(syscall::setregid(gid as usize, gid as usize)) as c_int;
+    }
+    setegid(gid)
+}
+
+pub fn setgroups(size: size_t, list: *const gid_t) -> c_int {
+    if e(syscall::geteuid()) != 0 {
+        unsafe {
+            errno = syscall::EPERM;
+        }
+        return -1;
+    }
+    if size > NGROUPS_MAX {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
     unsafe {
-        *old = itimerval::default();
+        GROUPS.clear();
+        if size > 0 {
+            GROUPS.extend_from_slice(slice::from_raw_parts(list, size));
+        }
     }
     0
 }
 
+pub fn sethostname(name: *const c_char, len: size_t) -> c_int {
+    // The hostname is only a file, so make sure not just anyone can change it
+    if e(syscall::geteuid()) != 0 {
+        unsafe {
+            errno = syscall::EPERM;
+        }
+        return -1;
+    }
+    if len >= UTSLENGTH {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    let name = unsafe { slice::from_raw_parts(name as *const u8, len) };
+
+    let fd = e(syscall::open(
+        "/etc/hostname",
+        O_WRONLY | O_CREAT | O_TRUNC | O_CLOEXEC | 0o644,
+    ));
+    if fd == !0 {
+        return -1;
+    }
+    let res = syscall::write(fd, name).and_then(|_| syscall::write(fd, b"\n"));
+    let _ = syscall::close(fd);
+    e(res.map(|_| 0)) as c_int
+}
+
 pub fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
     e(syscall::setpgid(pid as usize, pgid as usize)) as c_int
 }
@@ -902,6 +2365,13 @@ pub fn setreuid(ruid: uid_t, euid: uid_t) -> c_int {
     e(syscall::setreuid(ruid as usize, euid as usize)) as c_int
 }
 
+pub fn setuid(uid: uid_t) -> c_int {
+    if e(syscall::geteuid()) == 0 {
+        return e(syscall::setreuid(uid as usize, uid as usize)) as c_int;
+    }
+    seteuid(uid)
+}
+
 pub fn setsockopt(
     socket: c_int,
     level: c_int,
@@ -909,77 +2379,207 @@ pub fn setsockopt(
     option_value: *const c_void,
     option_len: socklen_t,
 ) -> c_int {
-    let _ = writeln!(
-        FileWriter(2),
-        "unimplemented: setsockopt({}, {}, {}, {:p}, {})",
-        socket,
-        level,
-        option_name,
-        option_value,
-        option_len
-    );
-    -1
+    if level != SOL_SOCKET {
+        unsafe {
+            errno = syscall::ENOPROTOOPT;
+        }
+        return -1;
+    }
+
+    if let Err(err) = socket_kind(socket) {
+        return e(Err(err)) as c_int;
+    }
+
+    match option_name {
+        SO_RCVTIMEO | SO_SNDTIMEO => {
+            if option_value.is_null() || option_len as usize != mem::size_of::<timeval>() {
+                unsafe {
+                    errno = syscall::EINVAL;
+                }
+                return -1;
+            }
+            let path = socket_timeout_path(option_name).unwrap();
+            let value = unsafe { &*(option_value as *const timeval) };
+            e(set_socket_timeout(socket, path, value).and(Ok(0))) as c_int
+        }
+        SO_REUSEADDR => {
+            if option_value.is_null() || (option_len as usize) < mem::size_of::<c_int>() {
+                unsafe {
+                    errno = syscall::EINVAL;
+                }
+                return -1;
+            }
+            let value = unsafe { *(option_value as *const c_int) };
+            socket_options()
+                .entry(socket)
+                .or_insert_with(SocketOptions::default)
+                .reuseaddr = value != 0;
+            0
+        }
+        _ => {
+            unsafe {
+                errno = syscall::ENOPROTOOPT;
+            }
+            -1
+        }
+    }
+}
+
+fn socket_is_connected(socket: c_int) -> Result<bool> {
+    // Format: tcp:remote/local, where remote is empty or unspecified until connected
+    let mut buf = [0; 256];
+    let len = syscall::fpath(socket as usize, &mut buf)?;
+    let buf = &buf[..len];
+    if len < 4 || (&buf[..4] != b"tcp:" && &buf[..4] != b"udp:") {
+        return Err(syscall::Error::new(syscall::ENOTSOCK));
+    }
+
+    let remote = buf[4..].split(|c| *c == b'/').next().unwrap_or(&[]);
+    Ok(!remote.is_empty() && remote != b"0.0.0.0:0")
 }
 
 pub fn shutdown(socket: c_int, how: c_int) -> c_int {
-    let _ = writeln!(
-        FileWriter(2),
-        "unimplemented: shutdown({}, {})",
-        socket,
-        how
-    );
-    -1
+    let direction: &[u8] = match how {
+        SHUT_RD => b"read",
+        SHUT_WR => b"write",
+        SHUT_RDWR => b"both",
+        _ => {
+            unsafe {
+                errno = syscall::EINVAL;
+            }
+            return -1;
+        }
+    };
+
+    match socket_is_connected(socket) {
+        Ok(true) => (),
+        Ok(false) => {
+            unsafe {
+                errno = syscall::ENOTCONN;
+            }
+            return -1;
+        }
+        Err(err) => return e(Err(err)) as c_int,
+    }
+
+    // The scheme closes the requested halves of the connection when the
+    // direction is written to its shutdown file
+    let fd = e(syscall::dup(socket as usize, b"shutdown"));
+    if fd == !0 {
+        return -1;
+    }
+    let res = syscall::write(fd, direction);
+    let _ = syscall::close(fd);
+    e(res.and(Ok(0))) as c_int
 }
 
 pub unsafe fn sigaction(sig: c_int, act: *const sigaction, oact: *mut sigaction) -> c_int {
-    if !oact.is_null() {
-        // Assumes the last sigaction() call was made by relibc and not a different one
-        if SIG_HANDLER.is_some() {
-            (*oact).sa_handler = SIG_HANDLER;
-        }
+    if sig <= 0 || sig as usize >= SIG_HANDLERS.len() {
+        errno = EINVAL;
+        return -1;
     }
+    let sig = sig as usize;
+    let old_handler = SIG_HANDLERS[sig];
+    let old_flags = SIG_FLAGS[sig];
+
     let act = if act.is_null() {
         None
     } else {
-        SIG_HANDLER = (*act).sa_handler;
+        // SIG_DFL and SIG_IGN are understood by the kernel, everything else goes through
+        // sig_handler
+        let handler = (*act).sa_handler.map_or(SIG_DFL, |f| f as usize);
+        let sa_handler = if handler == SIG_DFL || handler == SIG_IGN {
+            mem::transmute(handler)
+        } else {
+            sig_handler as extern "C" fn(usize)
+        };
+        SIG_HANDLERS[sig] = (*act).sa_handler;
+        SIG_FLAGS[sig] = (*act).sa_flags;
         let m = (*act).sa_mask;
         Some(syscall::SigAction {
-            sa_handler: sig_handler,
+            sa_handler: sa_handler,
             sa_mask: [0, m as u64],
             sa_flags: (*act).sa_flags as usize,
         })
     };
     let mut old = syscall::SigAction::default();
     let ret = e(syscall::sigaction(
-        sig as usize,
+        sig,
         act.as_ref(),
         if oact.is_null() { None } else { Some(&mut old) },
     )) as c_int;
+    if ret < 0 {
+        SIG_HANDLERS[sig] = old_handler;
+        SIG_FLAGS[sig] = old_flags;
+        return ret;
+    }
     if !oact.is_null() {
+        // Assumes the last sigaction() call was made by relibc and not a different one
         let m = old.sa_mask;
+        (*oact).sa_handler = old_handler;
         (*oact).sa_mask = m[1] as c_ulong;
-        (*oact).sa_flags = old.sa_flags as c_ulong;
+        (*oact).sa_flags = old_flags;
     }
     ret
 }
 
 pub fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int {
-    let _ = writeln!(
-        FileWriter(2),
-        "unimplemented: sigprocmask({}, {:p}, {:p})",
-        how,
-        set,
-        oset
-    );
+    // The mask is laid out the same way as the one in sigaction
+    let set = if set.is_null() {
+        None
+    } else {
+        Some([0, unsafe { *set } as u64])
+    };
+    let mut old = [0; 2];
+    let ret = e(syscall::sigprocmask(
+        how as usize,
+        set.as_ref(),
+        if oset.is_null() { None } else { Some(&mut old) },
+    )) as c_int;
+    if ret == 0 && !oset.is_null() {
+        unsafe {
+            *oset = old[1] as sigset_t;
+        }
+    }
+    ret
+}
+
+/// The kernel can't swap the signal mask and wait in one go, so this waits until sig_handler has
+/// been called since before the mask was changed. A signal that comes in between checking that
+/// and going to sleep is only noticed after the sleep, which is why the sleeps are short.
+pub fn sigsuspend(mask: *const sigset_t) -> c_int {
+    let caught = SIGNALS_CAUGHT.load(Ordering::SeqCst);
+    let mut old: sigset_t = 0;
+    if sigprocmask(SIG_SETMASK, mask, &mut old) < 0 {
+        return -1;
+    }
+
+    while SIGNALS_CAUGHT.load(Ordering::SeqCst) == caught {
+        let req = redox_timespec {
+            tv_sec: 0,
+            tv_nsec: 10_000_000,
+        };
+        let mut rem = redox_timespec::default();
+        let _ = syscall::nanosleep(&req, &mut rem);
+    }
+
+    sigprocmask(SIG_SETMASK, &old, ptr::null_mut());
+    unsafe {
+        errno = syscall::EINTR;
+    }
     -1
 }
 
 pub fn stat(path: *const c_char, buf: *mut stat) -> c_int {
+    fstatat(AT_FDCWD, path, buf, 0)
+}
+
+pub fn statvfs(path: *const c_char, buf: *mut statvfs) -> c_int {
     let path = unsafe { c_str(path) };
-    match syscall::open(path, O_STAT) {
+    match syscall::open(path, O_STAT | O_CLOEXEC) {
         Err(err) => e(Err(err)) as c_int,
         Ok(fd) => {
-            let res = fstat(fd as i32, buf);
+            let res = fstatvfs(fd as c_int, buf);
             let _ = syscall::close(fd);
             res
         }
@@ -987,7 +2587,7 @@ pub fn stat(path: *const c_char, buf: *mut stat) -> c_int {
 }
 
 pub unsafe fn socket(domain: c_int, mut kind: c_int, protocol: c_int) -> c_int {
-    if domain != AF_INET {
+    if domain != AF_INET && domain != AF_INET6 {
         errno = syscall::EAFNOSUPPORT;
         return -1;
     }
@@ -1008,14 +2608,19 @@ pub unsafe fn socket(domain: c_int, mut kind: c_int, protocol: c_int) -> c_int {
 
     // The tcp: and udp: schemes allow using no path,
     // and later specifying one using `dup`.
-    match kind {
+    let fd = match kind {
         SOCK_STREAM => e(syscall::open("tcp:", flags)) as c_int,
         SOCK_DGRAM => e(syscall::open("udp:", flags)) as c_int,
         _ => {
             errno = syscall::EPROTOTYPE;
             -1
         }
+    };
+    if fd >= 0 {
+        socket_options().insert(fd, SocketOptions::default());
+        track_cloexec(fd as usize, flags);
     }
+    fd
 }
 
 pub fn socketpair(domain: c_int, kind: c_int, protocol: c_int, socket_vector: *mut c_int) -> c_int {
@@ -1030,8 +2635,31 @@ pub fn socketpair(domain: c_int, kind: c_int, protocol: c_int, socket_vector: *m
     -1
 }
 
+pub fn symlink(target: *const c_char, linkpath: *const c_char) -> c_int {
+    symlinkat(target, AT_FDCWD, linkpath)
+}
+
+pub fn symlinkat(target: *const c_char, dirfd: c_int, linkpath: *const c_char) -> c_int {
+    let target = unsafe { c_str(target) };
+    // A symlink is created like a file with O_SYMLINK, and the target written as its contents
+    let fd = e(open_at(
+        dirfd,
+        linkpath,
+        O_CREAT | O_EXCL | O_WRONLY | O_SYMLINK | O_CLOEXEC | 0o777,
+    ));
+    if fd == !0 {
+        return -1;
+    }
+    let res = syscall::write(fd, target);
+    let _ = syscall::close(fd);
+    if e(res) == !0 {
+        return -1;
+    }
+    0
+}
+
 pub fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
-    let dup = e(syscall::dup(fd as usize, b"termios"));
+    let dup = e(termios_fd(fd));
     if dup == !0 {
         return -1;
     }
@@ -1048,11 +2676,22 @@ pub fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
     0
 }
 
-pub fn tcsetattr(fd: c_int, _act: c_int, value: *const termios) -> c_int {
-    let dup = e(syscall::dup(fd as usize, b"termios"));
+pub fn tcsetattr(fd: c_int, act: c_int, value: *const termios) -> c_int {
+    if act < TCSANOW || act > TCSAFLUSH {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    let dup = e(termios_fd(fd));
     if dup == !0 {
         return -1;
     }
+    // There's no way to throw away unread input, so TCSAFLUSH only waits for the output like
+    // TCSADRAIN does
+    if act != TCSANOW {
+        let _ = syscall::fsync(fd as usize);
+    }
 
     let write = e(syscall::write(dup, unsafe { slice::from_raw_parts(
         value as *const u8,
@@ -1066,32 +2705,171 @@ pub fn tcsetattr(fd: c_int, _act: c_int, value: *const termios) -> c_int {
     0
 }
 
+/// There's nothing to allocate, as clone gives the new context its own TLS block
+pub unsafe fn tls_new() -> Result<*mut c_void, ()> {
+    Ok(ptr::null_mut())
+}
+
+pub unsafe fn tls_free(_tp: *mut c_void) {}
+
 pub fn times(out: *mut tms) -> clock_t {
-    let _ = writeln!(FileWriter(2), "unimplemented: times({:p})", out);
-    !0
+    // Clock ticks are counted from boot
+    let mut now = timespec::default();
+    if clock_gettime(syscall::CLOCK_MONOTONIC as clockid_t, &mut now) < 0 {
+        return -1;
+    }
+
+    if !out.is_null() {
+        // Only the CPU time of the process itself is known
+        unsafe {
+            *out = tms {
+                tms_utime: ticks(&cpu_time()),
+                tms_stime: 0,
+                tms_cutime: 0,
+                tms_cstime: 0,
+            };
+        }
+    }
+    ticks(&now)
+}
+
+fn ticks(ts: &timespec) -> clock_t {
+    ts.tv_sec as clock_t * CLK_TCK + ts.tv_nsec as clock_t / (1_000_000_000 / CLK_TCK)
+}
+
+pub fn truncate(path: *const c_char, length: off_t) -> c_int {
+    let path = unsafe { c_str(path) };
+    let fd = e(syscall::open(path, O_WRONLY | O_CLOEXEC));
+    if fd == !0 {
+        return -1;
+    }
+    let res = ftruncate(fd as c_int, length);
+    let _ = syscall::close(fd);
+    res
 }
 
 pub fn umask(mask: mode_t) -> mode_t {
-    let _ = writeln!(FileWriter(2), "unimplemented: umask({})", mask);
+    unsafe { mem::replace(&mut UMASK, mask & 0o777) }
+}
+
+pub fn uname(utsname: *mut utsname) -> c_int {
+    #[cfg(target_arch = "aarch64")]
+    const MACHINE: &[u8] = b"aarch64";
+    #[cfg(target_arch = "x86_64")]
+    const MACHINE: &[u8] = b"x86_64";
+
+    fn copy(dst: &mut [c_char; UTSLENGTH], src: &[u8]) {
+        let len = cmp::min(src.len(), UTSLENGTH - 1);
+        for (d, s) in dst.iter_mut().zip(src[..len].iter()) {
+            *d = *s as c_char;
+        }
+        dst[len] = 0;
+    }
+
+    if utsname.is_null() {
+        unsafe {
+            errno = syscall::EFAULT;
+        }
+        return -1;
+    }
+    let uts = unsafe { &mut *utsname };
+
+    // sys:uname has a line for every field, but the kernel has no idea what
+    // the hostname is and leaves that line empty
+    let mut kernel = [0; 256];
+    let len = read_file("sys:uname", &mut kernel).unwrap_or(0);
+    let mut lines = kernel[..len].split(|&b| b == b'\n').skip(2);
+    let release = lines.next().unwrap_or(&[]);
+    let version = lines.next().unwrap_or(&[]);
+
+    let mut hostname = [0; UTSLENGTH];
+    let len = read_file("/etc/hostname", &mut hostname).unwrap_or(0);
+    let hostname = str::from_utf8(&hostname[..len]).unwrap_or("").trim();
+
+    copy(&mut uts.sysname, b"Redox");
+    copy(&mut uts.nodename, hostname.as_bytes());
+    copy(&mut uts.release, release);
+    copy(&mut uts.version, version);
+    copy(&mut uts.machine, MACHINE);
+    copy(&mut uts.domainname, &[]);
     0
 }
 
 pub fn unlink(path: *const c_char) -> c_int {
-    let path = unsafe { c_str(path) };
-    e(syscall::unlink(path)) as c_int
+    unlinkat(AT_FDCWD, path, 0)
+}
+
+pub fn unlinkat(dirfd: c_int, path: *const c_char, flags: c_int) -> c_int {
+    if flags & !AT_REMOVEDIR != 0 {
+        unsafe {
+            errno = EINVAL;
+        }
+        return -1;
+    }
+    let path = match at_path(dirfd, path) {
+        Ok(path) => path,
+        Err(err) => return e(Err(err)) as c_int,
+    };
+    if flags & AT_REMOVEDIR == AT_REMOVEDIR {
+        e(syscall::rmdir(&path)) as c_int
+    } else {
+        e(syscall::unlink(&path)) as c_int
+    }
+}
+
+/// Collects the threads that have exited, as far as this context started them. The rest are
+/// left for the context that did.
+fn reap_threads(pids: &mut BTreeSet<usize>) {
+    let mut gone = Vec::new();
+    for &pid in pids.iter() {
+        let mut status = 0;
+        match syscall::waitpid(pid, &mut status, WNOHANG) {
+            Ok(0) => (),
+            Err(ref err) if err.errno == syscall::ECHILD => (),
+            _ => gone.push(pid),
+        }
+    }
+    for pid in gone {
+        pids.remove(&pid);
+    }
+}
+
+/// Whether a thread started by clone_thread is gone for good, waiting for it to be if `block` is
+/// set. exit_thread clears `tid` and wakes up anyone waiting on it at that point, which works
+/// from any thread, unlike waiting for the context.
+pub fn wait_thread(tid: *mut pid_t, block: bool) -> bool {
+    reap_threads(&mut thread_pids());
+    loop {
+        let current = unsafe { ptr::read_volatile(tid) };
+        if current == 0 {
+            return true;
+        }
+        if !block {
+            return false;
+        }
+        futex_wait(tid, current, ptr::null());
+    }
 }
 
+/// Threads are contexts that the kernel counts as children, so waiting for any child skips over
+/// them. That means a process with threads but no children blocks until the last thread is gone,
+/// instead of failing with ECHILD right away.
 pub fn waitpid(mut pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
     if pid == !0 {
         pid = 0;
     }
-    unsafe {
+    loop {
         let mut temp: usize = 0;
         let res = e(syscall::waitpid(pid as usize, &mut temp, options as usize));
+        if pid <= 0 && res != 0 && res != !0 && thread_pids().remove(&res) {
+            continue;
+        }
         if !stat_loc.is_null() {
-            *stat_loc = temp as c_int;
+            unsafe {
+                *stat_loc = temp as c_int;
+            }
         }
-        res as pid_t
+        return res as pid_t;
     }
 }
 
@@ -1099,6 +2877,13 @@ pub fn write(fd: c_int, buf: &[u8]) -> ssize_t {
     e(syscall::write(fd as usize, buf)) as ssize_t
 }
 
+pub fn writev(fd: c_int, iov: &[iovec]) -> ssize_t {
+    e(vectored(iov, |iov| {
+        let buf = unsafe { slice::from_raw_parts(iov.iov_base as *const u8, iov.iov_len) };
+        syscall::write(fd as usize, buf)
+    })) as ssize_t
+}
+
 pub fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int {
     let mut redox_tp = unsafe { redox_timespec::from(&*tp) };
     match e(syscall::clock_gettime(clk_id as usize, &mut redox_tp)) as c_int {
@@ -61,6 +61,8 @@ pub type ino_t = c_ulong;
 pub type nlink_t = c_ulong;
 pub type blksize_t = c_long;
 pub type blkcnt_t = c_ulong;
+pub type fsblkcnt_t = c_ulong;
+pub type fsfilcnt_t = c_ulong;
 
 pub type useconds_t = c_uint;
 pub type suseconds_t = c_int;
@@ -106,6 +108,9 @@ impl<'a> From<&'a timespec> for redox_timespec {
     }
 }
 
+pub const UTIME_NOW: c_long = (1 << 30) - 1;
+pub const UTIME_OMIT: c_long = (1 << 30) - 2;
+
 #[repr(C)]
 pub struct stat {
     pub st_dev: dev_t,
@@ -129,12 +134,28 @@ pub struct stat {
     pub _pad: [c_char; 24],
 }
 
+pub const AF_UNSPEC: c_int = 0;
 pub const AF_INET: c_int = 2;
+pub const AF_INET6: c_int = 10;
 pub const SOCK_STREAM: c_int = 1;
 pub const SOCK_DGRAM: c_int = 2;
 pub const SOCK_NONBLOCK: c_int = 0o4000;
 pub const SOCK_CLOEXEC: c_int = 0o2000000;
 
+pub const SOL_SOCKET: c_int = 1;
+pub const SO_REUSEADDR: c_int = 2;
+pub const SO_TYPE: c_int = 3;
+pub const SO_ERROR: c_int = 4;
+pub const SO_RCVTIMEO: c_int = 20;
+pub const SO_SNDTIMEO: c_int = 21;
+
+pub const SHUT_RD: c_int = 0;
+pub const SHUT_WR: c_int = 1;
+pub const SHUT_RDWR: c_int = 2;
+
+pub const MSG_PEEK: c_int = 2;
+pub const MSG_DONTWAIT: c_int = 0x40;
+
 pub const SIG_BLOCK: c_int = 0;
 pub const SIG_UNBLOCK: c_int = 1;
 pub const SIG_SETMASK: c_int = 2;
@@ -144,6 +165,25 @@ pub type in_port_t = u16;
 pub type sa_family_t = u16;
 pub type socklen_t = u32;
 
+pub const ST_RDONLY: c_ulong = 1;
+pub const ST_NOSUID: c_ulong = 2;
+
+#[repr(C)]
+#[derive(Default)]
+pub struct statvfs {
+    pub f_bsize: c_ulong,
+    pub f_frsize: c_ulong,
+    pub f_blocks: fsblkcnt_t,
+    pub f_bfree: fsblkcnt_t,
+    pub f_bavail: fsblkcnt_t,
+    pub f_files: fsfilcnt_t,
+    pub f_ffree: fsfilcnt_t,
+    pub f_favail: fsfilcnt_t,
+    pub f_fsid: c_ulong,
+    pub f_flag: c_ulong,
+    pub f_namemax: c_ulong,
+}
+
 #[repr(C)]
 pub struct sockaddr {
     pub sa_family: sa_family_t,
@@ -163,6 +203,21 @@ pub struct sockaddr_in {
     pub sin_addr: in_addr,
 }
 
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct in6_addr {
+    pub s6_addr: [u8; 16],
+}
+
+#[repr(C)]
+pub struct sockaddr_in6 {
+    pub sin6_family: sa_family_t,
+    pub sin6_port: in_port_t,
+    pub sin6_flowinfo: u32,
+    pub sin6_addr: in6_addr,
+    pub sin6_scope_id: u32,
+}
+
 #[repr(C)]
 pub struct sigaction {
     pub sa_handler: Option<extern "C" fn(c_int)>,
@@ -173,7 +228,20 @@ pub struct sigaction {
 
 pub type sigset_t = c_ulong;
 
-const UTSLENGTH: usize = 65;
+#[repr(C)]
+#[derive(Default)]
+pub struct siginfo_t {
+    pub si_signo: c_int,
+    pub si_errno: c_int,
+    pub si_code: c_int,
+    _pad0: c_int,
+    pub si_pid: pid_t,
+    pub si_uid: uid_t,
+    pub si_status: c_int,
+    _pad: [c_int; 25],
+}
+
+pub const UTSLENGTH: usize = 65;
 
 #[repr(C)]
 pub struct utsname {
@@ -185,6 +253,15 @@ pub struct utsname {
     pub domainname: [c_char; UTSLENGTH],
 }
 
+pub const DT_UNKNOWN: c_uchar = 0;
+pub const DT_FIFO: c_uchar = 1;
+pub const DT_CHR: c_uchar = 2;
+pub const DT_DIR: c_uchar = 4;
+pub const DT_BLK: c_uchar = 6;
+pub const DT_REG: c_uchar = 8;
+pub const DT_LNK: c_uchar = 10;
+pub const DT_SOCK: c_uchar = 12;
+
 #[repr(C)]
 pub struct dirent {
     pub d_ino: ino_t,
@@ -197,12 +274,47 @@ pub struct dirent {
 #[repr(C)]
 #[derive(Default)]
 pub struct winsize {
-    ws_row: c_ushort,
-    ws_col: c_ushort,
-    ws_xpixel: c_ushort,
-    ws_ypixel: c_ushort,
+    pub ws_row: c_ushort,
+    pub ws_col: c_ushort,
+    pub ws_xpixel: c_ushort,
+    pub ws_ypixel: c_ushort,
+}
+
+pub const TIOCGWINSZ: c_ulong = 0x5413;
+pub const TIOCSWINSZ: c_ulong = 0x5414;
+pub const FIONREAD: c_ulong = 0x541B;
+pub const FIONBIO: c_ulong = 0x5421;
+
+pub const PRIO_PROCESS: c_int = 0;
+pub const PRIO_PGRP: c_int = 1;
+pub const PRIO_USER: c_int = 2;
+
+pub type rlim_t = u64;
+
+pub const RLIM_INFINITY: rlim_t = !0;
+
+pub const RLIMIT_CPU: c_int = 0;
+pub const RLIMIT_FSIZE: c_int = 1;
+pub const RLIMIT_DATA: c_int = 2;
+pub const RLIMIT_STACK: c_int = 3;
+pub const RLIMIT_CORE: c_int = 4;
+pub const RLIMIT_RSS: c_int = 5;
+pub const RLIMIT_NPROC: c_int = 6;
+pub const RLIMIT_NOFILE: c_int = 7;
+pub const RLIMIT_MEMLOCK: c_int = 8;
+pub const RLIMIT_AS: c_int = 9;
+pub const RLIM_NLIMITS: c_int = 10;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct rlimit {
+    pub rlim_cur: rlim_t,
+    pub rlim_max: rlim_t,
 }
 
+pub const RUSAGE_SELF: c_int = 0;
+pub const RUSAGE_CHILDREN: c_int = -1;
+
 #[repr(C)]
 pub struct rusage {
     pub ru_utime: timeval,
@@ -225,10 +337,10 @@ pub struct rusage {
 
 #[repr(C)]
 pub struct tms {
-    tms_utime: clock_t,
-    tms_stime: clock_t,
-    tms_cutime: clock_t,
-    tms_cstime: clock_t,
+    pub tms_utime: clock_t,
+    pub tms_stime: clock_t,
+    pub tms_cutime: clock_t,
+    pub tms_cstime: clock_t,
 }
 
 pub const FD_SETSIZE: usize = 1024;
@@ -237,11 +349,62 @@ pub struct fd_set {
     pub fds_bits: [c_ulong; FD_SETSIZE / (8 * mem::size_of::<c_ulong>())],
 }
 
+pub const F_DUPFD_CLOEXEC: c_int = 1030;
+
+pub const FD_CLOEXEC: c_int = 1;
+
+pub const F_GETLK: c_int = 5;
+pub const F_SETLK: c_int = 6;
+pub const F_SETLKW: c_int = 7;
+
+pub const F_RDLCK: c_int = 0;
+pub const F_WRLCK: c_int = 1;
+pub const F_UNLCK: c_int = 2;
+
+#[repr(C)]
+pub struct flock {
+    pub l_type: c_short,
+    pub l_whence: c_short,
+    pub l_start: off_t,
+    pub l_len: off_t,
+    pub l_pid: pid_t,
+}
+
+pub type nfds_t = c_ulong;
+#[repr(C)]
+pub struct pollfd {
+    pub fd: c_int,
+    pub events: c_short,
+    pub revents: c_short,
+}
+
+#[repr(C)]
+pub struct iovec {
+    pub iov_base: *mut c_void,
+    pub iov_len: size_t,
+}
+
+pub const POLLIN: c_short = 0x001;
+pub const POLLPRI: c_short = 0x002;
+pub const POLLOUT: c_short = 0x004;
+pub const POLLERR: c_short = 0x008;
+pub const POLLHUP: c_short = 0x010;
+pub const POLLNVAL: c_short = 0x020;
+
 pub const F_OK: c_int = 0;
 pub const R_OK: c_int = 4;
 pub const W_OK: c_int = 2;
 pub const X_OK: c_int = 1;
 
+pub const AT_FDCWD: c_int = -100;
+pub const AT_SYMLINK_NOFOLLOW: c_int = 0x100;
+pub const AT_REMOVEDIR: c_int = 0x200;
+pub const AT_EACCESS: c_int = 0x200;
+
+pub const TCSANOW: c_int = 0;
+pub const TCSADRAIN: c_int = 1;
+pub const TCSAFLUSH: c_int = 2;
+
 pub type cc_t = u8;
 pub type speed_t = u32;
 pub type tcflag_t = u32;
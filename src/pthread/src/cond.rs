@@ -0,0 +1,136 @@
+//! pthread_cond implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/pthread_cond_wait.html
+
+use core::ptr;
+use core::sync::atomic::Ordering;
+
+use errno;
+use mutex::{pthread_mutex_t, relock, unlock_all};
+use platform;
+use platform::types::*;
+use {atomic, futex_wait, futex_wake};
+
+/// All zeroes is a condition variable nobody waits on, which is what PTHREAD_COND_INITIALIZER gives
+#[repr(C)]
+pub struct pthread_cond_t {
+    // Bumped on every signal and broadcast, so a thread about to wait can tell that it missed one
+    pub seq: c_int,
+}
+
+#[repr(C)]
+pub struct pthread_condattr_t {
+    pub unused: c_int,
+}
+
+/// How long is left until `abstime` on the realtime clock, if it hasn't passed yet
+fn remaining(abstime: &timespec) -> Option<timespec> {
+    let mut now = timeval::default();
+    platform::gettimeofday(&mut now, ptr::null_mut());
+
+    let mut sec = abstime.tv_sec - now.tv_sec;
+    let mut nsec = abstime.tv_nsec - now.tv_usec as c_long * 1000;
+    if nsec < 0 {
+        sec -= 1;
+        nsec += 1_000_000_000;
+    }
+    if sec < 0 || (sec == 0 && nsec == 0) {
+        return None;
+    }
+    Some(timespec {
+        tv_sec: sec,
+        tv_nsec: nsec,
+    })
+}
+
+unsafe fn wait(
+    cond: *mut pthread_cond_t,
+    mutex: *mut pthread_mutex_t,
+    abstime: *const timespec,
+) -> c_int {
+    if !abstime.is_null() && ((*abstime).tv_nsec < 0 || (*abstime).tv_nsec >= 1_000_000_000) {
+        return errno::EINVAL;
+    }
+
+    // Read before unlocking, so that a signal sent right after that still wakes this thread
+    let addr = &mut (*cond).seq as *mut c_int;
+    let seq = atomic(addr).load(Ordering::SeqCst);
+    let count = match unlock_all(mutex) {
+        Ok(count) => count,
+        Err(err) => return err,
+    };
+
+    let mut status = 0;
+    if abstime.is_null() {
+        futex_wait(addr, seq, ptr::null());
+    } else {
+        match remaining(&*abstime) {
+            Some(timeout) => {
+                if futex_wait(addr, seq, &timeout) == errno::ETIMEDOUT {
+                    status = errno::ETIMEDOUT;
+                }
+            }
+            None => status = errno::ETIMEDOUT,
+        }
+    }
+
+    // Waking up for no reason is allowed, which callers deal with by checking their predicate
+    relock(mutex, count);
+    status
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_broadcast(cond: *mut pthread_cond_t) -> c_int {
+    let addr = &mut (*cond).seq as *mut c_int;
+    atomic(addr).fetch_add(1, Ordering::SeqCst);
+    futex_wake(addr, c_int::max_value());
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_cond_destroy(_cond: *mut pthread_cond_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_init(
+    cond: *mut pthread_cond_t,
+    _attr: *const pthread_condattr_t,
+) -> c_int {
+    ptr::write(cond, pthread_cond_t { seq: 0 });
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_signal(cond: *mut pthread_cond_t) -> c_int {
+    let addr = &mut (*cond).seq as *mut c_int;
+    atomic(addr).fetch_add(1, Ordering::SeqCst);
+    futex_wake(addr, 1);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_timedwait(
+    cond: *mut pthread_cond_t,
+    mutex: *mut pthread_mutex_t,
+    abstime: *const timespec,
+) -> c_int {
+    wait(cond, mutex, abstime)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_cond_wait(
+    cond: *mut pthread_cond_t,
+    mutex: *mut pthread_mutex_t,
+) -> c_int {
+    wait(cond, mutex, ptr::null())
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_condattr_destroy(_attr: *mut pthread_condattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_condattr_init(attr: *mut pthread_condattr_t) -> c_int {
+    (*attr).unused = 0;
+    0
+}
@@ -0,0 +1,143 @@
+//! pthread_key implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/pthread_key_create.html
+
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use errno;
+use mutex::{pthread_mutex_lock, pthread_mutex_t, pthread_mutex_unlock, PTHREAD_MUTEX_NORMAL};
+use platform::types::*;
+
+pub const PTHREAD_KEYS_MAX: c_int = 128;
+pub const PTHREAD_DESTRUCTOR_ITERATIONS: c_int = 4;
+
+pub type pthread_key_t = c_uint;
+
+#[derive(Clone, Copy)]
+struct Key {
+    // Bumped whenever the key is created or deleted, so it's in use while this is odd. A value set
+    // under an older one belongs to a deleted key and doesn't count.
+    seq: usize,
+    destructor: Option<extern "C" fn(value: *mut c_void)>,
+}
+
+static mut KEYS: [Key; PTHREAD_KEYS_MAX as usize] = [Key {
+    seq: 0,
+    destructor: None,
+}; PTHREAD_KEYS_MAX as usize];
+
+static mut KEYS_LOCK: pthread_mutex_t = pthread_mutex_t {
+    lock: 0,
+    kind: PTHREAD_MUTEX_NORMAL,
+    count: 0,
+    owner: ptr::null_mut(),
+};
+
+// The calling thread's values, along with the seq of the key they were set under
+#[thread_local]
+static mut VALUES: [(usize, *mut c_void); PTHREAD_KEYS_MAX as usize] =
+    [(0, ptr::null_mut()); PTHREAD_KEYS_MAX as usize];
+
+/// The seq of the key at `i`, which is only changed with KEYS_LOCK held but can be read without it
+unsafe fn seq<'a>(i: usize) -> &'a AtomicUsize {
+    &*(&KEYS[i].seq as *const usize as *const AtomicUsize)
+}
+
+/// The seq of `key`, if it is in use
+unsafe fn key_seq(key: pthread_key_t) -> Option<usize> {
+    if key >= PTHREAD_KEYS_MAX as pthread_key_t {
+        return None;
+    }
+    let seq = seq(key as usize).load(Ordering::SeqCst);
+    if seq % 2 == 1 {
+        Some(seq)
+    } else {
+        None
+    }
+}
+
+/// Calls the destructors of the calling thread's values, for as long as they keep setting new ones
+/// or PTHREAD_DESTRUCTOR_ITERATIONS runs out
+pub(crate) unsafe fn run_destructors() {
+    for _ in 0..PTHREAD_DESTRUCTOR_ITERATIONS {
+        pthread_mutex_lock(&mut KEYS_LOCK);
+        let keys = KEYS;
+        pthread_mutex_unlock(&mut KEYS_LOCK);
+
+        let mut ran = false;
+        for (i, key) in keys.iter().enumerate() {
+            let (seq, value) = VALUES[i];
+            if value.is_null() || seq != key.seq {
+                continue;
+            }
+            if let Some(destructor) = key.destructor {
+                VALUES[i] = (0, ptr::null_mut());
+                destructor(value);
+                ran = true;
+            }
+        }
+        if !ran {
+            break;
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_getspecific(key: pthread_key_t) -> *mut c_void {
+    match key_seq(key) {
+        Some(seq) if VALUES[key as usize].0 == seq => VALUES[key as usize].1,
+        _ => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_key_create(
+    key: *mut pthread_key_t,
+    destructor: Option<extern "C" fn(value: *mut c_void)>,
+) -> c_int {
+    pthread_mutex_lock(&mut KEYS_LOCK);
+    let free = KEYS.iter().position(|key| key.seq % 2 == 0);
+    if let Some(i) = free {
+        KEYS[i].destructor = destructor;
+        seq(i).fetch_add(1, Ordering::SeqCst);
+        *key = i as pthread_key_t;
+    }
+    pthread_mutex_unlock(&mut KEYS_LOCK);
+
+    match free {
+        Some(_) => 0,
+        None => errno::EAGAIN,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_key_delete(key: pthread_key_t) -> c_int {
+    if key >= PTHREAD_KEYS_MAX as pthread_key_t {
+        return errno::EINVAL;
+    }
+
+    // The values other threads still have are left alone, and no destructors get called
+    pthread_mutex_lock(&mut KEYS_LOCK);
+    let in_use = KEYS[key as usize].seq % 2 == 1;
+    if in_use {
+        seq(key as usize).fetch_add(1, Ordering::SeqCst);
+        KEYS[key as usize].destructor = None;
+    }
+    pthread_mutex_unlock(&mut KEYS_LOCK);
+
+    if in_use {
+        0
+    } else {
+        errno::EINVAL
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_setspecific(key: pthread_key_t, value: *const c_void) -> c_int {
+    match key_seq(key) {
+        Some(seq) => {
+            VALUES[key as usize] = (seq, value as *mut c_void);
+            0
+        }
+        None => errno::EINVAL,
+    }
+}
@@ -0,0 +1,373 @@
+//! pthread implementation for relibc, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/pthread.h.html
+
+#![no_std]
+#![feature(integer_atomics, thread_local)]
+
+extern crate errno;
+extern crate platform;
+extern crate sys_mman;
+
+use core::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+use core::{mem, ptr};
+use platform::types::*;
+use sys_mman::{MAP_ANON, MAP_PRIVATE, PROT_READ, PROT_WRITE};
+
+pub use cond::*;
+pub use key::*;
+pub use mutex::*;
+pub use once::*;
+pub use rwlock::*;
+
+mod cond;
+mod key;
+mod mutex;
+mod once;
+mod rwlock;
+
+pub const PTHREAD_CREATE_JOINABLE: c_int = 0;
+pub const PTHREAD_CREATE_DETACHED: c_int = 1;
+
+pub const PTHREAD_STACK_MIN: size_t = 16384;
+
+// The stack size when the attributes don't give one, the same as Rust's
+const DEFAULT_STACK_SIZE: size_t = 2 * 1024 * 1024;
+
+pub type pthread_t = *mut c_void;
+
+#[repr(C)]
+pub struct pthread_attr_t {
+    pub detachstate: c_int,
+    pub stacksize: size_t,
+}
+
+// What a thread's `state` can be. Whichever of pthread_detach and the thread exiting comes second
+// is the one that has to clean up.
+const JOINABLE: usize = 0;
+const DETACHED: usize = 1;
+const EXITED: usize = 2;
+
+// What a thread's `tid` is until the thread or the kernel fills it in. Joining it before then has
+// to wait, not take the 0 for a thread that is gone.
+const NO_TID: pid_t = -1;
+
+struct Thread {
+    // Filled in when the thread starts, and how platform::wait_thread tells that it's gone
+    tid: pid_t,
+    state: AtomicUsize,
+    start: Option<extern "C" fn(arg: *mut c_void) -> *mut c_void>,
+    arg: *mut c_void,
+    retval: *mut c_void,
+    stack: *mut c_void,
+    stack_size: size_t,
+    tls: *mut c_void,
+    // The next exited detached thread in FINISHED
+    next: *mut Thread,
+}
+
+// The thread that runs main(), which the kernel gave its stack and TLS block
+static mut MAIN_THREAD: Thread = Thread {
+    tid: 0,
+    state: AtomicUsize::new(JOINABLE),
+    start: None,
+    arg: ptr::null_mut(),
+    retval: ptr::null_mut(),
+    stack: ptr::null_mut(),
+    stack_size: 0,
+    tls: ptr::null_mut(),
+    next: ptr::null_mut(),
+};
+
+#[thread_local]
+static mut CURRENT: *mut Thread = ptr::null_mut();
+
+// How many threads haven't exited yet. The process ends when the last one does.
+static THREADS: AtomicUsize = AtomicUsize::new(1);
+
+// Detached threads that have exited, which can be freed once they aren't running on their stacks
+static FINISHED: AtomicUsize = AtomicUsize::new(0);
+
+/// The futex word at `addr`, which lives in a C struct as a plain int
+unsafe fn atomic<'a>(addr: *mut c_int) -> &'a AtomicI32 {
+    &*(addr as *const AtomicI32)
+}
+
+/// Waits on a futex, returning the error number instead of clobbering errno with it
+unsafe fn futex_wait(addr: *mut c_int, val: c_int, timeout: *const timespec) -> c_int {
+    let saved = platform::errno;
+    let status = if platform::futex_wait(addr, val, timeout) < 0 {
+        platform::errno
+    } else {
+        0
+    };
+    platform::errno = saved;
+    status
+}
+
+unsafe fn futex_wake(addr: *mut c_int, count: c_int) {
+    let saved = platform::errno;
+    platform::futex_wake(addr, count);
+    platform::errno = saved;
+}
+
+unsafe fn current() -> *mut Thread {
+    if CURRENT.is_null() {
+        // Every other thread sets this as soon as it starts
+        MAIN_THREAD.tid = platform::getpid();
+        CURRENT = &mut MAIN_THREAD;
+    }
+    CURRENT
+}
+
+unsafe fn push_finished(thread: *mut Thread) {
+    let mut head = FINISHED.load(Ordering::SeqCst);
+    loop {
+        (*thread).next = head as *mut Thread;
+        let old = FINISHED.compare_and_swap(head, thread as usize, Ordering::SeqCst);
+        if old == head {
+            break;
+        }
+        head = old;
+    }
+}
+
+/// Frees the stack and TLS block of a thread that is gone
+unsafe fn release(thread: *mut Thread) {
+    if thread == &mut MAIN_THREAD as *mut Thread {
+        return;
+    }
+    platform::munmap((*thread).stack, (*thread).stack_size);
+    platform::tls_free((*thread).tls);
+    platform::free(thread as *mut c_void);
+}
+
+/// Cleans up after the detached threads that are gone by now
+unsafe fn reap() {
+    let mut thread = FINISHED.swap(0, Ordering::SeqCst) as *mut Thread;
+    while !thread.is_null() {
+        let next = (*thread).next;
+        if platform::wait_thread(&mut (*thread).tid, false) {
+            release(thread);
+        } else {
+            push_finished(thread);
+        }
+        thread = next;
+    }
+}
+
+unsafe fn exit_current(retval: *mut c_void) -> ! {
+    extern "C" {
+        fn exit(status: c_int) -> !;
+    }
+
+    let thread = current();
+    (*thread).retval = retval;
+    key::run_destructors();
+
+    if THREADS.fetch_sub(1, Ordering::SeqCst) == 1 {
+        // Leaving no threads behind is the same as calling exit, atexit handlers and all
+        exit(0);
+    }
+
+    if (*thread).state.compare_and_swap(JOINABLE, EXITED, Ordering::SeqCst) == DETACHED {
+        push_finished(thread);
+    }
+    platform::exit_thread()
+}
+
+extern "C" fn thread_start(thread: *mut c_void) -> ! {
+    unsafe {
+        let thread = thread as *mut Thread;
+        CURRENT = thread;
+        let start = (*thread).start.unwrap();
+        exit_current(start((*thread).arg))
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_init(attr: *mut pthread_attr_t) -> c_int {
+    (*attr).detachstate = PTHREAD_CREATE_JOINABLE;
+    (*attr).stacksize = DEFAULT_STACK_SIZE;
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_attr_destroy(_attr: *mut pthread_attr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getdetachstate(
+    attr: *const pthread_attr_t,
+    detachstate: *mut c_int,
+) -> c_int {
+    *detachstate = (*attr).detachstate;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setdetachstate(
+    attr: *mut pthread_attr_t,
+    detachstate: c_int,
+) -> c_int {
+    if detachstate != PTHREAD_CREATE_JOINABLE && detachstate != PTHREAD_CREATE_DETACHED {
+        return errno::EINVAL;
+    }
+    (*attr).detachstate = detachstate;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getstacksize(
+    attr: *const pthread_attr_t,
+    stacksize: *mut size_t,
+) -> c_int {
+    *stacksize = (*attr).stacksize;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setstacksize(
+    attr: *mut pthread_attr_t,
+    stacksize: size_t,
+) -> c_int {
+    if stacksize < PTHREAD_STACK_MIN {
+        return errno::EINVAL;
+    }
+    (*attr).stacksize = stacksize;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_create(
+    thread: *mut pthread_t,
+    attr: *const pthread_attr_t,
+    start_routine: extern "C" fn(arg: *mut c_void) -> *mut c_void,
+    arg: *mut c_void,
+) -> c_int {
+    reap();
+
+    let (detachstate, stack_size) = if attr.is_null() {
+        (PTHREAD_CREATE_JOINABLE, DEFAULT_STACK_SIZE)
+    } else {
+        ((*attr).detachstate, (*attr).stacksize)
+    };
+
+    let stack = platform::mmap(
+        ptr::null_mut(),
+        stack_size,
+        PROT_READ | PROT_WRITE,
+        MAP_PRIVATE | MAP_ANON,
+        -1,
+        0,
+    );
+    if stack as isize == -1 {
+        return errno::EAGAIN;
+    }
+    let tls = match platform::tls_new() {
+        Ok(tls) => tls,
+        Err(()) => {
+            platform::munmap(stack, stack_size);
+            return errno::EAGAIN;
+        }
+    };
+    let new = platform::alloc(mem::size_of::<Thread>()) as *mut Thread;
+    if new.is_null() {
+        platform::munmap(stack, stack_size);
+        platform::tls_free(tls);
+        return errno::EAGAIN;
+    }
+    ptr::write(
+        new,
+        Thread {
+            tid: NO_TID,
+            state: AtomicUsize::new(if detachstate == PTHREAD_CREATE_DETACHED {
+                DETACHED
+            } else {
+                JOINABLE
+            }),
+            start: Some(start_routine),
+            arg: arg,
+            retval: ptr::null_mut(),
+            stack: stack,
+            stack_size: stack_size,
+            tls: tls,
+            next: ptr::null_mut(),
+        },
+    );
+
+    // The new thread pops the function to run and its argument off the top of its stack
+    let top = ((stack as usize + stack_size) & !15) as *mut usize;
+    let sp = top.offset(-2);
+    *sp = thread_start as usize;
+    *sp.offset(1) = new as usize;
+
+    // The new thread may well look for itself in here before this returns
+    *thread = new as pthread_t;
+
+    THREADS.fetch_add(1, Ordering::SeqCst);
+    if platform::clone_thread(sp, tls, &mut (*new).tid) < 0 {
+        THREADS.fetch_sub(1, Ordering::SeqCst);
+        release(new);
+        return errno::EAGAIN;
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_detach(thread: pthread_t) -> c_int {
+    let thread = thread as *mut Thread;
+    match (*thread)
+        .state
+        .compare_and_swap(JOINABLE, DETACHED, Ordering::SeqCst)
+    {
+        JOINABLE => 0,
+        EXITED => {
+            // Nobody is going to join it now
+            if !platform::wait_thread(&mut (*thread).tid, true) {
+                return errno::ESRCH;
+            }
+            release(thread);
+            0
+        }
+        _ => errno::EINVAL,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_equal(t1: pthread_t, t2: pthread_t) -> c_int {
+    (t1 == t2) as c_int
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_exit(retval: *mut c_void) -> ! {
+    exit_current(retval)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_join(thread: pthread_t, retval: *mut *mut c_void) -> c_int {
+    let thread = thread as *mut Thread;
+    if thread == current() {
+        return errno::EDEADLK;
+    }
+    // Nothing clears the main thread's tid when it exits, so waiting for it would never end
+    if thread == &mut MAIN_THREAD as *mut Thread {
+        return errno::ESRCH;
+    }
+    if (*thread).state.load(Ordering::SeqCst) == DETACHED {
+        return errno::EINVAL;
+    }
+
+    if !platform::wait_thread(&mut (*thread).tid, true) {
+        return errno::ESRCH;
+    }
+    if !retval.is_null() {
+        *retval = (*thread).retval;
+    }
+    release(thread);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_self() -> pthread_t {
+    current() as pthread_t
+}
@@ -0,0 +1,193 @@
+//! pthread_mutex implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/pthread_mutex_lock.html
+
+use core::ptr;
+use core::sync::atomic::Ordering;
+
+use errno;
+use platform::types::*;
+use {atomic, current, futex_wait, futex_wake, pthread_t};
+
+pub const PTHREAD_MUTEX_NORMAL: c_int = 0;
+pub const PTHREAD_MUTEX_RECURSIVE: c_int = 1;
+pub const PTHREAD_MUTEX_ERRORCHECK: c_int = 2;
+pub const PTHREAD_MUTEX_DEFAULT: c_int = PTHREAD_MUTEX_NORMAL;
+
+// What the lock word of a mutex can be. Once it's contended, whoever unlocks it has to wake up one
+// of the threads that might be waiting for it.
+const UNLOCKED: c_int = 0;
+const LOCKED: c_int = 1;
+const CONTENDED: c_int = 2;
+
+/// All zeroes is an unlocked normal mutex, which is what PTHREAD_MUTEX_INITIALIZER gives
+#[repr(C)]
+pub struct pthread_mutex_t {
+    pub lock: c_int,
+    pub kind: c_int,
+    // How many more times the owner of a recursive mutex has locked it
+    pub count: c_int,
+    pub owner: pthread_t,
+}
+
+#[repr(C)]
+pub struct pthread_mutexattr_t {
+    pub kind: c_int,
+}
+
+unsafe fn lock(mutex: *mut pthread_mutex_t) {
+    let addr = &mut (*mutex).lock as *mut c_int;
+    let mut state = atomic(addr).compare_and_swap(UNLOCKED, LOCKED, Ordering::Acquire);
+    if state == UNLOCKED {
+        return;
+    }
+
+    if state != CONTENDED {
+        state = atomic(addr).swap(CONTENDED, Ordering::Acquire);
+    }
+    while state != UNLOCKED {
+        futex_wait(addr, CONTENDED, ptr::null());
+        state = atomic(addr).swap(CONTENDED, Ordering::Acquire);
+    }
+}
+
+unsafe fn unlock(mutex: *mut pthread_mutex_t) {
+    let addr = &mut (*mutex).lock as *mut c_int;
+    if atomic(addr).swap(UNLOCKED, Ordering::Release) == CONTENDED {
+        futex_wake(addr, 1);
+    }
+}
+
+/// Unlocks a mutex the calling thread holds, however many times it locked it, so that
+/// pthread_cond_wait can wait. Returns what relock needs to put it back the way it was.
+pub(crate) unsafe fn unlock_all(mutex: *mut pthread_mutex_t) -> Result<c_int, c_int> {
+    if (*mutex).kind != PTHREAD_MUTEX_NORMAL && (*mutex).owner != current() as pthread_t {
+        return Err(errno::EPERM);
+    }
+
+    let count = (*mutex).count;
+    (*mutex).count = 0;
+    (*mutex).owner = ptr::null_mut();
+    unlock(mutex);
+    Ok(count)
+}
+
+pub(crate) unsafe fn relock(mutex: *mut pthread_mutex_t, count: c_int) {
+    lock(mutex);
+    (*mutex).owner = current() as pthread_t;
+    (*mutex).count = count;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_destroy(mutex: *mut pthread_mutex_t) -> c_int {
+    if atomic(&mut (*mutex).lock).load(Ordering::SeqCst) != UNLOCKED {
+        return errno::EBUSY;
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_init(
+    mutex: *mut pthread_mutex_t,
+    attr: *const pthread_mutexattr_t,
+) -> c_int {
+    ptr::write(
+        mutex,
+        pthread_mutex_t {
+            lock: UNLOCKED,
+            kind: if attr.is_null() {
+                PTHREAD_MUTEX_DEFAULT
+            } else {
+                (*attr).kind
+            },
+            count: 0,
+            owner: ptr::null_mut(),
+        },
+    );
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_lock(mutex: *mut pthread_mutex_t) -> c_int {
+    // Only the calling thread could have made itself the owner, so this can't change under it
+    let this = current() as pthread_t;
+    if (*mutex).owner == this {
+        match (*mutex).kind {
+            PTHREAD_MUTEX_RECURSIVE => {
+                (*mutex).count += 1;
+                return 0;
+            }
+            PTHREAD_MUTEX_ERRORCHECK => return errno::EDEADLK,
+            _ => (),
+        }
+    }
+
+    lock(mutex);
+    (*mutex).owner = this;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_trylock(mutex: *mut pthread_mutex_t) -> c_int {
+    let this = current() as pthread_t;
+    if (*mutex).kind == PTHREAD_MUTEX_RECURSIVE && (*mutex).owner == this {
+        (*mutex).count += 1;
+        return 0;
+    }
+
+    let addr = &mut (*mutex).lock as *mut c_int;
+    if atomic(addr).compare_and_swap(UNLOCKED, LOCKED, Ordering::Acquire) != UNLOCKED {
+        return errno::EBUSY;
+    }
+    (*mutex).owner = this;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutex_unlock(mutex: *mut pthread_mutex_t) -> c_int {
+    if (*mutex).kind != PTHREAD_MUTEX_NORMAL {
+        if (*mutex).owner != current() as pthread_t {
+            return errno::EPERM;
+        }
+        if (*mutex).count > 0 {
+            (*mutex).count -= 1;
+            return 0;
+        }
+    }
+
+    (*mutex).owner = ptr::null_mut();
+    unlock(mutex);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_mutexattr_destroy(_attr: *mut pthread_mutexattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_gettype(
+    attr: *const pthread_mutexattr_t,
+    kind: *mut c_int,
+) -> c_int {
+    *kind = (*attr).kind;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_init(attr: *mut pthread_mutexattr_t) -> c_int {
+    (*attr).kind = PTHREAD_MUTEX_DEFAULT;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_mutexattr_settype(
+    attr: *mut pthread_mutexattr_t,
+    kind: c_int,
+) -> c_int {
+    match kind {
+        PTHREAD_MUTEX_NORMAL | PTHREAD_MUTEX_RECURSIVE | PTHREAD_MUTEX_ERRORCHECK => {
+            (*attr).kind = kind;
+            0
+        }
+        _ => errno::EINVAL,
+    }
+}
@@ -0,0 +1,44 @@
+//! pthread_once implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/pthread_once.html
+
+use core::ptr;
+use core::sync::atomic::Ordering;
+
+use platform::types::*;
+use {atomic, futex_wait, futex_wake};
+
+/// Zero, which is what PTHREAD_ONCE_INIT gives, means the routine hasn't been run yet
+pub type pthread_once_t = c_int;
+
+// What a pthread_once_t goes through. Threads that find the routine running wait on it, and set
+// WAITING first so the thread running it knows to wake them up.
+const INCOMPLETE: c_int = 0;
+const RUNNING: c_int = 1;
+const WAITING: c_int = 2;
+const COMPLETE: c_int = 3;
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_once(
+    once_control: *mut pthread_once_t,
+    init_routine: extern "C" fn(),
+) -> c_int {
+    let state = atomic(once_control);
+    loop {
+        match state.compare_and_swap(INCOMPLETE, RUNNING, Ordering::SeqCst) {
+            INCOMPLETE => {
+                init_routine();
+                if state.swap(COMPLETE, Ordering::SeqCst) == WAITING {
+                    futex_wake(once_control, c_int::max_value());
+                }
+                return 0;
+            }
+            RUNNING => {
+                state.compare_and_swap(RUNNING, WAITING, Ordering::SeqCst);
+                futex_wait(once_control, WAITING, ptr::null());
+            }
+            WAITING => {
+                futex_wait(once_control, WAITING, ptr::null());
+            }
+            _ => return 0,
+        }
+    }
+}
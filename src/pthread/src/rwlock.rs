@@ -0,0 +1,141 @@
+//! pthread_rwlock implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/pthread_rwlock_rdlock.html
+//!
+//! Writers are preferred: once a writer is waiting, new readers wait for it too, so a steady
+//! stream of readers can't keep a writer out forever. The flip side is that a thread holding a
+//! read lock can deadlock by taking another one while a writer waits.
+
+use core::{mem, ptr};
+
+use cond::{pthread_cond_broadcast, pthread_cond_signal, pthread_cond_t, pthread_cond_wait};
+use errno;
+use mutex::{pthread_mutex_lock, pthread_mutex_t, pthread_mutex_unlock};
+use platform::types::*;
+
+/// All zeroes is an unlocked rwlock, which is what PTHREAD_RWLOCK_INITIALIZER gives
+#[repr(C)]
+pub struct pthread_rwlock_t {
+    // Protects the rest of the fields
+    pub lock: pthread_mutex_t,
+    pub readers: c_int,
+    pub writer: c_int,
+    pub waiting_writers: c_int,
+    pub readers_cond: pthread_cond_t,
+    pub writers_cond: pthread_cond_t,
+}
+
+#[repr(C)]
+pub struct pthread_rwlockattr_t {
+    pub unused: c_int,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_destroy(rwlock: *mut pthread_rwlock_t) -> c_int {
+    pthread_mutex_lock(&mut (*rwlock).lock);
+    let busy = (*rwlock).readers > 0 || (*rwlock).writer != 0;
+    pthread_mutex_unlock(&mut (*rwlock).lock);
+
+    if busy {
+        errno::EBUSY
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_init(
+    rwlock: *mut pthread_rwlock_t,
+    _attr: *const pthread_rwlockattr_t,
+) -> c_int {
+    ptr::write(rwlock, mem::zeroed());
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_rdlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    pthread_mutex_lock(&mut (*rwlock).lock);
+    while (*rwlock).writer != 0 || (*rwlock).waiting_writers > 0 {
+        pthread_cond_wait(&mut (*rwlock).readers_cond, &mut (*rwlock).lock);
+    }
+    (*rwlock).readers += 1;
+    pthread_mutex_unlock(&mut (*rwlock).lock);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_tryrdlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    pthread_mutex_lock(&mut (*rwlock).lock);
+    let busy = (*rwlock).writer != 0 || (*rwlock).waiting_writers > 0;
+    if !busy {
+        (*rwlock).readers += 1;
+    }
+    pthread_mutex_unlock(&mut (*rwlock).lock);
+
+    if busy {
+        errno::EBUSY
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_trywrlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    pthread_mutex_lock(&mut (*rwlock).lock);
+    let busy = (*rwlock).writer != 0 || (*rwlock).readers > 0;
+    if !busy {
+        (*rwlock).writer = 1;
+    }
+    pthread_mutex_unlock(&mut (*rwlock).lock);
+
+    if busy {
+        errno::EBUSY
+    } else {
+        0
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_unlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    pthread_mutex_lock(&mut (*rwlock).lock);
+    if (*rwlock).writer != 0 {
+        (*rwlock).writer = 0;
+    } else if (*rwlock).readers > 0 {
+        (*rwlock).readers -= 1;
+    } else {
+        pthread_mutex_unlock(&mut (*rwlock).lock);
+        return errno::EPERM;
+    }
+
+    if (*rwlock).readers == 0 {
+        if (*rwlock).waiting_writers > 0 {
+            pthread_cond_signal(&mut (*rwlock).writers_cond);
+        } else {
+            pthread_cond_broadcast(&mut (*rwlock).readers_cond);
+        }
+    }
+    pthread_mutex_unlock(&mut (*rwlock).lock);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlock_wrlock(rwlock: *mut pthread_rwlock_t) -> c_int {
+    pthread_mutex_lock(&mut (*rwlock).lock);
+    (*rwlock).waiting_writers += 1;
+    while (*rwlock).writer != 0 || (*rwlock).readers > 0 {
+        pthread_cond_wait(&mut (*rwlock).writers_cond, &mut (*rwlock).lock);
+    }
+    (*rwlock).waiting_writers -= 1;
+    (*rwlock).writer = 1;
+    pthread_mutex_unlock(&mut (*rwlock).lock);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn pthread_rwlockattr_destroy(_attr: *mut pthread_rwlockattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_rwlockattr_init(attr: *mut pthread_rwlockattr_t) -> c_int {
+    (*attr).unused = 0;
+    0
+}
@@ -8,20 +8,19 @@ extern crate errno;
 extern crate fcntl;
 extern crate platform;
 
-use alloc::vec::Vec;
-use core::ptr;
+use core::{ptr, str};
 use platform::types::*;
-use platform::RawFile;
+use platform::{RawFile, RawLines};
 
 #[repr(C)]
 pub struct passwd {
-    pw_name: *mut c_char,
-    pw_passwd: *mut c_char,
-    pw_uid: uid_t,
-    pw_gid: gid_t,
-    pw_gecos: *mut c_char,
-    pw_dir: *mut c_char,
-    pw_shell: *mut c_char,
+    pub pw_name: *mut c_char,
+    pub pw_passwd: *mut c_char,
+    pub pw_uid: uid_t,
+    pub pw_gid: gid_t,
+    pub pw_gecos: *mut c_char,
+    pub pw_dir: *mut c_char,
+    pub pw_shell: *mut c_char,
 }
 
 static mut PASSWD_BUF: *mut c_char = ptr::null_mut();
@@ -35,179 +34,171 @@ static mut PASSWD: passwd = passwd {
     pw_shell: ptr::null_mut(),
 };
 
+// The database getpwent() is walking through, opened on first use
+static mut PASSWD_DB: Option<RawLines> = None;
+
 enum OptionPasswd {
     Error,
     NotFound,
     Found(*mut c_char),
 }
 
-fn pwd_lookup<F>(
-    out: *mut passwd,
-    alloc: Option<(*mut c_char, size_t)>,
-    mut callback: F,
-) -> OptionPasswd
-where
-    // TODO F: FnMut(impl Iterator<Item = &[u8]>) -> bool
-    F: FnMut(&[&[u8]]) -> bool,
-{
-    let file = match RawFile::open(
+/// Opens /etc/passwd to be read one line at a time
+fn open_db() -> Result<RawLines, ()> {
+    let file = RawFile::open(
         "/etc/passwd\0".as_ptr() as *const c_char,
         fcntl::O_RDONLY,
         0,
-    ) {
-        Ok(file) => file,
-        Err(_) => return OptionPasswd::Error,
-    };
+    )?;
+    Ok(RawLines::new(file))
+}
 
-    let mut buf = Vec::new();
-    let mut newline = None;
+fn parse_id<T: str::FromStr>(part: &[u8]) -> Option<T> {
+    str::from_utf8(part).ok().and_then(|part| part.parse().ok())
+}
 
-    loop {
-        // TODO when nll becomes a thing:
-        // let mut newline;
+/// Splits an entry into its seven fields, or returns None if it is malformed
+fn split(line: &[u8]) -> Option<[&[u8]; 7]> {
+    let mut parts: [&[u8]; 7] = [&[]; 7];
+    let mut count = 0;
+    for (i, part) in line.splitn(7, |b| *b == b':').enumerate() {
+        parts[i] = part;
+        count += 1;
+    }
 
-        // WORKAROUND:
-        if let Some(newline) = newline {
-            buf.drain(..newline + 1);
+    if count < 7 || parse_id::<uid_t>(parts[2]).is_none() || parse_id::<gid_t>(parts[3]).is_none()
+    {
+        return None;
+    }
+    Some(parts)
+}
+
+fn fill(parts: &[&[u8]; 7], out: *mut passwd, alloc: Option<(*mut c_char, size_t)>) -> OptionPasswd {
+    let len = parts
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != 2 && *i != 3)
+        .map(|(_, part)| part.len() + 1)
+        .sum();
+
+    if alloc.map(|(_, s)| len > s as usize).unwrap_or(false) {
+        unsafe {
+            platform::errno = errno::ERANGE;
         }
+        return OptionPasswd::Error;
+    }
 
-        // Read until newline
-        loop {
-            newline = buf.iter().position(|b| *b == b'\n');
+    let alloc = match alloc {
+        Some((alloc, _)) => alloc,
+        None => unsafe { platform::alloc(len) as *mut c_char },
+    };
+    if alloc.is_null() {
+        unsafe {
+            platform::errno = errno::ENOMEM;
+        }
+        return OptionPasswd::Error;
+    }
+    // _ prefix so it won't complain about the trailing
+    // _off += <thing>
+    // in the macro that is never read
+    let mut _off = 0;
 
-            if newline.is_some() {
-                break;
-            }
+    let mut parts = parts.into_iter();
 
-            let len = buf.len();
+    macro_rules! copy_into {
+        ($entry:expr) => {
+            debug_assert!(_off as usize <= len);
 
-            if len >= buf.capacity() {
-                buf.reserve(1024);
-            }
+            let src = parts.next().unwrap_or(&(&[] as &[u8])); // this is madness
+            let dst = unsafe { alloc.offset(_off) };
 
-            unsafe {
-                let capacity = buf.capacity();
-                buf.set_len(capacity);
+            for (i, c) in src.iter().enumerate() {
+                unsafe {
+                    *dst.offset(i as isize) = *c as c_char;
+                }
             }
-
-            let read = platform::read(*file, &mut buf[len..]);
-
             unsafe {
-                buf.set_len(len + read as usize);
-            }
+                *dst.offset(src.len() as isize) = 0;
 
-            if read == 0 {
-                return OptionPasswd::NotFound;
-            }
-            if read < 0 {
-                return OptionPasswd::Error;
+                $entry = dst;
             }
-        }
-
-        // Parse into passwd
-        let newline = newline.unwrap(); // safe because it doesn't break the loop otherwise
-        let line = &buf[..newline];
-        let mut parts: [&[u8]; 7] = [&[]; 7];
-        for (i, part) in line.splitn(7, |b| *b == b':').enumerate() {
-            parts[i] = part;
-        }
-
-        if !callback(&parts) {
-            // TODO when nll becomes a thing:
-            // buf.drain(..newline + 1);
-            continue;
-        }
-
-        let len = parts
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| *i != 2 && *i != 3)
-            .map(|(_, part)| part.len() + 1)
-            .sum();
-
-        if alloc.map(|(_, s)| len > s as usize).unwrap_or(false) {
+            _off += src.len() as isize + 1;
+        };
+        ($entry:expr,parse) => {
             unsafe {
-                platform::errno = errno::ERANGE;
+                // split() made sure this is a number
+                $entry = parts.next().and_then(|part| parse_id(part)).unwrap_or(0);
             }
-            return OptionPasswd::Error;
-        }
-
-        let alloc = match alloc {
-            Some((alloc, _)) => alloc,
-            None => unsafe { platform::alloc(len) as *mut c_char },
         };
-        // _ prefix so it won't complain about the trailing
-        // _off += <thing>
-        // in the macro that is never read
-        let mut _off = 0;
-
-        let mut parts = parts.into_iter();
+    }
 
-        macro_rules! copy_into {
-            ($entry:expr) => {
-                debug_assert!(_off as usize <= len);
+    copy_into!((*out).pw_name);
+    copy_into!((*out).pw_passwd);
+    copy_into!((*out).pw_uid, parse);
+    copy_into!((*out).pw_gid, parse);
+    copy_into!((*out).pw_gecos);
+    copy_into!((*out).pw_dir);
+    copy_into!((*out).pw_shell);
 
-                let src = parts.next().unwrap_or(&(&[] as &[u8])); // this is madness
-                let dst = unsafe { alloc.offset(_off) };
+    OptionPasswd::Found(alloc)
+}
 
-                for (i, c) in src.iter().enumerate() {
-                    unsafe {
-                        *dst.offset(i as isize) = *c as c_char;
-                    }
-                }
-                unsafe {
-                    *dst.offset(src.len() as isize) = 0;
+/// Reads on from where `db` is until `callback` accepts an entry, skipping malformed lines
+fn pwd_search<F>(
+    db: &mut RawLines,
+    out: *mut passwd,
+    alloc: Option<(*mut c_char, size_t)>,
+    mut callback: F,
+) -> OptionPasswd
+where
+    F: FnMut(&[&[u8]; 7]) -> bool,
+{
+    loop {
+        let line = match db.next_line() {
+            Ok(Some(line)) => line,
+            Ok(None) => return OptionPasswd::NotFound,
+            Err(()) => return OptionPasswd::Error,
+        };
 
-                    $entry = dst;
-                }
-                _off += src.len() as isize + 1;
-            };
-            ($entry:expr,parse) => {
-                unsafe {
-                    $entry = parts
-                        .next()
-                        .and_then(|part| core::str::from_utf8(part).ok())
-                        .and_then(|part| part.parse().ok())
-                        .unwrap_or(0);
-                }
-            };
+        if let Some(parts) = split(&line) {
+            if callback(&parts) {
+                return fill(&parts, out, alloc);
+            }
         }
-
-        copy_into!((*out).pw_name);
-        copy_into!((*out).pw_passwd);
-        copy_into!((*out).pw_uid, parse);
-        copy_into!((*out).pw_gid, parse);
-        copy_into!((*out).pw_gecos);
-        copy_into!((*out).pw_dir);
-        copy_into!((*out).pw_shell);
-
-        return OptionPasswd::Found(alloc);
     }
 }
 
-#[no_mangle]
-pub extern "C" fn getpwnam_r(
-    name: *const c_char,
+fn pwd_lookup<F>(
     out: *mut passwd,
-    buf: *mut c_char,
-    size: size_t,
-    result: *mut *mut passwd,
-) -> c_int {
-    match pwd_lookup(out, Some((buf, size)), |parts| {
-        let part = parts.get(0).unwrap_or(&(&[] as &[u8]));
-        for (i, c) in part.iter().enumerate() {
-            // /etc/passwd should not contain any NUL bytes in the middle
-            // of entries, but if this happens, it can't possibly match the
-            // search query since it's NUL terminated.
-            if *c == 0 || unsafe { *name.offset(i as isize) } != *c as c_char {
-                return false;
-            }
-        }
-        true
-    }) {
+    alloc: Option<(*mut c_char, size_t)>,
+    callback: F,
+) -> OptionPasswd
+where
+    F: FnMut(&[&[u8]; 7]) -> bool,
+{
+    match open_db() {
+        Ok(mut db) => pwd_search(&mut db, out, alloc, callback),
+        Err(()) => OptionPasswd::Error,
+    }
+}
+
+fn name_matches(parts: &[&[u8]; 7], name: *const c_char) -> bool {
+    // /etc/passwd should not contain any NUL bytes in the middle of entries,
+    // but if this happens, it can't possibly match the search query since it's
+    // NUL terminated.
+    unsafe { platform::c_str(name) == parts[0] }
+}
+
+fn uid_matches(parts: &[&[u8]; 7], uid: uid_t) -> bool {
+    parse_id(parts[2]) == Some(uid)
+}
+
+/// Hands out the reentrant result, returning the error number like POSIX wants
+fn reentrant(res: OptionPasswd, out: *mut passwd, result: *mut *mut passwd) -> c_int {
+    match res {
         OptionPasswd::Error => unsafe {
             *result = ptr::null_mut();
-            -1
+            platform::errno
         },
         OptionPasswd::NotFound => unsafe {
             *result = ptr::null_mut();
@@ -220,6 +211,33 @@ pub extern "C" fn getpwnam_r(
     }
 }
 
+/// Hands out the static result, freeing the strings of the last one
+unsafe fn static_passwd(res: OptionPasswd) -> *mut passwd {
+    match res {
+        OptionPasswd::Error => ptr::null_mut(),
+        OptionPasswd::NotFound => ptr::null_mut(),
+        OptionPasswd::Found(buf) => {
+            if PASSWD_BUF != ptr::null_mut() {
+                platform::free(PASSWD_BUF as *mut c_void);
+            }
+            PASSWD_BUF = buf;
+            &mut PASSWD
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn getpwnam_r(
+    name: *const c_char,
+    out: *mut passwd,
+    buf: *mut c_char,
+    size: size_t,
+    result: *mut *mut passwd,
+) -> c_int {
+    let res = pwd_lookup(out, Some((buf, size)), |parts| name_matches(parts, name));
+    reentrant(res, out, result)
+}
+
 #[no_mangle]
 pub extern "C" fn getpwuid_r(
     uid: uid_t,
@@ -228,68 +246,52 @@ pub extern "C" fn getpwuid_r(
     size: size_t,
     result: *mut *mut passwd,
 ) -> c_int {
-    match pwd_lookup(out, Some((buf, size)), |parts| {
-        let part = parts
-            .get(2)
-            .and_then(|part| core::str::from_utf8(part).ok())
-            .and_then(|part| part.parse().ok());
-        part == Some(uid)
-    }) {
-        OptionPasswd::Error => unsafe {
-            *result = ptr::null_mut();
-            -1
-        },
-        OptionPasswd::NotFound => unsafe {
-            *result = ptr::null_mut();
-            0
-        },
-        OptionPasswd::Found(_) => unsafe {
-            *result = out;
-            0
-        },
-    }
+    let res = pwd_lookup(out, Some((buf, size)), |parts| uid_matches(parts, uid));
+    reentrant(res, out, result)
 }
 
 #[no_mangle]
 pub extern "C" fn getpwnam(name: *const c_char) -> *mut passwd {
-    match pwd_lookup(unsafe { &mut PASSWD }, None, |parts| {
-        let part = parts.get(0).unwrap_or(&(&[] as &[u8]));
-        for (i, c) in part.iter().enumerate() {
-            // /etc/passwd should not contain any NUL bytes in the middle
-            // of entries, but if this happens, it can't possibly match the
-            // search query since it's NUL terminated.
-            if *c == 0 || unsafe { *name.offset(i as isize) } != *c as c_char {
-                return false;
-            }
-        }
-        true
-    }) {
-        OptionPasswd::Error => ptr::null_mut(),
-        OptionPasswd::NotFound => ptr::null_mut(),
-        OptionPasswd::Found(buf) => unsafe {
-            PASSWD_BUF = buf;
-            &mut PASSWD
-        },
+    unsafe {
+        let res = pwd_lookup(&mut PASSWD, None, |parts| name_matches(parts, name));
+        static_passwd(res)
     }
 }
 
 #[no_mangle]
 pub extern "C" fn getpwuid(uid: uid_t) -> *mut passwd {
-    match pwd_lookup(unsafe { &mut PASSWD }, None, |parts| {
-        let part = parts
-            .get(2)
-            .and_then(|part| core::str::from_utf8(part).ok())
-            .and_then(|part| part.parse().ok());
-        part == Some(uid)
-    }) {
-        OptionPasswd::Error => ptr::null_mut(),
-        OptionPasswd::NotFound => ptr::null_mut(),
-        OptionPasswd::Found(buf) => unsafe {
-            if PASSWD_BUF != ptr::null_mut() {
-                platform::free(PASSWD_BUF as *mut c_void);
+    unsafe {
+        let res = pwd_lookup(&mut PASSWD, None, |parts| uid_matches(parts, uid));
+        static_passwd(res)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn getpwent() -> *mut passwd {
+    unsafe {
+        if PASSWD_DB.is_none() {
+            match open_db() {
+                Ok(db) => PASSWD_DB = Some(db),
+                Err(()) => return ptr::null_mut(),
             }
-            PASSWD_BUF = buf;
-            &mut PASSWD
-        },
+        }
+        let db = PASSWD_DB.as_mut().unwrap();
+        let res = pwd_search(db, &mut PASSWD, None, |_| true);
+        static_passwd(res)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn setpwent() {
+    // Reopening the file is the easiest way to start over
+    unsafe {
+        PASSWD_DB = None;
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn endpwent() {
+    unsafe {
+        PASSWD_DB = None;
     }
 }
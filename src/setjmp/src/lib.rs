@@ -1,8 +1,14 @@
 //! setjmp implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/setjmp.h.html
 
 #![no_std]
+#![allow(non_camel_case_types)]
 #![feature(global_asm)]
 
+extern crate platform;
+
+use core::ptr;
+use platform::types::*;
+
 macro_rules! platform_specific {
     ($($arch:expr,$ext:expr;)+) => {
         $(
@@ -31,3 +37,52 @@ platform_specific! {
     "x32","s";
     "x86_64","s";
 }
+
+// sigsetjmp is only there for the architectures Redox runs on
+macro_rules! sigsetjmp_specific {
+    ($($arch:expr,$ext:expr,$len:expr;)+) => {
+        $(
+            #[cfg(target_arch = $arch)]
+            global_asm!(include_str!(concat!("impl/", $arch, "/sigsetjmp.", $ext)));
+            #[cfg(target_arch = $arch)]
+            type jmp_buf = [c_ulong; $len];
+        )+
+    }
+}
+
+sigsetjmp_specific! {
+    "aarch64","s",22;
+    "x86_64","s",8;
+}
+
+/// The sigjmp_buf from setjmp.h. The assembly of sigsetjmp stores its return address in `fl`
+/// and uses the space after the signal mask in `ss` to keep a register around.
+#[repr(C)]
+pub struct sigjmp_buf {
+    jb: jmp_buf,
+    fl: c_ulong,
+    ss: [u8; 128],
+}
+
+extern "C" {
+    fn longjmp(buf: *mut c_ulong, value: c_int) -> !;
+}
+
+/// Where sigsetjmp ends up both times it returns: the first time (`ret` is 0) it saves the
+/// signal mask, after a siglongjmp it puts the saved mask back.
+#[no_mangle]
+pub unsafe extern "C" fn __sigsetjmp_tail(buf: *mut sigjmp_buf, ret: c_int) -> c_int {
+    let mask = (*buf).ss.as_mut_ptr() as *mut sigset_t;
+    if ret == 0 {
+        platform::sigprocmask(SIG_SETMASK, ptr::null(), mask);
+    } else {
+        platform::sigprocmask(SIG_SETMASK, mask, ptr::null_mut());
+    }
+    ret
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn siglongjmp(buf: *mut sigjmp_buf, value: c_int) -> ! {
+    // This returns into sigsetjmp, which restores the mask if it was asked to save it
+    longjmp((*buf).jb.as_mut_ptr(), value)
+}
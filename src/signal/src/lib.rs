@@ -29,32 +29,65 @@ pub const SIG_SETMASK: c_int = 2;
 #[repr(C)]
 #[derive(Clone)]
 pub struct sigaction {
+    // The handler is one of these depending on whether SA_SIGINFO is set. The C header makes
+    // sa_handler and sa_sigaction refer to the union members, like they would in an anonymous
+    // union.
+    pub __sa_handler: __sa_handler_union,
+    pub sa_flags: c_ulong,
+    pub sa_restorer: Option<unsafe extern "C" fn()>,
+    pub sa_mask: sigset_t,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union __sa_handler_union {
     // I don't actually want these to be optional. They can have more than just
     // one invalid value. But because of rust's non-null optimization, this
     // causes Some(sigaction) with a null sa_handler to become None.  Maybe
     // these should be usizes and transmuted when needed... However, then I
     // couldn't let cbindgen do its job.
     pub sa_handler: Option<extern "C" fn(c_int)>,
-    pub sa_flags: c_ulong,
-    pub sa_restorer: Option<unsafe extern "C" fn()>,
-    pub sa_mask: sigset_t,
+    pub sa_sigaction: Option<extern "C" fn(c_int, *mut siginfo_t, *mut c_void)>,
 }
 
 pub const NSIG: usize = 64;
 pub type sigset_t = c_ulong;
 
+/// Signal 0 is valid too, it only checks whether the process could be signalled
+fn is_valid_signal(sig: c_int) -> bool {
+    sig >= 0 && sig as usize <= SIGRTMAX
+}
+
 #[no_mangle]
 pub extern "C" fn kill(pid: pid_t, sig: c_int) -> c_int {
+    if !is_valid_signal(sig) {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
     platform::kill(pid, sig)
 }
 
 #[no_mangle]
 pub extern "C" fn killpg(pgrp: pid_t, sig: c_int) -> c_int {
+    if !is_valid_signal(sig) {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
     platform::killpg(pgrp, sig)
 }
 
 #[no_mangle]
 pub extern "C" fn raise(sig: c_int) -> c_int {
+    if !is_valid_signal(sig) {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
     platform::raise(sig)
 }
 
@@ -64,10 +97,20 @@ pub unsafe extern "C" fn sigaction(
     act: *const sigaction,
     oact: *mut sigaction,
 ) -> c_int {
+    if sig <= 0 || sig as usize > NSIG {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
     let mut _sigaction = None;
     let ptr = if !act.is_null() {
+        // SIGKILL and SIGSTOP can neither be caught nor ignored
+        if sig as usize == SIGKILL || sig as usize == SIGSTOP {
+            platform::errno = errno::EINVAL;
+            return -1;
+        }
         _sigaction = Some((*act).clone());
         _sigaction.as_mut().unwrap().sa_flags |= SA_RESTORER as c_ulong;
+        _sigaction.as_mut().unwrap().sa_restorer = Some(__restore_rt);
         _sigaction.as_mut().unwrap() as *mut _ as *mut platform::types::sigaction
     } else {
         ptr::null_mut()
@@ -76,7 +119,7 @@ pub unsafe extern "C" fn sigaction(
 }
 
 #[no_mangle]
-pub extern "C" fn sigaddset(set: *mut sigset_t, mut signo: c_int) -> c_int {
+pub extern "C" fn sigaddset(set: *mut sigset_t, signo: c_int) -> c_int {
     if signo <= 0 || signo as usize > NSIG {
         unsafe {
             platform::errno = errno::EINVAL;
@@ -138,9 +181,19 @@ pub extern "C" fn siginterrupt(sig: c_int, flag: c_int) -> c_int {
     unimplemented!();
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn sigismember(set: *const sigset_t, signo: c_int) -> c_int {
-    unimplemented!();
+    if signo <= 0 || signo as usize > NSIG {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
+
+    let signo = signo as usize - 1; // 0-indexed usize, please!
+    unsafe {
+        ((*set & (1 << (signo & (8 * mem::size_of::<sigset_t>() - 1)))) != 0) as c_int
+    }
 }
 
 extern "C" {
@@ -151,7 +204,7 @@ extern "C" {
 #[no_mangle]
 pub extern "C" fn signal(sig: c_int, func: Option<extern "C" fn(c_int)>) -> Option<extern "C" fn(c_int)> {
     let sa = sigaction {
-        sa_handler: func,
+        __sa_handler: __sa_handler_union { sa_handler: func },
         sa_flags: SA_RESTART as c_ulong,
         sa_restorer: Some(__restore_rt),
         sa_mask: sigset_t::default(),
@@ -161,7 +214,7 @@ pub extern "C" fn signal(sig: c_int, func: Option<extern "C" fn(c_int)>) -> Opti
         mem::forget(old_sa);
         return unsafe { mem::transmute(SIG_ERR) };
     }
-    old_sa.sa_handler
+    unsafe { old_sa.__sa_handler.sa_handler }
 }
 
 // #[no_mangle]
@@ -176,6 +229,13 @@ pub extern "C" fn sigpending(set: *mut sigset_t) -> c_int {
 
 #[no_mangle]
 pub extern "C" fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int {
+    // how is only looked at when there's a set to apply
+    if !set.is_null() && how != SIG_BLOCK && how != SIG_UNBLOCK && how != SIG_SETMASK {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
     platform::sigprocmask(how, set, oset)
 }
 
@@ -189,9 +249,11 @@ pub extern "C" fn sigset(sig: c_int, func: fn(c_int)) -> fn(c_int) {
     unimplemented!();
 }
 
-// #[no_mangle]
+/// Waits for a signal with `sigmask` as the signal mask, which is then put back. Only returns once
+/// a signal handler has run, always with EINTR.
+#[no_mangle]
 pub extern "C" fn sigsuspend(sigmask: *const sigset_t) -> c_int {
-    unimplemented!();
+    platform::sigsuspend(sigmask)
 }
 
 // #[no_mangle]
@@ -52,6 +52,8 @@ pub const SIGPOLL: usize = SIGIO;
 pub const SIGPWR: usize = 30;
 pub const SIGSYS: usize = 31;
 pub const SIGUNUSED: usize = SIGSYS;
+pub const SIGRTMIN: usize = 35;
+pub const SIGRTMAX: usize = 64;
 
 pub const SA_NOCLDSTOP: usize = 1;
 pub const SA_NOCLDWAIT: usize = 2;
@@ -6,6 +6,10 @@ pub const UNGET: size_t = 8;
 
 pub const FILENAME_MAX: c_int = 4096;
 
+#[allow(non_upper_case_globals)]
+pub const L_tmpnam: c_int = 20;
+pub const TMP_MAX: c_int = 238_328;
+
 pub const F_PERM: c_int = 1;
 pub const F_NORD: c_int = 4;
 pub const F_NOWR: c_int = 8;
@@ -1,7 +1,9 @@
 use super::{constants, BUFSIZ, FILE, UNGET};
+use alloc::vec::Vec;
 use core::cell::UnsafeCell;
 use core::ptr;
 use core::sync::atomic::AtomicBool;
+use platform;
 
 pub struct GlobalFile(UnsafeCell<FILE>);
 impl GlobalFile {
@@ -26,6 +28,7 @@ lazy_static! {
         buf_char: -1,
         unget: UNGET,
         lock: AtomicBool::new(false),
+        pid: None,
     });
 
     #[allow(non_upper_case_globals)]
@@ -35,9 +38,11 @@ lazy_static! {
         write: None,
         fd: 1,
         buf: vec![0u8;(BUFSIZ + UNGET) as usize],
-        buf_char: b'\n' as i8,
+        // Line buffered when attached to a terminal, fully buffered otherwise
+        buf_char: if platform::isatty(1) == 1 { b'\n' as i8 } else { -1 },
         unget: 0,
         lock: AtomicBool::new(false),
+        pid: None,
     });
 
     #[allow(non_upper_case_globals)]
@@ -46,10 +51,12 @@ lazy_static! {
         read: None,
         write: None,
         fd: 2,
-        buf: vec![0u8;(BUFSIZ + UNGET) as usize],
+        // Unbuffered
+        buf: Vec::new(),
         buf_char: -1,
         unget: 0,
         lock: AtomicBool::new(false),
+        pid: None,
     });
 }
 
@@ -1,7 +1,8 @@
 use super::constants::*;
 use super::{BUFSIZ, FILE, UNGET};
+use alloc::vec::Vec;
 use core::mem;
-use core::sync::atomic::AtomicBool;
+use core::sync::atomic::{AtomicBool, Ordering};
 use errno;
 use fcntl::*;
 use platform;
@@ -29,7 +30,7 @@ pub unsafe fn parse_mode_flags(mode_str: *const c_char) -> i32 {
     if (*mode_str) == b'w' as i8 {
         flags |= O_TRUNC;
     }
-    if (*mode_str) != b'a' as i8 {
+    if (*mode_str) == b'a' as i8 {
         flags |= O_APPEND;
     }
 
@@ -50,13 +51,13 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
     }
 
     if !strchr(mode, b'e' as i32).is_null() {
-        sys_fcntl(fd, F_SETFD, FD_CLOEXEC);
+        sys_fcntl(fd, F_SETFD, FD_CLOEXEC as c_ulong);
     }
 
     if *mode == 'a' as i8 {
         let f = sys_fcntl(fd, F_GETFL, 0);
         if (f & O_APPEND) == 0 {
-            sys_fcntl(fd, F_SETFL, f | O_APPEND);
+            sys_fcntl(fd, F_SETFL, (f | O_APPEND) as c_ulong);
         }
         flags |= F_APP;
     }
@@ -71,13 +72,32 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
         (*f).write = None;
         (*f).fd = fd;
         (*f).buf = vec![0u8; BUFSIZ + UNGET];
-        (*f).buf_char = -1;
+        // Terminals are line buffered, everything else is fully buffered
+        (*f).buf_char = if platform::isatty(fd) == 1 {
+            b'\n' as i8
+        } else {
+            -1
+        };
         (*f).unget = UNGET;
         (*f).lock = AtomicBool::new(false);
+        (*f).pid = None;
+        with_open_files(|files| files.push(f));
         Some(f)
     }
 }
 
+static OPEN_FILES_LOCK: AtomicBool = AtomicBool::new(false);
+static mut OPEN_FILES: Vec<*mut FILE> = Vec::new();
+
+/// Run `f` with the list of streams opened by fopen and fdopen, which is what
+/// fflush(NULL) has to go through besides the standard streams
+pub fn with_open_files<T, F: FnOnce(&mut Vec<*mut FILE>) -> T>(f: F) -> T {
+    while OPEN_FILES_LOCK.compare_and_swap(false, true, Ordering::Acquire) {}
+    let ret = f(unsafe { &mut OPEN_FILES });
+    OPEN_FILES_LOCK.store(false, Ordering::Release);
+    ret
+}
+
 /// Write buffer `buf` of length `l` into `stream`
 pub fn fwritex(buf: *const u8, l: size_t, stream: &mut FILE) -> size_t {
     use core::ptr::copy_nonoverlapping;
@@ -103,7 +123,8 @@ pub fn fwritex(buf: *const u8, l: size_t, stream: &mut FILE) -> size_t {
                 i -= 1;
             }
             if i > 0 {
-                let n = stream.write(buf);
+                // Everything up to the last newline goes out now, and only the rest is buffered
+                let n = stream.write(&buf[..i]);
                 if n < i {
                     return n;
                 }
@@ -114,6 +135,8 @@ pub fn fwritex(buf: *const u8, l: size_t, stream: &mut FILE) -> size_t {
         } else {
             0
         };
+        // Writing out the head emptied the buffer
+        let (wbase, wpos, wend) = stream.write.unwrap_or((wbase, wpos, wend));
 
         unsafe {
             copy_nonoverlapping(
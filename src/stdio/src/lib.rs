@@ -4,6 +4,7 @@
 // For Vec
 #![feature(alloc)]
 #![feature(const_fn)]
+#![feature(const_vec_new)]
 
 #[macro_use]
 extern crate alloc;
@@ -21,7 +22,6 @@ use core::sync::atomic::{AtomicBool, Ordering};
 use core::{ptr, str};
 
 use alloc::vec::Vec;
-use errno::STR_ERROR;
 use platform::types::*;
 use platform::{c_str, errno, Read, Write};
 use vl::VaList as va_list;
@@ -51,6 +51,8 @@ pub struct FILE {
     buf_char: i8,
     lock: AtomicBool,
     unget: usize,
+    // The child process of a stream opened by popen
+    pid: Option<pid_t>,
 }
 
 impl FILE {
@@ -95,7 +97,7 @@ impl FILE {
         */
 
         if self.flags & constants::F_NOWR > 0 {
-            self.flags &= constants::F_ERR;
+            self.flags |= constants::F_ERR;
             return false;
         }
         // Buffer repositioning
@@ -153,12 +155,17 @@ impl FILE {
         unreachable!()
     }
     pub fn read(&mut self, buf: &mut [u8]) -> usize {
-        let adj = if self.buf.len() > 0 { 0 } else { 1 };
-        let mut file_buf = &mut self.buf[self.unget..];
-        let count = if buf.len() <= 1 - adj {
-            platform::read(self.fd, &mut file_buf)
+        if buf.is_empty() {
+            return 0;
+        }
+        // Reads smaller than the file buffer go through it, so what is read
+        // past the end of buf is kept for later. There is no readv, and two
+        // reads in a row would block on terminals and pipes.
+        let direct = buf.len() >= self.buf.len().saturating_sub(self.unget);
+        let count = if direct {
+            platform::read(self.fd, buf)
         } else {
-            platform::read(self.fd, buf) + platform::read(self.fd, &mut file_buf)
+            platform::read(self.fd, &mut self.buf[self.unget..])
         };
         if count <= 0 {
             self.flags |= if count == 0 {
@@ -168,19 +175,15 @@ impl FILE {
             };
             return 0;
         }
-        if count as usize <= buf.len() - adj {
+        if direct {
             return count as usize;
         }
         // Adjust pointers
-        if file_buf.len() == 0 {
-            self.read = Some((0, 0));
-        } else if buf.len() > 0 {
-            self.read = Some((self.unget + 1, self.unget + (count as usize)));
-            buf[buf.len() - 1] = file_buf[0];
-        } else {
-            self.read = Some((self.unget, self.unget + (count as usize)));
-        }
-        buf.len()
+        let count = count as usize;
+        let n = count.min(buf.len());
+        buf[..n].copy_from_slice(&self.buf[self.unget..self.unget + n]);
+        self.read = Some((self.unget + n, self.unget + count));
+        n
     }
     pub fn seek(&self, off: off_t, whence: c_int) -> off_t {
         platform::lseek(self.fd, off, whence)
@@ -226,10 +229,10 @@ impl<'a> Write for LockGuard<'a> {
 }
 impl<'a> Read for LockGuard<'a> {
     fn read_u8(&mut self) -> Result<Option<u8>, ()> {
-        let mut buf = [0];
-        match self.0.read(&mut buf) {
-            0 => Ok(None),
-            _ => Ok(Some(buf[0]))
+        match getc_unlocked(self.0) {
+            -1 if self.0.flags & F_ERR == F_ERR => Err(()),
+            -1 => Ok(None),
+            c => Ok(Some(c as u8))
         }
     }
 }
@@ -260,17 +263,17 @@ pub extern "C" fn cuserid(_s: *mut c_char) -> *mut c_char {
 }
 
 /// Close a file
-/// This function does not guarentee that the file buffer will be flushed or that the file
-/// descriptor will be closed, so if it is important that the file be written to, use `fflush()`
-/// prior to using this function.
+/// Any buffered output is flushed before the file descriptor is closed.
 #[no_mangle]
 pub extern "C" fn fclose(stream: &mut FILE) -> c_int {
     flockfile(stream);
     let r = helpers::fflush_unlocked(stream) | platform::close(stream.fd);
     if stream.flags & constants::F_PERM == 0 {
         // Not one of stdin, stdout or stderr
+        let ptr = stream as *mut FILE;
+        helpers::with_open_files(|files| files.retain(|file| *file != ptr));
         unsafe {
-            platform::free(stream as *mut FILE as *mut c_void);
+            platform::free(ptr as *mut c_void);
         }
     } else {
         funlockfile(stream);
@@ -309,9 +312,31 @@ pub extern "C" fn ferror(stream: &mut FILE) -> c_int {
 
 /// Flush output to stream, or sync read position
 /// Ensure the file is unlocked before calling this function, as it will attempt to lock the file
-/// itself.
+/// itself. If `stream` is NULL, every stream with pending output is flushed.
 #[no_mangle]
-pub unsafe extern "C" fn fflush(stream: &mut FILE) -> c_int {
+pub unsafe extern "C" fn fflush(stream: Option<&mut FILE>) -> c_int {
+    let stream = match stream {
+        Some(stream) => stream,
+        None => {
+            let mut ret = 0;
+            let mut flush = |stream: &mut FILE| {
+                if stream.write.is_some() {
+                    flockfile(stream);
+                    ret |= helpers::fflush_unlocked(stream);
+                    funlockfile(stream);
+                }
+            };
+            flush(&mut *stdout);
+            flush(&mut *stderr);
+            helpers::with_open_files(|files| {
+                for file in files.iter() {
+                    flush(&mut **file);
+                }
+            });
+            return ret;
+        }
+    };
+
     flockfile(stream);
 
     let ret = helpers::fflush_unlocked(stream);
@@ -345,62 +370,38 @@ pub extern "C" fn fgetpos(stream: &mut FILE, pos: Option<&mut fpos_t>) -> c_int
 }
 
 /// Get a string from the stream
+/// Reading stops after a newline, which is kept, or once `n - 1` bytes were read. Returns
+/// NULL if nothing could be read.
 #[no_mangle]
 pub extern "C" fn fgets(s: *mut c_char, n: c_int, stream: &mut FILE) -> *mut c_char {
-    use core::slice;
-    flockfile(stream);
-    let st = unsafe { slice::from_raw_parts_mut(s as *mut u8, n as usize) };
-
-    let mut len = n;
+    if n <= 0 {
+        return ptr::null_mut();
+    }
 
-    // We can only fit one or less chars in
-    if n <= 1 {
-        funlockfile(stream);
-        if n <= 0 {
-            return ptr::null_mut();
+    flockfile(stream);
+    let mut len = 0;
+    while len < n - 1 {
+        let c = getc_unlocked(stream);
+        if c < 0 {
+            break;
         }
         unsafe {
-            (*s) = b'\0' as i8;
+            *s.offset(len as isize) = c as c_char;
         }
-        return s;
-    }
-    // Scope this so we can reuse stream mutably
-    {
-        // We can't read from this stream
-        if !stream.can_read() {
-            return ptr::null_mut();
+        len += 1;
+        if c == b'\n' as c_int {
+            break;
         }
     }
+    funlockfile(stream);
 
-    // TODO: Look at this later to determine correctness and efficiency
-    'outer: while stream.read(&mut []) == 0 && stream.flags & F_ERR == 0 {
-        if let Some((rpos, rend)) = stream.read {
-            let mut idiff = 0usize;
-            for _ in (0..(len - 1) as usize).take_while(|x| rpos + x < rend) {
-                let pos = (n - len) as usize;
-                st[pos] = stream.buf[rpos + idiff];
-                idiff += 1;
-                len -= 1;
-                if st[pos] == b'\n' || st[pos] as i8 == stream.buf_char {
-                    break 'outer;
-                }
-            }
-            stream.read = Some((rpos + idiff, rend));
-            if rend - rpos == 0 {
-                len -= stream.read(&mut st[((n - len) as usize)..]) as i32;
-                break;
-            }
-            if len <= 1 {
-                break;
-            }
-        }
-        // We can read, there's been no errors. We should have stream.read setbuf
-        //            -- Tommoa (3/7/2018)
-        unreachable!()
+    // We can fit nothing but the NUL in if n is 1, which is not a failure
+    if len == 0 && n > 1 {
+        return ptr::null_mut();
+    }
+    unsafe {
+        *s.offset(len as isize) = 0;
     }
-
-    st[(n - len) as usize] = 0;
-    funlockfile(stream);
     s
 }
 
@@ -438,7 +439,7 @@ pub extern "C" fn fopen(filename: *const c_char, mode: *const c_char) -> *mut FI
     }
 
     if flags & fcntl::O_CLOEXEC > 0 {
-        fcntl::sys_fcntl(fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC);
+        fcntl::sys_fcntl(fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC as c_ulong);
     }
 
     if let Some(f) = unsafe { helpers::_fdopen(fd, mode) } {
@@ -480,6 +481,7 @@ pub extern "C" fn fread(ptr: *mut c_void, size: usize, nitems: usize, stream: &m
     flockfile(stream);
 
     if !stream.can_read() {
+        funlockfile(stream);
         return 0;
     }
 
@@ -507,7 +509,7 @@ pub extern "C" fn fread(ptr: *mut c_void, size: usize, nitems: usize, stream: &m
 
             if k == 0 {
                 funlockfile(stream);
-                return (len - l as usize) / 2;
+                return (len - l as usize) / size;
             }
 
             l -= k as isize;
@@ -537,10 +539,10 @@ pub extern "C" fn freopen(
     if filename.is_null() {
         // Reopen stream in new mode
         if flags & fcntl::O_CLOEXEC > 0 {
-            fcntl::sys_fcntl(stream.fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC);
+            fcntl::sys_fcntl(stream.fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC as c_ulong);
         }
         flags &= !(fcntl::O_CREAT | fcntl::O_EXCL | fcntl::O_CLOEXEC);
-        if fcntl::sys_fcntl(stream.fd, fcntl::F_SETFL, flags) < 0 {
+        if fcntl::sys_fcntl(stream.fd, fcntl::F_SETFL, flags as c_ulong) < 0 {
             funlockfile(stream);
             fclose(stream);
             return ptr::null_mut();
@@ -556,7 +558,8 @@ pub extern "C" fn freopen(
         if new.fd == stream.fd {
             new.fd = -1;
         } else if platform::dup2(new.fd, stream.fd) < 0
-            || fcntl::sys_fcntl(stream.fd, fcntl::F_SETFL, flags & fcntl::O_CLOEXEC) < 0
+            || (flags & fcntl::O_CLOEXEC == fcntl::O_CLOEXEC
+                && fcntl::sys_fcntl(stream.fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC as c_ulong) < 0)
         {
             fclose(new);
             funlockfile(stream);
@@ -564,6 +567,10 @@ pub extern "C" fn freopen(
             return ptr::null_mut();
         }
         stream.flags = (stream.flags & constants::F_PERM) | new.flags;
+        // The new file decides the buffering, unless the stream is unbuffered
+        if !stream.buf.is_empty() {
+            stream.buf_char = new.buf_char;
+        }
         fclose(new);
     }
     funlockfile(stream);
@@ -678,6 +685,77 @@ pub extern "C" fn getchar() -> c_int {
     fgetc(unsafe { &mut *stdin })
 }
 
+/// Read from a stream up to and including `delim`, into `*lineptr`, which is
+/// reallocated as necessary. Returns the number of bytes read, or -1 at EOF
+#[no_mangle]
+pub unsafe extern "C" fn getdelim(
+    lineptr: *mut *mut c_char,
+    n: *mut size_t,
+    delim: c_int,
+    stream: &mut FILE,
+) -> ssize_t {
+    if lineptr.is_null() || n.is_null() {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+    if (*lineptr).is_null() {
+        *n = 0;
+    }
+
+    flockfile(stream);
+    let mut len = 0;
+    loop {
+        let c = getc_unlocked(stream);
+        if c < 0 {
+            break;
+        }
+
+        // Keep room for this byte and the NUL terminator
+        if len + 2 > *n {
+            let size = (len + 2).max(*n * 2).max(BUFSIZ);
+            // platform::realloc can't start from nothing on every platform
+            let new = if (*lineptr).is_null() {
+                platform::alloc(size)
+            } else {
+                platform::realloc(*lineptr as *mut c_void, size)
+            } as *mut c_char;
+            if new.is_null() {
+                funlockfile(stream);
+                platform::errno = errno::ENOMEM;
+                return -1;
+            }
+            *lineptr = new;
+            *n = size;
+        }
+
+        *(*lineptr).offset(len as isize) = c as c_char;
+        len += 1;
+        if c == delim as c_uchar as c_int {
+            break;
+        }
+    }
+    funlockfile(stream);
+
+    if *n > 0 {
+        *(*lineptr).offset(len as isize) = 0;
+    }
+    if len == 0 {
+        -1
+    } else {
+        len as ssize_t
+    }
+}
+
+/// Read a line from a stream, see `getdelim`
+#[no_mangle]
+pub unsafe extern "C" fn getline(
+    lineptr: *mut *mut c_char,
+    n: *mut size_t,
+    stream: &mut FILE,
+) -> ssize_t {
+    getdelim(lineptr, n, b'\n' as c_int, stream)
+}
+
 /// Get a char from a stream without locking the stream
 #[no_mangle]
 pub extern "C" fn getc_unlocked(stream: &mut FILE) -> c_int {
@@ -736,28 +814,106 @@ pub extern "C" fn getw(stream: &mut FILE) -> c_int {
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn pclose(_stream: &mut FILE) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn pclose(stream: &mut FILE) -> c_int {
+    let pid = match stream.pid {
+        Some(pid) => pid,
+        None => {
+            platform::errno = errno::ECHILD;
+            return -1;
+        }
+    };
+    fclose(stream);
+
+    let mut wstatus = 0;
+    while platform::waitpid(pid, &mut wstatus, 0) < 0 {
+        if platform::errno != errno::EINTR {
+            return -1;
+        }
+    }
+    wstatus
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn perror(s: *const c_char) {
-    let s_str = str::from_utf8_unchecked(c_str(s));
+    // Writing could fail and change errno, which the caller might still want
+    let err = errno;
+    let msg = string::strerror(err);
+
+    let mut w = (*stderr).lock();
+    // The prefix and its colon are left out if it's NULL or empty
+    if !s.is_null() && *s != 0 {
+        let _ = w.write_str(str::from_utf8_unchecked(c_str(s)));
+        let _ = w.write_str(": ");
+    }
+    let _ = w.write_str(str::from_utf8_unchecked(c_str(msg)));
+    let _ = w.write_str("\n");
+    drop(w);
+
+    errno = err;
+}
 
-    let mut w = platform::FileWriter(2);
-    if errno >= 0 && errno < STR_ERROR.len() as c_int {
-        w.write_fmt(format_args!("{}: {}\n", s_str, STR_ERROR[errno as usize]))
-            .unwrap();
+#[no_mangle]
+pub unsafe extern "C" fn popen(command: *const c_char, mode: *const c_char) -> *mut FILE {
+    let writing = match (*mode as u8, *mode.offset(1)) {
+        (b'r', 0) => false,
+        (b'w', 0) => true,
+        _ => {
+            platform::errno = errno::EINVAL;
+            return ptr::null_mut();
+        }
+    };
+
+    // Both ends are close-on-exec so that no other child inherits them, the child's end is
+    // duplicated onto stdin or stdout which clears the flag again
+    let mut fds = [0; 2];
+    if platform::pipe2(&mut fds, fcntl::O_CLOEXEC) < 0 {
+        return ptr::null_mut();
+    }
+    let (ours, theirs, target) = if writing {
+        (fds[1], fds[0], 0)
     } else {
-        w.write_fmt(format_args!("{}: Unknown error {}\n", s_str, errno))
-            .unwrap();
+        (fds[0], fds[1], 1)
+    };
+
+    let pid = platform::fork();
+    if pid < 0 {
+        platform::close(ours);
+        platform::close(theirs);
+        return ptr::null_mut();
+    } else if pid == 0 {
+        if theirs == target {
+            fcntl::sys_fcntl(theirs, fcntl::F_SETFD, 0);
+        } else {
+            platform::dup2(theirs, target);
+        }
+
+        let args = [
+            "sh\0".as_ptr(),
+            "-c\0".as_ptr(),
+            command as *const u8,
+            ptr::null(),
+        ];
+        platform::execve(
+            "/bin/sh\0".as_ptr() as *const c_char,
+            args.as_ptr() as *const *mut c_char,
+            platform::environ,
+        );
+        platform::exit(127);
     }
-}
 
-// #[no_mangle]
-pub extern "C" fn popen(_command: *const c_char, _mode: *const c_char) -> *mut FILE {
-    unimplemented!();
+    platform::close(theirs);
+    match helpers::_fdopen(ours, mode) {
+        Some(f) => {
+            (*f).pid = Some(pid);
+            f
+        }
+        None => {
+            platform::close(ours);
+            platform::waitpid(pid, ptr::null_mut(), 0);
+            ptr::null_mut()
+        }
+    }
 }
 
 /// Put a character `c` into `stream`
@@ -780,12 +936,14 @@ pub extern "C" fn putchar(c: c_int) -> c_int {
 pub extern "C" fn putc_unlocked(c: c_int, stream: &mut FILE) -> c_int {
     if stream.can_write() {
         if let Some((wbase, wpos, wend)) = stream.write {
-            if c as i8 != stream.buf_char {
+            // Flush along with this byte if the buffer is full or the line
+            // ended
+            if wpos < wend && c as i8 != stream.buf_char {
                 stream.buf[wpos] = c as u8;
                 stream.write = Some((wbase, wpos + 1, wend));
-                c
+                c as c_uchar as c_int
             } else if stream.write(&[c as u8]) == 1 {
-                c
+                c as c_uchar as c_int
             } else {
                 -1
             }
@@ -806,11 +964,10 @@ pub extern "C" fn putchar_unlocked(c: c_int) -> c_int {
 /// Put a string `s` into `stdout`
 #[no_mangle]
 pub extern "C" fn puts(s: *const c_char) -> c_int {
-    let ret = (fputs(s, unsafe { &mut *stdout }) > 0) || (putchar_unlocked(b'\n' as c_int) > 0);
-    if ret {
-        0
-    } else {
+    if fputs(s, unsafe { &mut *stdout }) < 0 || putchar(b'\n' as c_int) < 0 {
         -1
+    } else {
+        0
     }
 }
 
@@ -837,6 +994,16 @@ pub extern "C" fn rename(oldpath: *const c_char, newpath: *const c_char) -> c_in
     platform::rename(oldpath, newpath)
 }
 
+#[no_mangle]
+pub extern "C" fn renameat(
+    oldfd: c_int,
+    oldpath: *const c_char,
+    newfd: c_int,
+    newpath: *const c_char,
+) -> c_int {
+    platform::renameat(oldfd, oldpath, newfd, newpath)
+}
+
 /// Rewind `stream` back to the beginning of it
 #[no_mangle]
 pub extern "C" fn rewind(stream: &mut FILE) {
@@ -864,7 +1031,7 @@ pub extern "C" fn setvbuf(stream: &mut FILE, buf: *mut c_char, mode: c_int, size
     // Set a buffer of size `size` if no buffer is given
     let buf = if buf.is_null() {
         if mode != _IONBF {
-            vec![0u8; 1]
+            vec![0u8; stream.unget + if size == 0 { BUFSIZ } else { size }]
         } else {
             stream.unget = 0;
             if let Some(_) = stream.write {
@@ -888,26 +1055,81 @@ pub extern "C" fn setvbuf(stream: &mut FILE, buf: *mut c_char, mode: c_int, size
     0
 }
 
-// #[no_mangle]
-pub extern "C" fn tempnam(_dir: *const c_char, _pfx: *const c_char) -> *mut c_char {
-    unimplemented!();
+// P_tmpdir in bits/stdio.h, as cbindgen can't export strings
+const P_TMPDIR: &[u8] = b"/tmp\0";
+
+/// The directory temporary files go in: `$TMPDIR` if it can be used, else `dir` if it can be
+/// used, else `P_tmpdir`
+unsafe fn temp_dir(dir: *const c_char) -> *const c_char {
+    let errno_backup = errno;
+    let usable = |dir: *const c_char| !dir.is_null() && platform::access(dir, W_OK | X_OK) == 0;
+    let tmpdir = platform::getenv(b"TMPDIR\0".as_ptr() as *const c_char);
+    let dir = if usable(tmpdir) {
+        tmpdir
+    } else if usable(dir) {
+        dir
+    } else {
+        P_TMPDIR.as_ptr() as *const c_char
+    };
+    errno = errno_backup;
+    dir
+}
+
+/// `dir/prefixXXXXXX`, ready to have its Xs replaced
+unsafe fn temp_template(dir: *const c_char, prefix: &[u8]) -> Vec<u8> {
+    let mut template = Vec::new();
+    template.extend_from_slice(c_str(dir));
+    template.push(b'/');
+    template.extend_from_slice(prefix);
+    template.extend_from_slice(b"XXXXXX\0");
+    template
+}
+
+/// Returns a new name for a temporary file in `dir`, starting with the first 5 bytes of `pfx`.
+/// The name is allocated with `malloc`. Nothing stops another process from creating the file
+/// before the caller does, so use `mkstemp` instead.
+#[no_mangle]
+pub extern "C" fn tempnam(dir: *const c_char, pfx: *const c_char) -> *mut c_char {
+    extern "C" {
+        fn mktemp(name: *mut c_char) -> *mut c_char;
+    }
+
+    unsafe {
+        let prefix = if pfx.is_null() {
+            &b"file"[..]
+        } else {
+            let prefix = c_str(pfx);
+            &prefix[..prefix.len().min(5)]
+        };
+        let mut template = temp_template(temp_dir(dir), prefix);
+        if *mktemp(template.as_mut_ptr() as *mut c_char) == 0 {
+            return ptr::null_mut();
+        }
+
+        let name = platform::alloc(template.len()) as *mut c_char;
+        if !name.is_null() {
+            ptr::copy_nonoverlapping(template.as_ptr() as *const c_char, name, template.len());
+        }
+        name
+    }
 }
 
+/// Returns a new stream to a file that is deleted as soon as it's closed, in `$TMPDIR` if set
 #[no_mangle]
 pub extern "C" fn tmpfile() -> *mut FILE {
     extern "C" {
         fn mkstemp(name: *mut c_char) -> c_int;
     }
 
-    let mut file_name = *b"/tmp/tmpfileXXXXXX";
-    let file_name = file_name.as_mut_ptr() as *mut c_char;
+    let mut template = unsafe { temp_template(temp_dir(ptr::null()), b"tmpfile") };
+    let file_name = template.as_mut_ptr() as *mut c_char;
     let fd = unsafe { mkstemp(file_name) };
 
     if fd < 0 {
         return ptr::null_mut();
     }
 
-    let fp = fdopen(fd, b"w+".as_ptr() as *const i8);
+    let fp = fdopen(fd, b"w+\0".as_ptr() as *const i8);
     platform::unlink(file_name);
 
     if fp == ptr::null_mut() {
@@ -917,9 +1139,26 @@ pub extern "C" fn tmpfile() -> *mut FILE {
     fp
 }
 
-// #[no_mangle]
-pub extern "C" fn tmpnam(_s: *mut c_char) -> *mut c_char {
-    unimplemented!();
+/// Returns a new name for a temporary file in `P_tmpdir`, placed in `s` if it isn't NULL (which
+/// must have room for `L_tmpnam` bytes) or else in a static buffer. Nothing stops another process
+/// from creating the file before the caller does, so use `mkstemp` instead.
+#[no_mangle]
+pub extern "C" fn tmpnam(s: *mut c_char) -> *mut c_char {
+    extern "C" {
+        fn mktemp(name: *mut c_char) -> *mut c_char;
+    }
+    static mut TMPNAM: [c_char; L_tmpnam as usize] = [0; L_tmpnam as usize];
+
+    unsafe {
+        let mut template = temp_template(P_TMPDIR.as_ptr() as *const c_char, b"tmp");
+        if *mktemp(template.as_mut_ptr() as *mut c_char) == 0 {
+            return ptr::null_mut();
+        }
+
+        let s = if s.is_null() { TMPNAM.as_mut_ptr() } else { s };
+        ptr::copy_nonoverlapping(template.as_ptr() as *const c_char, s, template.len());
+        s
+    }
 }
 
 /// Push character `c` back onto `stream` so it'll be read next
@@ -966,6 +1205,10 @@ pub unsafe extern "C" fn vsnprintf(
     format: *const c_char,
     ap: va_list,
 ) -> c_int {
+    // The writer only terminates the string after writing to it
+    if n > 0 {
+        *s = 0;
+    }
     printf::printf(
         &mut platform::StringWriter(s as *mut u8, n as usize),
         format,
@@ -980,7 +1223,12 @@ pub unsafe extern "C" fn vsprintf(s: *mut c_char, format: *const c_char, ap: va_
 
 #[no_mangle]
 pub unsafe extern "C" fn vfscanf(file: &mut FILE, format: *const c_char, ap: va_list) -> c_int {
-    scanf::scanf(file.lock(), format, ap)
+    let mut unread = None;
+    let ret = scanf::scanf(file.lock(), format, ap, &mut unread);
+    if let Some(byte) = unread {
+        ungetc(byte as c_int, file);
+    }
+    ret
 }
 
 #[no_mangle]
@@ -994,5 +1242,6 @@ pub unsafe extern "C" fn vsscanf(s: *const c_char, format: *const c_char, ap: va
         &mut platform::UnsafeStringReader(s as *const u8),
         format,
         ap,
+        &mut None,
     )
 }
@@ -1,113 +1,401 @@
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt::Write as CoreWrite;
-use core::{slice, str};
+use core::{fmt, slice};
 
+use errno;
 use platform::types::*;
 use platform::{self, Write};
 use vl::VaList;
 
-pub unsafe fn printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) -> c_int {
-    let mut w = platform::CountingWriter::new(w);
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum IntKind {
+    Byte,
+    Short,
+    Int,
+    Long,
+    LongLong,
+    IntMax,
+    PtrDiff,
+    Size,
+}
 
-    let format = slice::from_raw_parts(format as *const u8, usize::max_value());
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    alt: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: IntKind,
+}
 
-    let mut found_percent = false;
-    for &b in format.iter() {
-        // check for NUL
-        if b == 0 {
-            break;
-        }
+unsafe fn pop_int(format: &mut *const u8) -> usize {
+    let mut n = 0;
+    while (**format).is_ascii_digit() {
+        n = n * 10 + (**format - b'0') as usize;
+        *format = format.offset(1);
+    }
+    n
+}
 
-        if found_percent {
-            if match b as char {
-                '%' => {
-                    found_percent = false;
-                    w.write_char('%')
-                }
-                'c' => {
-                    let a = ap.get::<u32>();
+unsafe fn get_signed(ap: &mut VaList, kind: IntKind) -> i64 {
+    match kind {
+        IntKind::Byte => ap.get::<c_int>() as c_schar as i64,
+        IntKind::Short => ap.get::<c_int>() as c_short as i64,
+        IntKind::Int => ap.get::<c_int>() as i64,
+        IntKind::Long | IntKind::LongLong | IntKind::IntMax => ap.get::<i64>(),
+        IntKind::PtrDiff | IntKind::Size => ap.get::<isize>() as i64,
+    }
+}
 
-                    found_percent = false;
+unsafe fn get_unsigned(ap: &mut VaList, kind: IntKind) -> u64 {
+    match kind {
+        IntKind::Byte => ap.get::<c_uint>() as c_uchar as u64,
+        IntKind::Short => ap.get::<c_uint>() as c_ushort as u64,
+        IntKind::Int => ap.get::<c_uint>() as u64,
+        IntKind::Long | IntKind::LongLong | IntKind::IntMax => ap.get::<u64>(),
+        IntKind::PtrDiff | IntKind::Size => ap.get::<usize>() as u64,
+    }
+}
 
-                    w.write_u8(a as u8)
-                }
-                'd' | 'i' => {
-                    let a = ap.get::<c_int>();
+fn write_bytes<W: Write>(w: &mut W, bytes: &[u8]) -> fmt::Result {
+    for &b in bytes {
+        w.write_u8(b)?;
+    }
+    Ok(())
+}
 
-                    found_percent = false;
+fn write_repeat<W: Write>(w: &mut W, byte: u8, n: usize) -> fmt::Result {
+    for _ in 0..n {
+        w.write_u8(byte)?;
+    }
+    Ok(())
+}
 
-                    w.write_fmt(format_args!("{}", a))
-                }
-                'f' | 'F' => {
-                    let a = ap.get::<f64>();
+// Writes `prefix` and `body` padded to the field width. Zero padding goes
+// between the two, so that signs and 0x stay in front.
+fn pad<W: Write>(w: &mut W, spec: &Spec, zero: bool, prefix: &[u8], body: &[u8]) -> fmt::Result {
+    let len = prefix.len() + body.len();
+    let fill = spec.width.saturating_sub(len);
+    if spec.left {
+        write_bytes(w, prefix)?;
+        write_bytes(w, body)?;
+        write_repeat(w, b' ', fill)
+    } else if zero {
+        write_bytes(w, prefix)?;
+        write_repeat(w, b'0', fill)?;
+        write_bytes(w, body)
+    } else {
+        write_repeat(w, b' ', fill)?;
+        write_bytes(w, prefix)?;
+        write_bytes(w, body)
+    }
+}
 
-                    found_percent = false;
+fn sign(spec: &Spec, negative: bool) -> &'static [u8] {
+    if negative {
+        b"-"
+    } else if spec.plus {
+        b"+"
+    } else if spec.space {
+        b" "
+    } else {
+        b""
+    }
+}
 
-                    w.write_fmt(format_args!("{}", a))
-                }
-                'n' => {
-                    let _a = ap.get::<c_int>();
+fn fmt_int<W: Write>(
+    w: &mut W,
+    spec: &Spec,
+    prefix: &[u8],
+    mut n: u64,
+    radix: u64,
+    upper: bool,
+) -> fmt::Result {
+    let digits: &[u8] = if upper {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
 
-                    found_percent = false;
-                    Ok(())
-                }
-                'p' => {
-                    let a = ap.get::<usize>();
+    let mut buf = [0; 64];
+    let mut i = buf.len();
+    while n > 0 {
+        i -= 1;
+        buf[i] = digits[(n % radix) as usize];
+        n /= radix;
+    }
 
-                    found_percent = false;
+    // The precision is the minimum number of digits, and an explicit zero
+    // precision prints nothing at all for zero. It can be any length, so the
+    // zeros it takes don't go in `buf`.
+    let min = spec.precision.unwrap_or(1);
+    let mut zeros = min.saturating_sub(buf.len() - i);
+    if radix == 8 && spec.alt && zeros == 0 && (i == buf.len() || buf[i] != b'0') {
+        zeros = 1;
+    }
+    let mut body = Vec::with_capacity(zeros + buf.len() - i);
+    body.resize(zeros, b'0');
+    body.extend_from_slice(&buf[i..]);
 
-                    w.write_fmt(format_args!("0x{:x}", a))
-                }
-                's' => {
-                    let a = ap.get::<*const c_char>();
+    let zero = spec.zero && spec.precision.is_none();
+    pad(w, spec, zero, prefix, &body)
+}
 
-                    found_percent = false;
+// Splits Rust's exponential formatting into its mantissa and exponent
+fn exp_parts(s: &str) -> (&str, i32) {
+    let e = s.find('e').unwrap_or(s.len());
+    let exp = s.get(e + 1..).and_then(|exp| exp.parse().ok()).unwrap_or(0);
+    (&s[..e], exp)
+}
 
-                    w.write_str(str::from_utf8_unchecked(platform::c_str(a)))
-                }
-                'u' => {
-                    let a = ap.get::<c_uint>();
+fn push_exp(s: &mut String, exp: i32, upper: bool) {
+    s.push(if upper { 'E' } else { 'e' });
+    s.push(if exp < 0 { '-' } else { '+' });
+    let _ = write!(s, "{:02}", exp.abs());
+}
 
-                    found_percent = false;
+// Removes trailing zeros after the decimal point, and the point itself if
+// nothing is left after it
+fn strip_zeros(s: &mut String) {
+    if s.contains('.') {
+        while s.ends_with('0') {
+            s.pop();
+        }
+        if s.ends_with('.') {
+            s.pop();
+        }
+    }
+}
 
-                    w.write_fmt(format_args!("{}", a))
-                }
-                'x' => {
-                    let a = ap.get::<c_uint>();
+fn fmt_float<W: Write>(w: &mut W, spec: &Spec, fmt: u8, n: f64) -> fmt::Result {
+    let upper = fmt.is_ascii_uppercase();
+    let prefix = sign(spec, n.is_sign_negative());
+    let n = n.abs();
 
-                    found_percent = false;
+    if !n.is_finite() {
+        let body: &[u8] = match (n.is_nan(), upper) {
+            (true, false) => b"nan",
+            (true, true) => b"NAN",
+            (false, false) => b"inf",
+            (false, true) => b"INF",
+        };
+        return pad(w, spec, false, prefix, body);
+    }
 
-                    w.write_fmt(format_args!("{:x}", a))
+    let precision = spec.precision.unwrap_or(6);
+    let mut s = String::new();
+    match fmt {
+        b'f' | b'F' => {
+            let _ = write!(s, "{:.*}", precision, n);
+            if spec.alt && precision == 0 {
+                s.push('.');
+            }
+        }
+        b'e' | b'E' => {
+            let formatted = format!("{:.*e}", precision, n);
+            let (mantissa, exp) = exp_parts(&formatted);
+            s.push_str(mantissa);
+            if spec.alt && precision == 0 {
+                s.push('.');
+            }
+            push_exp(&mut s, exp, upper);
+        }
+        _ => {
+            let precision = precision.max(1);
+            let formatted = format!("{:.*e}", precision - 1, n);
+            let (mantissa, exp) = exp_parts(&formatted);
+            if exp < -4 || exp >= precision as i32 {
+                s.push_str(mantissa);
+                if !spec.alt {
+                    strip_zeros(&mut s);
+                }
+                push_exp(&mut s, exp, upper);
+            } else {
+                let _ = write!(s, "{:.*}", (precision as i32 - 1 - exp) as usize, n);
+                if !spec.alt {
+                    strip_zeros(&mut s);
                 }
-                'X' => {
-                    let a = ap.get::<c_uint>();
+            }
+        }
+    }
+
+    pad(w, spec, spec.zero, prefix, s.as_bytes())
+}
+
+pub unsafe fn printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) -> c_int {
+    let mut w = platform::CountingWriter::new(w);
+    let mut format = format as *const u8;
 
-                    found_percent = false;
+    while *format != 0 {
+        if *format != b'%' {
+            if w.write_u8(*format).is_err() {
+                return -1;
+            }
+            format = format.offset(1);
+            continue;
+        }
+        format = format.offset(1);
+
+        let mut spec = Spec {
+            left: false,
+            plus: false,
+            space: false,
+            alt: false,
+            zero: false,
+            width: 0,
+            precision: None,
+            kind: IntKind::Int,
+        };
+
+        loop {
+            match *format {
+                b'-' => spec.left = true,
+                b'+' => spec.plus = true,
+                b' ' => spec.space = true,
+                b'#' => spec.alt = true,
+                b'0' => spec.zero = true,
+                _ => break,
+            }
+            format = format.offset(1);
+        }
+
+        if *format == b'*' {
+            format = format.offset(1);
+            let width = ap.get::<c_int>();
+            if width < 0 {
+                spec.left = true;
+            }
+            spec.width = (width as i64).abs() as usize;
+        } else {
+            spec.width = pop_int(&mut format);
+        }
 
-                    w.write_fmt(format_args!("{:X}", a))
+        if *format == b'.' {
+            format = format.offset(1);
+            if *format == b'*' {
+                format = format.offset(1);
+                let precision = ap.get::<c_int>();
+                // A negative precision is taken as if it was omitted
+                if precision >= 0 {
+                    spec.precision = Some(precision as usize);
                 }
-                'o' => {
-                    let a = ap.get::<c_uint>();
+            } else {
+                spec.precision = Some(pop_int(&mut format));
+            }
+        }
+
+        spec.kind = match *format {
+            b'h' if *format.offset(1) == b'h' => {
+                format = format.offset(1);
+                IntKind::Byte
+            }
+            b'h' => IntKind::Short,
+            b'l' if *format.offset(1) == b'l' => {
+                format = format.offset(1);
+                IntKind::LongLong
+            }
+            b'l' => IntKind::Long,
+            b'j' => IntKind::IntMax,
+            b'z' => IntKind::Size,
+            b't' => IntKind::PtrDiff,
+            _ => IntKind::Int,
+        };
+        // A long double can't be read off the argument list, so %Lf and the
+        // like fail rather than print whatever a double there would be
+        let long_double = *format == b'L';
+        if b"hljztL".contains(&*format) {
+            format = format.offset(1);
+        }
 
-                    found_percent = false;
+        let fmt = *format;
+        if fmt == 0 {
+            break;
+        }
+        format = format.offset(1);
 
-                    w.write_fmt(format_args!("{:o}", a))
+        if match fmt {
+            b'%' => w.write_u8(b'%'),
+            b'd' | b'i' => {
+                let a = get_signed(&mut ap, spec.kind);
+                let prefix = sign(&spec, a < 0);
+                fmt_int(&mut w, &spec, prefix, a.wrapping_abs() as u64, 10, false)
+            }
+            b'u' => {
+                let a = get_unsigned(&mut ap, spec.kind);
+                fmt_int(&mut w, &spec, b"", a, 10, false)
+            }
+            b'o' => {
+                let a = get_unsigned(&mut ap, spec.kind);
+                fmt_int(&mut w, &spec, b"", a, 8, false)
+            }
+            b'x' | b'X' => {
+                let a = get_unsigned(&mut ap, spec.kind);
+                let prefix: &[u8] = match (spec.alt && a != 0, fmt == b'X') {
+                    (false, _) => b"",
+                    (true, false) => b"0x",
+                    (true, true) => b"0X",
+                };
+                fmt_int(&mut w, &spec, prefix, a, 16, fmt == b'X')
+            }
+            b'c' => {
+                let a = ap.get::<c_int>();
+                pad(&mut w, &spec, false, b"", &[a as u8])
+            }
+            b's' => {
+                let a = ap.get::<*const c_char>();
+                let s = if a.is_null() {
+                    b"(null)"
+                } else {
+                    match spec.precision {
+                        // Don't read past the precision, the string
+                        // doesn't have to be NUL terminated then
+                        Some(max) => {
+                            let mut len = 0;
+                            while len < max && *a.offset(len as isize) != 0 {
+                                len += 1;
+                            }
+                            slice::from_raw_parts(a as *const u8, len)
+                        }
+                        None => platform::c_str(a),
+                    }
+                };
+                pad(&mut w, &spec, false, b"", s)
+            }
+            b'p' => {
+                let a = ap.get::<usize>();
+                spec.alt = false;
+                fmt_int(&mut w, &spec, b"0x", a as u64, 16, false)
+            }
+            b'f' | b'F' | b'e' | b'E' | b'g' | b'G' => {
+                if long_double {
+                    platform::errno = errno::EINVAL;
+                    return -1;
                 }
-                '-' => Ok(()),
-                '+' => Ok(()),
-                ' ' => Ok(()),
-                '#' => Ok(()),
-                '0'...'9' => Ok(()),
-                _ => Ok(()),
-            }.is_err()
-            {
-                return -1;
+                let a = ap.get::<f64>();
+                fmt_float(&mut w, &spec, fmt, a)
             }
-        } else if b == b'%' {
-            found_percent = true;
-        } else {
-            if w.write_u8(b).is_err() {
-                return -1;
+            b'n' => {
+                let written = w.written;
+                match spec.kind {
+                    IntKind::Byte => *ap.get::<*mut c_schar>() = written as c_schar,
+                    IntKind::Short => *ap.get::<*mut c_short>() = written as c_short,
+                    IntKind::Int => *ap.get::<*mut c_int>() = written as c_int,
+                    IntKind::Long | IntKind::LongLong | IntKind::IntMax => {
+                        *ap.get::<*mut i64>() = written as i64
+                    }
+                    IntKind::PtrDiff | IntKind::Size => {
+                        *ap.get::<*mut isize>() = written as isize
+                    }
+                }
+                Ok(())
             }
+            _ => Ok(()),
+        }.is_err()
+        {
+            return -1;
         }
     }
 
@@ -31,10 +31,12 @@ unsafe fn inner_scanf<R: Read>(
     mut r: R,
     mut format: *const c_char,
     mut ap: VaList,
+    unread: &mut Option<u8>,
 ) -> Result<c_int, c_int> {
     let mut matched = 0;
     let mut byte = 0;
     let mut skip_read = false;
+    let mut eof = false;
     let mut count = 0;
 
     macro_rules! read {
@@ -45,12 +47,33 @@ unsafe fn inner_scanf<R: Read>(
                     count += 1;
                     true
                 },
-                Ok(None) => false,
+                Ok(None) => {
+                    eof = true;
+                    false
+                },
                 Err(()) => return Err(-1)
             }
         }};
     }
 
+    // Running out of input before the first conversion is an input failure,
+    // which is reported as EOF rather than as zero matches
+    macro_rules! input_failure {
+        () => {
+            return Ok(if matched == 0 { -1 } else { matched });
+        };
+    }
+
+    // A directive failed to match, so the byte it looked at is left unread
+    macro_rules! matching_failure {
+        () => {{
+            if !eof {
+                *unread = Some(byte);
+            }
+            return Ok(matched);
+        }};
+    }
+
     macro_rules! maybe_read {
         () => {
             maybe_read!(inner false);
@@ -59,9 +82,12 @@ unsafe fn inner_scanf<R: Read>(
             maybe_read!(inner);
         };
         (inner $($placeholder:expr)*) => {
+            if eof {
+                input_failure!();
+            }
             if !skip_read {
                 if !read!() {
-                    return Ok(matched);
+                    input_failure!();
                 }
             }
             $(else {
@@ -75,12 +101,13 @@ unsafe fn inner_scanf<R: Read>(
         let mut c = *format as u8;
         format = format.offset(1);
 
-        if c == b' ' {
-            maybe_read!(noreset);
-
-            while (byte as char).is_whitespace() {
-                if !read!() {
-                    return Ok(matched);
+        if (c as char).is_whitespace() {
+            // Any amount of whitespace, including none, is skipped
+            if !eof && (skip_read || read!()) {
+                while (byte as char).is_whitespace() {
+                    if !read!() {
+                        break;
+                    }
                 }
             }
 
@@ -88,7 +115,7 @@ unsafe fn inner_scanf<R: Read>(
         } else if c != b'%' {
             maybe_read!();
             if c != byte {
-                return Ok(matched);
+                matching_failure!();
             }
         } else {
             c = next_byte(&mut format)?;
@@ -122,7 +149,11 @@ unsafe fn inner_scanf<R: Read>(
                         IntKind::Short
                     },
                     b'j' => IntKind::IntMax,
-                    b'l' => IntKind::Long,
+                    b'l' => if kind == IntKind::Long {
+                        IntKind::LongLong
+                    } else {
+                        IntKind::Long
+                    },
                     b'q' | b'L' => IntKind::LongLong,
                     b't' => IntKind::PtrDiff,
                     b'z' => IntKind::Size,
@@ -139,12 +170,12 @@ unsafe fn inner_scanf<R: Read>(
                 b'%' => {
                     while (byte as char).is_whitespace() {
                         if !read!() {
-                            return Ok(matched);
+                            input_failure!();
                         }
                     }
 
                     if byte != b'%' {
-                        return Err(matched);
+                        matching_failure!();
                     } else if !read!() {
                         return Ok(matched);
                     }
@@ -153,13 +184,13 @@ unsafe fn inner_scanf<R: Read>(
                 | b'p' => {
                     while (byte as char).is_whitespace() {
                         if !read!() {
-                            return Ok(matched);
+                            input_failure!();
                         }
                     }
 
                     let pointer = c == b'p';
                     // Pointers aren't automatic, but we do want to parse "0x"
-                    let auto = c == b'i' || pointer;
+                    let auto = c == b'i' || c == b'x' || c == b'X' || pointer;
                     let float = c == b'f' || c == b'e' || c == b'g' || c == b'E' || c == b'a';
 
                     let mut radix = match c {
@@ -168,28 +199,47 @@ unsafe fn inner_scanf<R: Read>(
                         _ => 10,
                     };
 
+                    let mut negative = false;
+                    if width.map(|w| w > 0).unwrap_or(true) && (byte == b'-' || byte == b'+') {
+                        negative = byte == b'-';
+                        width = width.map(|w| w - 1);
+                        if width.map(|w| w > 0).unwrap_or(true) && !read!() {
+                            input_failure!();
+                        }
+                    }
+
                     let mut n = String::new();
+                    let mut zero = false;
                     let mut dot = false;
+                    let mut exp = false;
 
                     while width.map(|w| w > 0).unwrap_or(true)
                         && ((byte >= b'0' && byte <= b'7')
                             || (radix >= 10 && (byte >= b'8' && byte <= b'9'))
-                            || (float && !dot && byte == b'.')
+                            || (float && !dot && !exp && byte == b'.')
+                            || (float && !exp && !n.is_empty() && (byte == b'e' || byte == b'E'))
+                            || (float
+                                && (byte == b'-' || byte == b'+')
+                                && (n.ends_with('e') || n.ends_with('E')))
                             || (radix == 16
                                 && ((byte >= b'a' && byte <= b'f')
                                     || (byte >= b'A' && byte <= b'F'))))
                     {
                         if auto
                             && n.is_empty()
+                            && !zero
                             && byte == b'0'
                             && width.map(|w| w > 0).unwrap_or(true)
                         {
-                            if !pointer {
+                            zero = true;
+                            if c == b'i' {
                                 radix = 8;
                             }
                             width = width.map(|w| w - 1);
-                            if !read!() {
-                                break;
+                            if width.map(|w| w > 0).unwrap_or(true) {
+                                if !read!() {
+                                    break;
+                                }
                             }
                             if width.map(|w| w > 0).unwrap_or(true)
                                 && (byte == b'x' || byte == b'X')
@@ -207,6 +257,8 @@ unsafe fn inner_scanf<R: Read>(
                         if byte == b'.' {
                             // Don't allow another dot
                             dot = true;
+                        } else if float && (byte == b'e' || byte == b'E') {
+                            exp = true;
                         }
                         n.push(byte as char);
                         width = width.map(|w| w - 1);
@@ -217,35 +269,35 @@ unsafe fn inner_scanf<R: Read>(
                         }
                     }
 
+                    // A lone 0 (possibly followed by an x) is a number, but
+                    // nothing at all is not
+                    if n.is_empty() && !zero {
+                        matching_failure!();
+                    }
+
                     macro_rules! parse_type {
-                        (noformat $type:ident) => {{
+                        (float $type:ident) => {{
                             let n = if n.is_empty() {
                                 0 as $type
                             } else {
                                 n.parse::<$type>().map_err(|_| 0)?
                             };
                             if !ignore {
-                                *ap.get::<*mut $type>() = n;
+                                *ap.get::<*mut $type>() = if negative { -n } else { n };
                                 matched += 1;
                             }
                         }};
-                        (c_double) => {
-                            parse_type!(noformat c_double);
-                        };
-                        (c_float) => {
-                            parse_type!(noformat c_float);
-                        };
-                        ($type:ident) => {
-                            parse_type!($type, $type);
-                        };
-                        ($type:ident, $final:ty) => {{
+                        ($type:ty) => {{
                             let n = if n.is_empty() {
-                                0 as $type
+                                0
                             } else {
-                                $type::from_str_radix(&n, radix).map_err(|_| 0)?
+                                u64::from_str_radix(&n, radix).map_err(|_| 0)?
                             };
+                            // Like strtoul, negative numbers wrap around for
+                            // unsigned conversions
+                            let n = if negative { n.wrapping_neg() } else { n };
                             if !ignore {
-                                *ap.get::<*mut $final>() = n as $final;
+                                *ap.get::<*mut $type>() = n as $type;
                                 matched += 1;
                             }
                         }};
@@ -253,12 +305,12 @@ unsafe fn inner_scanf<R: Read>(
 
                     if float {
                         if kind == IntKind::Long || kind == IntKind::LongLong {
-                            parse_type!(c_double);
+                            parse_type!(float c_double);
                         } else {
-                            parse_type!(c_float);
+                            parse_type!(float c_float);
                         }
                     } else if c == b'p' {
-                        parse_type!(size_t, *mut c_void);
+                        parse_type!(*mut c_void);
                     } else {
                         let unsigned = c == b'o' || c == b'u' || c == b'x' || c == b'X';
 
@@ -305,7 +357,7 @@ unsafe fn inner_scanf<R: Read>(
                 b's' => {
                     while (byte as char).is_whitespace() {
                         if !read!() {
-                            return Ok(matched);
+                            input_failure!();
                         }
                     }
 
@@ -383,6 +435,7 @@ unsafe fn inner_scanf<R: Read>(
                     }
 
                     let mut ptr: Option<*mut c_char> = if ignore { None } else { Some(ap.get()) };
+                    let mut len = 0;
 
                     while width.map(|w| w > 0).unwrap_or(true) && !invert == matches.contains(&byte)
                     {
@@ -390,6 +443,7 @@ unsafe fn inner_scanf<R: Read>(
                             **ptr = byte as c_char;
                             *ptr = ptr.offset(1);
                         }
+                        len += 1;
                         width = width.map(|w| w - 1);
                         if width.map(|w| w > 0).unwrap_or(true) {
                             if !read!() {
@@ -398,6 +452,10 @@ unsafe fn inner_scanf<R: Read>(
                         }
                     }
 
+                    // An empty scanset match is a matching failure
+                    if len == 0 {
+                        matching_failure!();
+                    }
                     if let Some(ptr) = ptr {
                         *ptr = 0;
                         matched += 1;
@@ -405,23 +463,48 @@ unsafe fn inner_scanf<R: Read>(
                 }
                 b'n' => {
                     if !ignore {
-                        *ap.get::<*mut c_int>() = count as c_int;
+                        // A byte that was read ahead but not matched yet
+                        // isn't consumed
+                        let count = if skip_read && !eof { count - 1 } else { count };
+                        match kind {
+                            IntKind::Byte => *ap.get::<*mut c_char>() = count as c_char,
+                            IntKind::Short => *ap.get::<*mut c_short>() = count as c_short,
+                            IntKind::Int => *ap.get::<*mut c_int>() = count as c_int,
+                            IntKind::Long | IntKind::LongLong | IntKind::IntMax => {
+                                *ap.get::<*mut intmax_t>() = count as intmax_t
+                            }
+                            IntKind::PtrDiff | IntKind::Size => {
+                                *ap.get::<*mut ssize_t>() = count as ssize_t
+                            }
+                        }
                     }
                 }
                 _ => return Err(-1),
             }
 
-            if width != Some(0) && c != b'n' {
-                // It didn't hit the width, so an extra character was read and matched.
-                // But this character did not match so let's reuse it.
-                skip_read = true;
+            if c != b'n' {
+                // If it didn't hit the width, an extra character was read
+                // ahead. It did not match, so let's reuse it.
+                skip_read = width != Some(0);
             }
         }
     }
+
+    if skip_read && !eof {
+        *unread = Some(byte);
+    }
     Ok(matched)
 }
-pub unsafe fn scanf<R: Read>(r: R, format: *const c_char, ap: VaList) -> c_int {
-    match inner_scanf(r, format, ap) {
+
+/// Scans `format` from `r`, storing the byte that was read ahead but not
+/// consumed (if any) in `unread` so that streams can push it back
+pub unsafe fn scanf<R: Read>(
+    r: R,
+    format: *const c_char,
+    ap: VaList,
+    unread: &mut Option<u8>,
+) -> c_int {
+    match inner_scanf(r, format, ap, unread) {
         Ok(n) => n,
         Err(n) => n,
     }
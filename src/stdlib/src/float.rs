@@ -0,0 +1,348 @@
+use core::ops::Neg;
+use core::str::FromStr;
+use core::{cmp, f64, str};
+
+use ctype;
+use errno::ERANGE;
+use platform;
+use platform::types::*;
+
+// core's decimal parser gives up on inputs with too many significant digits, so anything past
+// this is folded into a single sticky digit, which is enough to round correctly
+const MAX_SIG_DIGITS: usize = 370;
+
+pub trait Float: Copy + FromStr + Into<f64> + Neg<Output = Self> {
+    /// Number of bits in the significand, including the implicit one
+    const MANT_DIGITS: u32;
+    /// Exponent of the smallest normal number
+    const MIN_EXP: i32;
+
+    fn from_f64(f: f64) -> Self;
+}
+
+impl Float for f32 {
+    const MANT_DIGITS: u32 = 24;
+    const MIN_EXP: i32 = -126;
+
+    fn from_f64(f: f64) -> Self {
+        f as f32
+    }
+}
+
+impl Float for f64 {
+    const MANT_DIGITS: u32 = 53;
+    const MIN_EXP: i32 = -1022;
+
+    fn from_f64(f: f64) -> Self {
+        f
+    }
+}
+
+pub unsafe fn parse<T: Float>(s: *const c_char, endptr: *mut *mut c_char) -> T {
+    let mut idx = 0;
+    while ctype::isspace(*s.offset(idx) as c_int) != 0 {
+        idx += 1;
+    }
+
+    let negative = match *s.offset(idx) as u8 {
+        b'-' => {
+            idx += 1;
+            true
+        }
+        b'+' => {
+            idx += 1;
+            false
+        }
+        _ => false,
+    };
+
+    let num_str = s.offset(idx);
+    let (value, len, nonzero) = if let Some((value, len)) = parse_special(num_str) {
+        (T::from_f64(value), len, false)
+    } else if let Some((mant, exp, len)) = parse_hex(num_str) {
+        let value = round_binary(mant, exp, T::MANT_DIGITS, T::MIN_EXP);
+        (T::from_f64(value), len, mant != 0)
+    } else if let Some((value, len, nonzero)) = parse_decimal::<T>(num_str) {
+        (value, len, nonzero)
+    } else {
+        // nothing could be converted, so endptr has to point at the very start
+        if !endptr.is_null() {
+            *endptr = s as *mut _;
+        }
+        return T::from_f64(0.0);
+    };
+
+    let float: f64 = value.into();
+    let min_normal = f64::from_bits(((T::MIN_EXP + 1023) as u64) << 52);
+    if nonzero && (float.is_infinite() || float.abs() < min_normal) {
+        platform::errno = ERANGE;
+    }
+
+    if !endptr.is_null() {
+        // This is stupid, but apparently strto* functions want
+        // const input but mut output, yet the man page says
+        // "stores the address of the first invalid character in *endptr"
+        // so obviously it doesn't want us to clone it.
+        *endptr = num_str.offset(len) as *mut _;
+    }
+
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+unsafe fn starts_with_ignore_case(s: *const c_char, prefix: &[u8]) -> bool {
+    prefix
+        .iter()
+        .enumerate()
+        .all(|(i, &c)| (*s.offset(i as isize) as u8).to_ascii_lowercase() == c)
+}
+
+/// Parses "inf", "infinity", "nan" and "nan(n-char-sequence)"
+unsafe fn parse_special(s: *const c_char) -> Option<(f64, isize)> {
+    if starts_with_ignore_case(s, b"infinity") {
+        Some((f64::INFINITY, 8))
+    } else if starts_with_ignore_case(s, b"inf") {
+        Some((f64::INFINITY, 3))
+    } else if starts_with_ignore_case(s, b"nan") {
+        let mut len = 3;
+        if *s.offset(len) as u8 == b'(' {
+            let mut end = len + 1;
+            while (*s.offset(end) as u8).is_ascii_alphanumeric() || *s.offset(end) as u8 == b'_' {
+                end += 1;
+            }
+            if *s.offset(end) as u8 == b')' {
+                len = end + 1;
+            }
+        }
+        Some((f64::NAN, len))
+    } else {
+        None
+    }
+}
+
+/// Parses an optionally signed exponent, saturating instead of overflowing
+unsafe fn parse_exponent(s: *const c_char) -> Option<(i64, isize)> {
+    let mut idx = 0;
+    let negative = match *s as u8 {
+        b'-' => {
+            idx += 1;
+            true
+        }
+        b'+' => {
+            idx += 1;
+            false
+        }
+        _ => false,
+    };
+
+    if !(*s.offset(idx) as u8).is_ascii_digit() {
+        return None;
+    }
+
+    let mut exp: i64 = 0;
+    while (*s.offset(idx) as u8).is_ascii_digit() {
+        // anything this large is already far outside of the range of any float
+        exp = cmp::min(exp * 10 + (*s.offset(idx) as u8 - b'0') as i64, 1 << 32);
+        idx += 1;
+    }
+
+    Some((if negative { -exp } else { exp }, idx))
+}
+
+/// Parses a hexadecimal float like "0x1.8p3" into a significand and a binary exponent. Digits
+/// that don't fit are collapsed into the lowest bit so that rounding still sees them.
+unsafe fn parse_hex(s: *const c_char) -> Option<(u64, i64, isize)> {
+    if *s as u8 != b'0' || (*s.offset(1) as u8 != b'x' && *s.offset(1) as u8 != b'X') {
+        return None;
+    }
+
+    let mut idx = 2;
+    let mut mant: u64 = 0;
+    let mut exp: i64 = 0;
+    let mut sticky = false;
+    let mut digits = false;
+    let mut fraction = false;
+
+    loop {
+        let c = *s.offset(idx) as u8;
+        if c == b'.' && !fraction {
+            fraction = true;
+        } else if let Some(digit) = (c as char).to_digit(16) {
+            digits = true;
+            if mant >> 60 == 0 {
+                mant = mant << 4 | digit as u64;
+                if fraction {
+                    exp -= 4;
+                }
+            } else {
+                sticky |= digit != 0;
+                if !fraction {
+                    exp += 4;
+                }
+            }
+        } else {
+            break;
+        }
+        idx += 1;
+    }
+
+    if !digits {
+        // "0x" on its own is just a zero followed by garbage
+        return None;
+    }
+    if sticky {
+        mant |= 1;
+    }
+
+    if *s.offset(idx) as u8 == b'p' || *s.offset(idx) as u8 == b'P' {
+        if let Some((e, i)) = parse_exponent(s.offset(idx + 1)) {
+            exp += e;
+            idx += i + 1;
+        }
+    }
+
+    Some((mant, exp, idx))
+}
+
+/// Rounds mant * 2^exp to nearest-even with the given precision, accounting for the reduced
+/// precision of subnormals
+fn round_binary(mant: u64, exp: i64, mant_digits: u32, min_exp: i32) -> f64 {
+    if mant == 0 {
+        return 0.0;
+    }
+
+    let bits = 64 - mant.leading_zeros() as i64;
+    // exponents of the leading bit and of the smallest subnormal
+    let top = exp + bits - 1;
+    let quantum = min_exp as i64 - (mant_digits as i64 - 1);
+    let precision = cmp::min(mant_digits as i64, top - quantum + 1);
+
+    let mut mant = mant;
+    let mut exp = exp;
+    let shift = bits - precision;
+    if shift > 64 {
+        // less than half of the smallest subnormal
+        return 0.0;
+    } else if shift > 0 {
+        let wide = mant as u128;
+        let half = 1u128 << (shift - 1);
+        let rem = wide & ((1u128 << shift) - 1);
+        let mut rounded = wide >> shift;
+        if rem > half || (rem == half && rounded & 1 == 1) {
+            rounded += 1;
+        }
+        mant = rounded as u64;
+        exp += shift;
+    }
+
+    if mant == 0 {
+        return 0.0;
+    }
+
+    // mant now has at most 53 bits, so this multiplication is exact unless it overflows
+    let mut value = mant as f64;
+    while exp > 1000 && !value.is_infinite() {
+        value *= f64::from_bits((1000 + 1023u64) << 52);
+        exp -= 1000;
+    }
+    if value.is_infinite() {
+        return value;
+    }
+    if exp < -1000 {
+        value *= f64::from_bits((-1000i64 + 1023) as u64 << 52);
+        exp += 1000;
+    }
+    value * f64::from_bits(((exp + 1023) as u64) << 52)
+}
+
+/// Parses a decimal float by normalizing it into "<digits>e<exp>" and handing that to core,
+/// which rounds correctly
+unsafe fn parse_decimal<T: Float>(s: *const c_char) -> Option<(T, isize, bool)> {
+    // digits, a sticky digit, 'e', sign and exponent
+    let mut buf = [0u8; MAX_SIG_DIGITS + 16];
+    let mut len = 0;
+    let mut exp: i64 = 0;
+    let mut sticky = false;
+    let mut digits = false;
+    let mut fraction = false;
+
+    let mut idx = 0;
+    loop {
+        let c = *s.offset(idx) as u8;
+        if c == b'.' && !fraction {
+            fraction = true;
+        } else if c.is_ascii_digit() {
+            digits = true;
+            if len == 0 && c == b'0' {
+                // leading zeros don't count towards the significant digits
+                if fraction {
+                    exp -= 1;
+                }
+            } else if len < MAX_SIG_DIGITS {
+                buf[len] = c;
+                len += 1;
+                if fraction {
+                    exp -= 1;
+                }
+            } else {
+                sticky |= c != b'0';
+                if !fraction {
+                    exp += 1;
+                }
+            }
+        } else {
+            break;
+        }
+        idx += 1;
+    }
+
+    if !digits {
+        return None;
+    }
+
+    if *s.offset(idx) as u8 == b'e' || *s.offset(idx) as u8 == b'E' {
+        if let Some((e, i)) = parse_exponent(s.offset(idx + 1)) {
+            exp += e;
+            idx += i + 1;
+        }
+    }
+
+    if len == 0 {
+        return Some((T::from_f64(0.0), idx, false));
+    }
+    if sticky {
+        buf[len] = b'1';
+        len += 1;
+        exp -= 1;
+    }
+
+    // the exponent only has to be large enough to overflow or underflow every float
+    let exp = cmp::max(cmp::min(exp, 100000), -100000);
+    buf[len] = b'e';
+    len += 1;
+    if exp < 0 {
+        buf[len] = b'-';
+        len += 1;
+    }
+    let mut exp_digits = [0u8; 6];
+    let mut exp_len = 0;
+    let mut abs = exp.abs();
+    loop {
+        exp_digits[exp_len] = b'0' + (abs % 10) as u8;
+        exp_len += 1;
+        abs /= 10;
+        if abs == 0 {
+            break;
+        }
+    }
+    for &digit in exp_digits[..exp_len].iter().rev() {
+        buf[len] = digit;
+        len += 1;
+    }
+
+    let text = str::from_utf8_unchecked(&buf[..len]);
+    text.parse::<T>().ok().map(|value| (value, idx, true))
+}
@@ -1,23 +1,27 @@
 //! stdlib implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/stdlib.h.html
 
 #![no_std]
-#![feature(core_intrinsics)]
+#![feature(alloc, core_intrinsics, thread_local)]
 
+extern crate alloc;
 extern crate ctype;
 extern crate errno;
 extern crate fcntl;
 extern crate platform;
 extern crate rand;
+extern crate signal;
+extern crate stdio;
 extern crate string;
+extern crate sys_stat;
 extern crate time;
 extern crate unistd;
 extern crate wchar;
 
+use alloc::vec::Vec;
 use core::{iter, mem, ptr, slice, str};
 use rand::distributions::Alphanumeric;
-use rand::prng::XorShiftRng;
 use rand::rngs::JitterRng;
-use rand::{Rng, SeedableRng};
+use rand::{Rng, RngCore};
 use string::*;
 use wchar::*;
 
@@ -25,19 +29,31 @@ use errno::*;
 use fcntl::*;
 use platform::types::*;
 
+mod float;
+mod random;
 mod sort;
 
 pub const EXIT_FAILURE: c_int = 1;
 pub const EXIT_SUCCESS: c_int = 0;
 pub const RAND_MAX: c_int = 2147483647;
 
+const PATH_MAX: usize = 4096;
+// Following more symlinks than this while resolving a path is treated as a loop
+const SYMLOOP_MAX: usize = 40;
+
 //Maximum number of bytes in a multibyte character for the current locale
 pub const MB_CUR_MAX: c_int = 4;
 //Maximum number of bytes in a multibyte characters for any locale
 pub const MB_LEN_MAX: c_int = 4;
 
-static mut ATEXIT_FUNCS: [Option<extern "C" fn()>; 32] = [None; 32];
-static mut RNG: Option<XorShiftRng> = None;
+// Handlers registered by atexit and at_quick_exit, run from the back
+static mut ATEXIT_FUNCS: Option<Vec<extern "C" fn()>> = None;
+static mut AT_QUICK_EXIT_FUNCS: Option<Vec<extern "C" fn()>> = None;
+
+#[no_mangle]
+pub extern "C" fn _Exit(status: c_int) -> ! {
+    platform::exit(status);
+}
 
 #[no_mangle]
 pub unsafe extern "C" fn a64l(s: *const c_char) -> c_long {
@@ -67,36 +83,96 @@ pub unsafe extern "C" fn a64l(s: *const c_char) -> c_long {
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn abort() {
-    use core::intrinsics;
+pub unsafe extern "C" fn abort() -> ! {
+    stdio::fflush(None);
 
-    intrinsics::abort();
+    // Neither a handler nor the signal mask may keep SIGABRT from killing us
+    let sig = signal::SIGABRT as c_int;
+    let mut set = 0;
+    signal::sigaddset(&mut set, sig);
+    signal::sigprocmask(signal::SIG_UNBLOCK, &set, ptr::null_mut());
+    signal::signal(sig, None);
+    signal::raise(sig);
+
+    // If we're somehow still alive, exit with the status the shell would report for SIGABRT
+    platform::exit(128 + sig);
 }
 
+/// The absolute value of `i`. That of `INT_MIN` doesn't fit in an int, so it is returned as is
+/// instead, like the two's complement negation does in C.
 #[no_mangle]
 pub extern "C" fn abs(i: c_int) -> c_int {
-    i.abs()
+    i.wrapping_abs()
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn aligned_alloc(alignment: size_t, size: size_t) -> *mut c_void {
-    if size % alignment != 0 {
+    if !alignment.is_power_of_two() || size % alignment != 0 {
+        platform::errno = EINVAL;
         return ptr::null_mut();
     }
 
     memalign(alignment, size)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn at_quick_exit(func: Option<extern "C" fn()>) -> c_int {
+    register_exit_func(&mut AT_QUICK_EXIT_FUNCS, func)
+}
+
+/// Returns a random number that can't be predicted, from a generator seeded by the kernel
+#[no_mangle]
+pub unsafe extern "C" fn arc4random() -> u32 {
+    random::arc4random().next_u32()
+}
+
+/// Fills the `n` bytes at `buf` with random bytes that can't be predicted
+#[no_mangle]
+pub unsafe extern "C" fn arc4random_buf(buf: *mut c_void, n: size_t) {
+    random::arc4random().fill_bytes(slice::from_raw_parts_mut(buf as *mut u8, n));
+}
+
+/// Returns a random number below `upper_bound`, with every number equally likely
+#[no_mangle]
+pub unsafe extern "C" fn arc4random_uniform(upper_bound: u32) -> u32 {
+    if upper_bound < 2 {
+        return 0;
+    }
+
+    // Taking the remainder of anything below 2^32 % upper_bound would make the small numbers
+    // come up more often than the rest, so those are thrown away
+    let min = upper_bound.wrapping_neg() % upper_bound;
+    loop {
+        let r = arc4random();
+        if r >= min {
+            return r % upper_bound;
+        }
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn atexit(func: Option<extern "C" fn()>) -> c_int {
-    for i in 0..ATEXIT_FUNCS.len() {
-        if ATEXIT_FUNCS[i] == None {
-            ATEXIT_FUNCS[i] = func;
-            return 0;
+    register_exit_func(&mut ATEXIT_FUNCS, func)
+}
+
+unsafe fn register_exit_func(
+    funcs: &mut Option<Vec<extern "C" fn()>>,
+    func: Option<extern "C" fn()>,
+) -> c_int {
+    match func {
+        Some(func) => {
+            funcs.get_or_insert_with(Vec::new).push(func);
+            0
         }
+        None => -1,
     }
+}
 
-    1
+/// Runs the handlers last to first. Handlers may register more handlers, which run next.
+unsafe fn run_exit_funcs(funcs: &mut Option<Vec<extern "C" fn()>>) {
+    while let Some(func) = funcs.as_mut().and_then(|funcs| funcs.pop()) {
+        func();
+    }
 }
 
 #[no_mangle]
@@ -152,6 +228,11 @@ pub extern "C" fn atol(s: *const c_char) -> c_long {
     dec_num_from_ascii!(s, c_long)
 }
 
+#[no_mangle]
+pub extern "C" fn atoll(s: *const c_char) -> c_longlong {
+    dec_num_from_ascii!(s, c_longlong)
+}
+
 unsafe extern "C" fn void_cmp(a: *const c_void, b: *const c_void) -> c_int {
     return *(a as *const i32) - *(b as *const i32) as c_int;
 }
@@ -164,16 +245,18 @@ pub unsafe extern "C" fn bsearch(
     width: size_t,
     compar: Option<unsafe extern "C" fn(*const c_void, *const c_void) -> c_int>,
 ) -> *mut c_void {
-    let mut start = base;
+    let mut start = base as *const c_char;
     let mut len = nel;
     let cmp_fn = compar.unwrap_or(void_cmp);
+    // halve the remaining range each step; when the key is greater than the middle element,
+    // continue in the upper half just past it
     while len > 0 {
-        let med = (start as size_t + (len >> 1) * width) as *const c_void;
-        let diff = cmp_fn(key, med);
+        let med = start.add((len >> 1) * width);
+        let diff = cmp_fn(key, med as *const c_void);
         if diff == 0 {
             return med as *mut c_void;
         } else if diff > 0 {
-            start = (med as usize + width) as *const c_void;
+            start = med.add(width);
             len -= 1;
         }
         len >>= 1;
@@ -185,7 +268,13 @@ pub unsafe extern "C" fn bsearch(
 pub unsafe extern "C" fn calloc(nelem: size_t, elsize: size_t) -> *mut c_void {
     use core::intrinsics;
 
-    let size = nelem * elsize;
+    let size = match nelem.checked_mul(elsize) {
+        Some(size) => size,
+        None => {
+            platform::errno = ENOMEM;
+            return ptr::null_mut();
+        }
+    };
     let ptr = malloc(size);
     if !ptr.is_null() {
         intrinsics::write_bytes(ptr as *mut u8, 0, size);
@@ -195,15 +284,16 @@ pub unsafe extern "C" fn calloc(nelem: size_t, elsize: size_t) -> *mut c_void {
 
 #[repr(C)]
 pub struct div_t {
-    quot: c_int,
-    rem: c_int,
+    pub quot: c_int,
+    pub rem: c_int,
 }
 
+/// Divides `numer` by `denom`, rounding toward zero, and returns the quotient and remainder
 #[no_mangle]
 pub extern "C" fn div(numer: c_int, denom: c_int) -> div_t {
     div_t {
-        quot: numer / denom,
-        rem: numer % denom,
+        quot: numer.wrapping_div(denom),
+        rem: numer.wrapping_rem(denom),
     }
 }
 
@@ -228,12 +318,10 @@ pub extern "C" fn erand(xsubi: [c_ushort; 3]) -> c_double {
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn exit(status: c_int) {
-    for i in (0..ATEXIT_FUNCS.len()).rev() {
-        if let Some(func) = ATEXIT_FUNCS[i] {
-            (func)();
-        }
-    }
+pub unsafe extern "C" fn exit(status: c_int) -> ! {
+    run_exit_funcs(&mut ATEXIT_FUNCS);
+
+    stdio::fflush(None);
 
     platform::exit(status);
 }
@@ -250,7 +338,9 @@ pub extern "C" fn fcvt(
 
 #[no_mangle]
 pub unsafe extern "C" fn free(ptr: *mut c_void) {
-    platform::free(ptr);
+    if !ptr.is_null() {
+        platform::free(ptr);
+    }
 }
 
 // #[no_mangle]
@@ -293,8 +383,23 @@ unsafe fn find_env(search: *const c_char) -> Option<(usize, *mut c_char)> {
     None
 }
 
+unsafe fn is_valid_env_name(name: *const c_char) -> bool {
+    !name.is_null() && *name != 0 && strchr(name, b'=' as c_int).is_null()
+}
+
+unsafe fn insert_env(item: *mut c_char) {
+    let i = platform::inner_environ.len() - 1;
+    assert_eq!(platform::inner_environ[i], ptr::null_mut(), "environ did not end with null");
+    platform::inner_environ[i] = item;
+    platform::inner_environ.push(ptr::null_mut());
+    platform::environ = platform::inner_environ.as_mut_ptr();
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn getenv(name: *const c_char) -> *mut c_char {
+    if !is_valid_env_name(name) {
+        return ptr::null_mut();
+    }
     find_env(name).map(|val| val.1).unwrap_or(ptr::null_mut())
 }
 
@@ -312,9 +417,15 @@ pub extern "C" fn grantpt(fildes: c_int) -> c_int {
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn initstate(seec: c_uint, state: *mut c_char, size: size_t) -> *mut c_char {
-    unimplemented!();
+/// Makes `random` use the `size` bytes at `state`, which must be at least 8, seeded with `seed`.
+/// More state makes for better random numbers, up to 256 bytes. Returns the previous state.
+#[no_mangle]
+pub unsafe extern "C" fn initstate(seed: c_uint, state: *mut c_char, size: size_t) -> *mut c_char {
+    let old = random::initstate(seed, state, size);
+    if old.is_null() {
+        platform::errno = EINVAL;
+    }
+    old
 }
 
 // #[no_mangle]
@@ -327,9 +438,10 @@ pub extern "C" fn l64a(value: c_long) -> *mut c_char {
     unimplemented!();
 }
 
+/// Like `abs`, so `LONG_MIN` is returned as is
 #[no_mangle]
 pub extern "C" fn labs(i: c_long) -> c_long {
-    i.abs()
+    i.wrapping_abs()
 }
 
 // #[no_mangle]
@@ -339,15 +451,15 @@ pub extern "C" fn lcong48(param: [c_ushort; 7]) {
 
 #[repr(C)]
 pub struct ldiv_t {
-    quot: c_long,
-    rem: c_long,
+    pub quot: c_long,
+    pub rem: c_long,
 }
 
 #[no_mangle]
 pub extern "C" fn ldiv(numer: c_long, denom: c_long) -> ldiv_t {
     ldiv_t {
-        quot: numer / denom,
-        rem: numer % denom,
+        quot: numer.wrapping_div(denom),
+        rem: numer.wrapping_rem(denom),
     }
 }
 
@@ -356,25 +468,53 @@ pub extern "C" fn lrand48() -> c_long {
     unimplemented!();
 }
 
+/// Like `abs`, so `LLONG_MIN` is returned as is
 #[no_mangle]
 pub extern "C" fn llabs(i: c_longlong) -> c_longlong {
-    i.abs()
+    i.wrapping_abs()
+}
+
+#[repr(C)]
+pub struct lldiv_t {
+    pub quot: c_longlong,
+    pub rem: c_longlong,
+}
+
+#[no_mangle]
+pub extern "C" fn lldiv(numer: c_longlong, denom: c_longlong) -> lldiv_t {
+    lldiv_t {
+        quot: numer.wrapping_div(denom),
+        rem: numer.wrapping_rem(denom),
+    }
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn malloc(size: size_t) -> *mut c_void {
-    platform::alloc(size)
+    let ptr = platform::alloc(size);
+    if ptr.is_null() {
+        platform::errno = ENOMEM;
+    }
+    ptr
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn memalign(alignment: size_t, size: size_t) -> *mut c_void {
-    platform::alloc_align(size, alignment)
+    if !alignment.is_power_of_two() {
+        platform::errno = EINVAL;
+        return ptr::null_mut();
+    }
+
+    let ptr = platform::alloc_align(size, alignment);
+    if ptr.is_null() {
+        platform::errno = ENOMEM;
+    }
+    ptr
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mblen(s: *const c_char, n: size_t) -> c_int {
     let mut wc: wchar_t = 0;
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::default();
     let result: usize = mbrtowc(&mut wc, s, n, &mut state);
 
     if result == -1isize as usize {
@@ -389,14 +529,21 @@ pub unsafe extern "C" fn mblen(s: *const c_char, n: size_t) -> c_int {
 
 #[no_mangle]
 pub unsafe extern "C" fn mbstowcs(pwcs: *mut wchar_t, mut s: *const c_char, n: size_t) -> size_t {
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::default();
     mbsrtowcs(pwcs, &mut s, n, &mut state)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mbtowc(pwc: *mut wchar_t, s: *const c_char, n: size_t) -> c_int {
-    let mut state: mbstate_t = mbstate_t {};
-    mbrtowc(pwc, s, n, &mut state) as c_int
+    let mut state: mbstate_t = mbstate_t::default();
+    let result: usize = mbrtowc(pwc, s, n, &mut state);
+
+    // Without a state to keep it in, an incomplete character is as bad as an invalid one
+    if result == -1isize as usize || result == -2isize as usize {
+        return -1;
+    }
+
+    result as c_int
 }
 
 fn inner_mktemp<T, F>(name: *mut c_char, suffix_len: c_int, mut attempt: F) -> Option<T>
@@ -476,9 +623,10 @@ pub extern "C" fn mkostemps(name: *mut c_char, suffix_len: c_int, mut flags: c_i
     flags |= O_RDWR | O_CREAT | O_EXCL;
 
     inner_mktemp(name, suffix_len, || {
-        let fd = platform::open(name, flags, 0600);
+        let fd = platform::open(name, flags, 0o600);
 
-        if fd >= 0 {
+        // Only a name that's taken is worth trying again with another one
+        if fd >= 0 || unsafe { platform::errno } != EEXIST {
             Some(fd)
         } else {
             None
@@ -486,6 +634,20 @@ pub extern "C" fn mkostemps(name: *mut c_char, suffix_len: c_int, mut flags: c_i
     }).unwrap_or(-1)
 }
 
+/// Replaces the trailing `XXXXXX` of `name` to create a new directory only the caller may use
+#[no_mangle]
+pub extern "C" fn mkdtemp(name: *mut c_char) -> *mut c_char {
+    inner_mktemp(name, 0, || {
+        if platform::mkdir(name, 0o700) == 0 {
+            Some(name)
+        } else if unsafe { platform::errno } != EEXIST {
+            Some(ptr::null_mut())
+        } else {
+            None
+        }
+    }).unwrap_or(ptr::null_mut())
+}
+
 #[no_mangle]
 pub extern "C" fn mkstemp(name: *mut c_char) -> c_int {
     mkostemps(name, 0, 0)
@@ -509,6 +671,25 @@ pub extern "C" fn nrand48(xsubi: [c_ushort; 3]) -> c_long {
     unimplemented!();
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn posix_memalign(
+    memptr: *mut *mut c_void,
+    alignment: size_t,
+    size: size_t,
+) -> c_int {
+    // Errors are returned, errno is left alone
+    if !alignment.is_power_of_two() || alignment % mem::size_of::<*mut c_void>() != 0 {
+        return EINVAL;
+    }
+
+    let ptr = platform::alloc_align(size, alignment);
+    if ptr.is_null() {
+        return ENOMEM;
+    }
+    *memptr = ptr;
+    0
+}
+
 // #[no_mangle]
 pub extern "C" fn ptsname(fildes: c_int) -> *mut c_char {
     unimplemented!();
@@ -517,15 +698,11 @@ pub extern "C" fn ptsname(fildes: c_int) -> *mut c_char {
 #[no_mangle]
 pub unsafe extern "C" fn putenv(insert: *mut c_char) -> c_int {
     assert_ne!(insert, ptr::null_mut(), "putenv(NULL)");
+    // The string itself becomes part of the environment, so it is never copied or freed here
     if let Some((i, _)) = find_env(insert) {
-        platform::free(platform::inner_environ[i] as *mut c_void);
         platform::inner_environ[i] = insert;
     } else {
-        let i = platform::inner_environ.len() - 1;
-        assert_eq!(platform::inner_environ[i], ptr::null_mut(), "environ did not end with null");
-        platform::inner_environ[i] = insert;
-        platform::inner_environ.push(ptr::null_mut());
-        platform::environ = platform::inner_environ.as_mut_ptr();
+        insert_env(insert);
     }
     0
 }
@@ -542,121 +719,277 @@ pub extern "C" fn qsort(
         if nel > 0 {
             // XXX: maybe try to do mergesort/timsort first and fallback to introsort if memory
             //      allocation fails?  not sure what is ideal
-            sort::introsort(base as *mut c_char, nel, width, comp);
+            sort::introsort(base as *mut c_char, nel, width, &|a, b| comp(a, b));
         }
     }
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn rand() -> c_int {
-    match RNG {
-        Some(ref mut rng) => rng.gen_range(0, RAND_MAX),
-        None => {
-            let mut rng = XorShiftRng::from_seed([1; 16]);
-            let ret = rng.gen_range(0, RAND_MAX);
-            RNG = Some(rng);
-            ret
+pub extern "C" fn qsort_r(
+    base: *mut c_void,
+    nel: size_t,
+    width: size_t,
+    compar: Option<extern "C" fn(*const c_void, *const c_void, *mut c_void) -> c_int>,
+    arg: *mut c_void,
+) {
+    if let Some(comp) = compar {
+        if nel > 0 {
+            sort::introsort(base as *mut c_char, nel, width, &|a, b| comp(a, b, arg));
         }
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn rand_r(seed: *mut c_uint) -> c_int {
-    unimplemented!();
-}
+#[no_mangle]
+pub unsafe extern "C" fn quick_exit(status: c_int) -> ! {
+    run_exit_funcs(&mut AT_QUICK_EXIT_FUNCS);
 
-// #[no_mangle]
-pub extern "C" fn random() -> c_long {
-    unimplemented!();
+    platform::exit(status);
 }
 
+/// Returns a number from 0 to `RAND_MAX`. This is the same generator as `random`, so it gives the
+/// same sequences as glibc for the same seed.
 #[no_mangle]
-pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
-    platform::realloc(ptr, size)
+pub unsafe extern "C" fn rand() -> c_int {
+    random::random() as c_int
 }
 
-// #[no_mangle]
-pub extern "C" fn realpath(file_name: *const c_char, resolved_name: *mut c_char) -> *mut c_char {
-    unimplemented!();
+/// Like `rand`, but keeps all of its state in `seed`
+#[no_mangle]
+pub unsafe extern "C" fn rand_r(seed: *mut c_uint) -> c_int {
+    random::rand_r(&mut *seed)
 }
 
-// #[no_mangle]
-pub extern "C" fn seed48(seed16v: [c_ushort; 3]) -> c_ushort {
-    unimplemented!();
+/// Returns a number from 0 to 2^31 - 1, using an additive feedback generator over the state
+/// given to `initstate`, or a built in one of 128 bytes
+#[no_mangle]
+pub unsafe extern "C" fn random() -> c_long {
+    random::random()
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn setenv(mut key: *const c_char, mut value: *const c_char, overwrite: c_int) -> c_int {
-    let mut key_len = 0;
-    while *key.offset(key_len) != 0 {
-        key_len += 1;
+pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
+    if ptr.is_null() {
+        return malloc(size);
     }
-    let mut value_len = 0;
-    while *value.offset(value_len) != 0 {
-        value_len += 1;
+    // Shrinking to nothing is the same as freeing
+    if size == 0 {
+        free(ptr);
+        return ptr::null_mut();
     }
 
-    let index = if let Some((i, existing)) = find_env(key) {
-        if overwrite == 0 {
-            return 0;
-        }
+    let new = platform::realloc(ptr, size);
+    if new.is_null() {
+        platform::errno = ENOMEM;
+    }
+    new
+}
 
-        let mut existing_len = 0;
-        while *existing.offset(existing_len) != 0 {
-            existing_len += 1;
+#[no_mangle]
+pub unsafe extern "C" fn reallocarray(ptr: *mut c_void, nmemb: size_t, size: size_t) -> *mut c_void {
+    match nmemb.checked_mul(size) {
+        Some(size) => realloc(ptr, size),
+        None => {
+            platform::errno = ENOMEM;
+            ptr::null_mut()
         }
+    }
+}
 
-        if existing_len >= value_len {
-            // Reuse existing element's allocation
-            for i in 0..=value_len {
-                *existing.offset(i) = *value.offset(i);
-            }
-            return 0;
-        } else {
-            i
+#[no_mangle]
+pub unsafe extern "C" fn realpath(
+    file_name: *const c_char,
+    resolved_name: *mut c_char,
+) -> *mut c_char {
+    if file_name.is_null() {
+        platform::errno = EINVAL;
+        return ptr::null_mut();
+    }
+    let resolved = match canonicalize(platform::c_str(file_name)) {
+        Some(resolved) => resolved,
+        None => return ptr::null_mut(),
+    };
+
+    let out = if resolved_name.is_null() {
+        let ptr = malloc(resolved.len() + 1) as *mut c_char;
+        if ptr.is_null() {
+            platform::errno = ENOMEM;
+            return ptr::null_mut();
         }
+        ptr
     } else {
-        let i = platform::inner_environ.len() - 1;
-        assert_eq!(platform::inner_environ[i], ptr::null_mut(), "environ did not end with null");
-        platform::inner_environ.push(ptr::null_mut());
-        platform::environ = platform::inner_environ.as_mut_ptr();
-        i
+        resolved_name
     };
+    out.copy_from_nonoverlapping(resolved.as_ptr() as *const c_char, resolved.len());
+    *out.add(resolved.len()) = 0;
+    out
+}
 
-    //platform::free(platform::inner_environ[index] as *mut c_void);
-    let mut ptr = platform::alloc(key_len as usize + 1 + value_len as usize) as *mut c_char;
-    platform::inner_environ[index] = ptr;
+/// Length of the part of `path` that ".." can't go above, either the leading slash or a scheme
+/// like "file:/"
+fn root_len(path: &[u8]) -> usize {
+    if path.first() == Some(&b'/') {
+        return 1;
+    }
+    match path.iter().position(|&c| c == b'/' || c == b':') {
+        Some(i) if path[i] == b':' && path.get(i + 1) == Some(&b'/') => i + 2,
+        Some(i) if path[i] == b':' => i + 1,
+        _ => 0,
+    }
+}
 
-    while *key != 0 {
-        *ptr = *key;
-        ptr = ptr.offset(1);
-        key = key.offset(1);
+/// Makes `path` absolute, collapsing ".", ".." and repeated slashes and replacing symlinks by
+/// their targets. The result is never longer than PATH_MAX - 1.
+unsafe fn canonicalize(path: &[u8]) -> Option<Vec<u8>> {
+    if path.is_empty() {
+        platform::errno = ENOENT;
+        return None;
     }
-    *ptr = b'=' as c_char;
-    ptr = ptr.offset(1);
-    while *value != 0 {
-        *ptr = *value;
-        ptr = ptr.offset(1);
-        value = value.offset(1);
+    if path.len() >= PATH_MAX {
+        platform::errno = ENAMETOOLONG;
+        return None;
     }
-    *ptr = 0;
 
-    0
+    let mut rest = Vec::new();
+    if root_len(path) == 0 {
+        let mut cwd = [0; PATH_MAX];
+        if platform::getcwd(cwd.as_mut_ptr(), PATH_MAX).is_null() {
+            return None;
+        }
+        rest.extend_from_slice(platform::c_str(cwd.as_ptr()));
+        rest.push(b'/');
+    }
+    rest.extend_from_slice(path);
+
+    let mut root = root_len(&rest);
+    let mut resolved = rest[..root].to_vec();
+    let mut pos = root;
+    let mut links = 0;
+    while pos < rest.len() {
+        let end = rest[pos..]
+            .iter()
+            .position(|&c| c == b'/')
+            .map_or(rest.len(), |i| pos + i);
+        let component = rest[pos..end].to_vec();
+        pos = end + 1;
+
+        match &component[..] {
+            b"" | b"." => continue,
+            b".." => {
+                while resolved.len() > root && resolved.pop() != Some(b'/') {}
+                if resolved.len() < root {
+                    resolved.push(b'/');
+                }
+                continue;
+            }
+            _ => (),
+        }
+
+        let parent_len = resolved.len();
+        if parent_len > root {
+            resolved.push(b'/');
+        }
+        resolved.extend_from_slice(&component);
+        if resolved.len() >= PATH_MAX {
+            platform::errno = ENAMETOOLONG;
+            return None;
+        }
+
+        resolved.push(0);
+        let mut buf: stat = mem::zeroed();
+        if platform::lstat(resolved.as_ptr() as *const c_char, &mut buf) < 0 {
+            return None;
+        }
+        if buf.st_mode & sys_stat::S_IFMT != sys_stat::S_IFLNK {
+            resolved.pop();
+            continue;
+        }
+
+        links += 1;
+        if links > SYMLOOP_MAX {
+            platform::errno = ELOOP;
+            return None;
+        }
+        let mut target = [0; PATH_MAX];
+        let len = platform::readlink(resolved.as_ptr() as *const c_char, &mut target);
+        if len < 0 {
+            return None;
+        }
+        let target = &target[..len as usize];
+
+        // The link is replaced by its target, which is resolved like the rest of the path
+        let mut next = target.to_vec();
+        if pos < rest.len() {
+            next.push(b'/');
+            next.extend_from_slice(&rest[pos..]);
+        }
+        if next.len() >= PATH_MAX {
+            platform::errno = ENAMETOOLONG;
+            return None;
+        }
+        resolved.truncate(parent_len);
+        let target_root = root_len(target);
+        if target_root > 0 {
+            root = target_root;
+            resolved = target[..root].to_vec();
+        }
+        rest = next;
+        pos = target_root;
+    }
+
+    Some(resolved)
 }
 
 // #[no_mangle]
-pub extern "C" fn setkey(key: *const c_char) {
+pub extern "C" fn seed48(seed16v: [c_ushort; 3]) -> c_ushort {
     unimplemented!();
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn setenv(key: *const c_char, value: *const c_char, overwrite: c_int) -> c_int {
+    if !is_valid_env_name(key) {
+        platform::errno = EINVAL;
+        return -1;
+    }
+
+    let existing = find_env(key);
+    if existing.is_some() && overwrite == 0 {
+        return 0;
+    }
+
+    let key_len = strlen(key);
+    let value_len = strlen(value);
+    let ptr = platform::alloc(key_len + 1 + value_len + 1) as *mut c_char;
+    if ptr.is_null() {
+        platform::errno = ENOMEM;
+        return -1;
+    }
+    ptr.copy_from_nonoverlapping(key, key_len);
+    *ptr.add(key_len) = b'=' as c_char;
+    ptr.add(key_len + 1).copy_from_nonoverlapping(value, value_len + 1);
+
+    // The previous string is left alone, the caller of putenv or getenv may still be using it
+    match existing {
+        Some((i, _)) => platform::inner_environ[i] = ptr,
+        None => insert_env(ptr),
+    }
+
+    0
+}
+
 // #[no_mangle]
-pub extern "C" fn setstate(state: *const c_char) -> *mut c_char {
+pub extern "C" fn setkey(key: *const c_char) {
     unimplemented!();
 }
 
+/// Makes `random` continue from a state set up by `initstate`. Returns the previous state.
+#[no_mangle]
+pub unsafe extern "C" fn setstate(state: *const c_char) -> *mut c_char {
+    random::setstate(state as *mut c_char)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn srand(seed: c_uint) {
-    RNG = Some(XorShiftRng::from_seed([seed as u8; 16]));
+    random::srandom(seed);
 }
 
 // #[no_mangle]
@@ -664,61 +997,19 @@ pub extern "C" fn srand48(seed: c_long) {
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn srandom(seed: c_uint) {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn srandom(seed: c_uint) {
+    random::srandom(seed);
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn strtod(mut s: *const c_char, endptr: *mut *mut c_char) -> c_double {
-    while ctype::isspace(*s as c_int) != 0 {
-        s = s.offset(1);
-    }
-
-    let mut result = 0.0;
-    let mut radix = 10;
-
-    let negative = match *s as u8 {
-        b'-' => { s = s.offset(1); true },
-        b'+' => { s = s.offset(1); false },
-        _ => false
-    };
-
-    if *s as u8 == b'0' && *s.offset(1) as u8 == b'x' {
-        s = s.offset(2);
-        radix = 16;
-    }
-
-    while let Some(digit) = (*s as u8 as char).to_digit(radix) {
-        result *= radix as c_double;
-        result += digit as c_double;
-        s = s.offset(1);
-    }
-
-    if *s as u8 == b'.' {
-        s = s.offset(1);
-
-        let mut i = 1.0;
-        while let Some(digit) = (*s as u8 as char).to_digit(radix) {
-            i *= radix as c_double;
-            result += digit as c_double / i;
-            s = s.offset(1);
-        }
-    }
-
-    if !endptr.is_null() {
-        // This is stupid, but apparently strto* functions want
-        // const input but mut output, yet the man page says
-        // "stores the address of the first invalid character in *endptr"
-        // so obviously it doesn't want us to clone it.
-        *endptr = s as *mut _;
-    }
+pub unsafe extern "C" fn strtod(s: *const c_char, endptr: *mut *mut c_char) -> c_double {
+    float::parse(s, endptr)
+}
 
-    if negative {
-        -result
-    } else {
-        result
-    }
+#[no_mangle]
+pub unsafe extern "C" fn strtof(s: *const c_char, endptr: *mut *mut c_char) -> c_float {
+    float::parse(s, endptr)
 }
 
 pub fn is_positive(ch: c_char) -> Option<(bool, isize)> {
@@ -749,19 +1040,6 @@ pub fn detect_base(s: *const c_char) -> Option<(c_int, isize)> {
     }
 }
 
-pub unsafe fn convert_octal(s: *const c_char) -> Option<(c_ulong, isize, bool)> {
-    if *s != 0 && *s == b'0' as c_char {
-        if let Some((val, idx, overflow)) = convert_integer(s.offset(1), 8) {
-            Some((val, idx + 1, overflow))
-        } else {
-            // in case the prefix is not actually a prefix
-            Some((0, 1, false))
-        }
-    } else {
-        None
-    }
-}
-
 pub unsafe fn convert_hex(s: *const c_char) -> Option<(c_ulong, isize, bool)> {
     if (*s != 0 && *s == b'0' as c_char)
         && (*s.offset(1) != 0 && (*s.offset(1) == b'x' as c_char || *s.offset(1) == b'X' as c_char))
@@ -884,9 +1162,13 @@ macro_rules! strto_impl {
         let num_str = $s.offset(idx);
         let res = match $base {
             0 => detect_base(num_str)
-                .and_then(|($base, i)| convert_integer(num_str.offset(i), $base)),
-            8 => convert_octal(num_str),
-            16 => convert_hex(num_str),
+                .and_then(|(base, i)| {
+                    convert_integer(num_str.offset(i), base)
+                        .map(|(num, j, overflow)| (num, i + j, overflow))
+                })
+                // a "0x" prefix without any hex digits after it is just the number 0
+                .or_else(|| convert_integer(num_str, 8)),
+            16 => convert_hex(num_str).or_else(|| convert_integer(num_str, 16)),
             _ => convert_integer(num_str, $base),
         };
 
@@ -901,8 +1183,13 @@ macro_rules! strto_impl {
         };
         idx += i;
 
+        // a negative number may go one past the maximum, as the magnitude of the minimum
         let overflow = if CHECK_SIGN {
-            overflow || (num as c_long).is_negative()
+            overflow || if positive {
+                num > MAX_VAL as c_ulong
+            } else {
+                num > MIN_VAL as c_ulong
+            }
         } else {
             overflow
         };
@@ -969,47 +1256,95 @@ pub unsafe extern "C" fn strtoull(
     s: *const c_char,
     endptr: *mut *mut c_char,
     base: c_int,
-) -> c_ulong {
-    strtoul(s, endptr, base)
+) -> c_ulonglong {
+    strto_impl!(
+        c_ulonglong,
+        false,
+        c_ulonglong::max_value(),
+        c_ulonglong::min_value(),
+        s,
+        endptr,
+        base
+    )
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn strtoll(s: *const c_char, endptr: *mut *mut c_char, base: c_int) -> c_long {
-    strtol(s, endptr, base)
+pub unsafe extern "C" fn strtoll(
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+    base: c_int,
+) -> c_longlong {
+    strto_impl!(
+        c_longlong,
+        true,
+        c_longlong::max_value(),
+        c_longlong::min_value(),
+        s,
+        endptr,
+        base
+    )
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn system(command: *const c_char) -> c_int {
+    let shell = "/bin/sh\0".as_ptr() as *const c_char;
+    if command.is_null() {
+        // only asks whether there's a shell at all
+        return (unistd::access(shell, unistd::X_OK) == 0) as c_int;
+    }
+
+    // The child is waited for synchronously, so interrupting or quitting it shouldn't kill us and
+    // the child exiting shouldn't bother a SIGCHLD handler
+    let mut ignore: signal::sigaction = mem::zeroed();
+    ignore.__sa_handler.sa_handler = mem::transmute(1usize); // SIG_IGN
+    let mut old_int: signal::sigaction = mem::zeroed();
+    let mut old_quit: signal::sigaction = mem::zeroed();
+    signal::sigaction(signal::SIGINT as c_int, &ignore, &mut old_int);
+    signal::sigaction(signal::SIGQUIT as c_int, &ignore, &mut old_quit);
+
+    let mut block = 0;
+    let mut old_mask = 0;
+    signal::sigaddset(&mut block, signal::SIGCHLD as c_int);
+    signal::sigprocmask(signal::SIG_BLOCK, &block, &mut old_mask);
+
     let child_pid = unistd::fork();
     if child_pid == 0 {
-        let command_nonnull = if command.is_null() {
-            "exit 0\0".as_ptr()
-        } else {
-            command as *const u8
-        };
-
-        let shell = "/bin/sh\0".as_ptr();
+        // the shell gets the dispositions we had before
+        signal::sigaction(signal::SIGINT as c_int, &old_int, ptr::null_mut());
+        signal::sigaction(signal::SIGQUIT as c_int, &old_quit, ptr::null_mut());
+        signal::sigprocmask(signal::SIG_SETMASK, &old_mask, ptr::null_mut());
 
         let args = [
             "sh\0".as_ptr(),
             "-c\0".as_ptr(),
-            command_nonnull,
+            command as *const u8,
             ptr::null(),
         ];
 
-        unistd::execv(shell as *const c_char, args.as_ptr() as *const *mut c_char);
+        unistd::execv(shell, args.as_ptr() as *const *mut c_char);
 
-        exit(127);
+        // the handlers and buffers belong to the parent
+        _Exit(127);
+    }
 
-        unreachable!();
-    } else {
-        let mut wstatus = 0;
-        if platform::waitpid(child_pid, &mut wstatus, 0) == !0 {
-            return -1;
+    let mut wstatus = -1;
+    if child_pid > 0 {
+        while platform::waitpid(child_pid, &mut wstatus, 0) == !0 {
+            if platform::errno != EINTR {
+                wstatus = -1;
+                break;
+            }
         }
-
-        wstatus
     }
+
+    // restoring everything can't clobber the errno from fork or waitpid
+    let errno = platform::errno;
+    signal::sigaction(signal::SIGINT as c_int, &old_int, ptr::null_mut());
+    signal::sigaction(signal::SIGQUIT as c_int, &old_quit, ptr::null_mut());
+    signal::sigprocmask(signal::SIG_SETMASK, &old_mask, ptr::null_mut());
+    platform::errno = errno;
+
+    wstatus
 }
 
 // #[no_mangle]
@@ -1023,8 +1358,13 @@ pub extern "C" fn unlockpt(fildes: c_int) -> c_int {
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn unsetenv(key: *mut c_char) -> c_int {
-    if let Some((i, _)) = find_env(key) {
+pub unsafe extern "C" fn unsetenv(key: *const c_char) -> c_int {
+    if !is_valid_env_name(key) {
+        platform::errno = EINVAL;
+        return -1;
+    }
+
+    while let Some((i, _)) = find_env(key) {
         // No need to worry about updating the pointer, this does not
         // reallocate in any way. And the final null is already shifted back.
         platform::inner_environ.remove(i);
@@ -1038,14 +1378,14 @@ pub unsafe extern "C" fn valloc(size: size_t) -> *mut c_void {
 }
 
 #[no_mangle]
-pub extern "C" fn wcstombs(s: *mut c_char, pwcs: *mut *const wchar_t, n: size_t) -> size_t {
-    let mut state: mbstate_t = mbstate_t {};
-    wcsrtombs(s, pwcs, n, &mut state)
+pub unsafe extern "C" fn wcstombs(s: *mut c_char, mut pwcs: *const wchar_t, n: size_t) -> size_t {
+    let mut state: mbstate_t = mbstate_t::default();
+    wcsrtombs(s, &mut pwcs, n, &mut state)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn wctomb(s: *mut c_char, wc: wchar_t) -> c_int {
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::default();
     let result: usize = wcrtomb(s, wc, &mut state);
 
     if result == -1isize as usize {
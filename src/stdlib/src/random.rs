@@ -0,0 +1,203 @@
+//! The generators behind random(), which produces the same sequences as glibc's, and
+//! arc4random(), which is seeded from the kernel and can't be predicted.
+//!
+//! A random() state buffer starts with a word describing the generator, followed by
+//! `DEGREES[type]` words of state. Type 0 is a plain linear congruential generator, the others
+//! add the words `sep` apart to each other: r[i] = r[i - deg] + r[i - deg + sep].
+
+use core::ptr;
+use rand::prng::ChaChaRng;
+use rand::rngs::JitterRng;
+use rand::{RngCore, SeedableRng};
+
+use platform;
+use platform::types::*;
+
+const MAX_TYPES: usize = 5;
+const BREAKS: [usize; MAX_TYPES] = [8, 32, 64, 128, 256];
+const DEGREES: [usize; MAX_TYPES] = [0, 7, 15, 31, 63];
+const SEPS: [usize; MAX_TYPES] = [0, 3, 1, 3, 1];
+
+// The default state, as if from initstate(1, DEFAULT_STATE, 128)
+static mut DEFAULT_STATE: [i32; 32] = [0; 32];
+
+struct Random {
+    // The first word of the state, not the header before it
+    state: *mut i32,
+    kind: usize,
+    fptr: usize,
+    rptr: usize,
+}
+
+static mut RANDOM: Random = Random {
+    state: ptr::null_mut(),
+    kind: 3,
+    fptr: 0,
+    rptr: 0,
+};
+
+impl Random {
+    /// Records the position in the header, so setstate can pick up where this left off
+    unsafe fn save(&self) {
+        *self.state.offset(-1) = if self.kind == 0 {
+            0
+        } else {
+            (MAX_TYPES * self.rptr + self.kind) as i32
+        };
+    }
+
+    unsafe fn seed(&mut self, seed: c_uint) {
+        // A seed of 0 would leave the state all zeroes
+        let seed = if seed == 0 { 1 } else { seed as i32 };
+        *self.state = seed;
+        if self.kind == 0 {
+            return;
+        }
+
+        let deg = DEGREES[self.kind];
+        let mut word = seed as i64;
+        for i in 1..deg {
+            // word = (16807 * word) % 2147483647, without overflowing
+            let hi = word / 127773;
+            let lo = word % 127773;
+            word = 16807 * lo - 2836 * hi;
+            if word < 0 {
+                word += 2147483647;
+            }
+            *self.state.add(i) = word as i32;
+        }
+
+        self.fptr = SEPS[self.kind];
+        self.rptr = 0;
+        for _ in 0..deg * 10 {
+            self.next();
+        }
+    }
+
+    unsafe fn next(&mut self) -> i32 {
+        if self.kind == 0 {
+            let val = (*self.state as u32).wrapping_mul(1103515245).wrapping_add(12345) & 0x7fffffff;
+            *self.state = val as i32;
+            return val as i32;
+        }
+
+        let deg = DEGREES[self.kind];
+        let fptr = self.state.add(self.fptr);
+        let val = (*fptr as u32).wrapping_add(*self.state.add(self.rptr) as u32);
+        *fptr = val as i32;
+
+        self.fptr += 1;
+        self.rptr += 1;
+        if self.fptr >= deg {
+            self.fptr = 0;
+        } else if self.rptr >= deg {
+            self.rptr = 0;
+        }
+
+        // The lowest bit is the least random
+        (val >> 1) as i32
+    }
+}
+
+unsafe fn random_state() -> &'static mut Random {
+    if RANDOM.state.is_null() {
+        RANDOM.state = DEFAULT_STATE.as_mut_ptr().add(1);
+        RANDOM.seed(1);
+    }
+    &mut RANDOM
+}
+
+pub unsafe fn random() -> c_long {
+    random_state().next() as c_long
+}
+
+pub unsafe fn srandom(seed: c_uint) {
+    random_state().seed(seed);
+}
+
+/// Switches to the `size` bytes at `state`, seeded with `seed`. Returns the old state, or NULL
+/// if `size` is too small to be used.
+pub unsafe fn initstate(seed: c_uint, state: *mut c_char, size: size_t) -> *mut c_char {
+    let kind = match BREAKS.iter().rposition(|&min| size >= min) {
+        Some(kind) => kind,
+        None => return ptr::null_mut(),
+    };
+
+    let random = random_state();
+    random.save();
+    let old = random.state.offset(-1) as *mut c_char;
+
+    random.state = (state as *mut i32).add(1);
+    random.kind = kind;
+    random.seed(seed);
+    random.save();
+    old
+}
+
+/// Switches to a state that `initstate` set up earlier. Returns the old state.
+pub unsafe fn setstate(state: *mut c_char) -> *mut c_char {
+    let random = random_state();
+    random.save();
+    let old = random.state.offset(-1) as *mut c_char;
+
+    let header = *(state as *const i32) as usize;
+    random.state = (state as *mut i32).add(1);
+    random.kind = header % MAX_TYPES;
+    if random.kind != 0 {
+        let deg = DEGREES[random.kind];
+        random.rptr = header / MAX_TYPES;
+        random.fptr = (random.rptr + SEPS[random.kind]) % deg;
+    }
+    old
+}
+
+/// The generator rand_r() uses, which has to fit all of its state in the seed
+pub fn rand_r(seed: &mut c_uint) -> c_int {
+    let mut step = || {
+        *seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        *seed / 65536
+    };
+
+    let mut result = step() % 2048;
+    result = (result << 10) ^ (step() % 1024);
+    result = (result << 10) ^ (step() % 1024);
+    result as c_int
+}
+
+// The generator behind arc4random(), and the process that seeded it. Every thread has its own, so
+// they don't need a lock. A forked child has to reseed, or it would hand out the same numbers as
+// its parent.
+#[thread_local]
+static mut ARC4: Option<(ChaChaRng, pid_t)> = None;
+
+fn entropy() -> [u8; 32] {
+    let mut seed = [0; 32];
+    let mut filled = 0;
+    while filled < seed.len() {
+        let read = platform::getrandom(&mut seed[filled..]);
+        if read <= 0 {
+            break;
+        }
+        filled += read as usize;
+    }
+
+    if filled < seed.len() {
+        // Without the kernel's entropy, fall back to the timing jitter of the CPU
+        let mut rng = JitterRng::new_with_timer(super::get_nstime);
+        rng.test_timer();
+        rng.fill_bytes(&mut seed[filled..]);
+    }
+    seed
+}
+
+pub unsafe fn arc4random() -> &'static mut ChaChaRng {
+    let pid = platform::getpid();
+    let stale = match ARC4 {
+        Some((_, seeded_by)) => seeded_by != pid,
+        None => true,
+    };
+    if stale {
+        ARC4 = Some((ChaChaRng::from_seed(entropy()), pid));
+    }
+    &mut ARC4.as_mut().unwrap().0
+}
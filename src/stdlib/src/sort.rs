@@ -1,11 +1,9 @@
 use platform::types::*;
 
-pub fn introsort(
-    base: *mut c_char,
-    nel: size_t,
-    width: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) {
+pub fn introsort<F>(base: *mut c_char, nel: size_t, width: size_t, comp: &F)
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     let maxdepth = 2 * log2(nel);
     introsort_helper(base, nel, width, maxdepth, comp);
 }
@@ -24,13 +22,16 @@ fn log2(num: size_t) -> size_t {
     max_bits - num.to_le().leading_zeros() as size_t
 }
 
-fn introsort_helper(
+fn introsort_helper<F>(
     mut base: *mut c_char,
     mut nel: size_t,
     width: size_t,
     mut maxdepth: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) {
+    comp: &F,
+)
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     const THRESHOLD: size_t = 8;
 
     // this loop is a trick to save stack space because TCO is not a thing in Rustland
@@ -62,12 +63,10 @@ fn introsort_helper(
     }
 }
 
-fn insertion_sort(
-    base: *mut c_char,
-    nel: size_t,
-    width: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) {
+fn insertion_sort<F>(base: *mut c_char, nel: size_t, width: size_t, comp: &F)
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     for i in 0..nel {
         for j in (0..i).rev() {
             let current = unsafe { base.add(j * width) };
@@ -81,12 +80,10 @@ fn insertion_sort(
     }
 }
 
-fn heapsort(
-    base: *mut c_char,
-    nel: size_t,
-    width: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) {
+fn heapsort<F>(base: *mut c_char, nel: size_t, width: size_t, comp: &F)
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     heapify(base, nel, width, comp);
 
     let mut end = nel - 1;
@@ -98,12 +95,10 @@ fn heapsort(
     }
 }
 
-fn heapify(
-    base: *mut c_char,
-    nel: size_t,
-    width: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) {
+fn heapify<F>(base: *mut c_char, nel: size_t, width: size_t, comp: &F)
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     // we start at the last parent in the heap (the parent of the last child)
     let last_parent = (nel - 2) / 2;
 
@@ -112,13 +107,10 @@ fn heapify(
     }
 }
 
-fn heap_sift_down(
-    base: *mut c_char,
-    start: size_t,
-    end: size_t,
-    width: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) {
+fn heap_sift_down<F>(base: *mut c_char, start: size_t, end: size_t, width: size_t, comp: &F)
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     // get the left child of the node at the given index
     let left_child = |idx| 2 * idx + 1;
 
@@ -154,12 +146,10 @@ fn heap_sift_down(
 }
 
 #[inline]
-fn partition(
-    base: *mut c_char,
-    nel: size_t,
-    width: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) -> (size_t, size_t) {
+fn partition<F>(base: *mut c_char, nel: size_t, width: size_t, comp: &F) -> (size_t, size_t)
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     // calculate the median of the first, middle, and last elements and use it as the pivot
     // to do fewer comparisons, also swap the elements into their correct positions
     let mut pivot = median_of_three(base, nel, width, comp);
@@ -197,12 +187,10 @@ fn partition(
     (i, n)
 }
 
-fn median_of_three(
-    base: *mut c_char,
-    nel: size_t,
-    width: size_t,
-    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
-) -> size_t {
+fn median_of_three<F>(base: *mut c_char, nel: size_t, width: size_t, comp: &F) -> size_t
+where
+    F: Fn(*const c_void, *const c_void) -> c_int,
+{
     let pivot = nel / 2;
 
     let mid = unsafe { base.add(pivot * width) };
@@ -1,5 +1,8 @@
 //! string implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/string.h.html
 #![no_std]
+// The compiler turns copy and fill loops into calls to memcpy and memset, which must not happen
+// inside of memcpy and memset themselves
+#![no_builtins]
 
 extern crate errno;
 extern crate platform;
@@ -12,6 +15,18 @@ use core::usize;
 use errno::*;
 use platform::types::*;
 
+/// The GNU basename, which returns everything after the last slash without modifying `path`.
+/// cbindgen.toml leaves it out, bits/string.h only declares it for _GNU_SOURCE.
+#[no_mangle]
+pub unsafe extern "C" fn basename(path: *const c_char) -> *mut c_char {
+    let slash = strrchr(path, b'/' as c_int);
+    if slash.is_null() {
+        path as *mut c_char
+    } else {
+        slash.offset(1)
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn memccpy(
     dest: *mut c_void,
@@ -72,51 +87,136 @@ pub unsafe extern "C" fn memchr(s: *const c_void, c: c_int, n: usize) -> *mut c_
     ptr::null_mut()
 }
 
+const WORD: usize = mem::size_of::<usize>();
+
 #[no_mangle]
 pub unsafe extern "C" fn memcmp(s1: *const c_void, s2: *const c_void, n: usize) -> c_int {
-    let mut i = 0;
-    while i < n {
-        let a = *(s1 as *const u8).offset(i as isize);
-        let b = *(s2 as *const u8).offset(i as isize);
-        if a != b {
-            return a as i32 - b as i32;
+    let mut a = s1 as *const u8;
+    let mut b = s2 as *const u8;
+    let mut n = n;
+    if (a as usize ^ b as usize) % WORD == 0 {
+        while n > 0 && a as usize % WORD != 0 && *a == *b {
+            a = a.offset(1);
+            b = b.offset(1);
+            n -= 1;
         }
-        i += 1;
+        // skip over equal words, the bytes of the first differing one are compared below
+        if a as usize % WORD == 0 {
+            let mut aw = a as *const usize;
+            let mut bw = b as *const usize;
+            while n >= WORD && *aw == *bw {
+                aw = aw.offset(1);
+                bw = bw.offset(1);
+                n -= WORD;
+            }
+            a = aw as *const u8;
+            b = bw as *const u8;
+        }
+    }
+    while n > 0 {
+        if *a != *b {
+            return *a as c_int - *b as c_int;
+        }
+        a = a.offset(1);
+        b = b.offset(1);
+        n -= 1;
     }
     0
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn memcpy(s1: *mut c_void, s2: *const c_void, n: usize) -> *mut c_void {
-    platform::memcpy(s1, s2, n)
+    let mut d = s1 as *mut u8;
+    let mut s = s2 as *const u8;
+    let mut n = n;
+    // copy whole words if both pointers can be aligned at the same time
+    if (d as usize ^ s as usize) % WORD == 0 {
+        while n > 0 && d as usize % WORD != 0 {
+            *d = *s;
+            d = d.offset(1);
+            s = s.offset(1);
+            n -= 1;
+        }
+        let mut dw = d as *mut usize;
+        let mut sw = s as *const usize;
+        while n >= WORD {
+            *dw = *sw;
+            dw = dw.offset(1);
+            sw = sw.offset(1);
+            n -= WORD;
+        }
+        d = dw as *mut u8;
+        s = sw as *const u8;
+    }
+    while n > 0 {
+        *d = *s;
+        d = d.offset(1);
+        s = s.offset(1);
+        n -= 1;
+    }
+    s1
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn memmove(s1: *mut c_void, s2: *const c_void, n: usize) -> *mut c_void {
-    if s2 < s1 as *const c_void {
-        // copy from end
-        let mut i = n;
-        while i != 0 {
-            i -= 1;
-            *(s1 as *mut u8).offset(i as isize) = *(s2 as *const u8).offset(i as isize);
+    if s1 as usize <= s2 as usize || s1 as usize >= s2 as usize + n {
+        // memcpy copies from the beginning, which is fine when dest comes first
+        return memcpy(s1, s2, n);
+    }
+
+    // copy from end
+    let mut d = (s1 as *mut u8).offset(n as isize);
+    let mut s = (s2 as *const u8).offset(n as isize);
+    let mut n = n;
+    if (d as usize ^ s as usize) % WORD == 0 {
+        while n > 0 && d as usize % WORD != 0 {
+            d = d.offset(-1);
+            s = s.offset(-1);
+            *d = *s;
+            n -= 1;
         }
-    } else {
-        // copy from beginning
-        let mut i = 0;
-        while i < n {
-            *(s1 as *mut u8).offset(i as isize) = *(s2 as *const u8).offset(i as isize);
-            i += 1;
+        let mut dw = d as *mut usize;
+        let mut sw = s as *const usize;
+        while n >= WORD {
+            dw = dw.offset(-1);
+            sw = sw.offset(-1);
+            *dw = *sw;
+            n -= WORD;
         }
+        d = dw as *mut u8;
+        s = sw as *const u8;
+    }
+    while n > 0 {
+        d = d.offset(-1);
+        s = s.offset(-1);
+        *d = *s;
+        n -= 1;
     }
     s1
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn memset(s: *mut c_void, c: c_int, n: usize) -> *mut c_void {
-    let mut i = 0;
-    while i < n {
-        *(s as *mut u8).offset(i as isize) = c as u8;
-        i += 1;
+    let c = c as u8;
+    let mut d = s as *mut u8;
+    let mut n = n;
+    while n > 0 && d as usize % WORD != 0 {
+        *d = c;
+        d = d.offset(1);
+        n -= 1;
+    }
+    let mut dw = d as *mut usize;
+    let repeated_c = !0 as usize / 255 * c as usize;
+    while n >= WORD {
+        *dw = repeated_c;
+        dw = dw.offset(1);
+        n -= WORD;
+    }
+    d = dw as *mut u8;
+    while n > 0 {
+        *d = c;
+        d = d.offset(1);
+        n -= 1;
     }
     s
 }
@@ -129,13 +229,16 @@ pub unsafe extern "C" fn strcat(s1: *mut c_char, s2: *const c_char) -> *mut c_ch
 #[no_mangle]
 pub unsafe extern "C" fn strchr(mut s: *const c_char, c: c_int) -> *mut c_char {
     let c = c as c_char;
-    while *s != 0 {
+    // The terminating NUL is part of the string, and can be searched for
+    loop {
         if *s == c {
             return s as *mut c_char;
         }
+        if *s == 0 {
+            return ptr::null_mut();
+        }
         s = s.offset(1);
     }
-    ptr::null_mut()
 }
 
 #[no_mangle]
@@ -209,15 +312,13 @@ pub unsafe extern "C" fn strdup(s1: *const c_char) -> *mut c_char {
 pub unsafe extern "C" fn strndup(s1: *const c_char, size: usize) -> *mut c_char {
     let len = strnlen(s1, size);
 
-    // the "+ 1" is to account for the NUL byte
+    // the "+ 1" is to account for the NUL byte. This is the same allocator
+    // as malloc, so the caller can free the copy
     let buffer = platform::alloc(len + 1) as *mut c_char;
     if buffer.is_null() {
         platform::errno = ENOMEM as c_int;
     } else {
-        //memcpy(buffer, s1, len)
-        for i in 0..len as isize {
-            *buffer.offset(i) = *s1.offset(i);
-        }
+        memcpy(buffer as *mut c_void, s1 as *const c_void, len);
         *buffer.offset(len as isize) = 0;
     }
 
@@ -241,6 +342,35 @@ pub unsafe extern "C" fn strerror(errnum: c_int) -> *mut c_char {
     strerror_buf.as_mut_ptr() as *mut c_char
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strerror_r(errnum: c_int, buf: *mut c_char, buflen: size_t) -> c_int {
+    use core::fmt::Write;
+
+    // Long enough for "Unknown error -2147483648"
+    let mut unknown = [0; 32];
+    let (msg, ret) = if errnum >= 0 && errnum < STR_ERROR.len() as c_int {
+        (STR_ERROR[errnum as usize].as_bytes(), 0)
+    } else {
+        let mut w = platform::StringWriter(unknown.as_mut_ptr(), unknown.len());
+        let _ = w.write_fmt(format_args!("Unknown error {}", errnum));
+        (platform::c_str(unknown.as_ptr() as *const c_char), EINVAL)
+    };
+
+    if buflen == 0 {
+        return ERANGE;
+    }
+    // Whatever fits is copied and terminated, even if the message is cut off
+    let len = msg.len().min(buflen - 1);
+    memcpy(buf as *mut c_void, msg.as_ptr() as *const c_void, len);
+    *buf.offset(len as isize) = 0;
+
+    if len < msg.len() {
+        ERANGE
+    } else {
+        ret
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strlen(s: *const c_char) -> size_t {
     strnlen(s, usize::MAX)
@@ -269,14 +399,17 @@ pub unsafe extern "C" fn strncat(s1: *mut c_char, s2: *const c_char, n: usize) -
 
 #[no_mangle]
 pub unsafe extern "C" fn strncmp(s1: *const c_char, s2: *const c_char, n: usize) -> c_int {
-    let s1 = core::slice::from_raw_parts(s1 as *const c_uchar, n);
-    let s2 = core::slice::from_raw_parts(s2 as *const c_uchar, n);
+    let s1 = s1 as *const c_uchar;
+    let s2 = s2 as *const c_uchar;
 
-    for (&a, &b) in s1.iter().zip(s2.iter()) {
-        let val = (a as c_int) - (b as c_int);
+    let mut i = 0;
+    while i < n {
+        let a = *s1.offset(i as isize);
+        let b = *s2.offset(i as isize);
         if a != b || a == 0 {
-            return val;
+            return (a as c_int) - (b as c_int);
         }
+        i += 1;
     }
 
     0
@@ -286,7 +419,8 @@ pub unsafe extern "C" fn strncmp(s1: *const c_char, s2: *const c_char, n: usize)
 pub unsafe extern "C" fn strncpy(dst: *mut c_char, src: *const c_char, n: usize) -> *mut c_char {
     let mut i = 0;
 
-    while *src.offset(i) != 0 && (i as usize) < n {
+    // Check n first, src doesn't have to be NUL terminated if it's that long
+    while (i as usize) < n && *src.offset(i) != 0 {
         *dst.offset(i) = *src.offset(i);
         i += 1;
     }
@@ -312,7 +446,7 @@ pub unsafe extern "C" fn strpbrk(s1: *const c_char, s2: *const c_char) -> *mut c
 pub unsafe extern "C" fn strrchr(s: *const c_char, c: c_int) -> *mut c_char {
     let len = strlen(s) as isize;
     let c = c as i8;
-    let mut i = len - 1;
+    let mut i = len;
     while i >= 0 {
         if *s.offset(i) == c {
             return s.offset(i) as *mut c_char;
@@ -336,6 +470,10 @@ pub unsafe extern "C" fn strspn(s1: *const c_char, s2: *const c_char) -> size_t
 
 #[no_mangle]
 pub unsafe extern "C" fn strstr(s1: *const c_char, s2: *const c_char) -> *mut c_char {
+    if *s2 == 0 {
+        return s1 as *mut c_char;
+    }
+
     let mut i = 0;
     while *s1.offset(i) != 0 {
         let mut j = 0;
@@ -399,6 +537,54 @@ pub extern "C" fn strtok_r(
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strverscmp(s1: *const c_char, s2: *const c_char) -> c_int {
+    let l = s1 as *const u8;
+    let r = s2 as *const u8;
+    let digit = |s: *const u8, i: usize| (*s.offset(i as isize)).is_ascii_digit();
+
+    // Find the longest common prefix, tracking where its trailing digits
+    // start and whether those digits are all zeros
+    let mut i = 0;
+    let mut dp = 0;
+    let mut zeros = true;
+    while *l.offset(i as isize) == *r.offset(i as isize) {
+        let c = *l.offset(i as isize);
+        if c == 0 {
+            return 0;
+        }
+        if !c.is_ascii_digit() {
+            dp = i + 1;
+            zeros = true;
+        } else if c != b'0' {
+            zeros = false;
+        }
+        i += 1;
+    }
+
+    let lc = *l.offset(i as isize);
+    let rc = *r.offset(i as isize);
+    if *l.offset(dp as isize) != b'0' && *r.offset(dp as isize) != b'0' {
+        // Unless the digits began with a zero, the longer number is greater
+        let mut j = i;
+        while digit(l, j) {
+            if !digit(r, j) {
+                return 1;
+            }
+            j += 1;
+        }
+        if digit(r, j) {
+            return -1;
+        }
+    } else if zeros && dp < i && (lc.is_ascii_digit() || rc.is_ascii_digit()) {
+        // Otherwise, if the common digits are all zeros, digits sort before
+        // anything else
+        return lc.wrapping_sub(b'0') as c_int - rc.wrapping_sub(b'0') as c_int;
+    }
+
+    lc as c_int - rc as c_int
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strxfrm(s1: *mut c_char, s2: *const c_char, n: usize) -> size_t {
     // relibc has no locale stuff (yet)
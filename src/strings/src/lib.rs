@@ -6,7 +6,7 @@ extern crate alloc;
 extern crate platform;
 
 use alloc::Vec;
-use core::ptr;
+use core::{ptr, usize};
 use platform::types::*;
 
 #[no_mangle]
@@ -92,30 +92,8 @@ pub unsafe extern "C" fn rindex(mut s: *const c_char, c: c_int) -> *mut c_char {
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn strcasecmp(mut first: *const c_char, mut second: *const c_char) -> c_int {
-    while *first != 0 && *second != 0 {
-        let mut i = *first;
-        let mut j = *second;
-
-        if i >= b'A' as c_char && i <= b'Z' as c_char {
-            i += (b'a' - b'A') as c_char;
-        }
-        if j >= b'A' as c_char && j <= b'Z' as c_char {
-            j += (b'a' - b'A') as c_char;
-        }
-
-        if i != j {
-            return -1;
-        }
-
-        first = first.offset(1);
-        second = second.offset(1);
-    }
-    // Both strings didn't end with NUL bytes
-    if *first != *second {
-        return -1;
-    }
-    0
+pub unsafe extern "C" fn strcasecmp(first: *const c_char, second: *const c_char) -> c_int {
+    strncasecmp(first, second, usize::MAX)
 }
 #[no_mangle]
 pub unsafe extern "C" fn strncasecmp(
@@ -123,28 +101,17 @@ pub unsafe extern "C" fn strncasecmp(
     mut second: *const c_char,
     mut n: size_t,
 ) -> c_int {
-    while *first != 0 && *second != 0 && n > 0 {
-        let mut i = *first;
-        let mut j = *second;
-
-        if i >= b'A' as c_char && i <= b'Z' as c_char {
-            i += (b'a' - b'A') as c_char;
-        }
-        if j >= b'A' as c_char && j <= b'Z' as c_char {
-            j += (b'a' - b'A') as c_char;
-        }
+    while n > 0 {
+        let i = (*first as u8).to_ascii_lowercase();
+        let j = (*second as u8).to_ascii_lowercase();
 
-        if i != j {
-            return -1;
+        if i != j || i == 0 {
+            return i as c_int - j as c_int;
         }
 
         first = first.offset(1);
         second = second.offset(1);
         n -= 1;
     }
-    // Both strings didn't end with NUL bytes (unless we reached the limit)
-    if n != 0 && *first != *second {
-        return -1;
-    }
     0
 }
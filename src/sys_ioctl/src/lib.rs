@@ -1,4 +1,4 @@
-//! ioctl implementation for linux
+//! ioctl implementation for linux and redox
 
 #![no_std]
 
@@ -16,23 +16,37 @@ pub struct sgttyb {
     sg_flags: c_ushort,
 }
 
-#[cfg(target_os = "linux")]
+// This struct also exists in platform. They need to match. Reason it is not just re-exported is cbindgen.
+#[repr(C)]
+pub struct winsize {
+    pub ws_row: c_ushort,
+    pub ws_col: c_ushort,
+    pub ws_xpixel: c_ushort,
+    pub ws_ypixel: c_ushort,
+}
+
+/// Called by the variadic `ioctl` in C, which always passes the third
+/// argument on as a pointer. Unknown requests fail with `ENOTTY`.
+#[no_mangle]
+pub extern "C" fn sys_ioctl(fd: c_int, request: c_ulong, out: *mut c_void) -> c_int {
+    platform::ioctl(fd, request, out)
+}
+
+#[cfg(target_os = "redox")]
 pub mod inner {
     use *;
 
-    #[repr(C)]
-    pub struct winsize {
-        ws_row: c_ushort,
-        ws_col: c_ushort,
-        ws_xpixel: c_ushort,
-        ws_ypixel: c_ushort,
-    }
+    // Redox only understands these, which use the same numbers as Linux. FIONREAD only counts
+    // what's left of regular files, and gives 0 for pipes, sockets and the rest.
+    pub const TIOCGWINSZ: c_ulong = 0x5413;
+    pub const TIOCSWINSZ: c_ulong = 0x5414;
+    pub const FIONREAD: c_ulong = 0x541B;
+    pub const FIONBIO: c_ulong = 0x5421;
+}
 
-    #[no_mangle]
-    pub extern "C" fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) -> c_int {
-        // TODO: Somehow support varargs to syscall??
-        platform::ioctl(fd, request, out)
-    }
+#[cfg(target_os = "linux")]
+pub mod inner {
+    use *;
 
     pub const TCGETS: c_ulong = 0x5401;
     pub const TCSETS: c_ulong = 0x5402;
@@ -223,5 +237,4 @@ pub mod inner {
     pub const SIOCPROTOPRIVATE: c_ulong = 0x89E0;
 }
 
-#[cfg(target_os = "linux")]
 pub use inner::*;
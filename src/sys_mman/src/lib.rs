@@ -1,5 +1,6 @@
 #![no_std]
 
+extern crate errno;
 extern crate platform;
 
 use platform::types::*;
@@ -10,6 +11,9 @@ pub use sys::*;
 #[path = "linux.rs"]
 pub mod sys;
 
+// Addresses and offsets have to be aligned to this
+const PAGE_SIZE: usize = 4096;
+
 #[cfg(target_os = "redox")]
 #[path = "redox.rs"]
 pub mod sys;
@@ -33,12 +37,25 @@ pub unsafe extern "C" fn mmap(
     fildes: c_int,
     off: off_t,
 ) -> *mut c_void {
+    // Exactly one of MAP_SHARED and MAP_PRIVATE has to be given
+    let sharing = flags & (MAP_SHARED | MAP_PRIVATE);
+    if len == 0
+        || off as usize % PAGE_SIZE != 0
+        || (sharing != MAP_SHARED && sharing != MAP_PRIVATE)
+    {
+        platform::errno = errno::EINVAL;
+        return !0 as *mut c_void;
+    }
     platform::mmap(addr, len, prot, flags, fildes, off)
 }
 
-// #[no_mangle]
-pub extern "C" fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
+    if addr as usize % PAGE_SIZE != 0 {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+    platform::mprotect(addr, len, prot)
 }
 
 // #[no_mangle]
@@ -58,6 +75,10 @@ pub extern "C" fn munlockall() -> c_int {
 
 #[no_mangle]
 pub unsafe extern "C" fn munmap(addr: *mut c_void, len: usize) -> c_int {
+    if addr as usize % PAGE_SIZE != 0 || len == 0 {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
     platform::munmap(addr, len)
 }
 
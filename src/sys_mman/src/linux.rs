@@ -7,5 +7,6 @@ pub const PROT_NONE: c_int = 0x0;
 
 pub const MAP_SHARED: c_int = 0x1;
 pub const MAP_PRIVATE: c_int = 0x2;
+pub const MAP_FIXED: c_int = 0x10;
 pub const MAP_ANON: c_int = 0x20;
 pub const MAP_ANONYMOUS: c_int = MAP_ANON;
@@ -15,7 +15,27 @@ const RUSAGE_CHILDREN: c_int = -1;
 const RUSAGE_BOTH: c_int = -2;
 const RUSAGE_THREAD: c_int = 1;
 
-type rlim_t = u64;
+pub const PRIO_PROCESS: c_int = 0;
+pub const PRIO_PGRP: c_int = 1;
+pub const PRIO_USER: c_int = 2;
+
+pub type rlim_t = u64;
+
+pub const RLIM_INFINITY: rlim_t = 0xFFFF_FFFF_FFFF_FFFF;
+pub const RLIM_SAVED_CUR: rlim_t = 0xFFFF_FFFF_FFFF_FFFF;
+pub const RLIM_SAVED_MAX: rlim_t = 0xFFFF_FFFF_FFFF_FFFF;
+
+pub const RLIMIT_CPU: c_int = 0;
+pub const RLIMIT_FSIZE: c_int = 1;
+pub const RLIMIT_DATA: c_int = 2;
+pub const RLIMIT_STACK: c_int = 3;
+pub const RLIMIT_CORE: c_int = 4;
+pub const RLIMIT_RSS: c_int = 5;
+pub const RLIMIT_NPROC: c_int = 6;
+pub const RLIMIT_NOFILE: c_int = 7;
+pub const RLIMIT_MEMLOCK: c_int = 8;
+pub const RLIMIT_AS: c_int = 9;
+pub const RLIMIT_NLIMITS: c_int = 10;
 
 #[repr(C)]
 pub struct rlimit {
@@ -43,27 +63,33 @@ pub struct rusage {
     pub ru_nivcsw: c_long,
 }
 
-// #[no_mangle]
+/// Returns the nice value of `who`, or the calling process, group or user if it's 0. As -1 is a
+/// valid nice value, errno is cleared first so that it is only set on an error.
+#[no_mangle]
 pub unsafe extern "C" fn getpriority(which: c_int, who: id_t) -> c_int {
-    unimplemented!();
+    platform::errno = 0;
+    platform::getpriority(which, who)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub unsafe extern "C" fn getrlimit(resource: c_int, rlp: *mut rlimit) -> c_int {
-    unimplemented!();
+    platform::getrlimit(resource, rlp as *mut platform::types::rlimit)
 }
 
+/// Fills in what the calling process, or its children that have been waited for, have used.
+/// Counters the platform doesn't keep are 0.
 #[no_mangle]
 pub unsafe extern "C" fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
     platform::getrusage(who, r_usage as *mut platform::types::rusage)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub unsafe extern "C" fn setpriority(which: c_int, who: id_t, nice: c_int) -> c_int {
-    unimplemented!();
+    platform::setpriority(which, who, nice)
 }
 
-// #[no_mangle]
+/// Changes the limits on `resource`. Lowering the hard limit can't be undone without root.
+#[no_mangle]
 pub unsafe extern "C" fn setrlimit(resource: c_int, rlp: *const rlimit) -> c_int {
-    unimplemented!();
+    platform::setrlimit(resource, rlp as *const platform::types::rlimit)
 }
@@ -1,20 +1,128 @@
 //! sys/select.h implementation
 #![no_std]
+#![feature(alloc)]
+#![allow(non_snake_case)]
 
+extern crate alloc;
+extern crate errno;
 extern crate platform;
 
+use alloc::vec::Vec;
 use core::mem;
+use errno::*;
 use platform::types::*;
 
 // fd_set is defined in C because cbindgen is incompatible with mem::size_of booo
 
+fn fd_bit(fd: c_int) -> (usize, c_ulong) {
+    let bits = 8 * mem::size_of::<c_ulong>();
+    (fd as usize / bits, 1 << (fd as usize % bits))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn FD_CLR(fd: c_int, set: *mut fd_set) {
+    let (index, mask) = fd_bit(fd);
+    (*set).fds_bits[index] &= !mask;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn FD_ISSET(fd: c_int, set: *const fd_set) -> c_int {
+    let (index, mask) = fd_bit(fd);
+    ((*set).fds_bits[index] & mask == mask) as c_int
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn FD_SET(fd: c_int, set: *mut fd_set) {
+    let (index, mask) = fd_bit(fd);
+    (*set).fds_bits[index] |= mask;
+}
+
 #[no_mangle]
-pub extern "C" fn select(
+pub unsafe extern "C" fn FD_ZERO(set: *mut fd_set) {
+    for bits in (*set).fds_bits.iter_mut() {
+        *bits = 0;
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn select(
     nfds: c_int,
     readfds: *mut fd_set,
     writefds: *mut fd_set,
     exceptfds: *mut fd_set,
     timeout: *mut timeval,
 ) -> c_int {
-    platform::select(nfds, readfds, writefds, exceptfds, timeout)
+    if nfds < 0 || nfds as usize > FD_SETSIZE {
+        platform::errno = EINVAL;
+        return -1;
+    }
+
+    let isset = |set: *mut fd_set, fd| !set.is_null() && FD_ISSET(fd, set) != 0;
+
+    let mut fds = Vec::new();
+    for fd in 0..nfds {
+        let mut events = 0;
+        if isset(readfds, fd) {
+            events |= POLLIN;
+        }
+        if isset(writefds, fd) {
+            events |= POLLOUT;
+        }
+        if isset(exceptfds, fd) {
+            events |= POLLPRI;
+        }
+        if events != 0 {
+            fds.push(pollfd {
+                fd: fd,
+                events: events,
+                revents: 0,
+            });
+        }
+    }
+
+    let timeout = if timeout.is_null() {
+        -1
+    } else {
+        let timeout = &*timeout;
+        if timeout.tv_sec < 0 || timeout.tv_usec < 0 {
+            platform::errno = EINVAL;
+            return -1;
+        }
+        // Anything longer than poll can wait in milliseconds waits as long as it can
+        let ms = timeout
+            .tv_sec
+            .checked_mul(1000)
+            .and_then(|ms| ms.checked_add((timeout.tv_usec as time_t + 999) / 1000))
+            .unwrap_or(time_t::max_value());
+        ms.min(c_int::max_value() as time_t) as c_int
+    };
+
+    if platform::poll(fds.as_mut_ptr(), fds.len() as nfds_t, timeout) < 0 {
+        return -1;
+    }
+    if fds.iter().any(|pfd| pfd.revents & POLLNVAL == POLLNVAL) {
+        platform::errno = EBADF;
+        return -1;
+    }
+
+    for set in &[readfds, writefds, exceptfds] {
+        if !set.is_null() {
+            FD_ZERO(*set);
+        }
+    }
+
+    let mut total = 0;
+    for pfd in &fds {
+        // Only the sets the fd was asked about get it, whatever else happened to it
+        let mut mark = |set: *mut fd_set, asked, flags| {
+            if !set.is_null() && pfd.events & asked != 0 && pfd.revents & flags != 0 {
+                FD_SET(pfd.fd, set);
+                total += 1;
+            }
+        };
+        mark(readfds, POLLIN, POLLIN | POLLHUP | POLLERR);
+        mark(writefds, POLLOUT, POLLOUT | POLLERR);
+        mark(exceptfds, POLLPRI, POLLPRI);
+    }
+    total
 }
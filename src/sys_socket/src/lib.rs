@@ -5,7 +5,6 @@
 
 extern crate platform;
 
-use core::ptr;
 use platform::types::*;
 
 mod constants;
@@ -35,6 +34,22 @@ pub unsafe extern "C" fn accept(
     )
 }
 
+/// Like `accept`, except that SOCK_NONBLOCK and SOCK_CLOEXEC in `flags` apply to the new socket
+#[no_mangle]
+pub unsafe extern "C" fn accept4(
+    socket: c_int,
+    address: *mut sockaddr,
+    address_len: *mut socklen_t,
+    flags: c_int,
+) -> c_int {
+    platform::accept4(
+        socket,
+        address as *mut platform::types::sockaddr,
+        address_len,
+        flags,
+    )
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn bind(
     socket: c_int,
@@ -110,14 +125,7 @@ pub unsafe extern "C" fn recv(
     length: size_t,
     flags: c_int,
 ) -> ssize_t {
-    recvfrom(
-        socket,
-        buffer,
-        length,
-        flags,
-        ptr::null_mut(),
-        ptr::null_mut(),
-    )
+    platform::recv(socket, buffer, length, flags)
 }
 
 #[no_mangle]
@@ -146,7 +154,7 @@ pub unsafe extern "C" fn send(
     length: size_t,
     flags: c_int,
 ) -> ssize_t {
-    sendto(socket, message, length, flags, ptr::null(), 0)
+    platform::send(socket, message, length, flags)
 }
 
 #[no_mangle]
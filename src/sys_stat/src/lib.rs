@@ -34,6 +34,9 @@ pub const S_ISUID: c_int = 0o4000;
 pub const S_ISGID: c_int = 0o2000;
 pub const S_ISVTX: c_int = 0o1000;
 
+pub const UTIME_NOW: c_long = (1 << 30) - 1;
+pub const UTIME_OMIT: c_long = (1 << 30) - 2;
+
 #[repr(C)]
 pub struct stat {
     pub st_dev: dev_t,
@@ -72,11 +75,24 @@ pub extern "C" fn fstat(fildes: c_int, buf: *mut platform::types::stat) -> c_int
     platform::fstat(fildes, buf)
 }
 
+#[no_mangle]
+pub extern "C" fn fstatat(
+    fd: c_int,
+    path: *const c_char,
+    buf: *mut platform::types::stat,
+    flag: c_int,
+) -> c_int {
+    platform::fstatat(fd, path, buf, flag)
+}
+
 #[no_mangle]
 pub extern "C" fn __fxstat(_ver: c_int, fildes: c_int, buf: *mut platform::types::stat) -> c_int {
     fstat(fildes, buf)
 }
 
+/// Sets the access and modification times of `fd` to `times[0]` and `times[1]`. A null `times`
+/// sets both to the current time, as does a `tv_nsec` of `UTIME_NOW`, and `UTIME_OMIT` leaves
+/// that time as it is.
 #[no_mangle]
 pub extern "C" fn futimens(fd: c_int, times: *const timespec) -> c_int {
     platform::futimens(fd, times)
@@ -92,6 +108,11 @@ pub extern "C" fn mkdir(path: *const c_char, mode: mode_t) -> c_int {
     platform::mkdir(path, mode)
 }
 
+#[no_mangle]
+pub extern "C" fn mkdirat(fd: c_int, path: *const c_char, mode: mode_t) -> c_int {
+    platform::mkdirat(fd, path, mode)
+}
+
 #[no_mangle]
 pub extern "C" fn mkfifo(path: *const c_char, mode: mode_t) -> c_int {
     platform::mkfifo(path, mode)
@@ -0,0 +1,39 @@
+//! sys/statvfs implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/sysstatvfs.h.html
+
+#![no_std]
+#![allow(non_camel_case_types)]
+
+extern crate platform;
+
+use platform::types::*;
+
+pub const ST_RDONLY: c_ulong = 1;
+pub const ST_NOSUID: c_ulong = 2;
+
+// This struct also exists in platform. They need to match. Reason it is not
+// just re-exported is cbindgen.
+/// On Redox, the inode counts and f_namemax aren't known, and are all bits set instead
+#[repr(C)]
+pub struct statvfs {
+    pub f_bsize: c_ulong,
+    pub f_frsize: c_ulong,
+    pub f_blocks: fsblkcnt_t,
+    pub f_bfree: fsblkcnt_t,
+    pub f_bavail: fsblkcnt_t,
+    pub f_files: fsfilcnt_t,
+    pub f_ffree: fsfilcnt_t,
+    pub f_favail: fsfilcnt_t,
+    pub f_fsid: c_ulong,
+    pub f_flag: c_ulong,
+    pub f_namemax: c_ulong,
+}
+
+#[no_mangle]
+pub extern "C" fn fstatvfs(fildes: c_int, buf: *mut statvfs) -> c_int {
+    platform::fstatvfs(fildes, buf as *mut platform::types::statvfs)
+}
+
+#[no_mangle]
+pub extern "C" fn statvfs(path: *const c_char, buf: *mut statvfs) -> c_int {
+    platform::statvfs(path, buf as *mut platform::types::statvfs)
+}
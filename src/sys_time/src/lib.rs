@@ -4,6 +4,8 @@
 
 extern crate platform;
 
+use core::ptr;
+
 use platform::types::*;
 
 pub const ITIMER_REAL: c_int = 0;
@@ -73,6 +75,9 @@ pub extern "C" fn select(
 
 #[no_mangle]
 pub unsafe extern "C" fn utimes(path: *const c_char, times: *const timeval) -> c_int {
+    if times.is_null() {
+        return platform::utimens(path, ptr::null());
+    }
     let times_spec = [
         timespec {
             tv_sec: (*times.offset(0)).tv_sec,
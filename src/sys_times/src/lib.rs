@@ -8,12 +8,15 @@ use platform::types::*;
 
 #[repr(C)]
 pub struct tms {
-    tms_utime: clock_t,
-    tms_stime: clock_t,
-    tms_cutime: clock_t,
-    tms_cstime: clock_t,
+    pub tms_utime: clock_t,
+    pub tms_stime: clock_t,
+    pub tms_cutime: clock_t,
+    pub tms_cstime: clock_t,
 }
 
+/// Fills `out`, if it isn't NULL, with the CPU time of the process and its children that have
+/// been waited for. Returns the clock ticks since some point in the past, so only the difference
+/// between two calls means anything.
 #[no_mangle]
 pub extern "C" fn times(out: *mut tms) -> clock_t {
     platform::times(out as *mut platform::types::tms)
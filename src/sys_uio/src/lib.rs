@@ -0,0 +1,57 @@
+//! sys/uio implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/sysuio.h.html
+
+#![no_std]
+#![allow(non_camel_case_types)]
+
+extern crate errno;
+extern crate platform;
+
+use core::slice;
+
+use platform::types::*;
+
+pub const IOV_MAX: c_int = 1024;
+
+// This struct also exists in platform. They need to match. Reason it is not
+// just re-exported is cbindgen.
+#[repr(C)]
+pub struct iovec {
+    pub iov_base: *mut c_void,
+    pub iov_len: size_t,
+}
+
+/// Checks the vector before any of it is transferred, so that an invalid one has no effect
+unsafe fn iovecs<'a>(iov: *const iovec, iovcnt: c_int) -> Option<&'a [platform::types::iovec]> {
+    if iovcnt < 0 || iovcnt > IOV_MAX {
+        platform::errno = errno::EINVAL;
+        return None;
+    }
+    let iovs = slice::from_raw_parts(iov as *const platform::types::iovec, iovcnt as usize);
+    let mut total: size_t = 0;
+    for iov in iovs {
+        total = match total.checked_add(iov.iov_len) {
+            Some(total) if total <= ssize_t::max_value() as size_t => total,
+            _ => {
+                platform::errno = errno::EINVAL;
+                return None;
+            }
+        };
+    }
+    Some(iovs)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn readv(fd: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+    match iovecs(iov, iovcnt) {
+        Some(iovs) => platform::readv(fd, iovs),
+        None => -1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn writev(fd: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+    match iovecs(iov, iovcnt) {
+        Some(iovs) => platform::writev(fd, iovs),
+        None => -1,
+    }
+}
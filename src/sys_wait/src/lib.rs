@@ -3,9 +3,12 @@
 
 #![no_std]
 
+extern crate errno;
 extern crate platform;
+extern crate signal;
 extern crate sys_resource;
 
+use core::ptr;
 use platform::types::*;
 use sys_resource::rusage;
 
@@ -17,6 +20,19 @@ pub const WEXITED: c_int = 4;
 pub const WCONTINUED: c_int = 8;
 pub const WNOWAIT: c_int = 0x1000000;
 
+pub const P_ALL: idtype_t = 0;
+pub const P_PID: idtype_t = 1;
+pub const P_PGID: idtype_t = 2;
+
+pub const CLD_EXITED: c_int = 1;
+pub const CLD_KILLED: c_int = 2;
+pub const CLD_DUMPED: c_int = 3;
+pub const CLD_TRAPPED: c_int = 4;
+pub const CLD_STOPPED: c_int = 5;
+pub const CLD_CONTINUED: c_int = 6;
+
+pub type idtype_t = c_int;
+
 pub const __WNOTHREAD: c_int = 0x20000000;
 pub const __WALL: c_int = 0x40000000;
 pub const __WCLONE: c_int = 0x80000000;
@@ -35,19 +51,66 @@ pub unsafe extern "C" fn wait3(
     unimplemented!();
 }
 
-/*
- * TODO: implement idtype_t, id_t, and siginfo_t
- *
- * #[no_mangle]
- * pub unsafe extern "C" fn waitid(
- *     idtype: idtype_t,
- *     id: id_t,
- *     infop: siginfo_t,
- *     options: c_int
- *  ) -> c_int {
- *      unimplemented!();
- *  }
- */
+/// Built on waitpid, so WNOWAIT isn't supported and WSTOPPED or WCONTINUED on their own still
+/// reap children that exited
+#[no_mangle]
+pub unsafe extern "C" fn waitid(
+    idtype: idtype_t,
+    id: id_t,
+    infop: *mut siginfo_t,
+    options: c_int,
+) -> c_int {
+    let pid = match idtype {
+        P_ALL => -1,
+        P_PID => id as pid_t,
+        P_PGID => -(id as pid_t),
+        _ => {
+            platform::errno = errno::EINVAL;
+            return -1;
+        }
+    };
+    if options & (WEXITED | WSTOPPED | WCONTINUED) == 0 || options & WNOWAIT == WNOWAIT {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+
+    let mut flags = options & WNOHANG;
+    if options & WSTOPPED == WSTOPPED {
+        flags |= WUNTRACED;
+    }
+    if options & WCONTINUED == WCONTINUED {
+        flags |= WCONTINUED;
+    }
+
+    let mut status = 0;
+    let pid = waitpid(pid, &mut status, flags);
+    if pid < 0 {
+        return -1;
+    }
+
+    ptr::write_bytes(infop, 0, 1);
+    if pid == 0 {
+        // WNOHANG and nothing to report
+        return 0;
+    }
+    (*infop).si_signo = signal::SIGCHLD as c_int;
+    (*infop).si_pid = pid;
+    // Decodes the same layout as the macros in sys/wait.h
+    let (code, value) = if status == 0xffff {
+        (CLD_CONTINUED, signal::SIGCONT as c_int)
+    } else if status & 0xff == 0x7f {
+        (CLD_STOPPED, (status >> 8) & 0xff)
+    } else if status & 0x7f == 0 {
+        (CLD_EXITED, (status >> 8) & 0xff)
+    } else if status & 0x80 == 0x80 {
+        (CLD_DUMPED, status & 0x7f)
+    } else {
+        (CLD_KILLED, status & 0x7f)
+    };
+    (*infop).si_code = code;
+    (*infop).si_status = value;
+    0
+}
 
 #[no_mangle]
 pub unsafe extern "C" fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
@@ -1,6 +1,7 @@
 //! termios implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/termios.h.html
 #![no_std]
 
+extern crate errno;
 extern crate platform;
 
 use platform::types::*;
@@ -13,14 +14,14 @@ pub const NCCS: usize = 32;
 
 #[repr(C)]
 pub struct termios {
-    c_iflag: tcflag_t,
-    c_oflag: tcflag_t,
-    c_cflag: tcflag_t,
-    c_lflag: tcflag_t,
-    c_line: cc_t,
-    c_cc: [cc_t; NCCS],
-    __c_ispeed: speed_t,
-    __c_ospeed: speed_t
+    pub c_iflag: tcflag_t,
+    pub c_oflag: tcflag_t,
+    pub c_cflag: tcflag_t,
+    pub c_lflag: tcflag_t,
+    pub c_line: cc_t,
+    pub c_cc: [cc_t; NCCS],
+    pub __c_ispeed: speed_t,
+    pub __c_ospeed: speed_t,
 }
 
 #[no_mangle]
@@ -28,11 +29,49 @@ pub extern "C" fn tcgetattr(fd: c_int, out: *mut termios) -> c_int {
     platform::tcgetattr(fd, out as *mut platform::types::termios)
 }
 
+/// Changes the settings of the terminal `fd`, either right away with `TCSANOW`, after everything
+/// written to it has been sent with `TCSADRAIN`, or like that and with any unread input thrown
+/// away with `TCSAFLUSH`
 #[no_mangle]
 pub extern "C" fn tcsetattr(fd: c_int, act: c_int, value: *mut termios) -> c_int {
     platform::tcsetattr(fd, act, value as *mut platform::types::termios)
 }
 
+// Speeds are only recorded, in the same bits of c_cflag as Linux keeps them in. Input always
+// goes at the output speed.
+
+#[no_mangle]
+pub unsafe extern "C" fn cfgetispeed(termios_p: *const termios) -> speed_t {
+    cfgetospeed(termios_p)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cfgetospeed(termios_p: *const termios) -> speed_t {
+    (*termios_p).c_cflag & CBAUD as tcflag_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cfsetispeed(termios_p: *mut termios, speed: speed_t) -> c_int {
+    // 0 means the same as the output speed
+    if speed == 0 {
+        return 0;
+    }
+    cfsetospeed(termios_p, speed)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn cfsetospeed(termios_p: *mut termios, speed: speed_t) -> c_int {
+    if speed & !(CBAUD as speed_t) != 0 {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+    (*termios_p).c_cflag &= !(CBAUD as tcflag_t);
+    (*termios_p).c_cflag |= speed;
+    (*termios_p).__c_ispeed = speed;
+    (*termios_p).__c_ospeed = speed;
+    0
+}
+
 pub const VINTR: usize = 0;
 pub const VQUIT: usize = 1;
 pub const VERASE: usize = 2;
@@ -113,6 +152,8 @@ pub const B3000000: usize = 0o010015;
 pub const B3500000: usize = 0o010016;
 pub const B4000000: usize = 0o010017;
 
+pub const CBAUD: usize = 0o010017;
+
 pub const CSIZE: usize = 0o000060;
 pub const CS5: usize = 0o000000;
 pub const CS6: usize = 0o000020;
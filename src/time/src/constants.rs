@@ -49,4 +49,4 @@ pub(crate) const CLOCK_PROCESS_CPUTIME_ID: clockid_t = 2;
 pub(crate) const CLOCK_THREAD_CPUTIME_ID: clockid_t = 3;
 
 // Can't be time_t because cbindgen UGH
-pub(crate) const CLOCKS_PER_SEC: c_long = 1_000_000;
+pub const CLOCKS_PER_SEC: c_long = 1_000_000;
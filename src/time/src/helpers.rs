@@ -1,5 +1,11 @@
 use constants::*;
+use core::ptr;
+use platform;
 use platform::types::*;
+use tm;
+
+// Holds the zone name from TZ, as tm_zone has to point somewhere that outlives the call
+static mut TZ_NAME: [c_char; 16] = [0; 16];
 
 // compute year, month, day & day of year
 // for description of this algorithm see
@@ -34,7 +40,139 @@ pub(crate) fn civil_from_days(days: c_long) -> (c_int, c_int, c_int, c_int) {
     return (year, month, day, yearday);
 }
 
+// compute the number of days since the epoch, the inverse of civil_from_days
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+pub(crate) fn days_from_civil(year: c_long, month: c_long, day: c_long) -> c_long {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 {
+        year
+    } else {
+        year - (YEARS_PER_ERA as c_long - 1)
+    }) / YEARS_PER_ERA as c_long;
+    let erayear = year - era * YEARS_PER_ERA as c_long;
+    let yearday = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let eraday = erayear * DAYS_PER_YEAR as c_long + erayear / 4 - erayear / 100 + yearday;
+    era * DAYS_PER_ERA + eraday - EPOCH_ADJUSTMENT_DAYS
+}
+
+// convert the fields of a tm into seconds since the epoch, as if it was UTC, letting every field
+// overflow into the next larger one. Returns None if the result can't be turned back into a tm.
+pub(crate) fn tm_to_time(t: &tm) -> Option<time_t> {
+    // a negative month borrows from the year before
+    let mut year = t.tm_year as c_long + YEAR_BASE as c_long + t.tm_mon as c_long / 12;
+    let mut month = t.tm_mon as c_long % 12;
+    if month < 0 {
+        month += 12;
+        year -= 1;
+    }
+    let days = days_from_civil(year, month + 1, 1) + t.tm_mday as c_long - 1;
+    let time = days * SECSPERDAY
+        + t.tm_hour as c_long * SECSPERHOUR
+        + t.tm_min as c_long * SECSPERMIN
+        + t.tm_sec as c_long;
+
+    // keep tm_year of the normalized result within a c_int, with a day of slack for timezones
+    let limit = (c_int::max_value() as c_long - YEAR_BASE as c_long - 1) * DAYS_PER_YEAR as c_long;
+    if (time / SECSPERDAY).abs() + 1 > limit {
+        None
+    } else {
+        Some(time)
+    }
+}
+
 #[inline(always)]
 fn is_leap(y: c_int) -> c_int {
     ((y % 4 == 0 && y % 100 != 0) || y % 400 == 0) as c_int
 }
+
+// Returns the offset east of UTC in seconds and the zone name, from a POSIX TZ like "EST5" or
+// "<+0530>-5:30". Only the standard time part is used, and anything that can't be parsed,
+// including an unset TZ, means UTC.
+pub(crate) unsafe fn local_offset() -> (c_long, *const c_char) {
+    let mut tz = find_tz();
+    if tz.is_null() {
+        return (0, UTC);
+    }
+
+    let mut len = 0;
+    if *tz == b'<' as c_char {
+        tz = tz.offset(1);
+        while *tz.offset(len) != 0 && *tz.offset(len) != b'>' as c_char {
+            len += 1;
+        }
+        if *tz.offset(len) == 0 {
+            return (0, UTC);
+        }
+    } else {
+        while (*tz.offset(len) as u8).is_ascii_alphabetic() {
+            len += 1;
+        }
+        if len < 3 {
+            return (0, UTC);
+        }
+    }
+    let name = tz;
+    tz = tz.offset(len);
+    if *tz == b'>' as c_char {
+        tz = tz.offset(1);
+    }
+
+    let sign = match *tz as u8 {
+        b'-' => {
+            tz = tz.offset(1);
+            -1
+        }
+        b'+' => {
+            tz = tz.offset(1);
+            1
+        }
+        _ => 1,
+    };
+    // hours, then optionally minutes and seconds
+    let mut offset = 0;
+    for (i, &unit) in [SECSPERHOUR, SECSPERMIN, 1].iter().enumerate() {
+        if i > 0 {
+            if *tz != b':' as c_char {
+                break;
+            }
+            tz = tz.offset(1);
+        }
+        if !(*tz as u8).is_ascii_digit() {
+            return (0, UTC);
+        }
+        let mut value = 0;
+        while (*tz as u8).is_ascii_digit() {
+            value = value * 10 + (*tz as u8 - b'0') as c_long;
+            tz = tz.offset(1);
+        }
+        offset += value * unit;
+    }
+
+    let len = (len as usize).min(TZ_NAME.len() - 1);
+    for i in 0..len {
+        TZ_NAME[i] = *name.offset(i as isize);
+    }
+    TZ_NAME[len] = 0;
+
+    // POSIX offsets count westwards
+    (-sign * offset, TZ_NAME.as_ptr())
+}
+
+unsafe fn find_tz() -> *const c_char {
+    let mut env = platform::environ;
+    if env.is_null() {
+        return ptr::null();
+    }
+    while !(*env).is_null() {
+        let var = *env;
+        if *var == b'T' as c_char
+            && *var.offset(1) == b'Z' as c_char
+            && *var.offset(2) == b'=' as c_char
+            && *var.offset(3) != 0
+        {
+            return var.offset(3);
+        }
+        env = env.offset(1);
+    }
+    ptr::null()
+}
@@ -13,7 +13,7 @@ mod strftime;
 
 use constants::*;
 use core::mem::transmute;
-use errno::EIO;
+use errno::{EINVAL, EIO, EOVERFLOW};
 use helpers::*;
 use platform::types::*;
 
@@ -141,7 +141,11 @@ pub extern "C" fn ctime_r(clock: *const time_t, buf: *mut c_char) -> *mut c_char
 
 #[no_mangle]
 pub extern "C" fn difftime(time1: time_t, time0: time_t) -> c_double {
-    (time1 - time0) as c_double
+    // only fall back to subtracting as doubles when the exact difference overflows
+    match time1.checked_sub(time0) {
+        Some(diff) => diff as c_double,
+        None => time1 as c_double - time0 as c_double,
+    }
 }
 
 // #[no_mangle]
@@ -198,136 +202,40 @@ pub unsafe extern "C" fn localtime(clock: *const time_t) -> *mut tm {
     localtime_r(clock, &mut TM)
 }
 
-const MONTH_DAYS: [[c_int; 12]; 2] = [
-    // Non-leap years:
-    [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
-    // Leap years:
-    [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
-];
-
-fn leap_year(year: c_int) -> bool {
-    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
-}
-
 #[no_mangle]
 pub unsafe extern "C" fn localtime_r(clock: *const time_t, t: *mut tm) -> *mut tm {
-    let clock = *clock;
-
-    let mut day = (clock / (60 * 60 * 24)) as c_int;
-    if clock < 0 && clock % (60 * 60 * 24) != 0 {
-        // -1 because for negative values round upwards
-        // -0.3 == 0, but we want -1
-        day -= 1;
-    }
-
-    (*t).tm_sec = (clock % 60) as c_int;
-    (*t).tm_min = ((clock / 60) % 60) as c_int;
-    (*t).tm_hour = ((clock / (60 * 60)) % 24) as c_int;
-
-    while (*t).tm_sec < 0 {
-        (*t).tm_sec += 60;
-        (*t).tm_min -= 1;
-    }
-    while (*t).tm_min < 0 {
-        (*t).tm_min += 60;
-        (*t).tm_hour -= 1;
-    }
-    while (*t).tm_hour < 0 {
-        (*t).tm_hour += 24;
-    }
-
-    // Jan 1th was a thursday, 4th of a zero-indexed week.
-    (*t).tm_wday = (day + 4) % 7;
-    if (*t).tm_wday < 0 {
-        (*t).tm_wday += 7;
-    }
-
-    let mut year = 1970;
-    if day < 0 {
-        while day < 0 {
-            let days_in_year = if leap_year(year) { 366 } else { 365 };
-
-            day += days_in_year;
-            year -= 1;
-        }
-        (*t).tm_year = year - 1900;
-        (*t).tm_yday = day + 1;
-    } else {
-        loop {
-            let days_in_year = if leap_year(year) { 366 } else { 365 };
-
-            if day < days_in_year {
-                break;
-            }
-
-            day -= days_in_year;
-            year += 1;
-        }
-        (*t).tm_year = year - 1900;
-        (*t).tm_yday = day;
-    }
-
-    let leap = if leap_year(year) { 1 } else { 0 };
-    (*t).tm_mon = 0;
-    loop {
-        let days_in_month = MONTH_DAYS[leap][(*t).tm_mon as usize];
-
-        if day < days_in_month {
-            break;
-        }
-
-        day -= days_in_month;
-        (*t).tm_mon += 1;
-    }
-    (*t).tm_mday = 1 + day as c_int;
-    (*t).tm_isdst = 0;
+    let (offset, zone) = local_offset();
+    let local = *clock + offset;
+    gmtime_r(&local, t);
+    (*t).tm_gmtoff = offset;
+    (*t).tm_zone = zone;
 
     t
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mktime(t: *mut tm) -> time_t {
-    let mut year = (*t).tm_year + 1900;
-    let mut month = (*t).tm_mon;
-    let mut day = (*t).tm_mday as i64 - 1;
-
-    let leap = if leap_year(year) { 1 } else { 0 };
-
-    if year < 1970 {
-        day = MONTH_DAYS[if leap_year(year) { 1 } else { 0 }][(*t).tm_mon as usize] as i64 - day;
-
-        while year < 1969 {
-            year += 1;
-            day += if leap_year(year) { 366 } else { 365 };
+    let (offset, _) = local_offset();
+    match tm_to_time(&*t) {
+        Some(time) => {
+            let time = time - offset;
+            localtime_r(&time, t);
+            time
         }
-
-        while month < 11 {
-            month += 1;
-            day += MONTH_DAYS[leap][month as usize] as i64;
-        }
-
-        -(day * (60 * 60 * 24)
-            - (((*t).tm_hour as i64) * (60 * 60) + ((*t).tm_min as i64) * 60 + (*t).tm_sec as i64))
-    } else {
-        while year > 1970 {
-            year -= 1;
-            day += if leap_year(year) { 366 } else { 365 };
-        }
-
-        while month > 0 {
-            month -= 1;
-            day += MONTH_DAYS[leap][month as usize] as i64;
+        None => {
+            platform::errno = EOVERFLOW;
+            -1
         }
-
-        (day * (60 * 60 * 24)
-            + ((*t).tm_hour as i64) * (60 * 60)
-            + ((*t).tm_min as i64) * 60
-            + (*t).tm_sec as i64)
     }
 }
 
 #[no_mangle]
 pub extern "C" fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
+    let (sec, nsec) = unsafe { ((*rqtp).tv_sec, (*rqtp).tv_nsec) };
+    if sec < 0 || nsec < 0 || nsec >= 1_000_000_000 {
+        unsafe { platform::errno = EINVAL };
+        return -1;
+    }
     platform::nanosleep(
         rqtp as *const platform::types::timespec,
         rmtp as *mut platform::types::timespec,
@@ -360,14 +268,31 @@ pub extern "C" fn strptime(buf: *const c_char, format: *const c_char, tm: *mut t
 
 #[no_mangle]
 pub extern "C" fn time(tloc: *mut time_t) -> time_t {
-    let mut ts: platform::types::timespec = Default::default();
-    platform::clock_gettime(CLOCK_REALTIME, &mut ts);
+    // gettimeofday reads CLOCK_REALTIME, whatever its id is on the current platform
+    let mut tv: platform::types::timeval = Default::default();
+    if platform::gettimeofday(&mut tv, core::ptr::null_mut()) < 0 {
+        return -1;
+    }
     unsafe {
         if !tloc.is_null() {
-            *tloc = ts.tv_sec
+            *tloc = tv.tv_sec
         };
     }
-    ts.tv_sec
+    tv.tv_sec
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn timegm(t: *mut tm) -> time_t {
+    match tm_to_time(&*t) {
+        Some(time) => {
+            gmtime_r(&time, t);
+            time
+        }
+        None => {
+            platform::errno = EOVERFLOW;
+            -1
+        }
+    }
 }
 
 // #[no_mangle]
@@ -1,4 +1,5 @@
 use alloc::string::String;
+use core::ptr;
 use platform::types::*;
 use platform::{self, Write};
 use tm;
@@ -56,7 +57,7 @@ pub unsafe fn strftime<W: Write>(
         ];
         const MONTHS: [&'static str; 12] = [
             "January",
-            "Febuary",
+            "February",
             "March",
             "April",
             "May",
@@ -91,21 +92,14 @@ pub unsafe fn strftime<W: Write>(
                 b'A' => w!(WDAYS[(*t).tm_wday as usize]),
                 b'b' | b'h' => w!(&MONTHS[(*t).tm_mon as usize][..3]),
                 b'B' => w!(MONTHS[(*t).tm_mon as usize]),
-                b'C' => {
-                    let mut year = (*t).tm_year / 100;
-                    // Round up
-                    if (*t).tm_year % 100 != 0 {
-                        year += 1;
-                    }
-                    w!("{:02}", year + 19);
-                }
+                b'C' => w!("{:02}", ((*t).tm_year + 1900) / 100),
                 b'd' => w!("{:02}", (*t).tm_mday),
                 b'D' => w!(recurse "%m/%d/%y"),
                 b'e' => w!("{:2}", (*t).tm_mday),
                 b'F' => w!(recurse "%Y-%m-%d"),
                 b'H' => w!("{:02}", (*t).tm_hour),
                 b'I' => w!("{:02}", ((*t).tm_hour + 12 - 1) % 12 + 1),
-                b'j' => w!("{:03}", (*t).tm_yday),
+                b'j' => w!("{:03}", (*t).tm_yday + 1),
                 b'k' => w!("{:2}", (*t).tm_hour),
                 b'l' => w!("{:2}", ((*t).tm_hour + 12 - 1) % 12 + 1),
                 b'm' => w!("{:02}", (*t).tm_mon + 1),
@@ -114,18 +108,31 @@ pub unsafe fn strftime<W: Write>(
                 b'P' => w!(if (*t).tm_hour < 12 { "am" } else { "pm" }),
                 b'r' => w!(recurse "%I:%M:%S %p"),
                 b'R' => w!(recurse "%H:%M"),
-                // Nothing is modified in mktime, but the C standard of course requires a mutable pointer ._.
-                b's' => w!("{}", ::mktime(t as *mut tm)),
+                // mktime normalizes the tm it's given, so it gets a copy
+                b's' => w!("{}", ::mktime(&mut ptr::read(t))),
                 b'S' => w!("{:02}", (*t).tm_sec),
                 b'T' => w!(recurse "%H:%M:%S"),
                 b'u' => w!("{}", ((*t).tm_wday + 7 - 1) % 7 + 1),
-                b'U' => w!("{}", ((*t).tm_yday + 7 - (*t).tm_wday) / 7),
+                b'U' => w!("{:02}", ((*t).tm_yday + 7 - (*t).tm_wday) / 7),
                 b'w' => w!("{}", (*t).tm_wday),
-                b'W' => w!("{}", ((*t).tm_yday + 7 - ((*t).tm_wday + 6) % 7) / 7),
+                b'W' => w!("{:02}", ((*t).tm_yday + 7 - ((*t).tm_wday + 6) % 7) / 7),
                 b'y' => w!("{:02}", (*t).tm_year % 100),
                 b'Y' => w!("{}", (*t).tm_year + 1900),
-                b'z' => w!("+0000"), // TODO
-                b'Z' => w!("UTC"),   // TODO
+                b'z' => {
+                    let offset = (*t).tm_gmtoff;
+                    let sign = if offset < 0 { '-' } else { '+' };
+                    let offset = offset.abs() / 60;
+                    w!("{}{:02}{:02}", sign, offset / 60, offset % 60);
+                }
+                b'Z' => {
+                    if (*t).tm_zone.is_null() {
+                        w!("UTC");
+                    } else {
+                        for &b in platform::c_str((*t).tm_zone) {
+                            w!(byte b);
+                        }
+                    }
+                }
                 b'+' => w!(recurse "%a %b %d %T %Z %Y"),
                 _ => return false,
             }
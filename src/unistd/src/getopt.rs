@@ -112,6 +112,18 @@ unsafe fn parse_arg(
                 *current_arg as c_int
             }
         }
+        Some(GetoptOption::OptionalArg) => {
+            // an optional argument has to be attached, as in "-ovalue"
+            CURRENT_OPT = b"\0".as_ptr() as _;
+            optarg = if *current_arg.offset(1) == 0 {
+                ptr::null_mut()
+            } else {
+                current_arg.offset(1)
+            };
+            optind += 1;
+
+            *current_arg as c_int
+        }
         None => {
             // couldn't find the given option in optstring
             if opterr != 0 {
@@ -129,15 +141,25 @@ unsafe fn parse_arg(
 enum GetoptOption {
     Flag,
     OptArg,
+    OptionalArg,
 }
 
 unsafe fn find_option(ch: c_char, optstring: *const c_char) -> Option<GetoptOption> {
+    // ':' only ever describes the option before it
+    if ch == b':' as c_char {
+        return None;
+    }
+
     let mut i = 0;
 
     while *optstring.offset(i) != 0 {
         if *optstring.offset(i) == ch {
             let result = if *optstring.offset(i + 1) == b':' as c_char {
-                GetoptOption::OptArg
+                if *optstring.offset(i + 2) == b':' as c_char {
+                    GetoptOption::OptionalArg
+                } else {
+                    GetoptOption::OptArg
+                }
             } else {
                 GetoptOption::Flag
             };
@@ -4,21 +4,24 @@
 
 extern crate errno;
 extern crate platform;
+extern crate pwd;
 extern crate stdio;
 extern crate string;
 extern crate sys_time;
 
-use core::{ptr, slice};
+use core::{cmp, mem, ptr, slice};
 
 use platform::types::*;
 
 pub use brk::*;
 pub use getopt::*;
 pub use pathconf::*;
+pub use sysconf::*;
 
 mod brk;
 mod getopt;
 mod pathconf;
+mod sysconf;
 
 pub const F_OK: c_int = 0;
 pub const R_OK: c_int = 4;
@@ -38,13 +41,20 @@ pub const STDIN_FILENO: c_int = 0;
 pub const STDOUT_FILENO: c_int = 1;
 pub const STDERR_FILENO: c_int = 2;
 
+#[allow(non_upper_case_globals)]
+pub const L_cuserid: usize = 9;
+
+const LOGIN_NAME_MAX: usize = 256;
 const PATH_MAX: usize = 4096;
 
 #[no_mangle]
-pub extern "C" fn _exit(status: c_int) {
+pub extern "C" fn _exit(status: c_int) -> ! {
     platform::exit(status)
 }
 
+/// Checks whether the calling process could access `path` with `mode`, using its real user and
+/// group ids. The answer can be out of date by the time it is acted on, so opening the file and
+/// handling the error is the only safe way to guard an actual access.
 #[no_mangle]
 pub extern "C" fn access(path: *const c_char, mode: c_int) -> c_int {
     platform::access(path, mode)
@@ -102,6 +112,33 @@ pub extern "C" fn crypt(key: *const c_char, salt: *const c_char) -> *mut c_char
     unimplemented!();
 }
 
+/// Places the name of the effective user, cut to fit in `L_cuserid` bytes, in `s`, or in a
+/// static buffer if `s` is NULL
+#[no_mangle]
+pub extern "C" fn cuserid(s: *mut c_char) -> *mut c_char {
+    static mut CUSERID: [c_char; L_cuserid] = [0; L_cuserid];
+
+    let mut buf = [0; 1024];
+    unsafe {
+        let name = user_name(platform::geteuid(), &mut buf);
+        let out = if s.is_null() {
+            CUSERID.as_mut_ptr()
+        } else {
+            s
+        };
+        if name.is_null() {
+            *out = 0;
+            return if s.is_null() { ptr::null_mut() } else { s };
+        }
+
+        let name = platform::c_str(name);
+        let len = cmp::min(name.len(), L_cuserid - 1);
+        ptr::copy_nonoverlapping(name.as_ptr() as *const c_char, out, len);
+        *out.add(len) = 0;
+        out
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn dup(fildes: c_int) -> c_int {
     platform::dup(fildes)
@@ -112,6 +149,13 @@ pub extern "C" fn dup2(fildes: c_int, fildes2: c_int) -> c_int {
     platform::dup2(fildes, fildes2)
 }
 
+/// Like `dup2`, except that O_CLOEXEC in `flags` makes `fildes2` close-on-exec, and `fildes` can't
+/// be `fildes2`
+#[no_mangle]
+pub extern "C" fn dup3(fildes: c_int, fildes2: c_int, flags: c_int) -> c_int {
+    platform::dup3(fildes, fildes2, flags)
+}
+
 // #[no_mangle]
 pub extern "C" fn encrypt(block: [c_char; 64], edflag: c_int) {
     unimplemented!();
@@ -155,6 +199,13 @@ pub extern "C" fn execvp(file: *const c_char, argv: *const *mut c_char) -> c_int
     unimplemented!();
 }
 
+/// Like `access`, but relative to the directory `fd` (or `AT_FDCWD`) and, with `AT_EACCESS`,
+/// checked against the effective user and group ids instead.
+#[no_mangle]
+pub extern "C" fn faccessat(fd: c_int, path: *const c_char, mode: c_int, flag: c_int) -> c_int {
+    platform::faccessat(fd, path, mode, flag)
+}
+
 #[no_mangle]
 pub extern "C" fn fchown(fildes: c_int, owner: uid_t, group: gid_t) -> c_int {
     platform::fchown(fildes, owner, group)
@@ -233,9 +284,11 @@ pub extern "C" fn getgid() -> gid_t {
     platform::getgid()
 }
 
-// #[no_mangle]
+/// Places the supplementary group ids in `grouplist`, or only returns how many there are if
+/// `gidsetsize` is 0. Fails with `EINVAL` if `gidsetsize` is too small to hold them all.
+#[no_mangle]
 pub extern "C" fn getgroups(gidsetsize: c_int, grouplist: *mut gid_t) -> c_int {
-    unimplemented!();
+    platform::getgroups(gidsetsize, grouplist)
 }
 
 // #[no_mangle]
@@ -243,24 +296,93 @@ pub extern "C" fn gethostid() -> c_long {
     unimplemented!();
 }
 
+/// Places the name of this host in `name`. If it doesn't fit in `len` bytes, as much as fits
+/// is copied, terminated by a nul byte, and this fails with `ENAMETOOLONG`.
 #[no_mangle]
 pub unsafe extern "C" fn gethostname(name: *mut c_char, len: size_t) -> c_int {
-    platform::gethostname(name, len)
+    let mut uts: utsname = mem::zeroed();
+    if platform::uname(&mut uts) < 0 {
+        return -1;
+    }
+    if len == 0 {
+        platform::errno = errno::ENAMETOOLONG;
+        return -1;
+    }
+
+    let nodename = platform::c_str_n(uts.nodename.as_ptr(), uts.nodename.len());
+    let copied = cmp::min(nodename.len(), len - 1);
+    ptr::copy_nonoverlapping(nodename.as_ptr() as *const c_char, name, copied);
+    *name.add(copied) = 0;
+
+    if copied < nodename.len() {
+        platform::errno = errno::ENAMETOOLONG;
+        return -1;
+    }
+    0
 }
 
-// #[no_mangle]
+/// The name of the user with id `uid`, looked up without overwriting what `getpwuid` returned
+unsafe fn user_name(uid: uid_t, buf: &mut [c_char]) -> *const c_char {
+    let mut pwd: pwd::passwd = mem::zeroed();
+    let mut result = ptr::null_mut();
+    pwd::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result);
+    if result.is_null() {
+        ptr::null()
+    } else {
+        pwd.pw_name
+    }
+}
+
+#[no_mangle]
 pub extern "C" fn getlogin() -> *mut c_char {
-    unimplemented!();
+    static mut LOGIN: [c_char; LOGIN_NAME_MAX] = [0; LOGIN_NAME_MAX];
+    unsafe {
+        match getlogin_r(LOGIN.as_mut_ptr(), LOGIN_NAME_MAX) {
+            0 => LOGIN.as_mut_ptr(),
+            err => {
+                platform::errno = err;
+                ptr::null_mut()
+            }
+        }
+    }
 }
 
-// #[no_mangle]
+/// Places the name of the logged in user in `name`. That's whoever owns the terminal, or else
+/// whoever `LOGNAME` or `USER` say, or else the real user. Unlike most functions, this returns
+/// the error number instead of setting errno.
+#[no_mangle]
 pub extern "C" fn getlogin_r(name: *mut c_char, namesize: size_t) -> c_int {
-    unimplemented!();
-}
+    let mut buf = [0; 1024];
+    unsafe {
+        let mut login = ptr::null();
 
-// #[no_mangle]
-pub extern "C" fn getpagesize() -> c_int {
-    unimplemented!();
+        let mut st: stat = mem::zeroed();
+        if platform::isatty(STDIN_FILENO) == 1 && platform::fstat(STDIN_FILENO, &mut st) == 0 {
+            login = user_name(st.st_uid, &mut buf);
+        }
+        for var in &["LOGNAME\0", "USER\0"] {
+            if login.is_null() {
+                let value = platform::getenv(var.as_ptr() as *const c_char);
+                if !value.is_null() && *value != 0 {
+                    login = value;
+                }
+            }
+        }
+        if login.is_null() {
+            login = user_name(platform::getuid(), &mut buf);
+        }
+        if login.is_null() {
+            return errno::ENXIO;
+        }
+
+        let login = platform::c_str(login);
+        if login.len() >= namesize {
+            return errno::ERANGE;
+        }
+        ptr::copy_nonoverlapping(login.as_ptr() as *const c_char, name, login.len());
+        *name.add(login.len()) = 0;
+    }
+    0
 }
 
 // #[no_mangle]
@@ -303,6 +425,7 @@ pub extern "C" fn getwd(path_name: *mut c_char) -> *mut c_char {
     getcwd(path_name, PATH_MAX)
 }
 
+/// Whether `fd` is a terminal. If it isn't, errno is set to ENOTTY, or EBADF if it isn't open.
 #[no_mangle]
 pub extern "C" fn isatty(fd: c_int) -> c_int {
     platform::isatty(fd)
@@ -328,14 +451,40 @@ pub extern "C" fn lseek(fildes: c_int, offset: off_t, whence: c_int) -> off_t {
     platform::lseek(fildes, offset, whence)
 }
 
-// #[no_mangle]
+/// Adds `incr` to the nice value of the calling process and returns the new one. -1 is a valid
+/// nice value, so errno has to be set to 0 beforehand to tell it apart from an error.
+#[no_mangle]
 pub extern "C" fn nice(incr: c_int) -> c_int {
-    unimplemented!();
+    // getpriority() can also return -1 successfully, so errno is the only way to tell
+    let errno_backup = unsafe { platform::errno };
+    unsafe {
+        platform::errno = 0;
+    }
+    let old = platform::getpriority(PRIO_PROCESS, 0);
+    if old == -1 && unsafe { platform::errno } != 0 {
+        return -1;
+    }
+    unsafe {
+        platform::errno = errno_backup;
+    }
+    let new = cmp::max(-20, cmp::min(old.saturating_add(incr), 19));
+    if platform::setpriority(PRIO_PROCESS, 0, new) < 0 {
+        unsafe {
+            if platform::errno == errno::EACCES {
+                platform::errno = errno::EPERM;
+            }
+        }
+        return -1;
+    }
+    new
 }
 
-// #[no_mangle]
+/// Waits for a signal handler to run, and then always fails with EINTR
+#[no_mangle]
 pub extern "C" fn pause() -> c_int {
-    unimplemented!();
+    let mut mask: sigset_t = 0;
+    platform::sigprocmask(SIG_BLOCK, ptr::null(), &mut mask);
+    platform::sigsuspend(&mask)
 }
 
 #[no_mangle]
@@ -343,9 +492,22 @@ pub unsafe extern "C" fn pipe(fildes: *mut c_int) -> c_int {
     platform::pipe(slice::from_raw_parts_mut(fildes, 2))
 }
 
-// #[no_mangle]
+#[no_mangle]
+pub unsafe extern "C" fn pipe2(fildes: *mut c_int, flags: c_int) -> c_int {
+    platform::pipe2(slice::from_raw_parts_mut(fildes, 2), flags)
+}
+
+/// Reads from `offset` in the file without moving its current position
+#[no_mangle]
 pub extern "C" fn pread(fildes: c_int, buf: *mut c_void, nbyte: size_t, offset: off_t) -> ssize_t {
-    unimplemented!();
+    if offset < 0 {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
+    let buf = unsafe { slice::from_raw_parts_mut(buf as *mut u8, nbyte as usize) };
+    platform::pread(fildes, buf, offset)
 }
 
 // #[no_mangle]
@@ -357,14 +519,22 @@ pub extern "C" fn pthread_atfork(
     unimplemented!();
 }
 
-// #[no_mangle]
+/// Writes at `offset` in the file without moving its current position
+#[no_mangle]
 pub extern "C" fn pwrite(
     fildes: c_int,
     buf: *const c_void,
     nbyte: size_t,
     offset: off_t,
 ) -> ssize_t {
-    unimplemented!();
+    if offset < 0 {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
+    let buf = unsafe { slice::from_raw_parts(buf as *const u8, nbyte as usize) };
+    platform::pwrite(fildes, buf, offset)
 }
 
 #[no_mangle]
@@ -374,9 +544,23 @@ pub extern "C" fn read(fildes: c_int, buf: *const c_void, nbyte: size_t) -> ssiz
     platform::read(fildes, buf)
 }
 
-// #[no_mangle]
-pub extern "C" fn readlink(path: *const c_char, buf: *mut c_char, bufsize: size_t) -> c_int {
-    unimplemented!();
+/// Places the target of the symlink `path` in `buf`, truncated to `bufsize` bytes and without a
+/// NUL terminator, and returns how many bytes were placed
+#[no_mangle]
+pub extern "C" fn readlink(path: *const c_char, buf: *mut c_char, bufsize: size_t) -> ssize_t {
+    let buf = unsafe { slice::from_raw_parts_mut(buf as *mut u8, bufsize as usize) };
+    platform::readlink(path, buf)
+}
+
+#[no_mangle]
+pub extern "C" fn readlinkat(
+    fd: c_int,
+    path: *const c_char,
+    buf: *mut c_char,
+    bufsize: size_t,
+) -> ssize_t {
+    let buf = unsafe { slice::from_raw_parts_mut(buf as *mut u8, bufsize as usize) };
+    platform::readlinkat(fd, path, buf)
 }
 
 #[no_mangle]
@@ -384,9 +568,29 @@ pub extern "C" fn rmdir(path: *const c_char) -> c_int {
     platform::rmdir(path)
 }
 
+/// Changes the effective group id to the real or saved one. Only root may pick any group.
+#[no_mangle]
+pub extern "C" fn setegid(gid: gid_t) -> c_int {
+    platform::setegid(gid)
+}
+
+/// Changes the effective user id to the real or saved one. Only root may pick any user.
+#[no_mangle]
+pub extern "C" fn seteuid(uid: uid_t) -> c_int {
+    platform::seteuid(uid)
+}
+
+/// Changes the real, effective and saved group ids if called by root, otherwise only the
+/// effective group id like `setegid` does
 #[no_mangle]
 pub extern "C" fn setgid(gid: gid_t) -> c_int {
-    platform::setregid(gid, gid)
+    platform::setgid(gid)
+}
+
+/// Changes the name of this host to the `len` bytes at `name`. Only root may do this.
+#[no_mangle]
+pub extern "C" fn sethostname(name: *const c_char, len: size_t) -> c_int {
+    platform::sethostname(name, len)
 }
 
 #[no_mangle]
@@ -414,9 +618,11 @@ pub extern "C" fn setsid() -> pid_t {
     unimplemented!();
 }
 
+/// Changes the real, effective and saved user ids if called by root, otherwise only the
+/// effective user id like `seteuid` does
 #[no_mangle]
 pub extern "C" fn setuid(uid: uid_t) -> c_int {
-    platform::setreuid(uid, uid)
+    platform::setuid(uid)
 }
 
 #[no_mangle]
@@ -425,8 +631,11 @@ pub extern "C" fn sleep(seconds: c_uint) -> c_uint {
         tv_sec: seconds as i64,
         tv_nsec: 0,
     };
-    let rmtp = ptr::null_mut();
-    platform::nanosleep(&rqtp, rmtp);
+    let mut rmtp = timespec::default();
+    if platform::nanosleep(&rqtp, &mut rmtp) < 0 {
+        // report the time left if a signal woke us up early, rounding up partial seconds
+        return rmtp.tv_sec as c_uint + (rmtp.tv_nsec > 0) as c_uint;
+    }
     0
 }
 
@@ -435,18 +644,18 @@ pub extern "C" fn swab(src: *const c_void, dest: *mut c_void, nbytes: ssize_t) {
     unimplemented!();
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn symlink(path1: *const c_char, path2: *const c_char) -> c_int {
-    unimplemented!();
+    platform::symlink(path1, path2)
 }
 
-// #[no_mangle]
-pub extern "C" fn sync() {
-    unimplemented!();
+#[no_mangle]
+pub extern "C" fn symlinkat(path1: *const c_char, fd: c_int, path2: *const c_char) -> c_int {
+    platform::symlinkat(path1, fd, path2)
 }
 
 // #[no_mangle]
-pub extern "C" fn sysconf(name: c_int) -> c_long {
+pub extern "C" fn sync() {
     unimplemented!();
 }
 
@@ -460,19 +669,53 @@ pub extern "C" fn tcsetpgrp(fildes: c_int, pgid_id: pid_t) -> c_int {
     unimplemented!();
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn truncate(path: *const c_char, length: off_t) -> c_int {
-    unimplemented!();
+    if length < 0 {
+        unsafe {
+            platform::errno = errno::EINVAL;
+        }
+        return -1;
+    }
+    platform::truncate(path, length)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn ttyname(fildes: c_int) -> *mut c_char {
-    unimplemented!();
+    static mut TTYNAME: [c_char; PATH_MAX] = [0; PATH_MAX];
+    unsafe {
+        match ttyname_r(fildes, TTYNAME.as_mut_ptr(), PATH_MAX) {
+            0 => TTYNAME.as_mut_ptr(),
+            err => {
+                platform::errno = err;
+                ptr::null_mut()
+            }
+        }
+    }
 }
 
-// #[no_mangle]
+/// Places the path of the terminal `fildes` in `name`. Unlike most functions, this returns the
+/// error number instead of setting errno.
+#[no_mangle]
 pub extern "C" fn ttyname_r(fildes: c_int, name: *mut c_char, namesize: size_t) -> c_int {
-    unimplemented!();
+    if platform::isatty(fildes) == 0 {
+        return unsafe { platform::errno };
+    }
+
+    let mut buf = [0; PATH_MAX];
+    let len = platform::fpath(fildes, &mut buf);
+    if len < 0 {
+        return unsafe { platform::errno };
+    }
+    let len = len as usize;
+    if len >= namesize {
+        return errno::ERANGE;
+    }
+    unsafe {
+        ptr::copy_nonoverlapping(buf.as_ptr() as *const c_char, name, len);
+        *name.add(len) = 0;
+    }
+    0
 }
 
 #[no_mangle]
@@ -505,11 +748,16 @@ pub extern "C" fn unlink(path: *const c_char) -> c_int {
     platform::unlink(path)
 }
 
+#[no_mangle]
+pub extern "C" fn unlinkat(fd: c_int, path: *const c_char, flag: c_int) -> c_int {
+    platform::unlinkat(fd, path, flag)
+}
+
 #[no_mangle]
 pub extern "C" fn usleep(useconds: useconds_t) -> c_int {
     let rqtp = timespec {
         tv_sec: (useconds / 1_000_000) as i64,
-        tv_nsec: ((useconds % 1000) * 1000) as i64,
+        tv_nsec: ((useconds % 1_000_000) * 1000) as i64,
     };
     let rmtp = ptr::null_mut();
     platform::nanosleep(&rqtp, rmtp)
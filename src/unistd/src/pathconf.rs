@@ -1,3 +1,5 @@
+use errno::EINVAL;
+use platform;
 use platform::types::*;
 
 pub const _PC_LINK_MAX: c_int = 0;
@@ -22,12 +24,66 @@ pub const _PC_ALLOC_SIZE_MIN: c_int = 18;
 pub const _PC_SYMLINK_MAX: c_int = 19;
 pub const _PC_2_SYMLINKS: c_int = 20;
 
-// #[no_mangle]
+/// The limits that don't depend on the filesystem, where -1 means there is no limit. Those
+/// that do are looked up by `limit`.
+fn static_limit(name: c_int) -> Option<c_long> {
+    Some(match name {
+        _PC_LINK_MAX => 127,
+        _PC_MAX_CANON => 255,
+        _PC_MAX_INPUT => 255,
+        _PC_PATH_MAX => 4096,
+        _PC_PIPE_BUF => 4096,
+        _PC_CHOWN_RESTRICTED => 1,
+        _PC_NO_TRUNC => 1,
+        _PC_VDISABLE => 0,
+        _PC_SYNC_IO => 1,
+        _PC_ASYNC_IO => -1,
+        _PC_PRIO_IO => -1,
+        _PC_SOCK_MAXBUF => -1,
+        _PC_FILESIZEBITS => 64,
+        _PC_REC_INCR_XFER_SIZE => 4096,
+        _PC_REC_MAX_XFER_SIZE => 4096,
+        _PC_REC_MIN_XFER_SIZE => 4096,
+        _PC_REC_XFER_ALIGN => 4096,
+        _PC_ALLOC_SIZE_MIN => 4096,
+        _PC_SYMLINK_MAX => -1,
+        _PC_2_SYMLINKS => 1,
+        _ => return None,
+    })
+}
+
+fn limit<F>(name: c_int, statvfs: F) -> c_long
+where
+    F: FnOnce(&mut platform::types::statvfs) -> c_int,
+{
+    let static_limit = static_limit(name);
+    if name != _PC_NAME_MAX && static_limit.is_none() {
+        unsafe {
+            platform::errno = EINVAL;
+        }
+        return -1;
+    }
+
+    // Every limit is for some file, so one that isn't there fails whichever limit it is
+    let mut buf = platform::types::statvfs::default();
+    if statvfs(&mut buf) < 0 {
+        return -1;
+    }
+
+    match static_limit {
+        Some(limit) => limit,
+        // The filesystem knows this best, if it says. Not knowing is either 0 or all bits set.
+        None if buf.f_namemax != 0 && buf.f_namemax != !0 => buf.f_namemax as c_long,
+        None => 255,
+    }
+}
+
+#[no_mangle]
 pub extern "C" fn fpathconf(fildes: c_int, name: c_int) -> c_long {
-    unimplemented!();
+    limit(name, |buf| platform::fstatvfs(fildes, buf))
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn pathconf(path: *const c_char, name: c_int) -> c_long {
-    unimplemented!();
+    limit(name, |buf| platform::statvfs(path, buf))
 }
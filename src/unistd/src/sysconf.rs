@@ -0,0 +1,65 @@
+use core::cmp;
+
+use errno::EINVAL;
+use platform;
+use platform::types::*;
+
+// These match the values glibc uses on Linux
+pub const _SC_ARG_MAX: c_int = 0;
+pub const _SC_CHILD_MAX: c_int = 1;
+pub const _SC_CLK_TCK: c_int = 2;
+pub const _SC_NGROUPS_MAX: c_int = 3;
+pub const _SC_OPEN_MAX: c_int = 4;
+pub const _SC_STREAM_MAX: c_int = 5;
+pub const _SC_TZNAME_MAX: c_int = 6;
+pub const _SC_JOB_CONTROL: c_int = 7;
+pub const _SC_SAVED_IDS: c_int = 8;
+pub const _SC_VERSION: c_int = 29;
+pub const _SC_PAGESIZE: c_int = 30;
+pub const _SC_PAGE_SIZE: c_int = 30;
+pub const _SC_NPROCESSORS_CONF: c_int = 83;
+pub const _SC_NPROCESSORS_ONLN: c_int = 84;
+
+const ARG_MAX: c_long = 131_072;
+const CLK_TCK: c_long = 100;
+const NGROUPS_MAX: c_long = 65536;
+const PAGE_SIZE: c_long = 4096;
+
+#[no_mangle]
+pub extern "C" fn sysconf(name: c_int) -> c_long {
+    match name {
+        _SC_ARG_MAX => ARG_MAX,
+        _SC_CHILD_MAX => -1,
+        _SC_CLK_TCK => CLK_TCK,
+        _SC_NGROUPS_MAX => NGROUPS_MAX,
+        _SC_OPEN_MAX => {
+            let mut rlim = rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            if platform::getrlimit(RLIMIT_NOFILE, &mut rlim) < 0 || rlim.rlim_cur == RLIM_INFINITY {
+                -1
+            } else {
+                cmp::min(rlim.rlim_cur, c_long::max_value() as rlim_t) as c_long
+            }
+        }
+        _SC_STREAM_MAX => -1,
+        _SC_TZNAME_MAX => -1,
+        _SC_JOB_CONTROL => 1,
+        _SC_SAVED_IDS => 1,
+        _SC_VERSION => 200809,
+        _SC_PAGESIZE => PAGE_SIZE,
+        _SC_NPROCESSORS_CONF | _SC_NPROCESSORS_ONLN => platform::get_nprocs() as c_long,
+        _ => {
+            unsafe {
+                platform::errno = EINVAL;
+            }
+            -1
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn getpagesize() -> c_int {
+    PAGE_SIZE as c_int
+}
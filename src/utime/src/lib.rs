@@ -4,6 +4,8 @@
 
 extern crate platform;
 
+use core::ptr;
+
 use platform::types::*;
 
 #[repr(C)]
@@ -15,6 +17,9 @@ pub struct utimbuf {
 
 #[no_mangle]
 pub unsafe extern "C" fn utime(filename: *const c_char, times: *const utimbuf) -> c_int {
+    if times.is_null() {
+        return platform::utimens(filename, ptr::null());
+    }
     let times_spec = [
         timespec {
             tv_sec: (*times).actime,
@@ -1,7 +1,6 @@
 //! wchar implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/wchar.h.html
 
 #![no_std]
-#![feature(str_internals)]
 
 extern crate errno;
 extern crate platform;
@@ -24,9 +23,30 @@ const MB_CUR_MAX: c_int = 4;
 //Maximum number of bytes in a multibyte characters for any locale
 const MB_LEN_MAX: c_int = 4;
 
+/// The bytes of a character that mbrtowc() has only seen part of so far
 #[repr(C)]
-#[derive(Clone, Copy)]
-pub struct mbstate_t;
+#[derive(Clone, Copy, Default)]
+pub struct mbstate_t {
+    count: c_uint,
+    bytes: [c_uchar; 4],
+}
+
+const INITIAL_STATE: mbstate_t = mbstate_t {
+    count: 0,
+    bytes: [0; 4],
+};
+
+/// Wide characters are compared as wchar_t values, which can't just be subtracted without
+/// overflowing
+fn compare(a: wchar_t, b: wchar_t) -> c_int {
+    if a < b {
+        -1
+    } else if a > b {
+        1
+    } else {
+        0
+    }
+}
 
 #[no_mangle]
 pub unsafe extern "C" fn btowc(c: c_int) -> wint_t {
@@ -37,7 +57,7 @@ pub unsafe extern "C" fn btowc(c: c_int) -> wint_t {
 
     let uc = c as u8;
     let c = uc as c_char;
-    let mut ps: mbstate_t = mbstate_t;
+    let mut ps: mbstate_t = INITIAL_STATE;
     let mut wc: wchar_t = 0;
     let saved_errno = platform::errno;
     let status = mbrtowc(&mut wc, &c as (*const c_char), 1, &mut ps);
@@ -51,7 +71,7 @@ pub unsafe extern "C" fn btowc(c: c_int) -> wint_t {
 #[no_mangle]
 pub unsafe extern "C" fn fputwc(wc: wchar_t, stream: *mut FILE) -> wint_t {
     //Convert wchar_t to multibytes first
-    static mut INTERNAL: mbstate_t = mbstate_t;
+    static mut INTERNAL: mbstate_t = INITIAL_STATE;
     let mut bytes: [c_char; MB_CUR_MAX as usize] = [0; MB_CUR_MAX as usize];
 
     let amount = wcrtomb(bytes.as_mut_ptr(), wc, &mut INTERNAL);
@@ -85,18 +105,15 @@ pub extern "C" fn getwchar() -> wint_t {
 
 #[no_mangle]
 pub unsafe extern "C" fn mbsinit(ps: *const mbstate_t) -> c_int {
-    //Add a check for the state maybe
-    if ps.is_null() {
-        1
-    } else {
-        0
-    }
+    // The state is only ever not the initial one in the middle of a character
+    (ps.is_null() || (*ps).count == 0) as c_int
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mbrlen(s: *const c_char, n: size_t, ps: *mut mbstate_t) -> size_t {
-    static mut INTERNAL: mbstate_t = mbstate_t;
-    mbrtowc(ptr::null_mut(), s, n, &mut INTERNAL)
+    static mut INTERNAL: mbstate_t = INITIAL_STATE;
+    let ps: *mut mbstate_t = if ps.is_null() { &mut INTERNAL } else { ps };
+    mbrtowc(ptr::null_mut(), s, n, ps)
 }
 
 //Only works for UTF8 at the moment
@@ -107,11 +124,9 @@ pub unsafe extern "C" fn mbrtowc(
     n: size_t,
     ps: *mut mbstate_t,
 ) -> size_t {
-    static mut INTERNAL: mbstate_t = mbstate_t;
+    static mut INTERNAL: mbstate_t = INITIAL_STATE;
 
-    if ps.is_null() {
-        let ps = &mut INTERNAL;
-    }
+    let ps: *mut mbstate_t = if ps.is_null() { &mut INTERNAL } else { ps };
     if s.is_null() {
         let xs: [c_char; 1] = [0];
         return utf8::mbrtowc(pwc, &xs[0] as *const c_char, 1, ps);
@@ -130,11 +145,9 @@ pub unsafe extern "C" fn mbsnrtowcs(
     dst_len: size_t,
     ps: *mut mbstate_t,
 ) -> size_t {
-    static mut INTERNAL: mbstate_t = mbstate_t;
+    static mut INTERNAL: mbstate_t = INITIAL_STATE;
 
-    if ps.is_null() {
-        let ps = &mut INTERNAL;
-    }
+    let ps: *mut mbstate_t = if ps.is_null() { &mut INTERNAL } else { ps };
 
     let mut src = *src_ptr;
 
@@ -154,7 +167,7 @@ pub unsafe extern "C" fn mbsnrtowcs(
         // Stop in the event a decoding error occured.
         if amount == -1isize as usize {
             *src_ptr = src.offset(src_offset as isize);
-            return 1isize as usize;
+            return -1isize as usize;
         }
 
         // Stop decoding early in the event we encountered a partial character.
@@ -271,19 +284,29 @@ pub extern "C" fn wcrtomb(s: *mut c_char, wc: wchar_t, ps: *mut mbstate_t) -> si
     unsafe { utf8::wcrtomb(s_cpy, wc_cpy, ps) }
 }
 
-// #[no_mangle]
-pub extern "C" fn wcscat(ws1: *mut wchar_t, ws2: *const wchar_t) -> *mut wchar_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcscat(ws1: *mut wchar_t, ws2: *const wchar_t) -> *mut wchar_t {
+    wcscpy(ws1.add(wcslen(ws1)), ws2);
+    ws1
 }
 
-// #[no_mangle]
-pub extern "C" fn wcschr(ws1: *const wchar_t, ws2: wchar_t) -> *mut c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcschr(mut ws: *const wchar_t, wc: wchar_t) -> *mut wchar_t {
+    // The terminating NUL is part of the string, and can be searched for
+    loop {
+        if *ws == wc {
+            return ws as *mut wchar_t;
+        }
+        if *ws == 0 {
+            return ptr::null_mut();
+        }
+        ws = ws.add(1);
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn wcscmp(ws1: *const wchar_t, ws2: *const wchar_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcscmp(ws1: *const wchar_t, ws2: *const wchar_t) -> c_int {
+    wcsncmp(ws1, ws2, usize::max_value())
 }
 
 // #[no_mangle]
@@ -291,9 +314,17 @@ pub extern "C" fn wcscoll(ws1: *const wchar_t, ws2: *const wchar_t) -> c_int {
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wcscpy(ws1: *mut wchar_t, ws2: *const wchar_t) -> *mut wchar_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcscpy(ws1: *mut wchar_t, ws2: *const wchar_t) -> *mut wchar_t {
+    let mut i = 0;
+    loop {
+        let wc = *ws2.add(i);
+        *ws1.add(i) = wc;
+        if wc == 0 {
+            return ws1;
+        }
+        i += 1;
+    }
 }
 
 // #[no_mangle]
@@ -311,9 +342,13 @@ pub extern "C" fn wcsftime(
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wcslen(ws: *const wchar_t) -> c_ulong {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcslen(ws: *const wchar_t) -> size_t {
+    let mut i = 0;
+    while *ws.add(i) != 0 {
+        i += 1;
+    }
+    i
 }
 
 // #[no_mangle]
@@ -321,14 +356,33 @@ pub extern "C" fn wcsncat(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) ->
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wcsncmp(ws1: *const wchar_t, ws2: *const wchar_t, n: size_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcsncmp(ws1: *const wchar_t, ws2: *const wchar_t, n: size_t) -> c_int {
+    for i in 0..n {
+        let a = *ws1.add(i);
+        let b = *ws2.add(i);
+        if a != b || a == 0 {
+            return compare(a, b);
+        }
+    }
+    0
 }
 
-// #[no_mangle]
-pub extern "C" fn wcsncpy(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) -> *mut wchar_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcsncpy(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) -> *mut wchar_t {
+    let mut i = 0;
+
+    // Check n first, ws2 doesn't have to be NUL terminated if it's that long
+    while i < n && *ws2.add(i) != 0 {
+        *ws1.add(i) = *ws2.add(i);
+        i += 1;
+    }
+
+    for i in i..n {
+        *ws1.add(i) = 0;
+    }
+
+    ws1
 }
 
 // #[no_mangle]
@@ -336,19 +390,62 @@ pub extern "C" fn wcspbrk(ws1: *const wchar_t, ws2: *const wchar_t) -> *mut wcha
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wcsrchr(ws1: *const wchar_t, ws2: wchar_t) -> *mut wchar_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcsrchr(ws: *const wchar_t, wc: wchar_t) -> *mut wchar_t {
+    let mut i = wcslen(ws) + 1;
+    while i > 0 {
+        i -= 1;
+        if *ws.add(i) == wc {
+            return ws.add(i) as *mut wchar_t;
+        }
+    }
+    ptr::null_mut()
 }
 
-// #[no_mangle]
-pub extern "C" fn wcsrtombs(
+//Convert a wide string to a multibyte string
+#[no_mangle]
+pub unsafe extern "C" fn wcsrtombs(
     dst: *mut c_char,
     src: *mut *const wchar_t,
     len: size_t,
     ps: *mut mbstate_t,
 ) -> size_t {
-    unimplemented!();
+    let mut ws = *src;
+    let mut written = 0;
+
+    loop {
+        let mut bytes: [c_char; MB_CUR_MAX as usize] = [0; MB_CUR_MAX as usize];
+        let amount = wcrtomb(bytes.as_mut_ptr(), *ws, ps);
+
+        if amount == -1isize as usize {
+            if !dst.is_null() {
+                *src = ws;
+            }
+            return amount;
+        }
+
+        if !dst.is_null() {
+            // Only whole characters are written
+            if written + amount > len {
+                *src = ws;
+                return written;
+            }
+            for i in 0..amount {
+                *dst.add(written + i) = bytes[i];
+            }
+        }
+
+        // The NUL is converted, but not counted
+        if *ws == 0 {
+            if !dst.is_null() {
+                *src = ptr::null();
+            }
+            return written;
+        }
+
+        written += amount;
+        ws = ws.add(1);
+    }
 }
 
 // #[no_mangle]
@@ -415,14 +512,22 @@ pub extern "C" fn wmemchr(ws: *const wchar_t, wc: wchar_t, n: size_t) -> *mut c_
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wmemcmp(ws1: *const wchar_t, ws2: *const wchar_t, n: size_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wmemcmp(ws1: *const wchar_t, ws2: *const wchar_t, n: size_t) -> c_int {
+    for i in 0..n {
+        let a = *ws1.add(i);
+        let b = *ws2.add(i);
+        if a != b {
+            return compare(a, b);
+        }
+    }
+    0
 }
 
-// #[no_mangle]
-pub extern "C" fn wmemcpy(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) -> *mut wchar_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wmemcpy(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) -> *mut wchar_t {
+    ptr::copy_nonoverlapping(ws2, ws1, n);
+    ws1
 }
 
 // #[no_mangle]
@@ -430,9 +535,12 @@ pub extern "C" fn wmemmove(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) ->
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wmemset(ws1: *mut wchar_t, ws2: wchar_t, n: size_t) -> *mut wchar_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wmemset(ws: *mut wchar_t, wc: wchar_t, n: size_t) -> *mut wchar_t {
+    for i in 0..n {
+        *ws.add(i) = wc;
+    }
+    ws
 }
 
 // #[no_mangle]
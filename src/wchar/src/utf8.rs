@@ -10,36 +10,46 @@ use platform::types::*;
 
 //It's guaranteed that we don't have any nullpointers here
 pub unsafe fn mbrtowc(pwc: *mut wchar_t, s: *const c_char, n: usize, ps: *mut mbstate_t) -> usize {
-    let mut size = str::utf8_char_width(*s as u8);
-    if size > n {
-        platform::errno = errno::EILSEQ;
-        return -2isize as usize;
-    }
-    if size == 0 {
-        platform::errno = errno::EILSEQ;
-        return -1isize as usize;
-    }
+    // Carry on with the bytes of a character the last call ran out of input for
+    let mut buf = (*ps).bytes;
+    let mut len = (*ps).count as usize;
 
-    let slice = slice::from_raw_parts(s as *const u8, size);
-    let decoded = str::from_utf8(slice);
-    if decoded.is_err() {
-        platform::errno = errno::EILSEQ;
-        return -1isize as usize;
-    }
+    let mut used = 0;
+    let result = loop {
+        if used == n {
+            // Incomplete, keep what there is for the next call
+            (*ps).bytes = buf;
+            (*ps).count = len as c_uint;
+            return -2isize as usize;
+        }
+        buf[len] = *s.add(used) as u8;
+        len += 1;
+        used += 1;
 
-    let wc = decoded.unwrap();
+        // from_utf8 tells invalid sequences, including surrogates and overlong encodings, apart
+        // from ones that are cut short
+        match str::from_utf8(&buf[..len]) {
+            Ok(decoded) => break decoded.chars().next().unwrap() as wchar_t,
+            Err(err) => if err.error_len().is_some() {
+                *ps = mbstate_t::default();
+                platform::errno = errno::EILSEQ;
+                return -1isize as usize;
+            },
+        }
+    };
 
-    let result: wchar_t = wc.chars().next().unwrap() as wchar_t;
+    *ps = mbstate_t::default();
 
     if !pwc.is_null() {
         *pwc = result;
     }
 
-    return if result != 0 { size } else { 0 };
+    return if result != 0 { used } else { 0 };
 }
 
 //It's guaranteed that we don't have any nullpointers here
 pub unsafe fn wcrtomb(s: *mut c_char, wc: wchar_t, ps: *mut mbstate_t) -> usize {
+    // This refuses surrogates as well as anything past U+10FFFF
     let dc = char::from_u32(wc as u32);
 
     if dc.is_none() {
@@ -21,4 +21,27 @@ int main(int argc, char** argv) {
     inet_aton(addr_str, addr);
     assert(strcmp(inet_ntoa(*addr), addr_str) == 0);
 
+    assert(inet_aton("0x7f.1", addr) == 1);
+    assert(strcmp(inet_ntoa(*addr), "127.0.0.1") == 0);
+    assert(inet_aton("010.0.258", addr) == 1);
+    assert(strcmp(inet_ntoa(*addr), "8.0.1.2") == 0);
+    assert(inet_aton(" 1.2.3.4", addr) == 0);
+    assert(inet_aton("1.2.3.4.5", addr) == 0);
+    assert(inet_addr("192.168.1.1") == htonl(0xC0A80101));
+    assert(inet_addr("256.1.1.1") == INADDR_NONE);
+    assert(inet_network("10.1") == 0x0A01);
+
+    char buf[INET6_ADDRSTRLEN];
+    struct in6_addr addr6;
+    assert(inet_pton(AF_INET6, "2001:db8:0:0:0:0:0:1", &addr6) == 1);
+    assert(strcmp(inet_ntop(AF_INET6, &addr6, buf, sizeof buf), "2001:db8::1") == 0);
+    assert(inet_pton(AF_INET6, "::ffff:192.168.0.1", &addr6) == 1);
+    assert(strcmp(inet_ntop(AF_INET6, &addr6, buf, sizeof buf), "::ffff:192.168.0.1") == 0);
+    assert(inet_pton(AF_INET6, "::", &addr6) == 1);
+    assert(strcmp(inet_ntop(AF_INET6, &addr6, buf, sizeof buf), "::") == 0);
+    assert(inet_pton(AF_INET6, "1::2::3", &addr6) == 0);
+    assert(inet_pton(AF_INET, "256.0.0.1", addr) == 0);
+    assert(inet_pton(AF_INET, "1.2.3", addr) == 0);
+    assert(inet_ntop(AF_INET, addr, buf, 4) == NULL);
+    assert(inet_pton(12345, "1.2.3.4", addr) == -1);
 }
@@ -1,14 +1,32 @@
 #include <assert.h>
+#include <signal.h>
 #include <stdlib.h>
 #include <stdio.h>
+#include <sys/wait.h>
+#include <unistd.h>
 
 int main() {
     assert(1 == 1);
     assert(1 + 1 == 2);
 
     puts("yay!");
+    fflush(stdout);
 
-    //This fails, but I can't test it because that'd
-    //make the test fail lol
-    //assert(42 == 1337);
+    pid_t pid = fork();
+    if (pid == 0) {
+        assert(42 == 1337);
+        exit(0);
+    }
+    int status = 0;
+    waitpid(pid, &status, 0);
+    printf("signaled: %d\n", WIFSIGNALED(status));
+    printf("SIGABRT: %d\n", WTERMSIG(status) == SIGABRT);
+
+// assert.h has to be reevaluated every time it's included
+#define NDEBUG
+#include <assert.h>
+    int evaluated = 0;
+    assert(evaluated++);
+    printf("NDEBUG evaluated: %d\n", evaluated);
+    return 0;
 }
@@ -36,6 +36,11 @@ size_t num_test_cases = sizeof(test_cases)/sizeof(struct test_case);
         retval = -1; \
         printf("Unexpected result: " #fn "('%c') != %d\n", tc.c, tc.fn); \
     }
+#define CHECK_CLASS(c, fn, expected, retval) \
+    if (!fn(c) != !(expected)) { \
+        retval = -1; \
+        printf("Unexpected result: " #fn "(%d) != %d\n", c, !!(expected)); \
+    }
 int main(int argc, char* argv[]) {
     int i;
     int retval = 0;
@@ -49,6 +54,39 @@ int main(int argc, char* argv[]) {
         CHECK_TEST(tc, isspace, retval);
         CHECK_TEST(tc, isupper, retval);
     }
+
+    // Every ASCII character and EOF against the classes of the C locale
+    for (int c = -1; c < 128; ++c) {
+        int digit = c >= '0' && c <= '9';
+        int upper = c >= 'A' && c <= 'Z';
+        int lower = c >= 'a' && c <= 'z';
+        int alpha = upper || lower;
+        int print = c >= 0x20 && c < 0x7f;
+        int graph = print && c != ' ';
+        int space = c == ' ' || (c >= '\t' && c <= '\r');
+        int xdigit = digit || (c >= 'a' && c <= 'f') || (c >= 'A' && c <= 'F');
+        CHECK_CLASS(c, isalnum, alpha || digit, retval);
+        CHECK_CLASS(c, isalpha, alpha, retval);
+        CHECK_CLASS(c, isascii, c >= 0, retval);
+        CHECK_CLASS(c, isblank, c == ' ' || c == '\t', retval);
+        CHECK_CLASS(c, iscntrl, (c >= 0 && c < 0x20) || c == 0x7f, retval);
+        CHECK_CLASS(c, isdigit, digit, retval);
+        CHECK_CLASS(c, isgraph, graph, retval);
+        CHECK_CLASS(c, islower, lower, retval);
+        CHECK_CLASS(c, isprint, print, retval);
+        CHECK_CLASS(c, ispunct, graph && !alpha && !digit, retval);
+        CHECK_CLASS(c, isspace, space, retval);
+        CHECK_CLASS(c, isupper, upper, retval);
+        CHECK_CLASS(c, isxdigit, xdigit, retval);
+        if (toupper(c) != (lower ? c - 'a' + 'A' : c)) {
+            retval = -1;
+            printf("Unexpected result: toupper(%d) == %d\n", c, toupper(c));
+        }
+        if (tolower(c) != (upper ? c - 'A' + 'a' : c)) {
+            retval = -1;
+            printf("Unexpected result: tolower(%d) == %d\n", c, tolower(c));
+        }
+    }
     if (!retval) {
         printf("Success: %d\n", retval);
     } else {
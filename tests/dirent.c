@@ -1,5 +1,7 @@
+#include <assert.h>
 #include <dirent.h>
 #include <errno.h>
+#include <fcntl.h>
 #include <stdio.h>
 
 int main() {
@@ -16,6 +18,7 @@ int main() {
 
     //int tell = 0;
 
+    errno = 0;
     for (char counter = 0; (entry = readdir(dir)); counter += 1) {
         puts(entry->d_name);
 
@@ -24,6 +27,9 @@ int main() {
         //}
     }
 
+    // Reaching the end is not an error
+    assert(errno == 0);
+
     puts("--- Testing rewind ---");
     rewinddir(dir);
     entry = readdir(dir);
@@ -35,4 +41,14 @@ int main() {
     // seekdir(dir, tell);
     // entry = readdir(dir);
     // puts(entry->d_name);
+
+    closedir(dir);
+
+    puts("--- Testing fdopendir ---");
+    int fd = open("example_dir/", O_RDONLY | O_DIRECTORY);
+    dir = fdopendir(fd);
+    assert(dir != NULL);
+    entry = readdir(dir);
+    puts(entry->d_name);
+    closedir(dir);
 }
@@ -0,0 +1,30 @@
+#include <dlfcn.h>
+#include <stdio.h>
+
+int main() {
+    printf("no error at first: %d\n", dlerror() == NULL);
+
+    void* handle = dlopen(NULL, RTLD_NOW | RTLD_GLOBAL);
+    if (handle == NULL) {
+        printf("dlopen(NULL): %s\n", dlerror());
+        return 1;
+    }
+    puts("opened the program itself");
+
+    printf("found puts: %d\n", dlsym(handle, "puts") == (void*) puts);
+    printf("found puts by default: %d\n", dlsym(RTLD_DEFAULT, "puts") == (void*) puts);
+    printf("no error after finding: %d\n", dlerror() == NULL);
+
+    void* sym = dlsym(handle, "relibc_no_such_symbol");
+    printf("missing symbol: %d, error set: %d\n", sym == NULL, dlerror() != NULL);
+    printf("error cleared after reading: %d\n", dlerror() == NULL);
+
+    void* lib = dlopen("relibc_no_such_library.so", RTLD_LAZY | RTLD_LOCAL);
+    printf("missing library: %d, error set: %d\n", lib == NULL, dlerror() != NULL);
+
+    if (dlclose(handle) != 0) {
+        printf("dlclose: %s\n", dlerror());
+        return 1;
+    }
+    puts("closed the program");
+}
@@ -7,5 +7,17 @@ int main(int argc, char** argv) {
     chdir("nonexistent");
     printf("errno: %d = %s\n", errno, strerror(errno));
     perror("perror");
+
+    printf("%s\n", strerror(EAFNOSUPPORT));
+    printf("%s\n", strerror(-1));
+    printf("%s\n", strerror(4096));
+
+    char buf[64];
+    int ret = strerror_r(ENOENT, buf, sizeof(buf));
+    printf("strerror_r: %d \"%s\"\n", ret, buf);
+    ret = strerror_r(ENOENT, buf, 8);
+    printf("strerror_r: %d \"%s\"\n", ret == ERANGE, buf);
+    ret = strerror_r(4096, buf, sizeof(buf));
+    printf("strerror_r: %d \"%s\"\n", ret == EINVAL, buf);
     return 0;
 }
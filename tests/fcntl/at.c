@@ -0,0 +1,61 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#define CHECK(expr) \
+    if ((expr) < 0) { \
+        perror(#expr); \
+        return 1; \
+    }
+
+int main() {
+    CHECK(mkdirat(AT_FDCWD, "at-dir.out", 0755));
+
+    int dir = open("at-dir.out", O_RDONLY | O_DIRECTORY);
+    CHECK(dir);
+
+    int fd = openat(dir, "file", O_CREAT | O_WRONLY | O_EXCL, 0644);
+    CHECK(fd);
+    CHECK(write(fd, "Hello", 5));
+    CHECK(close(fd));
+
+    struct stat st;
+    CHECK(fstatat(dir, "file", &st, 0));
+    printf("file size: %ld\n", (long) st.st_size);
+    CHECK(fstatat(AT_FDCWD, "at-dir.out/file", &st, 0));
+    printf("file size from AT_FDCWD: %ld\n", (long) st.st_size);
+
+    // Absolute paths ignore the dirfd
+    CHECK(fstatat(dir, "/", &st, 0));
+    printf("/ is a directory: %d\n", S_ISDIR(st.st_mode));
+
+    CHECK(symlinkat("file", dir, "link"));
+    CHECK(fstatat(dir, "link", &st, 0));
+    printf("following the link gives a file: %d\n", S_ISREG(st.st_mode));
+    CHECK(fstatat(dir, "link", &st, AT_SYMLINK_NOFOLLOW));
+    printf("not following it gives a link: %d\n", S_ISLNK(st.st_mode));
+
+    CHECK(renameat(dir, "file", AT_FDCWD, "at-dir.out/renamed"));
+    errno = 0;
+    if (fstatat(dir, "file", &st, 0) != -1 || errno != ENOENT) {
+        puts("the renamed file still exists");
+        return 1;
+    }
+    CHECK(fstatat(dir, "renamed", &st, 0));
+    printf("renamed size: %ld\n", (long) st.st_size);
+
+    CHECK(mkdirat(dir, "sub", 0755));
+    errno = 0;
+    if (unlinkat(dir, "sub", 0) != -1) {
+        puts("unlinkat removed a directory without AT_REMOVEDIR");
+        return 1;
+    }
+    CHECK(unlinkat(dir, "sub", AT_REMOVEDIR));
+    CHECK(unlinkat(dir, "link", 0));
+    CHECK(unlinkat(dir, "renamed", 0));
+    CHECK(close(dir));
+    CHECK(unlinkat(AT_FDCWD, "at-dir.out", AT_REMOVEDIR));
+    puts("done");
+}
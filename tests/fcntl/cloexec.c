@@ -0,0 +1,33 @@
+#include <assert.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <unistd.h>
+
+int main() {
+    int fds[2];
+    assert(pipe(fds) == 0);
+    assert(fcntl(fds[0], F_GETFD, 0) == 0);
+    assert(fcntl(fds[0], F_SETFD, FD_CLOEXEC) == 0);
+    assert(fcntl(fds[0], F_GETFD, 0) == FD_CLOEXEC);
+    assert(fcntl(fds[0], F_SETFD, 0) == 0);
+    assert(fcntl(fds[0], F_GETFD, 0) == 0);
+    close(fds[0]);
+    close(fds[1]);
+
+    assert(pipe2(fds, O_CLOEXEC) == 0);
+    assert(fcntl(fds[0], F_GETFD, 0) == FD_CLOEXEC);
+    assert(fcntl(fds[1], F_GETFD, 0) == FD_CLOEXEC);
+    close(fds[0]);
+    close(fds[1]);
+
+    assert(pipe(fds) == 0);
+    assert(dup3(fds[0], 100, O_CLOEXEC) == 100);
+    assert(fcntl(100, F_GETFD, 0) == FD_CLOEXEC);
+    // Replacing it with dup2 leaves a descriptor that isn't close-on-exec
+    assert(dup2(fds[0], 100) == 100);
+    assert(fcntl(100, F_GETFD, 0) == 0);
+    assert(dup3(fds[0], fds[0], 0) == -1 && errno == EINVAL);
+    close(100);
+    close(fds[0]);
+    close(fds[1]);
+}
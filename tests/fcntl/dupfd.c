@@ -0,0 +1,29 @@
+#include <assert.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <unistd.h>
+
+int main() {
+    int fd = open("fcntl_dupfd.out", O_RDWR | O_CREAT | O_TRUNC, 0644);
+    assert(fd >= 0);
+
+    int dup = fcntl(fd, F_DUPFD, 20);
+    assert(dup >= 20);
+    assert(fcntl(dup, F_GETFD, 0) == 0);
+
+    // Both descriptors share the same offset
+    assert(write(fd, "hello", 5) == 5);
+    assert(lseek(dup, 0, SEEK_CUR) == 5);
+
+    int dup2 = fcntl(fd, F_DUPFD_CLOEXEC, dup);
+    assert(dup2 > dup);
+    assert(fcntl(dup2, F_GETFD, 0) == FD_CLOEXEC);
+
+    assert(fcntl(fd, F_DUPFD, -1) == -1);
+    assert(errno == EINVAL);
+
+    close(dup2);
+    close(dup);
+    close(fd);
+    unlink("fcntl_dupfd.out");
+}
@@ -0,0 +1,27 @@
+#include <assert.h>
+#include <fcntl.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+int main() {
+    int fd = socket(AF_INET, SOCK_STREAM | SOCK_NONBLOCK, 0);
+    assert(fd >= 0);
+
+    int flags = fcntl(fd, F_GETFL, 0);
+    assert(flags & O_NONBLOCK);
+    assert(fcntl(fd, F_SETFL, flags & ~O_NONBLOCK) == 0);
+    assert(!(fcntl(fd, F_GETFL, 0) & O_NONBLOCK));
+    assert(fcntl(fd, F_SETFL, flags) == 0);
+    assert(fcntl(fd, F_GETFL, 0) & O_NONBLOCK);
+    close(fd);
+
+    // The access mode can't be changed after open
+    fd = open("fcntl_flags.out", O_RDWR | O_CREAT | O_TRUNC, 0644);
+    assert(fd >= 0);
+    assert(fcntl(fd, F_SETFL, O_WRONLY | O_APPEND) == 0);
+    flags = fcntl(fd, F_GETFL, 0);
+    assert((flags & O_ACCMODE) == O_RDWR);
+    assert(flags & O_APPEND);
+    close(fd);
+    unlink("fcntl_flags.out");
+}
@@ -0,0 +1,27 @@
+#include <assert.h>
+#include <fcntl.h>
+#include <unistd.h>
+
+int main() {
+    int fd = open("fcntl_lock.out", O_RDWR | O_CREAT | O_TRUNC, 0644);
+    assert(fd >= 0);
+
+    struct flock lock = {
+        .l_type = F_WRLCK,
+        .l_whence = SEEK_SET,
+        .l_start = 0,
+        .l_len = 10,
+    };
+    assert(fcntl(fd, F_SETLK, &lock) == 0);
+
+    // Our own locks never conflict with us
+    lock.l_type = F_RDLCK;
+    assert(fcntl(fd, F_GETLK, &lock) == 0);
+    assert(lock.l_type == F_UNLCK);
+
+    lock.l_type = F_UNLCK;
+    assert(fcntl(fd, F_SETLKW, &lock) == 0);
+
+    close(fd);
+    unlink("fcntl_lock.out");
+}
@@ -0,0 +1,72 @@
+#include <getopt.h>
+#include <stdio.h>
+
+#define RUN(...) \
+    { \
+        optind = 1; \
+        optarg = NULL; \
+        optopt = -1; \
+        char *args_arr[] = { __VA_ARGS__ }; \
+        runner(sizeof(args_arr) / sizeof(args_arr[0]), args_arr); \
+    }
+
+int verbose_flag = 0;
+
+struct option longopts[] = {
+    { "verbose", no_argument, &verbose_flag, 1 },
+    { "version", no_argument, NULL, 'V' },
+    { "name", required_argument, NULL, 'n' },
+    { "count", required_argument, NULL, 'c' },
+    { "level", optional_argument, NULL, 'l' },
+    { 0, 0, 0, 0 },
+};
+
+void runner(int argc, char *argv[]) {
+    int c;
+    int longindex = -1;
+    while ((c = getopt_long(argc, argv, "ao:l::", longopts, &longindex)) != -1) {
+        switch (c) {
+            case 0:
+                printf("flag set by --%s: %d\n", longopts[longindex].name, verbose_flag);
+                break;
+            case '?':
+                printf("error, optopt: %d\n", optopt);
+                break;
+            default:
+                printf("option %c, optarg: %s\n", c, optarg ? optarg : "(none)");
+        }
+        longindex = -1;
+    }
+    printf("remaining:");
+    for (int i = optind; i < argc; i++) {
+        printf(" %s", argv[i]);
+    }
+    printf("\n");
+}
+
+int main(int argc, char *argv[]) {
+    // Options and non-options are mixed, non-options end up at the end
+    opterr = 1;
+    RUN("test", "file1", "-a", "--verbose", "file2", "--name=x", "-o", "out", "--count", "3",
+        "file3", "--level", "--level=2", "-l", "-l5", "--", "-notanoption");
+
+    // Abbreviations are accepted if they are unambiguous
+    RUN("test", "--na", "abbreviated", "--vers", "file");
+
+    // Errors are reported silently when opterr is 0
+    opterr = 0;
+    RUN("test", "--ver", "--unknown", "--verbose=1", "-x", "--name");
+
+    // Otherwise they also end up on stderr
+    opterr = 1;
+    RUN("test", "--bogus", "file");
+
+    // A leading '+' stops at the first non-option
+    optind = 1;
+    char *posix_args[] = { "test", "-a", "file", "-a" };
+    int c;
+    while ((c = getopt_long(4, posix_args, "+a", longopts, NULL)) != -1) {
+        printf("option %c\n", c);
+    }
+    printf("optind: %d\n", optind);
+}
@@ -0,0 +1,53 @@
+#include <errno.h>
+#include <grp.h>
+#include <stdio.h>
+
+int main() {
+    errno = 0;
+    struct group* grp = getgrgid(0);
+    if (grp == NULL) {
+        perror("getgrgid");
+        return 1;
+    }
+    printf("getgrgid(0) found gid %d\n", grp->gr_gid);
+    if (getgrnam(grp->gr_name) == NULL) {
+        perror("getgrnam");
+        return 1;
+    }
+
+    errno = 0;
+    if (getgrnam("relibc_no_such_group") != NULL || errno != 0) {
+        puts("Found a group that doesn't exist!");
+        return 1;
+    }
+    puts("missing group not found");
+
+    // The fixture has a group with several members, a malformed line and no final newline
+    FILE* file = fopen("grp.in", "r");
+    if (file == NULL) {
+        perror("fopen");
+        return 1;
+    }
+    while ((grp = fgetgrent(file)) != NULL) {
+        printf("%s %d:", grp->gr_name, grp->gr_gid);
+        for (size_t i = 0; grp->gr_mem[i] != NULL; i++) {
+            printf(" %s", grp->gr_mem[i]);
+        }
+        putchar('\n');
+    }
+    fclose(file);
+
+    int count = 0;
+    setgrent();
+    while (getgrent() != NULL) {
+        count++;
+    }
+    endgrent();
+    printf("getgrent found groups: %d\n", count > 0);
+
+    char small[1];
+    struct group grp3;
+    struct group* result;
+    int err = getgrgid_r(0, &grp3, small, sizeof(small), &result);
+    printf("getgrgid_r with a tiny buffer: ERANGE: %d, result NULL: %d\n", err == ERANGE, result == NULL);
+}
@@ -0,0 +1,33 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <unistd.h>
+
+static volatile int handled = 0;
+
+void handler(int sig) {
+    handled = sig;
+}
+
+int main() {
+    signal(SIGUSR1, handler);
+    printf("raise: %d\n", raise(SIGUSR1));
+    printf("delivered: %d\n", handled == SIGUSR1);
+
+    handled = 0;
+    printf("kill: %d\n", kill(getpid(), SIGUSR1));
+    printf("delivered: %d\n", handled == SIGUSR1);
+
+    // signal 0 only checks that the process exists
+    handled = 0;
+    printf("kill 0: %d\n", kill(getpid(), 0));
+    printf("nothing delivered: %d\n", handled == 0);
+
+    errno = 0;
+    printf("kill -1: %d, EINVAL: %d\n", kill(getpid(), -1), errno == EINVAL);
+    errno = 0;
+    printf("kill 1000: %d, EINVAL: %d\n", kill(getpid(), 1000), errno == EINVAL);
+    errno = 0;
+    printf("raise 1000: %d, EINVAL: %d\n", raise(1000), errno == EINVAL);
+    return 0;
+}
@@ -0,0 +1,40 @@
+#include <libgen.h>
+#include <stdio.h>
+#include <string.h>
+
+struct test {
+    const char* path;
+    const char* dirname;
+    const char* basename;
+};
+
+int main(int argc, char** argv) {
+    // the examples from POSIX and a few more edge cases
+    struct test tests[] = {
+        { "/usr/lib", "/usr", "lib" },
+        { "/usr/", "/", "usr" },
+        { "usr", ".", "usr" },
+        { "usr/lib/", "usr", "lib" },
+        { "a//b//", "a", "b" },
+        { "/", "/", "/" },
+        { "//", "/", "/" },
+        { "///", "/", "/" },
+        { ".", ".", "." },
+        { "..", ".", ".." },
+        { "", ".", "." },
+    };
+
+    for (size_t i = 0; i < sizeof(tests) / sizeof(tests[0]); i++) {
+        char dir_path[64];
+        char base_path[64];
+        strcpy(dir_path, tests[i].path);
+        strcpy(base_path, tests[i].path);
+        char* dir = dirname(dir_path);
+        char* base = basename(base_path);
+        printf("\"%s\": dirname \"%s\", basename \"%s\"%s\n", tests[i].path, dir, base,
+            strcmp(dir, tests[i].dirname) == 0 && strcmp(base, tests[i].basename) == 0 ? "" : " FAIL");
+    }
+
+    printf("NULL: dirname \"%s\", basename \"%s\"\n", dirname(NULL), basename(NULL));
+    return 0;
+}
@@ -1,13 +1,55 @@
+#include <limits.h>
 #include <locale.h>
 #include <stdio.h>
+#include <stdlib.h>
 #include <string.h>
 
+void show(const char * call, const char * name) {
+    printf("%s = %s\n", call, name ? name : "NULL");
+}
+
 int main() {
-    // TODO: Implement locale properly and test it here
-    char* val = setlocale(LC_ALL, NULL);
-    if (strcmp(val, "C") == 0) {
-        puts("success!");
-    } else {
-        printf("setlocale returned the wrong value: %s", val);
-    }
+    show("setlocale(LC_ALL, NULL)", setlocale(LC_ALL, NULL));
+
+    show("setlocale(LC_ALL, \"C.UTF-8\")", setlocale(LC_ALL, "C.UTF-8"));
+    show("setlocale(LC_CTYPE, NULL)", setlocale(LC_CTYPE, NULL));
+    show("setlocale(LC_ALL, \"C\")", setlocale(LC_ALL, "C"));
+    show("setlocale(LC_CTYPE, NULL)", setlocale(LC_CTYPE, NULL));
+
+    show("setlocale(LC_NUMERIC, \"POSIX\")", setlocale(LC_NUMERIC, "POSIX"));
+    show("setlocale(LC_ALL, \"no_SUCH.locale\")", setlocale(LC_ALL, "no_SUCH.locale"));
+    show("setlocale(LC_ALL, NULL)", setlocale(LC_ALL, NULL));
+
+    // A mix of locales can be restored from the name it is given
+    setlocale(LC_CTYPE, "C.UTF-8");
+    char saved[256];
+    strcpy(saved, setlocale(LC_ALL, NULL));
+    setlocale(LC_ALL, "C");
+    printf("restored: %d\n", setlocale(LC_ALL, saved) != NULL);
+    show("setlocale(LC_CTYPE, NULL)", setlocale(LC_CTYPE, NULL));
+    show("setlocale(LC_TIME, NULL)", setlocale(LC_TIME, NULL));
+
+    // The empty name comes from the environment
+    setenv("LC_ALL", "C.UTF-8", 1);
+    show("setlocale(LC_ALL, \"\")", setlocale(LC_ALL, ""));
+    show("setlocale(LC_COLLATE, NULL)", setlocale(LC_COLLATE, NULL));
+    setenv("LC_ALL", "C", 1);
+    show("setlocale(LC_MESSAGES, \"\")", setlocale(LC_MESSAGES, ""));
+
+    show("setlocale(-1, NULL)", setlocale(-1, NULL));
+
+    // The C locale's conventions
+    setlocale(LC_ALL, "C");
+    struct lconv * conv = localeconv();
+    printf("decimal_point = \"%s\"\n", conv->decimal_point);
+    printf("thousands_sep = \"%s\", grouping = \"%s\"\n", conv->thousands_sep, conv->grouping);
+    printf("currency_symbol = \"%s\", int_curr_symbol = \"%s\"\n", conv->currency_symbol, conv->int_curr_symbol);
+    printf("mon_decimal_point = \"%s\", positive_sign = \"%s\", negative_sign = \"%s\"\n",
+        conv->mon_decimal_point, conv->positive_sign, conv->negative_sign);
+    printf("frac_digits is CHAR_MAX: %d\n", conv->frac_digits == CHAR_MAX && conv->int_frac_digits == CHAR_MAX);
+    printf("sign positions are CHAR_MAX: %d\n", conv->p_sign_posn == CHAR_MAX && conv->n_sign_posn == CHAR_MAX
+        && conv->int_p_sign_posn == CHAR_MAX && conv->int_n_sign_posn == CHAR_MAX);
+
+    setlocale(LC_ALL, "C.UTF-8");
+    printf("C.UTF-8 decimal_point = \"%s\"\n", localeconv()->decimal_point);
 }
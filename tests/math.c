@@ -5,5 +5,39 @@ int main(int argc, char ** argv) {
     double pi = 3.14;
     float c = cos(pi);
     printf("cos(%f) = %f\n", pi, c);
+
+    printf("sqrt(2) = %.15f\n", sqrt(2.0));
+    printf("cbrt(27) = %f\n", cbrt(27.0));
+    printf("pow(2, 10) = %f\n", pow(2.0, 10.0));
+    printf("exp(1) = %.15f\n", exp(1.0));
+    printf("exp2(10) = %f\n", exp2(10.0));
+    printf("log(e) = %f\n", log(M_E));
+    printf("log2(1024) = %f\n", log2(1024.0));
+    printf("log10(1000) = %f\n", log10(1000.0));
+    printf("sin(pi / 2) = %f\n", sin(M_PI / 2));
+    printf("tan(pi / 4) = %f\n", tan(M_PI / 4));
+    printf("atan(1) * 4 = %.15f\n", atan(1.0) * 4);
+    printf("atan2(1, -1) = %f\n", atan2(1.0, -1.0));
+
+    printf("sqrtf(2) = %f\n", sqrtf(2.0f));
+    printf("powf(3, 3) = %f\n", powf(3.0f, 3.0f));
+    printf("expf(0) = %f\n", expf(0.0f));
+    printf("logf(1) = %f\n", logf(1.0f));
+    printf("sinf(0) = %f\n", sinf(0.0f));
+    printf("cosf(0) = %f\n", cosf(0.0f));
+    printf("tanf(0) = %f\n", tanf(0.0f));
+    printf("atan2f(0, 1) = %f\n", atan2f(0.0f, 1.0f));
+
+    // Edge cases
+    printf("log(0) is -inf: %d\n", isinf(log(0.0)) && log(0.0) < 0);
+    printf("log(-1) is nan: %d\n", isnan(log(-1.0)));
+    printf("sqrt(-1) is nan: %d\n", isnan(sqrt(-1.0)));
+    printf("pow(0, 0) = %f\n", pow(0.0, 0.0));
+    printf("pow(nan, 0) = %f\n", pow(NAN, 0.0));
+    printf("pow(0, -1) is inf: %d\n", isinf(pow(0.0, -1.0)));
+    printf("exp(1000) is inf: %d\n", isinf(exp(1000.0)));
+    printf("exp(-1000) = %f\n", exp(-1000.0));
+    printf("atan(inf) = %f\n", atan(INFINITY));
+    printf("sin(inf) is nan: %d\n", isnan(sin(INFINITY)));
     return 0;
 }
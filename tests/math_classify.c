@@ -0,0 +1,81 @@
+#include <float.h>
+#include <math.h>
+#include <stdio.h>
+
+const char * class_name(int class) {
+    switch (class) {
+        case FP_NAN: return "FP_NAN";
+        case FP_INFINITE: return "FP_INFINITE";
+        case FP_ZERO: return "FP_ZERO";
+        case FP_SUBNORMAL: return "FP_SUBNORMAL";
+        case FP_NORMAL: return "FP_NORMAL";
+        default: return "unknown";
+    }
+}
+
+#define CLASSIFY(x) printf("%-16s %-12s isnan=%d isinf=%d isfinite=%d isnormal=%d signbit=%d\n", \
+    #x, class_name(fpclassify(x)), !!isnan(x), !!isinf(x), !!isfinite(x), !!isnormal(x), !!signbit(x))
+
+int main(int argc, char ** argv) {
+    volatile double zero = 0.0;
+
+    printf("isnan(0.0 / 0.0) = %d\n", !!isnan(zero / zero));
+
+    CLASSIFY(NAN);
+    CLASSIFY(1.0 / zero);
+    CLASSIFY(-1.0 / zero);
+    CLASSIFY(0.0);
+    CLASSIFY(-0.0);
+    CLASSIFY(1.0);
+    CLASSIFY(-DBL_MIN);
+    CLASSIFY(DBL_MIN / 2);
+    CLASSIFY(DBL_MAX);
+    CLASSIFY(-NAN);
+    CLASSIFY(0.0f);
+    CLASSIFY(FLT_MIN / 2);
+    CLASSIFY(-INFINITY);
+    CLASSIFY(2.5f);
+
+    int exp;
+    double mant = frexp(8.0, &exp);
+    printf("frexp(8.0) = %f * 2^%d\n", mant, exp);
+    mant = frexp(-3.0, &exp);
+    printf("frexp(-3.0) = %f * 2^%d\n", mant, exp);
+    mant = frexp(0.0, &exp);
+    printf("frexp(0.0) = %f * 2^%d\n", mant, exp);
+    mant = frexp(DBL_MIN / 4, &exp);
+    printf("frexp(DBL_MIN / 4) = %f * 2^%d\n", mant, exp);
+    float mantf = frexpf(0.75f, &exp);
+    printf("frexpf(0.75f) = %f * 2^%d\n", mantf, exp);
+
+    printf("ldexp(0.5, 4) = %f\n", ldexp(0.5, 4));
+    printf("ldexp(1.0, -1074) == DBL_TRUE_MIN: %d\n", ldexp(1.0, -1074) == DBL_MIN * DBL_EPSILON);
+    printf("ldexp(1.0, 1024) is inf: %d\n", !!isinf(ldexp(1.0, 1024)));
+    printf("ldexpf(3.0f, 2) = %f\n", ldexpf(3.0f, 2));
+
+    double integral;
+    double frac = modf(3.75, &integral);
+    printf("modf(3.75) = %f + %f\n", integral, frac);
+    frac = modf(-2.5, &integral);
+    printf("modf(-2.5) = %f + %f\n", integral, frac);
+    frac = modf(-INFINITY, &integral);
+    printf("modf(-inf): integral is inf: %d, frac is -0: %d\n", !!isinf(integral), frac == 0.0 && signbit(frac));
+    float integralf;
+    float fracf = modff(1.25f, &integralf);
+    printf("modff(1.25f) = %f + %f\n", integralf, fracf);
+
+    printf("scalbn(1.5, 3) = %f\n", scalbn(1.5, 3));
+    printf("scalbln(1.5, -1) = %f\n", scalbln(1.5, -1L));
+    printf("scalbnf(1.0f, 10) = %f\n", scalbnf(1.0f, 10));
+
+    printf("ilogb(8.0) = %d\n", ilogb(8.0));
+    printf("ilogb(0.1) = %d\n", ilogb(0.1));
+    printf("ilogb(DBL_MIN / 2) = %d\n", ilogb(DBL_MIN / 2));
+    printf("ilogb(0.0) == FP_ILOGB0: %d\n", ilogb(0.0) == FP_ILOGB0);
+    printf("ilogb(NAN) == FP_ILOGBNAN: %d\n", ilogb(NAN) == FP_ILOGBNAN);
+    printf("ilogbf(1024.0f) = %d\n", ilogbf(1024.0f));
+    printf("logb(8.0) = %f\n", logb(8.0));
+    printf("logb(0.0) is -inf: %d\n", isinf(logb(0.0)) && logb(0.0) < 0);
+    printf("logbf(0.25f) = %f\n", logbf(0.25f));
+    return 0;
+}
@@ -0,0 +1,75 @@
+#include <math.h>
+#include <stdio.h>
+
+// Prints the sign separately, so negative zero shows up
+void print(const char * expr, double value) {
+    if (isnan(value)) {
+        printf("%s = nan\n", expr);
+    } else if (isinf(value)) {
+        printf("%s = %sinf\n", expr, signbit(value) ? "-" : "");
+    } else {
+        printf("%s = %s%f\n", expr, signbit(value) ? "-" : "", fabs(value));
+    }
+}
+
+#define TEST(expr) print(#expr, expr)
+
+int main(int argc, char ** argv) {
+    TEST(fabs(-2.5));
+    TEST(fabs(-0.0));
+    TEST(fabsf(-INFINITY));
+
+    TEST(floor(-0.0));
+    TEST(floor(-1.5));
+    TEST(floor(1.5));
+    TEST(floorf(-0.5f));
+    TEST(ceil(-0.5));
+    TEST(ceil(1.2));
+    TEST(ceilf(-INFINITY));
+
+    TEST(round(2.5));
+    TEST(round(-2.5));
+    TEST(round(0.49999999999999994));
+    TEST(roundf(-0.4f));
+    TEST(trunc(-2.7));
+    TEST(trunc(2.7));
+    TEST(truncf(-0.7f));
+
+    // The default rounding mode is to nearest, ties to even
+    TEST(rint(2.5));
+    TEST(rint(3.5));
+    TEST(rint(-0.5));
+    TEST(rintf(1.5f));
+    TEST(nearbyint(2.5));
+    TEST(nearbyintf(-3.5f));
+
+    TEST(fmod(5.0, 3.0));
+    TEST(fmod(-5.0, 3.0));
+    TEST(fmod(5.0, -3.0));
+    TEST(fmod(-0.0, 1.0));
+    TEST(fmod(1.0, 0.0));
+    TEST(fmod(INFINITY, 1.0));
+    TEST(fmod(1.0, INFINITY));
+    TEST(fmodf(7.5f, 2.0f));
+
+    TEST(remainder(5.0, 3.0));
+    TEST(remainder(7.0, 2.0));
+    TEST(remainder(5.0, 2.0));
+    TEST(remainder(1.0, 0.0));
+    TEST(remainderf(-5.0f, 3.0f));
+
+    int quo;
+    double rem = remquo(10.0, 3.0, &quo);
+    printf("remquo(10.0, 3.0) = %f, quo & 7 = %d\n", rem, quo & 7);
+    float remf = remquof(-7.0f, 2.0f, &quo);
+    printf("remquof(-7.0f, 2.0f) = %f, quo & 7 = %d, sign = %d\n", remf, (quo < 0 ? -quo : quo) & 7, quo < 0);
+
+    TEST(copysign(3.0, -0.0));
+    TEST(copysign(-3.0, 1.0));
+    TEST(copysign(0.0, -1.0));
+    TEST(copysignf(INFINITY, -2.0f));
+
+    TEST(floor(NAN));
+    TEST(round(INFINITY));
+    return 0;
+}
@@ -0,0 +1,38 @@
+#include <arpa/inet.h>
+#include <assert.h>
+#include <netdb.h>
+#include <stddef.h>
+#include <string.h>
+#include <sys/socket.h>
+
+int main(int argc, char** argv) {
+    struct addrinfo hints;
+    memset(&hints, 0, sizeof(hints));
+    hints.ai_family = AF_INET;
+    hints.ai_socktype = SOCK_STREAM;
+
+    struct addrinfo* res = NULL;
+    assert(getaddrinfo("127.0.0.1", "80", &hints, &res) == 0);
+    assert(res != NULL);
+    assert(res->ai_family == AF_INET);
+    assert(res->ai_socktype == SOCK_STREAM);
+
+    struct sockaddr_in* addr = (struct sockaddr_in*) res->ai_addr;
+    assert(ntohs(addr->sin_port) == 80);
+    assert(strcmp(inet_ntoa(addr->sin_addr), "127.0.0.1") == 0);
+    assert(res->ai_next == NULL);
+    freeaddrinfo(res);
+
+    hints.ai_flags = AI_PASSIVE;
+    assert(getaddrinfo(NULL, "8080", &hints, &res) == 0);
+    addr = (struct sockaddr_in*) res->ai_addr;
+    assert(addr->sin_addr.s_addr == htonl(INADDR_ANY));
+    freeaddrinfo(res);
+
+    hints.ai_flags = AI_NUMERICHOST;
+    assert(getaddrinfo("example.invalid", NULL, &hints, &res) == EAI_NONAME);
+
+    hints.ai_flags = 0;
+    hints.ai_family = AF_UNIX;
+    assert(getaddrinfo("127.0.0.1", NULL, &hints, &res) == EAI_FAMILY);
+}
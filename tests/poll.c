@@ -0,0 +1,29 @@
+#include <assert.h>
+#include <poll.h>
+#include <unistd.h>
+
+int main() {
+    int pipefd[2];
+    assert(pipe(pipefd) == 0);
+
+    struct pollfd fds[2] = {
+        { .fd = pipefd[0], .events = POLLIN },
+        { .fd = 1234, .events = POLLIN },
+    };
+
+    // Nothing written yet, so only the invalid fd is reported
+    assert(poll(fds, 2, 0) == 1);
+    assert(fds[0].revents == 0);
+    assert(fds[1].revents == POLLNVAL);
+
+    assert(write(pipefd[1], "x", 1) == 1);
+    assert(poll(fds, 1, -1) == 1);
+    assert(fds[0].revents == POLLIN);
+
+    char c;
+    assert(read(pipefd[0], &c, 1) == 1);
+    assert(poll(fds, 1, 10) == 0);
+
+    close(pipefd[0]);
+    close(pipefd[1]);
+}
@@ -0,0 +1,95 @@
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <sys/time.h>
+#include <time.h>
+
+#define CAPACITY 4
+#define ITEMS 1000
+#define CONSUMERS 3
+
+static pthread_mutex_t lock = PTHREAD_MUTEX_INITIALIZER;
+static pthread_cond_t not_empty = PTHREAD_COND_INITIALIZER;
+static pthread_cond_t not_full = PTHREAD_COND_INITIALIZER;
+
+static int buffer[CAPACITY];
+static int head = 0;
+static int len = 0;
+static int done = 0;
+
+void *produce(void *arg) {
+    for (int i = 1; i <= ITEMS; i++) {
+        pthread_mutex_lock(&lock);
+        while (len == CAPACITY) {
+            pthread_cond_wait(&not_full, &lock);
+        }
+        buffer[(head + len) % CAPACITY] = i;
+        len++;
+        pthread_cond_signal(&not_empty);
+        pthread_mutex_unlock(&lock);
+    }
+
+    pthread_mutex_lock(&lock);
+    done = 1;
+    pthread_cond_broadcast(&not_empty);
+    pthread_mutex_unlock(&lock);
+    return NULL;
+}
+
+void *consume(void *arg) {
+    long sum = 0;
+    for (;;) {
+        pthread_mutex_lock(&lock);
+        while (len == 0 && !done) {
+            pthread_cond_wait(&not_empty, &lock);
+        }
+        if (len == 0) {
+            pthread_mutex_unlock(&lock);
+            return (void *) sum;
+        }
+        sum += buffer[head];
+        head = (head + 1) % CAPACITY;
+        len--;
+        pthread_cond_signal(&not_full);
+        pthread_mutex_unlock(&lock);
+    }
+}
+
+int main() {
+    pthread_t producer;
+    pthread_t consumers[CONSUMERS];
+    pthread_create(&producer, NULL, produce, NULL);
+    for (int i = 0; i < CONSUMERS; i++) {
+        pthread_create(&consumers[i], NULL, consume, NULL);
+    }
+
+    pthread_join(producer, NULL);
+    long total = 0;
+    for (int i = 0; i < CONSUMERS; i++) {
+        void *sum;
+        pthread_join(consumers[i], &sum);
+        total += (long) sum;
+    }
+    printf("consumed: %ld, expected: %d\n", total, ITEMS * (ITEMS + 1) / 2);
+
+    // Nobody signals this one, so the wait has to time out
+    pthread_cond_t cond;
+    pthread_cond_init(&cond, NULL);
+    struct timeval now;
+    gettimeofday(&now, NULL);
+    struct timespec abstime;
+    abstime.tv_sec = now.tv_sec;
+    abstime.tv_nsec = now.tv_usec * 1000 + 100000000;
+    if (abstime.tv_nsec >= 1000000000) {
+        abstime.tv_sec++;
+        abstime.tv_nsec -= 1000000000;
+    }
+    pthread_mutex_lock(&lock);
+    printf("timedwait: %d\n", pthread_cond_timedwait(&cond, &lock, &abstime) == ETIMEDOUT);
+    printf("still locked: %d\n", pthread_mutex_trylock(&lock) == EBUSY);
+
+    abstime.tv_nsec = 1000000000;
+    printf("bad timeout: %d\n", pthread_cond_timedwait(&cond, &lock, &abstime) == EINVAL);
+    pthread_mutex_unlock(&lock);
+    pthread_cond_destroy(&cond);
+}
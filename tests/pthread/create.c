@@ -0,0 +1,83 @@
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#define THREADS 4
+#define INCREMENTS 100000
+
+static int counter = 0;
+static pthread_t threads[THREADS];
+
+void *increment(void *arg) {
+    long id = (long) arg;
+
+    // Every thread has an errno of its own
+    errno = (int) id;
+    for (int i = 0; i < INCREMENTS; i++) {
+        __atomic_fetch_add(&counter, 1, __ATOMIC_SEQ_CST);
+    }
+    if (errno != (int) id) {
+        return NULL;
+    }
+
+    if (!pthread_equal(pthread_self(), threads[id])) {
+        return NULL;
+    }
+    return (void *) (id * 10 + 1);
+}
+
+void *big_stack(void *arg) {
+    // Uses more than the default stack size
+    volatile char buf[4 * 1024 * 1024];
+    buf[0] = 1;
+    buf[sizeof(buf) - 1] = 2;
+    return (void *) (long) (buf[0] + buf[sizeof(buf) - 1]);
+}
+
+void *exits(void *arg) {
+    pthread_exit(arg);
+    return NULL;
+}
+
+int main() {
+    errno = 0;
+    for (long i = 0; i < THREADS; i++) {
+        int status = pthread_create(&threads[i], NULL, increment, (void *) i);
+        if (status != 0) {
+            printf("pthread_create: %d\n", status);
+            return 1;
+        }
+    }
+    for (int i = 0; i < THREADS; i++) {
+        void *retval;
+        int status = pthread_join(threads[i], &retval);
+        printf("pthread_join(%d) = %d, returned %ld\n", i, status, (long) retval);
+    }
+    printf("counter: %d\n", counter);
+    printf("main errno: %d\n", errno);
+
+    pthread_attr_t attr;
+    pthread_attr_init(&attr);
+    printf("setstacksize(1) = %d\n", pthread_attr_setstacksize(&attr, 1) == EINVAL);
+    pthread_attr_setstacksize(&attr, 8 * 1024 * 1024);
+    size_t stacksize;
+    pthread_attr_getstacksize(&attr, &stacksize);
+    printf("stack size: %zu\n", stacksize);
+
+    pthread_t thread;
+    void *retval;
+    pthread_create(&thread, &attr, big_stack, NULL);
+    pthread_join(thread, &retval);
+    printf("big stack returned %ld\n", (long) retval);
+    pthread_attr_destroy(&attr);
+
+    pthread_create(&thread, NULL, exits, (void *) 42);
+    pthread_join(thread, &retval);
+    printf("pthread_exit returned %ld\n", (long) retval);
+
+    pthread_create(&thread, NULL, exits, NULL);
+    printf("pthread_detach = %d\n", pthread_detach(thread));
+
+    printf("joining self: %d\n", pthread_join(pthread_self(), NULL) == EDEADLK);
+}
@@ -0,0 +1,59 @@
+#include <errno.h>
+#include <limits.h>
+#include <pthread.h>
+#include <stdio.h>
+
+static pthread_key_t key;
+static pthread_key_t resetting_key;
+static int destructed = 0;
+static int resets = 0;
+
+void destructor(void *value) {
+    __atomic_fetch_add(&destructed, (int) (long) value, __ATOMIC_SEQ_CST);
+}
+
+void resetting_destructor(void *value) {
+    // Setting a new value means it gets called again, but only so many times
+    __atomic_fetch_add(&resets, 1, __ATOMIC_SEQ_CST);
+    pthread_setspecific(resetting_key, value);
+}
+
+void *thread(void *arg) {
+    long before = (long) pthread_getspecific(key);
+    pthread_setspecific(key, arg);
+    pthread_setspecific(resetting_key, arg);
+    return (void *) (before * 1000 + (long) pthread_getspecific(key));
+}
+
+int main() {
+    printf("pthread_key_create: %d\n", pthread_key_create(&key, destructor));
+    pthread_key_create(&resetting_key, resetting_destructor);
+    pthread_setspecific(key, (void *) 1);
+
+    pthread_t threads[2];
+    pthread_create(&threads[0], NULL, thread, (void *) 10);
+    pthread_create(&threads[1], NULL, thread, (void *) 20);
+    for (int i = 0; i < 2; i++) {
+        void *seen;
+        pthread_join(threads[i], &seen);
+        printf("thread %d saw %ld before and %ld after setting its value\n", i, (long) seen / 1000, (long) seen % 1000);
+    }
+    printf("main thread still has %ld\n", (long) pthread_getspecific(key));
+    printf("destructors got %d\n", destructed);
+    printf("resetting destructor ran %d times\n", resets == 2 * PTHREAD_DESTRUCTOR_ITERATIONS);
+
+    printf("pthread_key_delete: %d\n", pthread_key_delete(key));
+    printf("deleting again: %d\n", pthread_key_delete(key) == EINVAL);
+
+    pthread_key_t reused;
+    pthread_key_create(&reused, NULL);
+    printf("reused key starts out empty: %d\n", pthread_getspecific(reused) == NULL);
+
+    int created = 0;
+    int status;
+    pthread_key_t extra;
+    while ((status = pthread_key_create(&extra, NULL)) == 0) {
+        created++;
+    }
+    printf("keys ran out: %d\n", status == EAGAIN && created > 0 && created < PTHREAD_KEYS_MAX);
+}
@@ -0,0 +1,96 @@
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+
+#define THREADS 4
+#define INCREMENTS 100000
+
+static pthread_mutex_t lock = PTHREAD_MUTEX_INITIALIZER;
+static int counter = 0;
+
+void *increment(void *arg) {
+    for (int i = 0; i < INCREMENTS; i++) {
+        pthread_mutex_lock(&lock);
+        // Not atomic, so only the mutex keeps increments from getting lost
+        int value = counter;
+        counter = value + 1;
+        pthread_mutex_unlock(&lock);
+    }
+    return NULL;
+}
+
+void *try_other_thread(void *arg) {
+    pthread_mutex_t *mutex = arg;
+    long status = pthread_mutex_trylock(mutex) == EBUSY;
+    if (pthread_mutex_unlock(mutex) == EPERM) {
+        status |= 2;
+    }
+    return (void *) status;
+}
+
+pthread_mutex_t make_mutex(int kind) {
+    pthread_mutexattr_t attr;
+    pthread_mutexattr_init(&attr);
+    pthread_mutexattr_settype(&attr, kind);
+    pthread_mutex_t mutex;
+    pthread_mutex_init(&mutex, &attr);
+    pthread_mutexattr_destroy(&attr);
+    return mutex;
+}
+
+int main() {
+    pthread_t threads[THREADS];
+    for (int i = 0; i < THREADS; i++) {
+        pthread_create(&threads[i], NULL, increment, NULL);
+    }
+    for (int i = 0; i < THREADS; i++) {
+        pthread_join(threads[i], NULL);
+    }
+    printf("counter: %d\n", counter);
+
+    pthread_mutexattr_t attr;
+    pthread_mutexattr_init(&attr);
+    int kind;
+    pthread_mutexattr_gettype(&attr, &kind);
+    printf("default type is normal: %d\n", kind == PTHREAD_MUTEX_NORMAL);
+    printf("settype(-1) fails: %d\n", pthread_mutexattr_settype(&attr, -1) == EINVAL);
+    pthread_mutexattr_destroy(&attr);
+
+    pthread_mutex_t normal;
+    pthread_mutex_init(&normal, NULL);
+    printf("normal trylock: %d\n", pthread_mutex_trylock(&normal));
+    printf("normal trylock again: %d\n", pthread_mutex_trylock(&normal) == EBUSY);
+    printf("normal destroy while locked: %d\n", pthread_mutex_destroy(&normal) == EBUSY);
+    printf("normal unlock: %d\n", pthread_mutex_unlock(&normal));
+    printf("normal destroy: %d\n", pthread_mutex_destroy(&normal));
+
+    pthread_mutex_t recursive = make_mutex(PTHREAD_MUTEX_RECURSIVE);
+    printf("recursive lock: %d\n", pthread_mutex_lock(&recursive));
+    printf("recursive lock again: %d\n", pthread_mutex_lock(&recursive));
+    printf("recursive trylock: %d\n", pthread_mutex_trylock(&recursive));
+
+    pthread_t thread;
+    void *status;
+    pthread_create(&thread, NULL, try_other_thread, &recursive);
+    pthread_join(thread, &status);
+    printf("recursive from another thread: busy %d, unlock fails %d\n", (int) (long) status & 1, (int) (long) status >> 1);
+
+    printf("recursive unlocks: %d %d %d\n",
+        pthread_mutex_unlock(&recursive),
+        pthread_mutex_unlock(&recursive),
+        pthread_mutex_unlock(&recursive));
+    printf("recursive unlock once too often: %d\n", pthread_mutex_unlock(&recursive) == EPERM);
+    pthread_mutex_destroy(&recursive);
+
+    pthread_mutex_t errorcheck = make_mutex(PTHREAD_MUTEX_ERRORCHECK);
+    printf("errorcheck unlock while unlocked: %d\n", pthread_mutex_unlock(&errorcheck) == EPERM);
+    printf("errorcheck lock: %d\n", pthread_mutex_lock(&errorcheck));
+    printf("errorcheck relock: %d\n", pthread_mutex_lock(&errorcheck) == EDEADLK);
+
+    pthread_create(&thread, NULL, try_other_thread, &errorcheck);
+    pthread_join(thread, &status);
+    printf("errorcheck from another thread: busy %d, unlock fails %d\n", (int) (long) status & 1, (int) (long) status >> 1);
+
+    printf("errorcheck unlock: %d\n", pthread_mutex_unlock(&errorcheck));
+    pthread_mutex_destroy(&errorcheck);
+}
@@ -0,0 +1,41 @@
+#include <pthread.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#define THREADS 8
+
+static pthread_once_t once = PTHREAD_ONCE_INIT;
+static int calls = 0;
+static int initialized = 0;
+
+void init(void) {
+    calls++;
+    // Give the other threads time to pile up behind this
+    usleep(100000);
+    initialized = 1;
+}
+
+void *run(void *arg) {
+    pthread_once(&once, init);
+    // Nobody gets past pthread_once before init is done
+    return (void *) (long) initialized;
+}
+
+int main() {
+    pthread_t threads[THREADS];
+    for (int i = 0; i < THREADS; i++) {
+        pthread_create(&threads[i], NULL, run, NULL);
+    }
+
+    int saw_initialized = 0;
+    for (int i = 0; i < THREADS; i++) {
+        void *seen;
+        pthread_join(threads[i], &seen);
+        saw_initialized += (int) (long) seen;
+    }
+    printf("init ran %d time(s)\n", calls);
+    printf("threads that saw it done: %d\n", saw_initialized);
+
+    printf("pthread_once again: %d\n", pthread_once(&once, init));
+    printf("init ran %d time(s)\n", calls);
+}
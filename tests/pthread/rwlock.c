@@ -0,0 +1,77 @@
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#define READERS 3
+
+static pthread_rwlock_t rwlock = PTHREAD_RWLOCK_INITIALIZER;
+static int value = 42;
+static int readers_in = 0;
+static int writer_started = 0;
+static int writer_done = 0;
+
+void *reader(void *arg) {
+    pthread_rwlock_rdlock(&rwlock);
+
+    // Hold on to the lock until every reader has it at the same time
+    __atomic_fetch_add(&readers_in, 1, __ATOMIC_SEQ_CST);
+    while (__atomic_load_n(&readers_in, __ATOMIC_SEQ_CST) < READERS) {
+        usleep(1000);
+    }
+    long first = value;
+
+    // The writer can't get in while the readers are still here
+    while (!__atomic_load_n(&writer_started, __ATOMIC_SEQ_CST)) {
+        usleep(1000);
+    }
+    usleep(100000);
+    long second = value;
+    long blocked = !__atomic_load_n(&writer_done, __ATOMIC_SEQ_CST);
+
+    pthread_rwlock_unlock(&rwlock);
+    return (void *) (first * 1000 + second * 10 + blocked);
+}
+
+void *writer(void *arg) {
+    __atomic_store_n(&writer_started, 1, __ATOMIC_SEQ_CST);
+    pthread_rwlock_wrlock(&rwlock);
+    value = 43;
+    __atomic_store_n(&writer_done, 1, __ATOMIC_SEQ_CST);
+    pthread_rwlock_unlock(&rwlock);
+    return NULL;
+}
+
+int main() {
+    pthread_t readers[READERS];
+    for (int i = 0; i < READERS; i++) {
+        pthread_create(&readers[i], NULL, reader, NULL);
+    }
+    while (__atomic_load_n(&readers_in, __ATOMIC_SEQ_CST) < READERS) {
+        usleep(1000);
+    }
+    printf("trywrlock with readers in: %d\n", pthread_rwlock_trywrlock(&rwlock) == EBUSY);
+
+    pthread_t writer_thread;
+    pthread_create(&writer_thread, NULL, writer, NULL);
+
+    for (int i = 0; i < READERS; i++) {
+        void *seen;
+        pthread_join(readers[i], &seen);
+        long result = (long) seen;
+        printf("reader %d saw %ld, then %ld, writer blocked: %ld\n", i, result / 1000, result / 10 % 100, result % 10);
+    }
+    pthread_join(writer_thread, NULL);
+    printf("after the writer: %d\n", value);
+
+    printf("trywrlock: %d\n", pthread_rwlock_trywrlock(&rwlock));
+    printf("tryrdlock with a writer in: %d\n", pthread_rwlock_tryrdlock(&rwlock) == EBUSY);
+    printf("unlock: %d\n", pthread_rwlock_unlock(&rwlock));
+
+    pthread_rwlock_t other;
+    pthread_rwlock_init(&other, NULL);
+    printf("tryrdlock: %d %d\n", pthread_rwlock_tryrdlock(&other), pthread_rwlock_tryrdlock(&other));
+    pthread_rwlock_unlock(&other);
+    pthread_rwlock_unlock(&other);
+    printf("destroy: %d\n", pthread_rwlock_destroy(&other));
+}
@@ -36,6 +36,30 @@ int main() {
         print(pwd);
     }
 
+    puts("--- Checking getpwnam for a missing user ---");
+    errno = 0;
+    pwd = getpwnam("relibc_no_such_user");
+    if (pwd != NULL || errno != 0) {
+        puts("Found a user that doesn't exist!");
+        return 1;
+    }
+    puts("Not found 👍");
+
+    puts("--- Checking getpwent ---");
+    int found_root = 0;
+    setpwent();
+    while ((pwd = getpwent()) != NULL) {
+        if (pwd->pw_uid == 0) {
+            found_root = 1;
+        }
+    }
+    endpwent();
+    if (!found_root) {
+        puts("getpwent never returned root");
+        return 1;
+    }
+    puts("Found root 👍");
+
     puts("--- Checking getpwuid_r ---");
     struct passwd pwd2;
     struct passwd* result;
@@ -70,11 +94,12 @@ int main() {
 
     puts("--- Checking getpwuid_r error handling ---");
     char buf2[1];
-    if (getpwuid_r(0, &pwd2, buf2, 1, &result) == 0) {
+    int err = getpwuid_r(0, &pwd2, buf2, 1, &result);
+    if (err == 0) {
         puts("This shouldn't have succeeded, but did!");
         return 1;
     }
-    if (errno != ERANGE) {
+    if (err != ERANGE || result != NULL) {
         perror("getpwuid_r");
         return 1;
     }
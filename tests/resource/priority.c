@@ -0,0 +1,62 @@
+#include <errno.h>
+#include <stdio.h>
+#include <sys/resource.h>
+#include <unistd.h>
+
+int main() {
+    // -1 is a valid priority, so errno is the only way to spot an error
+    errno = 0;
+    int prio = getpriority(PRIO_PROCESS, 0);
+    if (prio == -1 && errno != 0) {
+        perror("getpriority");
+        return 1;
+    }
+
+    errno = 0;
+    int new = nice(1);
+    if (new == -1 && errno != 0) {
+        perror("nice");
+        return 1;
+    }
+    printf("nice(1) added one: %d\n", new == prio + 1 || (prio == 19 && new == 19));
+
+    errno = 0;
+    int now = getpriority(PRIO_PROCESS, getpid());
+    if (now == -1 && errno != 0) {
+        perror("getpriority");
+        return 1;
+    }
+    printf("getpriority agrees: %d\n", now == new);
+
+    if (setpriority(PRIO_PROCESS, 0, 19) < 0) {
+        perror("setpriority");
+        return 1;
+    }
+    errno = 0;
+    printf("after setpriority: %d\n", getpriority(PRIO_PROCESS, 0));
+    printf("nice clamps at 19: %d\n", nice(5));
+
+    errno = 0;
+    if (getpriority(12345, 0) != -1 || errno != EINVAL) {
+        puts("getpriority with an invalid which didn't fail with EINVAL");
+        return 1;
+    }
+
+    if (geteuid() != 0) {
+        errno = 0;
+        if (nice(-1) != -1 || errno != EPERM) {
+            puts("lowering the nice value as a user didn't fail with EPERM");
+            return 1;
+        }
+    } else {
+        if (setpriority(PRIO_PROCESS, 0, -1) < 0) {
+            perror("setpriority");
+            return 1;
+        }
+        errno = 0;
+        if (getpriority(PRIO_PROCESS, 0) != -1 || errno != 0) {
+            puts("a nice value of -1 came back as an error");
+            return 1;
+        }
+    }
+}
@@ -0,0 +1,60 @@
+#include <errno.h>
+#include <stdio.h>
+#include <sys/resource.h>
+#include <unistd.h>
+
+int main() {
+    struct rlimit rlim;
+    if (getrlimit(RLIMIT_NOFILE, &rlim) < 0) {
+        perror("getrlimit");
+        return 1;
+    }
+    printf("NOFILE soft <= hard: %d\n", rlim.rlim_max == RLIM_INFINITY || rlim.rlim_cur <= rlim.rlim_max);
+
+    rlim_t hard = rlim.rlim_max;
+    rlim.rlim_cur = 64;
+    if (setrlimit(RLIMIT_NOFILE, &rlim) < 0) {
+        perror("setrlimit");
+        return 1;
+    }
+    if (getrlimit(RLIMIT_NOFILE, &rlim) < 0) {
+        perror("getrlimit");
+        return 1;
+    }
+    printf("NOFILE soft limit: %lu\n", (unsigned long) rlim.rlim_cur);
+    printf("NOFILE hard limit unchanged: %d\n", rlim.rlim_max == hard);
+
+    struct rlimit stack, data, core;
+    if (getrlimit(RLIMIT_STACK, &stack) < 0
+            || getrlimit(RLIMIT_DATA, &data) < 0
+            || getrlimit(RLIMIT_CORE, &core) < 0) {
+        perror("getrlimit");
+        return 1;
+    }
+    printf("STACK soft <= hard: %d\n", stack.rlim_cur <= stack.rlim_max);
+    printf("DATA soft <= hard: %d\n", data.rlim_cur <= data.rlim_max);
+    printf("CORE soft <= hard: %d\n", core.rlim_cur <= core.rlim_max);
+
+    errno = 0;
+    rlim.rlim_cur = rlim.rlim_max;
+    rlim.rlim_max = 1;
+    if (setrlimit(RLIMIT_NOFILE, &rlim) != -1 || errno != EINVAL) {
+        puts("a soft limit above the hard one didn't fail with EINVAL");
+        return 1;
+    }
+    errno = 0;
+    if (getrlimit(-1, &rlim) != -1 || errno != EINVAL) {
+        puts("getrlimit on an invalid resource didn't fail with EINVAL");
+        return 1;
+    }
+
+    if (geteuid() != 0 && hard != RLIM_INFINITY) {
+        rlim.rlim_cur = 64;
+        rlim.rlim_max = hard + 1;
+        errno = 0;
+        if (setrlimit(RLIMIT_NOFILE, &rlim) != -1 || errno != EPERM) {
+            puts("raising the hard limit didn't fail with EPERM");
+            return 1;
+        }
+    }
+}
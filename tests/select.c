@@ -1,3 +1,5 @@
+#include <assert.h>
+#include <errno.h>
 #include <fcntl.h>
 #include <stdio.h>
 #include <sys/select.h>
@@ -18,5 +20,14 @@ int main() {
 
     printf("Is set after? %d\n", FD_ISSET(fd, &read));
 
+    FD_CLR(fd, &read);
+    assert(!FD_ISSET(fd, &read));
+
+    assert(select(FD_SETSIZE + 1, &read, NULL, NULL, NULL) == -1);
+    assert(errno == EINVAL);
+
+    struct timeval timeout = { 0 };
+    assert(select(fd + 1, &read, NULL, NULL, &timeout) == 0);
+
     close(fd);
 }
@@ -1,5 +1,30 @@
 #include <stdio.h>
 #include <setjmp.h>
+#include <signal.h>
+
+static jmp_buf nested_buf;
+static sigjmp_buf sig_buf;
+
+void inner(int depth) {
+    if (depth == 0) {
+        longjmp(nested_buf, 42);
+    }
+    inner(depth - 1);
+    puts("not reached");
+}
+
+int blocked(int sig) {
+    sigset_t set;
+    sigprocmask(SIG_SETMASK, NULL, &set);
+    return sigismember(&set, sig);
+}
+
+void block(int sig) {
+    sigset_t set;
+    sigemptyset(&set);
+    sigaddset(&set, sig);
+    sigprocmask(SIG_BLOCK, &set, NULL);
+}
 
 int main() {
     jmp_buf buf;
@@ -9,4 +34,30 @@ int main() {
         puts("jumping...");
         longjmp(buf, 0);
     }
+
+    // Out of a few nested calls, with a value
+    volatile int calls = 0;
+    int value = setjmp(nested_buf);
+    calls++;
+    if (value == 0) {
+        inner(5);
+    }
+    printf("setjmp returned %d after %d calls\n", value, calls);
+
+    // sigsetjmp restores the mask it saved
+    value = sigsetjmp(sig_buf, 1);
+    if (value == 0) {
+        block(SIGUSR1);
+        printf("blocked before jump: %d\n", blocked(SIGUSR1));
+        siglongjmp(sig_buf, 7);
+    }
+    printf("sigsetjmp returned %d, blocked after jump: %d\n", value, blocked(SIGUSR1));
+
+    // ... and leaves it alone if it didn't save it
+    value = sigsetjmp(sig_buf, 0);
+    if (value == 0) {
+        block(SIGUSR2);
+        siglongjmp(sig_buf, 0);
+    }
+    printf("sigsetjmp returned %d, blocked after jump: %d\n", value, blocked(SIGUSR2));
 }
@@ -0,0 +1,59 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+
+static volatile int handled = 0;
+static volatile int info_signo = 0;
+
+void handler(int sig) {
+    handled = sig;
+}
+
+void info_handler(int sig, siginfo_t* info, void* context) {
+    handled = sig;
+    info_signo = info->si_signo;
+}
+
+int main() {
+    struct sigaction sa;
+    struct sigaction old;
+    memset(&sa, 0, sizeof(sa));
+    sigemptyset(&sa.sa_mask);
+
+    sa.sa_handler = handler;
+    sa.sa_flags = SA_RESTART;
+    printf("sigaction: %d\n", sigaction(SIGUSR1, &sa, NULL));
+    raise(SIGUSR1);
+    printf("handler ran: %d\n", handled == SIGUSR1);
+
+    handled = 0;
+    sa.sa_sigaction = info_handler;
+    sa.sa_flags = SA_SIGINFO;
+    printf("sigaction: %d\n", sigaction(SIGUSR2, &sa, NULL));
+    raise(SIGUSR2);
+    printf("siginfo handler ran: %d, si_signo: %d\n", handled == SIGUSR2, info_signo == SIGUSR2);
+
+    // the previous disposition is reported back
+    sa.sa_handler = SIG_IGN;
+    sa.sa_flags = 0;
+    printf("sigaction: %d\n", sigaction(SIGUSR1, &sa, &old));
+    printf("old handler: %d\n", old.sa_handler == handler);
+    handled = 0;
+    raise(SIGUSR1);
+    printf("ignored: %d\n", handled == 0);
+
+    printf("signal: %d\n", signal(SIGUSR1, SIG_DFL) == SIG_IGN);
+    printf("signal old: %d\n", signal(SIGUSR1, handler) == SIG_DFL);
+    handled = 0;
+    raise(SIGUSR1);
+    printf("signal handler ran: %d\n", handled == SIGUSR1);
+
+    errno = 0;
+    printf("SIGKILL: %d, EINVAL: %d\n", sigaction(SIGKILL, &sa, NULL), errno == EINVAL);
+    errno = 0;
+    printf("SIGSTOP: %d, EINVAL: %d\n", signal(SIGSTOP, handler) == SIG_ERR, errno == EINVAL);
+    errno = 0;
+    printf("query SIGKILL: %d\n", sigaction(SIGKILL, NULL, &old));
+    return 0;
+}
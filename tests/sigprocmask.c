@@ -0,0 +1,56 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+
+static volatile int handled = 0;
+
+void handler(int sig) {
+    handled = sig;
+}
+
+int main() {
+    sigset_t set;
+    sigset_t old;
+
+    sigemptyset(&set);
+    printf("empty: %d %d\n", sigismember(&set, SIGUSR1), sigismember(&set, SIGINT));
+    printf("add: %d\n", sigaddset(&set, SIGUSR1));
+    printf("member: %d %d\n", sigismember(&set, SIGUSR1), sigismember(&set, SIGINT));
+    printf("del: %d\n", sigdelset(&set, SIGUSR1));
+    printf("member: %d\n", sigismember(&set, SIGUSR1));
+    sigfillset(&set);
+    printf("full: %d %d\n", sigismember(&set, SIGUSR1), sigismember(&set, SIGTERM));
+
+    errno = 0;
+    printf("add 0: %d, EINVAL: %d\n", sigaddset(&set, 0), errno == EINVAL);
+    errno = 0;
+    printf("del 65: %d, EINVAL: %d\n", sigdelset(&set, 65), errno == EINVAL);
+    errno = 0;
+    printf("member -1: %d, EINVAL: %d\n", sigismember(&set, -1), errno == EINVAL);
+
+    signal(SIGUSR1, handler);
+
+    // blocking holds the signal back until it's unblocked again
+    sigemptyset(&set);
+    sigaddset(&set, SIGUSR1);
+    printf("block: %d\n", sigprocmask(SIG_BLOCK, &set, &old));
+    printf("old mask: %d\n", sigismember(&old, SIGUSR1));
+    raise(SIGUSR1);
+    printf("held back: %d\n", handled == 0);
+
+    printf("query: %d\n", sigprocmask(SIG_SETMASK, NULL, &old));
+    printf("blocked: %d\n", sigismember(&old, SIGUSR1));
+
+    printf("unblock: %d\n", sigprocmask(SIG_UNBLOCK, &set, &old));
+    printf("old mask: %d\n", sigismember(&old, SIGUSR1));
+    printf("delivered: %d\n", handled == SIGUSR1);
+
+    sigemptyset(&old);
+    printf("setmask: %d\n", sigprocmask(SIG_SETMASK, &set, NULL));
+    printf("query: %d\n", sigprocmask(SIG_BLOCK, NULL, &old));
+    printf("blocked: %d\n", sigismember(&old, SIGUSR1));
+
+    errno = 0;
+    printf("bad how: %d, EINVAL: %d\n", sigprocmask(1234, &set, NULL), errno == EINVAL);
+    return 0;
+}
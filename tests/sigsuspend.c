@@ -0,0 +1,60 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <unistd.h>
+
+static volatile sig_atomic_t caught = 0;
+
+void handler(int sig) {
+    caught = sig;
+}
+
+int blocked(int sig) {
+    sigset_t set;
+    sigprocmask(SIG_SETMASK, NULL, &set);
+    return sigismember(&set, sig);
+}
+
+int main() {
+    struct sigaction sa;
+    sa.sa_handler = handler;
+    sa.sa_flags = 0;
+    sigemptyset(&sa.sa_mask);
+    sigaction(SIGALRM, &sa, NULL);
+    sigaction(SIGUSR1, &sa, NULL);
+
+    // SIGALRM wakes up pause()
+    alarm(1);
+    errno = 0;
+    int status = pause();
+    printf("pause() = %d, EINTR: %d, caught SIGALRM: %d\n", status, errno == EINTR, caught == SIGALRM);
+
+    // A pending signal is let through by sigsuspend(), which puts the old mask back after
+    sigset_t set;
+    sigemptyset(&set);
+    sigaddset(&set, SIGUSR1);
+    sigprocmask(SIG_BLOCK, &set, NULL);
+    caught = 0;
+    kill(getpid(), SIGUSR1);
+    printf("caught while blocked: %d\n", caught != 0);
+
+    sigset_t unblocked;
+    sigemptyset(&unblocked);
+    errno = 0;
+    status = sigsuspend(&unblocked);
+    printf("sigsuspend() = %d, EINTR: %d, caught SIGUSR1: %d\n", status, errno == EINTR, caught == SIGUSR1);
+    printf("SIGUSR1 blocked again: %d\n", blocked(SIGUSR1));
+
+    // The mask given to sigsuspend() holds back signals that aren't in it
+    caught = 0;
+    sigset_t only_alarm;
+    sigfillset(&only_alarm);
+    sigdelset(&only_alarm, SIGALRM);
+    kill(getpid(), SIGUSR1);
+    alarm(1);
+    sigsuspend(&only_alarm);
+    printf("woken by SIGALRM: %d\n", caught == SIGALRM);
+
+    sigprocmask(SIG_UNBLOCK, &set, NULL);
+    printf("SIGUSR1 delivered after unblocking: %d\n", caught == SIGUSR1);
+}
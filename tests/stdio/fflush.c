@@ -0,0 +1,38 @@
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+int main() {
+    FILE *w = fopen("stdio/fflush.out", "w");
+    assert(w != NULL);
+    assert(fwrite("Hello", 1, 5, w) == 5);
+
+    // Nothing reaches the file until it's flushed
+    FILE *r = fopen("stdio/fflush.out", "r");
+    assert(r != NULL);
+    char buf[16] = { 0 };
+    assert(fread(buf, 1, sizeof(buf), r) == 0);
+    assert(feof(r));
+    assert(!ferror(r));
+    clearerr(r);
+
+    assert(fflush(NULL) == 0);
+    assert(fread(buf, 1, sizeof(buf), r) == 5);
+    assert(strcmp(buf, "Hello") == 0);
+    fclose(r);
+
+    // Appending continues where the file ended
+    fclose(w);
+    w = fopen("stdio/fflush.out", "a");
+    assert(w != NULL);
+    fputs(" World", w);
+    fclose(w);
+
+    r = fopen("stdio/fflush.out", "r");
+    memset(buf, 0, sizeof(buf));
+    assert(fread(buf, 1, sizeof(buf), r) == 11);
+    assert(strcmp(buf, "Hello World") == 0);
+    fclose(r);
+
+    remove("stdio/fflush.out");
+}
@@ -0,0 +1,36 @@
+#include <stdio.h>
+
+int main(int argc, char ** argv) {
+    FILE *f = fopen("stdio/stdio.in", "r");
+    if (f == NULL) {
+        perror("fopen");
+        return 1;
+    }
+
+    // Pushing back at the start of the file is allowed
+    if (ungetc('>', f) != '>') {
+        puts("ungetc failed");
+        return 1;
+    }
+
+    char line[8];
+    while (fgets(line, sizeof(line), f) != NULL) {
+        fputs("[", stdout);
+        fputs(line, stdout);
+        puts("]");
+    }
+    printf("feof: %d, ferror: %d\n", feof(f) != 0, ferror(f) != 0);
+
+    // ungetc clears EOF
+    ungetc('x', f);
+    printf("feof: %d\n", feof(f) != 0);
+    printf("fgetc: %c\n", fgetc(f));
+    printf("fgetc at EOF: %d\n", fgetc(f));
+
+    fputc('o', stdout);
+    putc('k', stdout);
+    putchar('\n');
+
+    fclose(f);
+    return 0;
+}
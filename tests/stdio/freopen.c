@@ -5,5 +5,21 @@ int main(int argc, char ** argv) {
 	char in[6];
 	fgets(in, 6, stdin);
 	printf("%s\n", in); // should print Hello
+
+	// The standard streams keep their identity when redirected
+	FILE *err = stderr;
+	if (freopen("stdio/freopen.out", "w", stderr) != err) {
+		puts("freopen returned a different stream");
+		return 1;
+	}
+	fputs("Written to stderr\n", stderr);
+	fclose(stderr);
+
+	FILE *f = fopen("stdio/freopen.out", "r");
+	char line[32];
+	fgets(line, sizeof(line), f);
+	fputs(line, stdout); // should print Written to stderr
+	fclose(f);
+	remove("stdio/freopen.out");
 	return 0;
 }
@@ -0,0 +1,32 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+int main(int argc, char ** argv) {
+    FILE *f = fopen("stdio/stdio.in", "r");
+    if (f == NULL) {
+        perror("fopen");
+        return 1;
+    }
+
+    char *line = NULL;
+    size_t n = 0;
+    ssize_t len;
+    while ((len = getline(&line, &n, f)) != -1) {
+        printf("%zd: %s", len, line);
+    }
+
+    rewind(f);
+    len = getdelim(&line, &n, ' ', f);
+    printf("getdelim: %zd \"%s\"\n", len, line);
+
+    errno = 0;
+    if (getline(NULL, &n, f) != -1 || errno != EINVAL) {
+        puts("getline accepted a NULL lineptr");
+        return 1;
+    }
+
+    free(line);
+    fclose(f);
+    return 0;
+}
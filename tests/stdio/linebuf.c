@@ -0,0 +1,16 @@
+#include <stdio.h>
+#include <unistd.h>
+
+int main(int argc, char ** argv) {
+	setvbuf(stdout, NULL, _IOLBF, 0);
+
+	// Only the part up to the last newline goes out right away
+	fputs("a\nb", stdout);
+	write(STDOUT_FILENO, "|", 1);
+	fputs("c\n", stdout);
+
+	fputs("d\ne\nf", stdout);
+	write(STDOUT_FILENO, "|", 1);
+	fputs("\n", stdout);
+	return 0;
+}
@@ -0,0 +1,16 @@
+#include <errno.h>
+#include <stdio.h>
+
+int main(int argc, char ** argv) {
+    errno = ENOENT;
+    perror("open");
+    perror("");
+    perror(NULL);
+
+    // perror leaves errno alone
+    printf("errno: %d\n", errno);
+
+    errno = EINVAL;
+    perror("second");
+    return 0;
+}
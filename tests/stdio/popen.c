@@ -0,0 +1,35 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/wait.h>
+
+int main(int argc, char** argv) {
+    char line[256];
+
+    FILE* fp = popen("echo hi", "r");
+    if (fp == NULL) {
+        perror("popen");
+        return 1;
+    }
+    while (fgets(line, sizeof(line), fp) != NULL) {
+        printf("read: %s", line);
+    }
+    int status = pclose(fp);
+    printf("exited: %d, status: %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+    fflush(stdout);
+    fp = popen("tr a-z A-Z; exit 2", "w");
+    if (fp == NULL) {
+        perror("popen");
+        return 1;
+    }
+    fputs("written to the child\n", fp);
+    status = pclose(fp);
+    printf("exited: %d, status: %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+    errno = 0;
+    printf("mode rw: %d, EINVAL: %d\n", popen("true", "rw") == NULL, errno == EINVAL);
+    errno = 0;
+    printf("mode x: %d, EINVAL: %d\n", popen("true", "x") == NULL, errno == EINVAL);
+    return 0;
+}
@@ -13,5 +13,7 @@ int main(int argc, char ** argv) {
         "end"
     );
     printf("len of previous write: %d\n", len);
+    printf("long precision: %.80d\n", 1);
+    printf("long precision octal: %#.70o\n", 8);
     return 0;
 }
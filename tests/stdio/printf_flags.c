@@ -0,0 +1,32 @@
+#include <stdio.h>
+#include <string.h>
+
+int main(int argc, char ** argv) {
+    printf("[%5d] [%-5d] [%05d] [%+d] [% d]\n", 42, 42, 42, 42, 42);
+    printf("[%.3d] [%8.3d] [%-8.3d] [%.0d]\n", 7, -7, 7, 0);
+    printf("[%#x] [%#X] [%#o] [%#08x] [%o]\n", 255, 255, 8, 255, 0);
+    printf("[%*d] [%-*d] [%.*s] [%*d]\n", 4, 1, 4, 1, 3, "abcdef", -4, 1);
+    printf("[%hhd] [%hu] [%ld] [%llu] [%zu]\n", 257, 65537, -1L, 18446744073709551615ULL, sizeof(int));
+    printf("[%10s] [%-10s] [%.2s] [%c] [%3c]\n", "right", "left", "abc", 'x', 'y');
+    printf("[%f] [%.2f] [%8.3f] [%-8.1f] [%+.1f] [%#.0f]\n", 3.14159, 2.5, -1.0, 1.26, 2.0, 3.0);
+    printf("[%e] [%.2E] [%g] [%g] [%G] [%#g]\n", 12345.678, 0.00012, 0.0001, 1e-5, 123456789.0, 1.5);
+    printf("[%g] [%.3g] [%010.2f] [%5.1e]\n", 100.0, 3.14159, -3.14159, 0.0);
+    printf("[%%]\n");
+
+    int n = 0;
+    printf("12345%n\n", &n);
+    printf("%%n stored %d\n", n);
+
+    char buffer[8];
+    int ret = snprintf(buffer, sizeof(buffer), "%s-%d", "overflowing", 12345);
+    printf("snprintf: \"%s\" %d\n", buffer, ret);
+
+    memset(buffer, 'x', sizeof(buffer));
+    ret = snprintf(buffer, 1, "abc");
+    printf("size 1: %d %d\n", buffer[0], ret);
+
+    ret = snprintf(NULL, 0, "%d", 123456);
+    printf("size 0: %d\n", ret);
+
+    return 0;
+}
@@ -37,4 +37,10 @@ int main(int argc, char ** argv) {
     test("%c%3c", "hello", &p, &p.c, &p.string);
     test("test: %2i%n", "test: 0xFF", &p, &p.ia, &p.ib);
     test("hello world%%", "hello world%", &p);
+    test("%d %u", "-12 -1", &p, &p.ia, &p.ib);
+    test(" %*d%d\n%x", "  1 2\n0x1f", &p, &p.ia, &p.ib);
+    test("%e %lg", "1.5e3 -25E-1", &p, &p.fa, &p.da);
+    test("%[a-z]%n", "abc123", &p, &p.string, &p.ic);
+    test("%d", "abc", &p, &p.ia);
+    test("%d", "", &p, &p.ia);
 }
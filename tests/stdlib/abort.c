@@ -0,0 +1,31 @@
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+static void handler(int sig) {
+    // abort() has to kill us even with a handler installed
+}
+
+int main(int argc, char** argv) {
+    fflush(stdout);
+    pid_t pid = fork();
+    if (pid == 0) {
+        signal(SIGABRT, handler);
+        sigset_t set;
+        sigemptyset(&set);
+        sigaddset(&set, SIGABRT);
+        sigprocmask(SIG_BLOCK, &set, NULL);
+
+        // buffered output is flushed before the process dies
+        printf("child: aborting");
+        abort();
+    }
+
+    int status = 0;
+    waitpid(pid, &status, 0);
+    printf("\nsignaled: %d\n", WIFSIGNALED(status));
+    printf("SIGABRT: %d\n", WTERMSIG(status) == SIGABRT);
+    return 0;
+}
@@ -0,0 +1,39 @@
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main() {
+    // Every face of the die comes up, and nothing else does
+    int counts[6] = { 0 };
+    for (int i = 0; i < 60000; i++) {
+        uint32_t r = arc4random_uniform(6);
+        if (r > 5) {
+            printf("arc4random_uniform(6) returned %u\n", r);
+            return 1;
+        }
+        counts[r]++;
+    }
+    int all = 1;
+    for (int i = 0; i < 6; i++) {
+        all = all && counts[i] > 0;
+    }
+    printf("arc4random_uniform(6) stays in 0..=5 and hits them all: %d\n", all);
+    printf("arc4random_uniform(1): %u\n", arc4random_uniform(1));
+
+    // Mark the buffer so any byte left untouched stands out. The chance of a byte getting the
+    // same value by chance is 1 in 256 per byte, so check that most bytes changed in each half.
+    unsigned char buf[4096];
+    memset(buf, 0xAA, sizeof(buf));
+    arc4random_buf(buf, sizeof(buf));
+    int changed_start = 0;
+    int changed_end = 0;
+    for (size_t i = 0; i < sizeof(buf) / 2; i++) {
+        changed_start += buf[i] != 0xAA;
+        changed_end += buf[sizeof(buf) / 2 + i] != 0xAA;
+    }
+    printf("arc4random_buf filled the whole buffer: %d\n",
+        changed_start > 1900 && changed_end > 1900);
+
+    printf("arc4random differs between calls: %d\n", arc4random() != arc4random() || arc4random() != arc4random());
+}
@@ -0,0 +1,71 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+static int count = 0;
+
+void counter(void) {
+    count++;
+}
+
+void report(void) {
+    printf("counted: %d\n", count);
+}
+
+void first(void) {
+    puts("first");
+}
+
+void second(void) {
+    puts("second");
+}
+
+void late(void) {
+    puts("registered while exiting");
+}
+
+void registers_late(void) {
+    puts("registering another handler");
+    atexit(late);
+}
+
+void quick(void) {
+    puts("quick_exit handler");
+    fflush(stdout);
+}
+
+int main(int argc, char** argv) {
+    fflush(stdout);
+
+    // quick_exit only runs its own handlers
+    pid_t pid = fork();
+    if (pid == 0) {
+        atexit(first);
+        at_quick_exit(quick);
+        quick_exit(0);
+    }
+    waitpid(pid, NULL, 0);
+
+    // _Exit runs no handlers and flushes nothing
+    pid = fork();
+    if (pid == 0) {
+        atexit(first);
+        printf("not flushed");
+        _Exit(0);
+    }
+    waitpid(pid, NULL, 0);
+
+    atexit(report);
+    // more handlers than the 32 the standard guarantees
+    for (int i = 0; i < 40; i++) {
+        if (atexit(counter) != 0) {
+            puts("atexit failed");
+        }
+    }
+    atexit(first);
+    atexit(second);
+    atexit(registers_late);
+    puts("exiting");
+    exit(0);
+}
@@ -8,5 +8,8 @@ int main(int argc, char* argv[]) {
     printf("%ld\n", atol("         -42"));
     printf("%ld\n", atol(" +555"));
     printf("%ld\n", atol("   1234567890    "));
+    printf("%lld\n", atoll("         -42"));
+    printf("%lld\n", atoll(" +555"));
+    printf("%lld\n", atoll("   -9000000000000    "));
     return 0;
 }
@@ -26,7 +26,6 @@ int main(int argc, char* argv[]) {
   BSEARCH_TEST_INT(x, empty, 0, NULL);
 
   int singleton[] = {42};
-  printf("%p\n%p\n", singleton, &singleton[1]);
   BSEARCH_TEST_INT(x, singleton, 1, NULL);
   BSEARCH_TEST_INT(singleton[0], singleton, 1, &singleton[0]);
   BSEARCH_TEST_INT(y, singleton, 1, NULL);
@@ -50,6 +49,19 @@ int main(int argc, char* argv[]) {
   BSEARCH_TEST_INT(big[3], big, 7, &big[3]);
   BSEARCH_TEST_INT(x, big, 7, NULL);
 
+  int sorted[1000];
+  for (int i = 0; i < 1000; i++) {
+    sorted[i] = i * 2;
+  }
+  for (int i = 0; i < 1000; i++) {
+    int present = i * 2;
+    int absent = i * 2 + 1;
+    BSEARCH_TEST_INT(present, sorted, 1000, &sorted[i]);
+    BSEARCH_TEST_INT(absent, sorted, 1000, NULL);
+  }
+  int below = -1;
+  BSEARCH_TEST_INT(below, sorted, 1000, NULL);
+
   printf("PASS bsearch\n");
   return 0;
 }
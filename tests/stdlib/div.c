@@ -0,0 +1,23 @@
+#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+int main() {
+    printf("abs: %d %d %d\n", abs(-5), abs(5), abs(0));
+    printf("labs: %ld %ld\n", labs(-5000000000L), labs(5000000000L));
+    printf("llabs: %lld %lld\n", llabs(-5000000000LL), llabs(5000000000LL));
+
+    // The quotient is rounded toward zero, and the remainder takes the sign of the numerator
+    div_t d = div(-7, 2);
+    printf("div(-7, 2): quot %d, rem %d\n", d.quot, d.rem);
+    d = div(7, -2);
+    printf("div(7, -2): quot %d, rem %d\n", d.quot, d.rem);
+    d = div(7, 2);
+    printf("div(7, 2): quot %d, rem %d\n", d.quot, d.rem);
+
+    ldiv_t ld = ldiv(-7000000000L, 2000000000L);
+    printf("ldiv(-7000000000, 2000000000): quot %ld, rem %ld\n", ld.quot, ld.rem);
+
+    lldiv_t lld = lldiv(-7000000000LL, 3);
+    printf("lldiv(-7000000000, 3): quot %lld, rem %lld\n", lld.quot, lld.rem);
+}
@@ -1,3 +1,4 @@
+#include <errno.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
@@ -15,12 +16,11 @@ int main() {
     strcpy(owned, "TEST=Updates accordingly.");
     puts(getenv("TEST"));
 
-    // Allocation is reused
-    setenv("TEST", "in place", 1);
+    // The string given to putenv is replaced, never written to
+    setenv("TEST", "short", 1);
     puts(getenv("TEST"));
     puts(owned);
 
-    // Allocation is not reused
     setenv("TEST", "Value overwritten and not in place because it's really long", 1);
     puts(getenv("TEST"));
     puts(owned);
@@ -29,6 +29,19 @@ int main() {
     setenv("TEST", "Value not overwritten", 0);
     puts(getenv("TEST"));
 
+    // But a missing variable is still created
+    setenv("NEW_TEST", "created", 0);
+    puts(getenv("NEW_TEST"));
+
+    // Invalid names are rejected
+    errno = 0;
+    printf("setenv(\"A=B\"): %d, EINVAL: %d\n", setenv("A=B", "value", 1), errno == EINVAL);
+    errno = 0;
+    printf("setenv(\"\"): %d, EINVAL: %d\n", setenv("", "value", 1), errno == EINVAL);
+    errno = 0;
+    printf("unsetenv(\"A=B\"): %d, EINVAL: %d\n", unsetenv("A=B"), errno == EINVAL);
+    printf("getenv(\"TEST=\") is NULL: %d\n", getenv("TEST=") == NULL);
+
     unsetenv("TEST");
     char* env = getenv("TEST");
     if (env) {
@@ -0,0 +1,42 @@
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main(int argc, char ** argv) {
+    size_t alignments[] = { 64, 4096 };
+    for (int i = 0; i < 2; i++) {
+        size_t align = alignments[i];
+
+        void *ptr = NULL;
+        int ret = posix_memalign(&ptr, align, 100);
+        printf("posix_memalign(%zu): %d, aligned: %d\n", align, ret, ((uintptr_t)ptr % align) == 0);
+        memset(ptr, 0xAA, 100);
+        ptr = realloc(ptr, 8192);
+        printf("realloc kept contents: %d\n", ((unsigned char *)ptr)[99] == 0xAA);
+        free(ptr);
+
+        ptr = aligned_alloc(align, align * 2);
+        printf("aligned_alloc(%zu): aligned: %d\n", align, ((uintptr_t)ptr % align) == 0);
+        memset(ptr, 0x55, align * 2);
+        free(ptr);
+    }
+
+    // Errors are returned without touching errno or the pointer
+    void *ptr = NULL;
+    errno = 0;
+    int ret = posix_memalign(&ptr, 3, 16);
+    printf("posix_memalign(3): EINVAL: %d, errno: %d, ptr: %p\n", ret == EINVAL, errno, ptr);
+    ret = posix_memalign(&ptr, sizeof(void *) / 2, 16);
+    printf("posix_memalign(sizeof(void *) / 2): EINVAL: %d\n", ret == EINVAL);
+
+    errno = 0;
+    ptr = aligned_alloc(64, 100);
+    printf("aligned_alloc(64, 100): %p, EINVAL: %d\n", ptr, errno == EINVAL);
+    errno = 0;
+    ptr = aligned_alloc(48, 96);
+    printf("aligned_alloc(48, 96): %p, EINVAL: %d\n", ptr, errno == EINVAL);
+
+    return 0;
+}
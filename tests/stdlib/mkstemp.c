@@ -0,0 +1,61 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+int main() {
+    char first[] = "mkstempXXXXXX";
+    char second[] = "mkstempXXXXXX";
+    int fd1 = mkstemp(first);
+    int fd2 = mkstemp(second);
+    if (fd1 < 0 || fd2 < 0) {
+        perror("mkstemp");
+        return 1;
+    }
+    printf("templates filled in: %d\n", strstr(first, "XXXXXX") == NULL && strstr(second, "XXXXXX") == NULL);
+    printf("distinct files: %d\n", strcmp(first, second) != 0 && fd1 != fd2);
+    struct stat st;
+    printf("private to the owner: %d\n", stat(first, &st) == 0 && (st.st_mode & 0777) == 0600);
+    close(fd1);
+    close(fd2);
+    unlink(first);
+    unlink(second);
+
+    char bad[] = "mkstempXXXXX";
+    errno = 0;
+    int ret = mkstemp(bad);
+    printf("too few Xs: %d, EINVAL: %d\n", ret, errno == EINVAL);
+
+    char dir[] = "mkdtempXXXXXX";
+    if (mkdtemp(dir) == NULL) {
+        perror("mkdtemp");
+        return 1;
+    }
+    printf("mkdtemp made a private directory: %d\n",
+        stat(dir, &st) == 0 && S_ISDIR(st.st_mode) && (st.st_mode & 0777) == 0700);
+    rmdir(dir);
+
+    FILE* tmp = tmpfile();
+    if (tmp == NULL) {
+        perror("tmpfile");
+        return 1;
+    }
+    char buf[16] = { 0 };
+    fputs("temporary", tmp);
+    rewind(tmp);
+    fgets(buf, sizeof(buf), tmp);
+    printf("tmpfile read back: %s\n", buf);
+    fclose(tmp);
+
+    char name[L_tmpnam];
+    printf("tmpnam is in P_tmpdir: %d\n",
+        tmpnam(name) == name && strncmp(name, P_tmpdir "/", strlen(P_tmpdir) + 1) == 0);
+
+    setenv("TMPDIR", ".", 1);
+    char* temp = tempnam(NULL, "relibc");
+    printf("tempnam honors TMPDIR and cuts the prefix: %d\n",
+        temp != NULL && strncmp(temp, "./relib", 7) == 0 && strlen(temp) == 13);
+    free(temp);
+}
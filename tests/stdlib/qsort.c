@@ -0,0 +1,63 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+struct entry {
+    int key;
+    char name[12];
+};
+
+int entry_cmp(const void *a, const void *b) {
+    return ((const struct entry *) a)->key - ((const struct entry *) b)->key;
+}
+
+int int_cmp_r(const void *a, const void *b, void *arg) {
+    int direction = *(int *) arg;
+    return direction * (*(const int *) a - *(const int *) b);
+}
+
+int main(int argc, char ** argv) {
+    struct entry entries[] = {
+        { 5, "five" },
+        { 2, "two" },
+        { 9, "nine" },
+        { 1, "one" },
+        { 7, "seven" },
+        { 2, "two again" },
+        { 0, "zero" },
+        { 8, "eight" },
+        { 3, "three" },
+        { 6, "six" },
+        { 4, "four" },
+    };
+    size_t len = sizeof(entries) / sizeof(entries[0]);
+    qsort(entries, len, sizeof(struct entry), entry_cmp);
+    for (size_t i = 0; i < len; i++) {
+        printf("%d ", entries[i].key);
+    }
+    printf("\n");
+
+    // Equal keys may come out in either order, only check the names of unique keys
+    printf("%s %s %s\n", entries[0].name, entries[5].name, entries[10].name);
+
+    // Zero-length and single-element arrays are left untouched
+    qsort(NULL, 0, sizeof(struct entry), entry_cmp);
+    struct entry single = { 42, "single" };
+    qsort(&single, 1, sizeof(struct entry), entry_cmp);
+    printf("%d %s\n", single.key, single.name);
+
+    int numbers[1000];
+    for (int i = 0; i < 1000; i++) {
+        numbers[i] = (i * 7919) % 1000;
+    }
+    int direction = -1;
+    qsort_r(numbers, 1000, sizeof(int), int_cmp_r, &direction);
+    int sorted = 1;
+    for (int i = 0; i < 1000; i++) {
+        if (numbers[i] != 999 - i) {
+            sorted = 0;
+        }
+    }
+    printf("qsort_r descending: %d\n", sorted);
+
+    return 0;
+}
@@ -5,4 +5,32 @@ int main(int argc, char** argv) {
     printf("%d\n", rand());
     srand(259);
     printf("%d\n", rand());
+
+    // The same seed always gives the same sequence
+    int first[5];
+    srand(42);
+    for (int i = 0; i < 5; i++) {
+        first[i] = rand();
+    }
+    srand(42);
+    int same = 1;
+    for (int i = 0; i < 5; i++) {
+        same = same && rand() == first[i];
+    }
+    printf("same sequence: %d\n", same);
+
+    srandom(1);
+    printf("%ld\n", random());
+
+    unsigned int seed = 5;
+    int r = rand_r(&seed);
+    printf("%d %d\n", r, rand_r(&seed));
+
+    static long state[32];
+    char* old = initstate(7, (char*) state, sizeof(state));
+    long a = random();
+    long b = random();
+    printf("%ld %ld\n", a, b);
+    setstate(old);
+    printf("%ld\n", random());
 }
@@ -0,0 +1,50 @@
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+int main(int argc, char ** argv) {
+    // Contents survive growing and shrinking
+    unsigned char *ptr = realloc(NULL, 16);
+    for (int i = 0; i < 16; i++) {
+        ptr[i] = (unsigned char)i;
+    }
+    ptr = realloc(ptr, 4096);
+    for (int i = 16; i < 4096; i++) {
+        ptr[i] = (unsigned char)i;
+    }
+    ptr = realloc(ptr, 32);
+    int intact = 1;
+    for (int i = 0; i < 32; i++) {
+        intact &= ptr[i] == (unsigned char)i;
+    }
+    printf("realloc kept contents: %d\n", intact);
+    printf("realloc to 0: %p\n", realloc(ptr, 0));
+
+    // Every allocation is aligned for any type
+    int aligned = 1;
+    for (size_t size = 1; size < 100; size += 7) {
+        void *p = malloc(size);
+        aligned &= ((uintptr_t)p % 16) == 0;
+        free(p);
+    }
+    printf("malloc aligned: %d\n", aligned);
+
+    errno = 0;
+    void *overflow = calloc(SIZE_MAX / 2, 3);
+    printf("calloc overflow: %p, ENOMEM: %d\n", overflow, errno == ENOMEM);
+    errno = 0;
+    overflow = reallocarray(NULL, SIZE_MAX, 2);
+    printf("reallocarray overflow: %p, ENOMEM: %d\n", overflow, errno == ENOMEM);
+
+    unsigned char *zeroed = calloc(64, 4);
+    int zero = 1;
+    for (int i = 0; i < 256; i++) {
+        zero &= zeroed[i] == 0;
+    }
+    printf("calloc zeroed: %d\n", zero);
+    free(zeroed);
+
+    free(NULL);
+    return 0;
+}
@@ -0,0 +1,31 @@
+#include <errno.h>
+#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+int main(int argc, char** argv) {
+    char cwd[PATH_MAX];
+    char expected[PATH_MAX];
+    char resolved[PATH_MAX];
+    getcwd(cwd, sizeof(cwd));
+
+    snprintf(expected, sizeof(expected), "%s/stdlib/realpath.c", cwd);
+    char* ret = realpath("stdlib/../stdlib/./realpath.c", resolved);
+    printf("relative: %d\n", ret == resolved && strcmp(resolved, expected) == 0);
+
+    char* allocated = realpath(".//stdlib//realpath.c", NULL);
+    printf("allocated: %d\n", allocated != NULL && strcmp(allocated, expected) == 0);
+    free(allocated);
+
+    printf("cwd: %d\n", strcmp(realpath(".", resolved), cwd) == 0);
+    printf("root: %s\n", realpath("/../..", resolved));
+
+    errno = 0;
+    printf("missing: %d, ENOENT: %d\n", realpath("stdlib/missing/../realpath.c", resolved) == NULL,
+        errno == ENOENT);
+    errno = 0;
+    printf("empty: %d, ENOENT: %d\n", realpath("", resolved) == NULL, errno == ENOENT);
+    return 0;
+}
@@ -1,3 +1,6 @@
+#include <errno.h>
+#include <float.h>
+#include <math.h>
 #include <stdlib.h>
 #include <stdio.h>
 
@@ -14,4 +17,48 @@ int main() {
         d = strtod(inputs[i], &endptr);
         printf("d: %f Endptr: \"%s\"\n", d, endptr);
     }
+
+    // Results are compared against the compiler's own correctly rounded constants
+    errno = 0;
+    printf("1e308: %d %d\n", strtod("1e308", NULL) == 1e308, errno);
+    printf("0x1.8p3: %d\n", strtod("0x1.8p3", NULL) == 12.0);
+    printf("0X1P-2: %d\n", strtod("0X1P-2", NULL) == 0.25);
+    printf("0.1: %d\n", strtod("0.1", NULL) == 0.1);
+    printf("2^53 + 1: %d\n", strtod("9007199254740993", NULL) == 9007199254740992.0);
+    printf("max: %d\n", strtod("1.7976931348623157e308", NULL) == DBL_MAX);
+    printf("-2.5E-3: %d\n", strtod("-2.5E-3", NULL) == -2.5e-3);
+
+    errno = 0;
+    printf("min subnormal: %d %d\n",
+        strtod("4.9406564584124654e-324", NULL) == 4.9406564584124654e-324, errno == ERANGE);
+    printf("subnormal: %d\n", strtod("2.2250738585072009e-308", NULL) == 2.2250738585072009e-308);
+    printf("hex subnormal: %d\n", strtod("0x0.0000000000001p-1022", NULL) == 4.9406564584124654e-324);
+
+    errno = 0;
+    d = strtod("1e309", NULL);
+    printf("1e309: %d %d\n", d == HUGE_VAL, errno == ERANGE);
+    errno = 0;
+    d = strtod("-0x1p2000", NULL);
+    printf("-0x1p2000: %d %d\n", d == -HUGE_VAL, errno == ERANGE);
+    errno = 0;
+    d = strtod("1e-400", NULL);
+    printf("1e-400: %d %d\n", d == 0.0, errno == ERANGE);
+
+    char* specials[] = { "inf", "-Infinity", "INFINITE", "nan", "NaN(123)x", "nan(" };
+    for (int i = 0; i < sizeof(specials) / sizeof(char*); i += 1) {
+        d = strtod(specials[i], &endptr);
+        printf("%s: isinf %d isnan %d Endptr: \"%s\"\n", specials[i], isinf(d) != 0, isnan(d) != 0, endptr);
+    }
+
+    char* partial[] = { "1e", "1e+", "0x", "0x1p", ".", "-.5e1x", "  +", "1.5.3" };
+    for (int i = 0; i < sizeof(partial) / sizeof(char*); i += 1) {
+        d = strtod(partial[i], &endptr);
+        printf("%s: d: %f Endptr: \"%s\"\n", partial[i], d, endptr);
+    }
+
+    printf("strtof 0.1: %d\n", strtof("0.1", NULL) == 0.1f);
+    printf("strtof 0x1.000001p0: %d\n", strtof("0x1.000001p0", NULL) == 1.0f);
+    errno = 0;
+    printf("strtof 1e39: %d %d\n", strtof("1e39", NULL) == HUGE_VALF, errno == ERANGE);
+    printf("strtold 0x1.8p3: %d\n", strtold("0x1.8p3", NULL) == 12.0L);
 }
@@ -26,5 +26,37 @@ int main(int argc, char* argv[]) {
         printf("errno is not 0 (%d), something went wrong\n", errno);
     }
 
+    // base autodetection and explicit bases without prefixes
+    char *end;
+    const char *hex = "0x1fz";
+    printf("%ld %ld\n", strtol(hex, &end, 0), end - hex);
+    printf("%ld\n", strtol("017", NULL, 0));
+    printf("%ld\n", strtol("ff", NULL, 16));
+    printf("%ld\n", strtol("17", NULL, 8));
+    printf("%ld\n", strtol("101", NULL, 2));
+    printf("%ld\n", strtol("Zz", NULL, 36));
+    const char *bare = "0xg";
+    printf("%ld %ld\n", strtol(bare, &end, 0), end - bare);
+
+    // overflow clamps to the limits of the type
+    errno = 0;
+    printf("%ld %d\n", strtol("9223372036854775808", NULL, 10), errno == ERANGE);
+    errno = 0;
+    printf("%ld %d\n", strtol("-9223372036854775808", NULL, 10), errno == ERANGE);
+    errno = 0;
+    printf("%ld %d\n", strtol("-9223372036854775809", NULL, 10), errno == ERANGE);
+    errno = 0;
+    printf("%lld %d\n", strtoll("99999999999999999999", NULL, 0), errno == ERANGE);
+    errno = 0;
+    printf("%lu %d\n", strtoul("18446744073709551616", NULL, 10), errno == ERANGE);
+    errno = 0;
+    printf("%llu %d\n", strtoull("0xffffffffffffffff", NULL, 0), errno == ERANGE);
+
+    // nothing to parse leaves endptr at the start
+    const char *empty = "";
+    printf("%ld %d\n", strtol(empty, &end, 10), end == empty);
+    const char *invalid = "   abc";
+    printf("%ld %d\n", strtol(invalid, &end, 10), end == invalid);
+
     return 0;
 }
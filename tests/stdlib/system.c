@@ -1,6 +1,17 @@
+#include <stdio.h>
 #include <stdlib.h>
+#include <sys/wait.h>
 
 int main(int argc, char ** argv) {
+    fflush(stdout);
     system("echo test of system");
+
+    printf("shell available: %d\n", system(NULL) != 0);
+
+    int status = system("exit 3");
+    printf("exited: %d, status: %d\n", WIFEXITED(status), WEXITSTATUS(status));
+
+    status = system("exec /this/does/not/exist 2>/dev/null");
+    printf("exited: %d, status: %d\n", WIFEXITED(status), WEXITSTATUS(status));
     return 0;
 }
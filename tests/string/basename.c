@@ -0,0 +1,12 @@
+#define _GNU_SOURCE
+#include <stdio.h>
+#include <string.h>
+
+int main(int argc, char** argv) {
+    // unlike the POSIX version this one never modifies the path
+    const char* paths[] = { "/usr/lib", "/usr/lib/", "usr", "/", "" };
+    for (size_t i = 0; i < sizeof(paths) / sizeof(paths[0]); i++) {
+        printf("\"%s\": \"%s\"\n", paths[i], basename(paths[i]));
+    }
+    return 0;
+}
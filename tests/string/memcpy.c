@@ -0,0 +1,101 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define SIZE 64
+
+// Reference results are computed byte by byte
+void fill(unsigned char *buf) {
+	for (int i = 0; i < SIZE; i++) {
+		buf[i] = (unsigned char)(i * 7 + 1);
+	}
+}
+
+void check(const char *name, unsigned char *got, unsigned char *expected) {
+	for (int i = 0; i < SIZE; i++) {
+		if (got[i] != expected[i]) {
+			printf("Incorrect %s at byte %d\n", name, i);
+			exit(1);
+		}
+	}
+}
+
+int main(int argc, char ** argv) {
+	unsigned char buf[SIZE], expected[SIZE], src[SIZE];
+
+	// Every combination of alignment and length, including zero
+	for (int dst_off = 0; dst_off < 9; dst_off++) {
+		for (int src_off = 0; src_off < 9; src_off++) {
+			for (int len = 0; len + dst_off < SIZE - 8 && len + src_off < SIZE - 8; len++) {
+				fill(src);
+				memset(buf, 0xAA, SIZE);
+				memset(expected, 0xAA, SIZE);
+				for (int i = 0; i < len; i++) {
+					expected[dst_off + i] = src[src_off + i];
+				}
+				if (memcpy(buf + dst_off, src + src_off, len) != buf + dst_off) {
+					puts("memcpy returned the wrong pointer");
+					exit(1);
+				}
+				check("memcpy", buf, expected);
+
+				// Overlapping copies within one buffer, in both directions
+				fill(buf);
+				fill(expected);
+				unsigned char tmp[SIZE];
+				for (int i = 0; i < len; i++) {
+					tmp[i] = expected[src_off + i];
+				}
+				for (int i = 0; i < len; i++) {
+					expected[dst_off + i] = tmp[i];
+				}
+				memmove(buf + dst_off, buf + src_off, len);
+				check("memmove", buf, expected);
+
+				fill(buf);
+				memset(expected, 0xAA, SIZE);
+				for (int i = 0; i < SIZE; i++) {
+					if (i < dst_off || i >= dst_off + len) {
+						expected[i] = buf[i];
+					} else {
+						expected[i] = 0x5C;
+					}
+				}
+				memset(buf + dst_off, 0x5C, len);
+				check("memset", buf, expected);
+			}
+		}
+	}
+	printf("Correct memcpy\n");
+	printf("Correct memmove\n");
+	printf("Correct memset\n");
+
+	// memcmp compares unsigned bytes and finds differences past whole words
+	unsigned char a[SIZE], b[SIZE];
+	fill(a);
+	fill(b);
+	if (memcmp(a, b, SIZE) != 0 || memcmp(a, b, 0) != 0) {
+		printf("Incorrect memcmp on equal buffers\n");
+		exit(1);
+	}
+	for (int i = 0; i < SIZE; i++) {
+		fill(b);
+		b[i] = 0xFF;
+		if (a[i] != 0xFF && memcmp(a, b, SIZE) >= 0) {
+			printf("Incorrect memcmp at byte %d\n", i);
+			exit(1);
+		}
+		if (a[i] != 0xFF && memcmp(b + 1, a + 1, SIZE - 1) <= 0 && i > 0) {
+			printf("Incorrect unaligned memcmp at byte %d\n", i);
+			exit(1);
+		}
+	}
+	printf("Correct memcmp\n");
+
+	if (memchr(a, a[SIZE - 1], 0) != NULL) {
+		printf("Incorrect memchr\n");
+		exit(1);
+	}
+	printf("Correct memchr\n");
+	return 0;
+}
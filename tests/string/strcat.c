@@ -0,0 +1,21 @@
+#include <stdio.h>
+#include <string.h>
+
+int main() {
+    char dst[20] = "";
+
+    strcat(dst, "");
+    printf("\"%s\" %zu\n", dst, strlen(dst));
+    strcat(dst, "strcat");
+    strcat(dst, "");
+    printf("\"%s\" %zu\n", dst, strlen(dst));
+
+    // At most n bytes are appended, and the result is always terminated
+    strncat(dst, " works!", 3);
+    printf("\"%s\" %zu\n", dst, strlen(dst));
+    strncat(dst, "", 5);
+    strncat(dst, "xyz", 0);
+    printf("\"%s\" %zu\n", dst, strlen(dst));
+
+    printf("%zu %zu %zu\n", strnlen(dst, 4), strnlen(dst, 100), strnlen("", 10));
+}
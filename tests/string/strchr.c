@@ -4,7 +4,9 @@
 int main(int argc, char* argv[]) {
 	printf("%s\n", strchr("hello", 'e')); // should be ello
 	printf("%s\n", strchr("world", 'l')); // should be ld
-	printf("%i\n", strchr("world", 0) == NULL); // should be 1
+	char* world = "world";
+	printf("%i\n", strchr(world, 0) == world + 5); // should be 1
+	printf("%i\n", strchr(world, 'a') == NULL); // should be 1
 
     return 0;
 }
@@ -0,0 +1,22 @@
+#include <stdio.h>
+#include <string.h>
+#include <strings.h>
+
+int sign(int n) {
+    return (n > 0) - (n < 0);
+}
+
+int main(int argc, char* argv[]) {
+    printf("%d %d %d\n", sign(strcmp("abc", "abc")), sign(strcmp("abc", "abd")), sign(strcmp("abd", "abc")));
+    printf("%d %d\n", sign(strcmp("ab", "abc")), sign(strcmp("abc", "ab")));
+    printf("%d %d\n", sign(strcmp("", "a")), sign(strcmp("\xFF", "a")));
+    printf("%d %d\n", sign(strncmp("abcX", "abcY", 3)), sign(strncmp("abcX", "abcY", 4)));
+
+    printf("%d %d\n", sign(strcasecmp("Hello", "hELLO")), sign(strcasecmp("Hello", "help")));
+    printf("%d %d\n", sign(strcasecmp("ABC", "abcd")), sign(strcasecmp("abcd", "ABC")));
+    printf("%d %d\n", sign(strcasecmp("a", "B")), sign(strcasecmp("[", "a")));
+    printf("%d %d\n", sign(strncasecmp("HELLO world", "hello WORLD!", 11)), sign(strncasecmp("a", "b", 0)));
+
+    printf("%d %d\n", sign(strcoll("apple", "banana")), sign(strcoll("same", "same")));
+    return 0;
+}
@@ -14,4 +14,21 @@ int main() {
     dst[19] = 0;
     strncpy(dst, "strncpy should work here too", 10);
     puts(dst);
+
+    // A source of exactly n bytes isn't terminated either
+    memset(dst, 'b', 20);
+    dst[19] = 0;
+    strncpy(dst, "exactly", 7);
+    puts(dst);
+
+    // The rest is padded with NULs
+    memset(dst, 'c', 20);
+    strncpy(dst, "pad", 20);
+    for (int i = 3; i < 20; i++) {
+        if (dst[i] != 0) {
+            printf("strncpy didn't pad byte %d\n", i);
+            return 1;
+        }
+    }
+    puts(dst);
 }
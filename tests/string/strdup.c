@@ -0,0 +1,26 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main(int argc, char* argv[]) {
+    char source[] = "relibc";
+
+    char* copy = strdup(source);
+    source[0] = 'R';
+    printf("%s %s\n", source, copy); // the copy doesn't share memory
+
+    char* truncated = strndup(source, 3);
+    printf("%s %zu\n", truncated, strlen(truncated));
+
+    char* whole = strndup(source, 100);
+    printf("%s %zu\n", whole, strlen(whole));
+
+    char* empty = strdup("");
+    printf("\"%s\"\n", empty);
+
+    free(copy);
+    free(truncated);
+    free(whole);
+    free(empty);
+    return 0;
+}
@@ -17,6 +17,12 @@ int main(int argc, char* argv[]) {
     printf("strrchr FAIL, exit with status code %d\n", 1);
     return 1;
   }
+  ptr = strrchr(s0, 0);
+  if (ptr != &s0[12]) {
+    printf("%p != %p\n", ptr, &s0[12]);
+    printf("strrchr FAIL, exit with status code %d\n", 1);
+    return 1;
+  }
   printf("strrch PASS, exiting with status code %d\n", 0);
   return 0;
 }
@@ -14,5 +14,13 @@ int main(int argc, char* argv[]) {
     char* res3 = strstr("In relibc we trust", "bugs");
     printf("%s\n", (res3) ? res3 : "NULL"); 
 
+    // should be "In relibc we trust"
+    char* res4 = strstr("In relibc we trust", "");
+    printf("%s\n", (res4) ? res4 : "NULL");
+
+    // should be ""
+    char* res5 = strstr("", "");
+    printf("\"%s\"\n", (res5) ? res5 : "NULL");
+
     return 0;
 }
@@ -14,5 +14,16 @@ int main(int argc, char* argv[]) {
         }
     }
 
+    printf("\n");
+
+    // Runs of delimiters, including at the start and the end, are skipped
+    char runs[] = ",;a,,b;;,c;,";
+    token = strtok_r(runs, ",;", &sp);
+    while (token) {
+        printf("[%s]", token);
+        token = strtok_r(NULL, ",;", &sp);
+    }
+    printf("\n");
+
     return 0;
 }
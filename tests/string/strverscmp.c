@@ -0,0 +1,11 @@
+#include <assert.h>
+#include <string.h>
+
+int main() {
+    assert(strverscmp("abc", "abc") == 0);
+    assert(strverscmp("file2", "file10") < 0);
+    assert(strverscmp("file10", "file2") > 0);
+    assert(strverscmp("1.9", "1.10") < 0);
+    assert(strverscmp("000", "00") < 0);
+    assert(strverscmp("a", "b") < 0);
+}
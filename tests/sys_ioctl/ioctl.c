@@ -0,0 +1,71 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/ioctl.h>
+#include <unistd.h>
+
+int main() {
+    struct winsize ws;
+
+    // The tests don't normally run in a terminal, so this only does anything if there is one
+    int tty = open("/dev/tty", O_RDWR);
+    if (tty >= 0) {
+        if (ioctl(tty, TIOCGWINSZ, &ws) < 0) {
+            perror("ioctl(TIOCGWINSZ)");
+            return 1;
+        }
+        if (ws.ws_row == 0 || ws.ws_col == 0) {
+            printf("the terminal is %dx%d\n", ws.ws_col, ws.ws_row);
+            return 1;
+        }
+        close(tty);
+    }
+
+    int fds[2];
+    if (pipe(fds) < 0) {
+        perror("pipe");
+        return 1;
+    }
+
+    errno = 0;
+    int ret = ioctl(fds[0], TIOCGWINSZ, &ws);
+    printf("TIOCGWINSZ on a pipe: %d, ENOTTY: %d\n", ret, errno == ENOTTY);
+
+    errno = 0;
+    ret = ioctl(fds[0], 0x7fff, NULL);
+    printf("unknown request: %d, ENOTTY: %d\n", ret, errno == ENOTTY);
+
+    int on = 1;
+    if (ioctl(fds[0], FIONBIO, &on) < 0) {
+        perror("ioctl(FIONBIO)");
+        return 1;
+    }
+    printf("FIONBIO on: %d\n", (fcntl(fds[0], F_GETFL) & O_NONBLOCK) != 0);
+    int off = 0;
+    if (ioctl(fds[0], FIONBIO, &off) < 0) {
+        perror("ioctl(FIONBIO)");
+        return 1;
+    }
+    printf("FIONBIO off: %d\n", (fcntl(fds[0], F_GETFL) & O_NONBLOCK) != 0);
+    close(fds[0]);
+    close(fds[1]);
+
+    int fd = open("ioctl.out", O_RDWR | O_CREAT | O_TRUNC, 0644);
+    if (fd < 0) {
+        perror("open");
+        return 1;
+    }
+    if (write(fd, "hello world", 11) != 11) {
+        perror("write");
+        return 1;
+    }
+    lseek(fd, 6, SEEK_SET);
+    int avail = -1;
+    if (ioctl(fd, FIONREAD, &avail) < 0) {
+        perror("ioctl(FIONREAD)");
+        return 1;
+    }
+    printf("FIONREAD: %d\n", avail);
+    close(fd);
+    unlink("ioctl.out");
+}
@@ -0,0 +1,46 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+int main(int argc, char** argv) {
+    size_t size = 4096;
+
+    char* page = mmap(NULL, size, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    if (page == MAP_FAILED) {
+        perror("mmap");
+        return 1;
+    }
+    printf("zeroed: %d\n", page[0] == 0 && page[size - 1] == 0);
+    strcpy(page, "hello mmap");
+    page[size - 1] = 'x';
+    printf("contents: %s %c\n", page, page[size - 1]);
+    printf("mprotect: %d\n", mprotect(page, size, PROT_READ));
+    printf("munmap: %d\n", munmap(page, size));
+
+    // file mappings see the contents of the file
+    int fd = open("sys_mman/mmap.c", O_RDONLY);
+    char* file = mmap(NULL, size, PROT_READ, MAP_SHARED, fd, 0);
+    if (file == MAP_FAILED) {
+        perror("mmap file");
+        return 1;
+    }
+    printf("file: %.18s\n", file);
+    printf("munmap: %d\n", munmap(file, size));
+    close(fd);
+
+    errno = 0;
+    printf("length 0: %d, EINVAL: %d\n",
+        mmap(NULL, 0, PROT_READ, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0) == MAP_FAILED, errno == EINVAL);
+    errno = 0;
+    printf("unaligned offset: %d, EINVAL: %d\n",
+        mmap(NULL, size, PROT_READ, MAP_PRIVATE | MAP_ANONYMOUS, -1, 100) == MAP_FAILED, errno == EINVAL);
+    errno = 0;
+    printf("no sharing type: %d, EINVAL: %d\n",
+        mmap(NULL, size, PROT_READ, MAP_ANONYMOUS, -1, 0) == MAP_FAILED, errno == EINVAL);
+    errno = 0;
+    printf("unaligned munmap: %d, EINVAL: %d\n", munmap((char*) page + 1, size), errno == EINVAL);
+    return 0;
+}
@@ -0,0 +1,59 @@
+#include <arpa/inet.h>
+#include <netinet/in.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+int main(int argc, char ** argv) {
+	int server = socket(AF_INET, SOCK_STREAM, 0);
+	struct sockaddr_in addr;
+	memset(&addr, 0, sizeof(addr));
+	addr.sin_family = AF_INET;
+	addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+	addr.sin_port = 0;
+	if (bind(server, (struct sockaddr *) &addr, sizeof(addr)) < 0) {
+		perror("bind");
+		return 1;
+	}
+	socklen_t len = sizeof(addr);
+	if (getsockname(server, (struct sockaddr *) &addr, &len) < 0) {
+		perror("getsockname");
+		return 1;
+	}
+
+	printf("accept before listen: %d\n", accept(server, NULL, NULL));
+	printf("listen: %d\n", listen(server, 2));
+
+	// Both fit in the backlog, so they connect before anything is accepted
+	int clients[2];
+	for (int i = 0; i < 2; i++) {
+		clients[i] = socket(AF_INET, SOCK_STREAM, 0);
+		printf("connect %d: %d\n", i, connect(clients[i], (struct sockaddr *) &addr, sizeof(addr)));
+	}
+
+	// A copy of the listening socket accepts just as well
+	int listeners[2] = { server, dup(server) };
+	for (int i = 0; i < 2; i++) {
+		struct sockaddr_in peer;
+		socklen_t peer_len = sizeof(peer);
+		int stream = accept(listeners[i], (struct sockaddr *) &peer, &peer_len);
+		if (stream < 0) {
+			perror("accept");
+			return 1;
+		}
+		printf("accepted %d from %s\n", i, inet_ntoa(peer.sin_addr));
+
+		char buf[8] = { 0 };
+		write(clients[i], "hello", 5);
+		read(stream, buf, sizeof(buf) - 1);
+		printf("read %d: %s\n", i, buf);
+		close(stream);
+	}
+
+	close(clients[0]);
+	close(clients[1]);
+	close(listeners[1]);
+	close(server);
+	return 0;
+}
@@ -0,0 +1,96 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/stat.h>
+#include <sys/time.h>
+#include <time.h>
+#include <unistd.h>
+#include <utime.h>
+
+int print_times(const char* what) {
+    struct stat st;
+    if (stat("futimens.out", &st)) {
+        perror("stat");
+        return -1;
+    }
+    printf("%s: atime %ld.%09ld, mtime %ld.%09ld\n", what,
+        (long) st.st_atim.tv_sec, st.st_atim.tv_nsec,
+        (long) st.st_mtim.tv_sec, st.st_mtim.tv_nsec);
+    return 0;
+}
+
+int main() {
+    int fd = creat("futimens.out", 0644);
+    if (fd < 0) {
+        perror("creat");
+        return 1;
+    }
+
+    struct timespec times[2] = {
+        { .tv_sec = 1000000000, .tv_nsec = 123456789 },
+        { .tv_sec = 1500000000, .tv_nsec = 987654321 },
+    };
+    if (futimens(fd, times)) {
+        perror("futimens");
+        return 1;
+    }
+    if (print_times("futimens") < 0) {
+        return 1;
+    }
+
+    // Only change the access time
+    times[0].tv_sec = 1100000000;
+    times[0].tv_nsec = 0;
+    times[1].tv_nsec = UTIME_OMIT;
+    if (futimens(fd, times)) {
+        perror("futimens UTIME_OMIT");
+        return 1;
+    }
+    if (print_times("UTIME_OMIT") < 0) {
+        return 1;
+    }
+
+    struct timeval tv[2] = {
+        { .tv_sec = 1200000000, .tv_usec = 5 },
+        { .tv_sec = 1300000000, .tv_usec = 6 },
+    };
+    if (utimes("futimens.out", tv)) {
+        perror("utimes");
+        return 1;
+    }
+    if (print_times("utimes") < 0) {
+        return 1;
+    }
+
+    struct utimbuf buf = { .actime = 1400000000, .modtime = 1600000000 };
+    if (utime("futimens.out", &buf)) {
+        perror("utime");
+        return 1;
+    }
+    if (print_times("utime") < 0) {
+        return 1;
+    }
+
+    // Passing NULL sets both times to now
+    time_t before = time(NULL);
+    if (utime("futimens.out", NULL)) {
+        perror("utime NULL");
+        return 1;
+    }
+    struct stat st;
+    stat("futimens.out", &st);
+    printf("utime(NULL) set the current time: %d\n",
+        st.st_atim.tv_sec >= before && st.st_mtim.tv_sec >= before);
+
+    times[0].tv_nsec = UTIME_NOW;
+    times[1].tv_nsec = UTIME_NOW;
+    if (futimens(fd, times)) {
+        perror("futimens UTIME_NOW");
+        return 1;
+    }
+    stat("futimens.out", &st);
+    printf("UTIME_NOW set the current time: %d\n",
+        st.st_atim.tv_sec >= before && st.st_mtim.tv_sec >= before);
+
+    close(fd);
+    unlink("futimens.out");
+}
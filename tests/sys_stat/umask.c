@@ -0,0 +1,36 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+int main() {
+    mode_t old = umask(077);
+    printf("umask now: %03o\n", umask(077));
+
+    if (mkdir("umask-dir", 0777)) {
+        perror("mkdir");
+        return 1;
+    }
+    struct stat st;
+    if (stat("umask-dir", &st)) {
+        perror("stat");
+        return 1;
+    }
+    printf("directory mode: %03o\n", st.st_mode & 0777);
+    rmdir("umask-dir");
+
+    int fd = open("umask-file", O_CREAT | O_WRONLY | O_EXCL, 0666);
+    if (fd < 0) {
+        perror("open");
+        return 1;
+    }
+    close(fd);
+    if (stat("umask-file", &st)) {
+        perror("stat");
+        return 1;
+    }
+    printf("file mode: %03o\n", st.st_mode & 0777);
+    unlink("umask-file");
+
+    umask(old);
+}
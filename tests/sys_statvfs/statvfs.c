@@ -0,0 +1,28 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/statvfs.h>
+#include <unistd.h>
+
+int main() {
+    struct statvfs buf;
+    if (statvfs("/", &buf)) {
+        perror("statvfs");
+        return 1;
+    }
+    printf("f_bsize is non-zero: %d\n", buf.f_bsize != 0);
+    printf("f_bfree <= f_blocks: %d\n", buf.f_bfree <= buf.f_blocks);
+    printf("f_bavail <= f_bfree: %d\n", buf.f_bavail <= buf.f_bfree);
+
+    int fd = open("/", O_RDONLY | O_DIRECTORY);
+    if (fd < 0) {
+        perror("open");
+        return 1;
+    }
+    struct statvfs fbuf;
+    if (fstatvfs(fd, &fbuf)) {
+        perror("fstatvfs");
+        return 1;
+    }
+    close(fd);
+    printf("fstatvfs matches: %d\n", fbuf.f_bsize == buf.f_bsize && fbuf.f_fsid == buf.f_fsid);
+}
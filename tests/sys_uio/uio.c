@@ -0,0 +1,53 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/uio.h>
+#include <unistd.h>
+
+int main() {
+    char hello[] = "Hello, ";
+    char world[] = "world!\n";
+    struct iovec out[] = {
+        { .iov_base = hello, .iov_len = strlen(hello) },
+        { .iov_base = world, .iov_len = strlen(world) },
+    };
+    ssize_t count = writev(STDOUT_FILENO, out, 2);
+    printf("writev to stdout: %zd\n", count);
+
+    int fd = open("uio.out", O_CREAT | O_RDWR | O_TRUNC, 0644);
+    if (fd < 0) {
+        perror("open");
+        return 1;
+    }
+    count = writev(fd, out, 2);
+    printf("writev to file: %zd\n", count);
+
+    char first[5] = { 0 };
+    char second[16] = { 0 };
+    struct iovec in[] = {
+        { .iov_base = first, .iov_len = sizeof(first) - 1 },
+        { .iov_base = second, .iov_len = sizeof(second) - 1 },
+    };
+    lseek(fd, 0, SEEK_SET);
+    count = readv(fd, in, 2);
+    printf("readv: %zd \"%s\" \"%s\"\n", count, first, second);
+
+    errno = 0;
+    if (writev(fd, out, IOV_MAX + 1) != -1 || errno != EINVAL) {
+        puts("writev with more than IOV_MAX vectors didn't fail with EINVAL");
+        return 1;
+    }
+    struct iovec huge[] = {
+        { .iov_base = hello, .iov_len = (size_t) -1 / 2 },
+        { .iov_base = world, .iov_len = (size_t) -1 / 2 },
+    };
+    errno = 0;
+    if (writev(fd, huge, 2) != -1 || errno != EINVAL) {
+        puts("writev overflowing ssize_t didn't fail with EINVAL");
+        return 1;
+    }
+
+    close(fd);
+    unlink("uio.out");
+}
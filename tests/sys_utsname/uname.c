@@ -0,0 +1,52 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/utsname.h>
+#include <unistd.h>
+
+int main() {
+    struct utsname uts;
+    if (uname(&uts) < 0) {
+        perror("uname");
+        return 1;
+    }
+
+#ifdef __redox__
+    const char* sysname = "Redox";
+#else
+    const char* sysname = "Linux";
+#endif
+    printf("sysname matches: %d\n", strcmp(uts.sysname, sysname) == 0);
+    printf("machine is set: %d\n", uts.machine[0] != 0);
+
+    char hostname[256];
+    if (gethostname(hostname, sizeof(hostname)) < 0) {
+        perror("gethostname");
+        return 1;
+    }
+    printf("gethostname matches nodename: %d\n", strcmp(hostname, uts.nodename) == 0);
+
+    // Too small to hold the terminating nul byte
+    size_t len = strlen(hostname);
+    if (len > 0) {
+        char small[256];
+        errno = 0;
+        if (gethostname(small, len) != -1 || errno != ENAMETOOLONG) {
+            puts("a truncated hostname didn't fail with ENAMETOOLONG");
+            return 1;
+        }
+        if (strncmp(small, hostname, len - 1) != 0) {
+            puts("the hostname wasn't truncated");
+            return 1;
+        }
+    }
+
+    // Only root may rename the host
+    if (geteuid() != 0) {
+        errno = 0;
+        if (sethostname("relibc", 6) != -1 || errno != EPERM) {
+            puts("sethostname didn't fail with EPERM");
+            return 1;
+        }
+    }
+}
@@ -0,0 +1,80 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <termios.h>
+#include <unistd.h>
+
+int round_trip(int fd) {
+    struct termios old;
+    if (tcgetattr(fd, &old) < 0) {
+        perror("tcgetattr");
+        return -1;
+    }
+
+    struct termios raw = old;
+    raw.c_lflag &= ~(ECHO | ICANON);
+    raw.c_cc[VMIN] = 1;
+    raw.c_cc[VTIME] = 0;
+    if (tcsetattr(fd, TCSANOW, &raw) < 0) {
+        perror("tcsetattr");
+        return -1;
+    }
+
+    struct termios check;
+    if (tcgetattr(fd, &check) < 0) {
+        perror("tcgetattr");
+        return -1;
+    }
+    int ok = !(check.c_lflag & ECHO) && !(check.c_lflag & ICANON)
+        && check.c_cc[VMIN] == 1 && check.c_cc[VTIME] == 0;
+
+    if (tcsetattr(fd, TCSAFLUSH, &old) < 0) {
+        perror("tcsetattr");
+        return -1;
+    }
+    if (tcgetattr(fd, &check) < 0) {
+        perror("tcgetattr");
+        return -1;
+    }
+    ok = ok && (check.c_lflag & ECHO) == (old.c_lflag & ECHO);
+    if (!ok) {
+        puts("the terminal settings didn't round-trip");
+        return -1;
+    }
+    return 0;
+}
+
+int main() {
+    // The tests don't normally run in a terminal, so this only does anything if there is one
+    int tty = open("/dev/tty", O_RDWR);
+    if (tty >= 0) {
+        if (round_trip(tty) < 0) {
+            return 1;
+        }
+        close(tty);
+    }
+
+    int fds[2];
+    if (pipe(fds) < 0) {
+        perror("pipe");
+        return 1;
+    }
+    struct termios t;
+    errno = 0;
+    int result = tcgetattr(fds[0], &t);
+    printf("tcgetattr on a pipe: %d, ENOTTY: %d\n", result, errno == ENOTTY);
+    close(fds[0]);
+    close(fds[1]);
+
+    t.c_cflag = CS8 | CREAD;
+    if (cfsetospeed(&t, B9600) < 0 || cfsetispeed(&t, B9600) < 0) {
+        perror("cfsetspeed");
+        return 1;
+    }
+    printf("speed: %d %d\n", cfgetispeed(&t) == B9600, cfgetospeed(&t) == B9600);
+    printf("other flags kept: %d\n", (t.c_cflag & (CS8 | CREAD)) == (CS8 | CREAD));
+
+    errno = 0;
+    result = cfsetospeed(&t, 12345);
+    printf("invalid speed: %d, EINVAL: %d\n", result, errno == EINVAL);
+}
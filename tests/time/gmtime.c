@@ -24,5 +24,17 @@ int main(int argc, char** argv) {
         info->tm_gmtoff != expected.tm_gmtoff || strcmp(info->tm_zone, expected.tm_zone) != 0) {
             exit(1);
     }
+
+    // Leap days, far beyond 2038 and before the epoch
+    time_t inputs[] = { 1234567890, 951782400, 4102444800, 253402300799, -500 };
+    for (int i = 0; i < sizeof(inputs) / sizeof(time_t); i += 1) {
+        tm result;
+        tm *t = gmtime_r(&inputs[i], &result);
+        printf(
+            "%ld: %d-%d-%d %d:%d:%d, wday %d, yday %d, isdst %d, same: %d\n",
+            inputs[i], t->tm_year, t->tm_mon, t->tm_mday, t->tm_hour, t->tm_min, t->tm_sec,
+            t->tm_wday, t->tm_yday, t->tm_isdst, t == &result
+        );
+    }
     return 0;
 }
@@ -1,4 +1,5 @@
 #include <stdio.h>
+#include <stdlib.h>
 #include <time.h>
 
 int main() {
@@ -15,4 +16,13 @@ int main() {
 
     time_t input = 1531461823;
     fputs(ctime(&input), stdout); // Omit newline
+
+    // Offsets in TZ count westwards
+    char *zones[] = { "IST-5:30", "<-03>3", "EST5EDT,M3.2.0,M11.1.0", "garbage" };
+    for (int i = 0; i < 4; i += 1) {
+        setenv("TZ", zones[i], 1);
+        struct tm t;
+        localtime_r(&inputs[4], &t);
+        printf("%s: %d:%d:%d, gmtoff %ld, zone %s\n", zones[i], t.tm_hour, t.tm_min, t.tm_sec, t.tm_gmtoff, t.tm_zone);
+    }
 }
@@ -1,4 +1,6 @@
 #include <assert.h>
+#include <errno.h>
+#include <limits.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <time.h>
@@ -34,6 +36,29 @@ int main() {
         }
     }
 
+    // Out of range fields are normalized, and wday/yday are recomputed
+    struct tm normal = { .tm_year = 118, .tm_mon = 6, .tm_mday = 13, .tm_hour = 4, .tm_min = 9, .tm_sec = 10 };
+    printf("timegm: %ld\n", timegm(&normal));
+    struct tm denormal = { .tm_year = 100, .tm_mon = 13, .tm_mday = -1, .tm_hour = 25, .tm_wday = 6, .tm_yday = 300 };
+    printf("timegm: %ld\n", timegm(&denormal));
+    printf(
+        "Year %d, Day of year: %d, Month %d, Day of month: %d, Day of week: %d, %d:%d:%d\n",
+        denormal.tm_year, denormal.tm_yday, denormal.tm_mon, denormal.tm_mday, denormal.tm_wday,
+        denormal.tm_hour, denormal.tm_min, denormal.tm_sec
+    );
+    struct tm leap_second = { .tm_year = 99, .tm_mon = 11, .tm_mday = 31, .tm_hour = 23, .tm_min = 59, .tm_sec = 60 };
+    printf("mktime: %ld, month %d\n", mktime(&leap_second), leap_second.tm_mon);
+
+    // mktime applies the offset from TZ
+    setenv("TZ", "IST-5:30", 1);
+    struct tm local = { .tm_year = 118, .tm_mon = 6, .tm_mday = 13, .tm_hour = 9, .tm_min = 39, .tm_sec = 10 };
+    printf("mktime in IST: %ld\n", mktime(&local));
+    unsetenv("TZ");
+
+    struct tm huge = { .tm_year = INT_MAX, .tm_mon = 11, .tm_mday = 31 };
+    errno = 0;
+    printf("unrepresentable: %ld, EOVERFLOW: %d\n", timegm(&huge), errno == EOVERFLOW);
+
     srand(time(NULL));
 
     for (int i = 0; i < 10; i += 1) {
@@ -10,7 +10,7 @@ void print(time_t timestamp, char* fmt) {
 }
 int main() {
     print(1531808742, "%a %A %b %B");
-    print(1531808742, "The %Cst century");
+    print(1531808742, "Century %C");
     print(1531808742, "%I:%M:%S %p");
     print(1531839600, "%r");
     print(1531839600, "%R");
@@ -18,4 +18,22 @@ int main() {
     print(1531839600, "%j %U");
     print(1531839600, "%+");
     print(1533669431, "%+%+%+%+%+"); // will overflow 50 characters
+
+    // A fixed tm, independent of the conversion functions
+    struct tm t = {
+        .tm_sec = 5, .tm_min = 4, .tm_hour = 3, .tm_mday = 9, .tm_mon = 1, .tm_year = 100,
+        .tm_wday = 3, .tm_yday = 39, .tm_isdst = 0, .tm_gmtoff = -16200, .tm_zone = "NST"
+    };
+    char buf[100];
+    const char *formats[] = {
+        "%Y %m %d %H %M %S %p", "%A %a %B %b", "%j %w %y %Z %z %%", "%F %T %R", "%C %e %I %D",
+    };
+    for (int i = 0; i < sizeof(formats) / sizeof(formats[0]); i += 1) {
+        size_t n = strftime(buf, sizeof(buf), formats[i], &t);
+        printf("%zu: %s\n", n, buf);
+    }
+
+    // Exactly filling the buffer leaves no room for the NUL, so it doesn't fit
+    printf("%zu\n", strftime(buf, 4, "%Y", &t));
+    printf("%zu: %s\n", strftime(buf, 5, "%Y", &t), buf);
 }
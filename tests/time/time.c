@@ -1,4 +1,6 @@
+#include <sys/time.h>
 #include <time.h>
+#include <limits.h>
 #include <stdio.h>
 
 int main(int argc, char** argv) {
@@ -9,5 +11,21 @@ int main(int argc, char** argv) {
     perror("time");
     clock_t c = clock();
     perror("clock");
+
+    // Both clocks agree and never go backwards between two calls
+    time_t stored = 0;
+    time_t first = time(&stored);
+    struct timeval tv = {0, 0};
+    int ret = gettimeofday(&tv, NULL);
+    time_t second = time(NULL);
+    printf("time stored: %d\n", first == stored);
+    printf("gettimeofday: %d\n", ret);
+    printf("non-decreasing: %d\n", first > 0 && first <= tv.tv_sec && tv.tv_sec <= second);
+    printf("usec in range: %d\n", tv.tv_usec >= 0 && tv.tv_usec < 1000000);
+
+    printf("difftime: %f\n", difftime(first + 3600, first));
+    printf("difftime: %f\n", difftime(first, first + 3600));
+    printf("difftime far apart: %d\n", difftime(LONG_MAX, LONG_MIN) == 18446744073709551615.0);
+    printf("clock: %d, CLOCKS_PER_SEC: %ld\n", c != (clock_t) -1 && c >= 0, (long) CLOCKS_PER_SEC);
     return 0;
 }
@@ -3,11 +3,26 @@
 #include <unistd.h>
 
 int main() {
-    struct tms tms;
-    printf("return: %ld\n", times(&tms));
+    long ticks_per_sec = sysconf(_SC_CLK_TCK);
+    printf("sysconf(_SC_CLK_TCK) > 0: %d\n", ticks_per_sec > 0);
 
-    printf("tm_utime: %ld\n", tms.tms_utime);
-    printf("tm_stime: %ld\n", tms.tms_stime);
-    printf("tm_cutime: %ld\n", tms.tms_cutime);
-    printf("tm_cstime: %ld\n", tms.tms_cstime);
+    struct tms before;
+    clock_t start = times(&before);
+    printf("times(&tms) != -1: %d\n", start != (clock_t) -1);
+
+    // Use up some CPU time
+    volatile unsigned long sum = 0;
+    for (unsigned long i = 0; i < 50000000; i++) {
+        sum += i;
+    }
+
+    struct tms after;
+    clock_t end = times(&after);
+    printf("return value didn't decrease: %d\n", end >= start);
+    printf("tms_utime didn't decrease: %d\n", after.tms_utime >= before.tms_utime);
+    printf("all times >= 0: %d\n", after.tms_utime >= 0 && after.tms_stime >= 0
+        && after.tms_cutime >= 0 && after.tms_cstime >= 0);
+
+    clock_t null_ticks = times(NULL);
+    printf("times(NULL) didn't decrease: %d\n", null_ticks != (clock_t) -1 && null_ticks >= end);
 }
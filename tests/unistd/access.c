@@ -1,7 +1,13 @@
+#include <errno.h>
+#include <fcntl.h>
 #include <stdio.h>
 #include <unistd.h>
 
 int main() {
+    if (access("example_dir/1-never-gonna-give-you-up", F_OK)) {
+        perror("access F_OK");
+        return 1;
+    }
     if (access("example_dir/1-never-gonna-give-you-up", R_OK | W_OK)) {
         perror("access");
         return 1;
@@ -11,4 +17,39 @@ int main() {
         puts("Please run `chmod 644 example_dir/*` and try again.");
         return 1;
     }
+
+    errno = 0;
+    if (access("example_dir/this-file-does-not-exist", F_OK) != -1 || errno != ENOENT) {
+        puts("access on a missing file didn't fail with ENOENT");
+        return 1;
+    }
+
+    if (faccessat(AT_FDCWD, "example_dir/1-never-gonna-give-you-up", R_OK, AT_EACCESS)) {
+        perror("faccessat AT_EACCESS");
+        return 1;
+    }
+
+    int dir = open("example_dir", O_RDONLY | O_DIRECTORY);
+    if (dir < 0) {
+        perror("open");
+        return 1;
+    }
+    if (faccessat(dir, "1-never-gonna-give-you-up", R_OK, 0)) {
+        perror("faccessat");
+        return 1;
+    }
+    errno = 0;
+    if (faccessat(dir, "this-file-does-not-exist", F_OK, 0) != -1 || errno != ENOENT) {
+        puts("faccessat on a missing file didn't fail with ENOENT");
+        return 1;
+    }
+    close(dir);
+
+    errno = 0;
+    if (faccessat(AT_FDCWD, "example_dir", F_OK, 0x1234) != -1 || errno != EINVAL) {
+        puts("faccessat with bad flags didn't fail with EINVAL");
+        return 1;
+    }
+
+    puts("access checks passed");
 }
@@ -0,0 +1,76 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <sys/time.h>
+#include <time.h>
+#include <unistd.h>
+
+static volatile sig_atomic_t alarms = 0;
+
+void handler(int sig) {
+    alarms++;
+}
+
+// Waits for up to `seconds` until `count` alarms have come in
+void wait_for(int count, int seconds) {
+    struct timespec start, now;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    while (alarms < count) {
+        usleep(10000);
+        clock_gettime(CLOCK_MONOTONIC, &now);
+        if (now.tv_sec - start.tv_sec > seconds) {
+            break;
+        }
+    }
+}
+
+int main() {
+    struct sigaction sa;
+    sa.sa_handler = handler;
+    sa.sa_flags = 0;
+    sigemptyset(&sa.sa_mask);
+    sigaction(SIGALRM, &sa, NULL);
+
+    // Each alarm replaces the last one
+    printf("alarm(10) = %u\n", alarm(10));
+    printf("alarm(5) = %u\n", alarm(5));
+    printf("alarm(0) = %u\n", alarm(0));
+    printf("alarm(0) = %u\n", alarm(0));
+
+    alarm(1);
+    wait_for(1, 2);
+    printf("SIGALRM after alarm(1): %d\n", alarms);
+
+    // A repeating timer
+    alarms = 0;
+    struct itimerval timer = {
+        .it_interval = { .tv_sec = 0, .tv_usec = 50000 },
+        .it_value = { .tv_sec = 0, .tv_usec = 50000 },
+    };
+    printf("setitimer: %d\n", setitimer(ITIMER_REAL, &timer, NULL));
+    wait_for(3, 2);
+    printf("at least 3 SIGALRMs: %d\n", alarms >= 3);
+
+    struct itimerval old;
+    struct itimerval off = { 0 };
+    setitimer(ITIMER_REAL, &off, &old);
+    printf("old interval: %ld.%06ld\n", (long) old.it_interval.tv_sec, (long) old.it_interval.tv_usec);
+
+    struct itimerval current;
+    getitimer(ITIMER_REAL, &current);
+    printf("disabled: %d\n", current.it_value.tv_sec == 0 && current.it_value.tv_usec == 0);
+
+    timer.it_interval.tv_usec = 0;
+    timer.it_value.tv_sec = 10;
+    timer.it_value.tv_usec = 0;
+    setitimer(ITIMER_REAL, &timer, NULL);
+    getitimer(ITIMER_REAL, &current);
+    printf("between 9 and 10 seconds left: %d\n", current.it_value.tv_sec == 9
+        || (current.it_value.tv_sec == 10 && current.it_value.tv_usec == 0));
+    alarm(0);
+
+    timer.it_value.tv_usec = 1000000;
+    errno = 0;
+    int status = setitimer(ITIMER_REAL, &timer, NULL);
+    printf("setitimer with tv_usec = 1000000: %d, EINVAL: %d\n", status, errno == EINVAL);
+}
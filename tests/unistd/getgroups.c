@@ -0,0 +1,46 @@
+#include <errno.h>
+#include <grp.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+
+int main() {
+    int count = getgroups(0, NULL);
+    if (count < 0) {
+        perror("getgroups");
+        return 1;
+    }
+
+    gid_t* list = malloc((count + 1) * sizeof(gid_t));
+    int ret = getgroups(count + 1, list);
+    if (ret != count) {
+        printf("getgroups returned %d groups, then %d\n", count, ret);
+        return 1;
+    }
+    puts("getgroups into a buffer with room to spare succeeded");
+
+    // Too small to hold them all
+    if (count > 0) {
+        errno = 0;
+        if (getgroups(count - 1, list) != -1 || errno != EINVAL) {
+            puts("getgroups with a small buffer didn't fail with EINVAL");
+            return 1;
+        }
+    }
+    free(list);
+
+    // Only root may change them
+    if (geteuid() != 0) {
+        gid_t gid = getgid();
+        errno = 0;
+        if (setgroups(1, &gid) != -1 || errno != EPERM) {
+            puts("setgroups didn't fail with EPERM");
+            return 1;
+        }
+        errno = 0;
+        if (initgroups("root", gid) != -1 || errno != EPERM) {
+            puts("initgroups didn't fail with EPERM");
+            return 1;
+        }
+    }
+}
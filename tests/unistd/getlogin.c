@@ -0,0 +1,27 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+int main() {
+    char* login = getlogin();
+    if (login == NULL) {
+        perror("getlogin");
+        return 1;
+    }
+    printf("getlogin returned a name: %d\n", login[0] != 0);
+
+    char buf[256];
+    int err = getlogin_r(buf, sizeof(buf));
+    if (err != 0) {
+        printf("getlogin_r: %s\n", strerror(err));
+        return 1;
+    }
+    printf("getlogin_r agrees: %d\n", strcmp(buf, login) == 0);
+
+    err = getlogin_r(buf, 1);
+    printf("getlogin_r with a tiny buffer: ERANGE: %d\n", err == ERANGE);
+
+    char* user = cuserid(NULL);
+    printf("cuserid returned a name: %d\n", user != NULL && user[0] != 0);
+}
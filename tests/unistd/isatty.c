@@ -1,11 +1,36 @@
+#include <errno.h>
 #include <stdio.h>
 #include <unistd.h>
 
 int main() {
-    // 1 is stdout
+    // 1 is stdout. The tests are run with it piped into a file, so this expects it not to be a
+    // terminal
     if (isatty(1)) {
         puts("'Tis a tty :D");
     } else {
         puts("Whatever a tty is, it's not me");
     }
+
+    int fds[2];
+    if (pipe(fds) < 0) {
+        perror("pipe");
+        return 1;
+    }
+    errno = 0;
+    int result = isatty(fds[0]);
+    printf("isatty on a pipe: %d, ENOTTY: %d\n", result, errno == ENOTTY);
+
+    errno = 0;
+    char* name = ttyname(fds[0]);
+    printf("ttyname on a pipe: %s, ENOTTY: %d\n", name ? name : "NULL", errno == ENOTTY);
+
+    char buf[64];
+    printf("ttyname_r on a pipe returns ENOTTY: %d\n", ttyname_r(fds[0], buf, sizeof(buf)) == ENOTTY);
+
+    close(fds[0]);
+    close(fds[1]);
+
+    errno = 0;
+    result = isatty(fds[0]);
+    printf("isatty on a closed fd: %d, EBADF: %d\n", result, errno == EBADF);
 }
@@ -0,0 +1,42 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+int main() {
+    errno = 0;
+    long name_max = pathconf("unistd/pathconf.c", _PC_NAME_MAX);
+    if (name_max == -1 && errno != 0) {
+        perror("pathconf");
+        return 1;
+    }
+    printf("name max: %d\n", name_max >= 14);
+
+    int fd = open("unistd/pathconf.c", O_RDONLY);
+    if (fd < 0) {
+        perror("open");
+        return 1;
+    }
+    printf("fpathconf agrees: %d\n", fpathconf(fd, _PC_NAME_MAX) == name_max);
+    close(fd);
+
+    printf("path max: %d\n", pathconf("unistd/pathconf.c", _PC_PATH_MAX) >= 256);
+    printf("pipe buf: %d\n", pathconf("unistd/pathconf.c", _PC_PIPE_BUF) >= 512);
+    printf("link max: %d\n", pathconf("unistd/pathconf.c", _PC_LINK_MAX) >= 8);
+
+    errno = 0;
+    if (pathconf("unistd/pathconf.c", -1) != -1 || errno != EINVAL) {
+        puts("pathconf on an unknown name didn't fail with EINVAL");
+        return 1;
+    }
+    errno = 0;
+    if (pathconf("unistd/pathconf-missing.c", _PC_NAME_MAX) != -1 || errno != ENOENT) {
+        puts("pathconf on a missing file didn't fail with ENOENT");
+        return 1;
+    }
+    errno = 0;
+    if (pathconf("unistd/pathconf-missing.c", _PC_LINK_MAX) != -1 || errno != ENOENT) {
+        puts("pathconf for another limit on a missing file didn't fail with ENOENT");
+        return 1;
+    }
+}
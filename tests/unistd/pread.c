@@ -0,0 +1,50 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+int main() {
+    int fd = open("pread.out", O_CREAT | O_RDWR | O_TRUNC, 0644);
+    if (fd < 0) {
+        perror("open");
+        return 1;
+    }
+    if (write(fd, "Hello", 5) != 5) {
+        perror("write");
+        return 1;
+    }
+
+    if (pwrite(fd, "World", 5, 100) != 5) {
+        perror("pwrite");
+        return 1;
+    }
+    printf("position after pwrite: %ld\n", (long) lseek(fd, 0, SEEK_CUR));
+
+    char buf[6] = { 0 };
+    if (pread(fd, buf, 5, 100) != 5) {
+        perror("pread");
+        return 1;
+    }
+    printf("pread at 100: %s\n", buf);
+    if (pread(fd, buf, 5, 0) != 5) {
+        perror("pread");
+        return 1;
+    }
+    printf("pread at 0: %s\n", buf);
+    printf("position after pread: %ld\n", (long) lseek(fd, 0, SEEK_CUR));
+    printf("file size: %ld\n", (long) lseek(fd, 0, SEEK_END));
+
+    errno = 0;
+    if (pread(fd, buf, 5, -1) != -1 || errno != EINVAL) {
+        puts("pread with a negative offset didn't fail with EINVAL");
+        return 1;
+    }
+    errno = 0;
+    if (pwrite(fd, buf, 5, -1) != -1 || errno != EINVAL) {
+        puts("pwrite with a negative offset didn't fail with EINVAL");
+        return 1;
+    }
+
+    close(fd);
+    unlink("pread.out");
+}
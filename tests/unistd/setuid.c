@@ -0,0 +1,44 @@
+#include <errno.h>
+#include <stdio.h>
+#include <unistd.h>
+
+int main() {
+    // Switching to the ids we already have is always allowed
+    if (setuid(getuid()) < 0) {
+        perror("setuid");
+        return 1;
+    }
+    if (seteuid(geteuid()) < 0) {
+        perror("seteuid");
+        return 1;
+    }
+    if (setgid(getgid()) < 0) {
+        perror("setgid");
+        return 1;
+    }
+    if (setegid(getegid()) < 0) {
+        perror("setegid");
+        return 1;
+    }
+
+    // Anybody else's are off limits unless we're root
+    if (geteuid() != 0) {
+        uid_t other = getuid() + 1;
+        errno = 0;
+        if (seteuid(other) != -1 || errno != EPERM) {
+            puts("seteuid to another user didn't fail with EPERM");
+            return 1;
+        }
+        errno = 0;
+        if (setuid(other) != -1 || errno != EPERM) {
+            puts("setuid to another user didn't fail with EPERM");
+            return 1;
+        }
+        errno = 0;
+        if (setegid(getgid() + 1) != -1 || errno != EPERM) {
+            puts("setegid to another group didn't fail with EPERM");
+            return 1;
+        }
+    }
+    puts("the ids were changed as allowed");
+}
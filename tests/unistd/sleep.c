@@ -1,7 +1,14 @@
+#include <errno.h>
 #include <time.h>
 #include <unistd.h>
 #include <stdio.h>
 
+static long elapsed_ns(timespec* start) {
+    timespec end = {0, 0};
+    clock_gettime(CLOCK_MONOTONIC, &end);
+    return (end.tv_sec - start->tv_sec) * 1000000000L + (end.tv_nsec - start->tv_nsec);
+}
+
 int main(int argc, char** argv) {
     sleep(2);
     perror("sleep");
@@ -10,5 +17,30 @@ int main(int argc, char** argv) {
     timespec tm = {0, 10000};
     nanosleep(&tm, NULL);
     perror("nanosleep");
+
+    timespec start = {0, 0};
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    printf("sleep: %u\n", sleep(1));
+    printf("sleep elapsed: %d\n", elapsed_ns(&start) >= 1000000000L);
+
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    printf("usleep: %d\n", usleep(10000));
+    printf("usleep elapsed: %d\n", elapsed_ns(&start) >= 10000000L);
+
+    // the remaining time is left alone when the sleep isn't interrupted
+    timespec rq = {0, 10000000};
+    timespec rm = {123, 456};
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    printf("nanosleep: %d\n", nanosleep(&rq, &rm));
+    printf("nanosleep elapsed: %d\n", elapsed_ns(&start) >= 10000000L);
+    printf("remaining untouched: %d\n", rm.tv_sec == 123 && rm.tv_nsec == 456);
+
+    timespec bad = {0, 1000000000};
+    errno = 0;
+    printf("invalid nsec: %d, EINVAL: %d\n", nanosleep(&bad, NULL), errno == EINVAL);
+    bad.tv_sec = -1;
+    bad.tv_nsec = 0;
+    errno = 0;
+    printf("negative sec: %d, EINVAL: %d\n", nanosleep(&bad, NULL), errno == EINVAL);
     return 0;
 }
@@ -0,0 +1,76 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+int main() {
+    const char* target = "unistd/symlink.c";
+    if (symlink(target, "symlink.out")) {
+        perror("symlink");
+        return 1;
+    }
+
+    char buf[64];
+    ssize_t len = readlink("symlink.out", buf, sizeof(buf));
+    if (len < 0) {
+        perror("readlink");
+        return 1;
+    }
+    printf("readlink: %.*s (%zd bytes)\n", (int) len, buf, len);
+
+    // The contents are truncated, not NUL-terminated
+    memset(buf, 'x', sizeof(buf));
+    len = readlink("symlink.out", buf, 6);
+    printf("truncated: %.*s (%zd bytes), next byte %c\n", (int) len, buf, len, buf[len]);
+
+    struct stat st;
+    if (lstat("symlink.out", &st)) {
+        perror("lstat");
+        return 1;
+    }
+    printf("lstat is a link: %d\n", S_ISLNK(st.st_mode));
+    if (stat("symlink.out", &st)) {
+        perror("stat");
+        return 1;
+    }
+    printf("stat is a regular file: %d\n", S_ISREG(st.st_mode));
+
+    errno = 0;
+    if (readlink(target, buf, sizeof(buf)) != -1 || errno != EINVAL) {
+        puts("readlink on a regular file didn't fail with EINVAL");
+        return 1;
+    }
+
+    if (unlink("symlink.out")) {
+        perror("unlink");
+        return 1;
+    }
+
+    int dir = open("unistd", O_RDONLY | O_DIRECTORY);
+    if (dir < 0) {
+        perror("open");
+        return 1;
+    }
+    if (symlinkat("symlink.c", dir, "symlink.out")) {
+        perror("symlinkat");
+        return 1;
+    }
+    len = readlinkat(dir, "symlink.out", buf, sizeof(buf));
+    if (len < 0) {
+        perror("readlinkat");
+        return 1;
+    }
+    printf("readlinkat: %.*s\n", (int) len, buf);
+    if (stat("unistd/symlink.out", &st)) {
+        perror("stat");
+        return 1;
+    }
+    printf("relative link resolves: %d\n", S_ISREG(st.st_mode));
+    if (unlink("unistd/symlink.out")) {
+        perror("unlink");
+        return 1;
+    }
+    close(dir);
+}
@@ -0,0 +1,25 @@
+#include <errno.h>
+#include <stdio.h>
+#include <unistd.h>
+
+int main() {
+    long page_size = sysconf(_SC_PAGESIZE);
+    printf("page size is a power of two: %d\n", page_size > 0 && (page_size & (page_size - 1)) == 0);
+    printf("_SC_PAGE_SIZE agrees: %d\n", sysconf(_SC_PAGE_SIZE) == page_size);
+    printf("getpagesize agrees: %d\n", getpagesize() == page_size);
+
+    long conf = sysconf(_SC_NPROCESSORS_CONF);
+    long onln = sysconf(_SC_NPROCESSORS_ONLN);
+    printf("processors: %d\n", onln >= 1 && conf >= onln);
+
+    long open_max = sysconf(_SC_OPEN_MAX);
+    printf("open max: %d\n", open_max == -1 || open_max >= 16);
+    printf("clock ticks: %d\n", sysconf(_SC_CLK_TCK) > 0);
+    printf("arg max: %d\n", sysconf(_SC_ARG_MAX) >= 4096);
+
+    errno = 0;
+    if (sysconf(-1) != -1 || errno != EINVAL) {
+        puts("sysconf on an unknown name didn't fail with EINVAL");
+        return 1;
+    }
+}
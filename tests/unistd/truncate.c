@@ -0,0 +1,48 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+int main() {
+    int fd = creat("truncate.out", 0644);
+    if (fd < 0) {
+        perror("creat");
+        return 1;
+    }
+    write(fd, "Hello, world!", 13);
+    close(fd);
+
+    struct stat st;
+    if (truncate("truncate.out", 100)) {
+        perror("truncate");
+        return 1;
+    }
+    stat("truncate.out", &st);
+    printf("after growing: %ld\n", (long) st.st_size);
+
+    if (truncate("truncate.out", 5)) {
+        perror("truncate");
+        return 1;
+    }
+    stat("truncate.out", &st);
+    printf("after shrinking: %ld\n", (long) st.st_size);
+
+    errno = 0;
+    if (truncate("truncate.out", -1) != -1 || errno != EINVAL) {
+        puts("truncate with a negative length didn't fail with EINVAL");
+        return 1;
+    }
+    errno = 0;
+    if (truncate("truncate-missing.out", 0) != -1 || errno != ENOENT) {
+        puts("truncate on a missing file didn't fail with ENOENT");
+        return 1;
+    }
+    errno = 0;
+    if (truncate("unistd", 0) != -1 || errno != EISDIR) {
+        puts("truncate on a directory didn't fail with EISDIR");
+        return 1;
+    }
+
+    unlink("truncate.out");
+}
@@ -0,0 +1,45 @@
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+int main(int argc, char** argv) {
+    int status = 0;
+
+    pid_t pid = fork();
+    if (pid == 0) {
+        _exit(42);
+    }
+    printf("wait: %d\n", wait(&status) == pid);
+    printf("exited: %d, status: %d, signaled: %d, stopped: %d\n",
+        WIFEXITED(status), WEXITSTATUS(status), WIFSIGNALED(status), WIFSTOPPED(status));
+
+    pid = fork();
+    if (pid == 0) {
+        raise(SIGKILL);
+        _exit(0);
+    }
+    printf("waitpid: %d\n", waitpid(pid, &status, 0) == pid);
+    printf("exited: %d, signaled: %d, SIGKILL: %d, core: %d\n",
+        WIFEXITED(status), WIFSIGNALED(status), WTERMSIG(status) == SIGKILL, WCOREDUMP(status) != 0);
+
+    pid = fork();
+    if (pid == 0) {
+        _exit(7);
+    }
+    siginfo_t info;
+    printf("waitid: %d\n", waitid(P_PID, pid, &info, WEXITED));
+    printf("pid: %d, SIGCHLD: %d, CLD_EXITED: %d, status: %d\n",
+        info.si_pid == pid, info.si_signo == SIGCHLD, info.si_code == CLD_EXITED, info.si_status);
+
+    pid = fork();
+    if (pid == 0) {
+        raise(SIGTERM);
+        _exit(0);
+    }
+    printf("waitid: %d\n", waitid(P_ALL, 0, &info, WEXITED));
+    printf("pid: %d, CLD_KILLED: %d, SIGTERM: %d\n",
+        info.si_pid == pid, info.si_code == CLD_KILLED, info.si_status == SIGTERM);
+    return 0;
+}
@@ -0,0 +1,94 @@
+#include <errno.h>
+#include <locale.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <wchar.h>
+
+void show_bytes(const char * s) {
+    for (; *s; s++) {
+        printf("%02x ", (unsigned char) *s);
+    }
+    puts("");
+}
+
+void round_trip(const char * s) {
+    wchar_t wcs[16];
+    char back[16];
+
+    size_t len = mbstowcs(wcs, s, 16);
+    printf("%zu wide characters:", len);
+    for (size_t i = 0; i < len; i++) {
+        printf(" %x", (unsigned) wcs[i]);
+    }
+    puts("");
+
+    printf("%zu bytes needed, ", wcstombs(NULL, wcs, 0));
+    len = wcstombs(back, wcs, sizeof(back));
+    printf("%zu converted back, same: %d\n", len, strcmp(s, back) == 0);
+}
+
+int main(void) {
+    // relibc always uses UTF-8, other libcs need to be told to
+    setlocale(LC_ALL, "C.UTF-8");
+
+    round_trip("caf\xc3\xa9");
+    round_trip("\xf0\x9f\x98\x80!");
+
+    mbstate_t state;
+    memset(&state, 0, sizeof(state));
+    wchar_t wc;
+
+    // One byte at a time
+    const char * smiley = "\xf0\x9f\x98\x80";
+    for (int i = 0; i < 4; i++) {
+        size_t rc = mbrtowc(&wc, smiley + i, 1, &state);
+        printf("byte %d: %d, initial state: %d\n", i, (int) rc, mbsinit(&state) != 0);
+    }
+    printf("decoded %x\n", (unsigned) wc);
+
+    // Incomplete, then invalid
+    printf("mbrlen(\"\\xc3\", 1) = %d\n", (int) mbrlen("\xc3", 1, &state));
+    errno = 0;
+    size_t rc = mbrlen("A", 1, &state);
+    printf("mbrlen(\"A\", 1) = %d, EILSEQ: %d\n", (int) rc, errno == EILSEQ);
+    memset(&state, 0, sizeof(state));
+
+    // Invalid sequences: a stray continuation byte, an overlong encoding, a surrogate and a code
+    // point past U+10FFFF
+    const char * invalid[] = { "\x80", "\xc0\xaf", "\xed\xa0\x80", "\xf4\x90\x80\x80" };
+    for (int i = 0; i < 4; i++) {
+        errno = 0;
+        rc = mbrtowc(&wc, invalid[i], strlen(invalid[i]), &state);
+        printf("invalid %d: %d, EILSEQ: %d\n", i, (int) rc, errno == EILSEQ);
+        memset(&state, 0, sizeof(state));
+    }
+
+    errno = 0;
+    rc = mbstowcs(NULL, "ok\xff", 0);
+    printf("mbstowcs with invalid input: %d, EILSEQ: %d\n", (int) rc, errno == EILSEQ);
+
+    // The NUL takes no bytes
+    printf("mbrtowc(\"\") = %d\n", (int) mbrtowc(&wc, "", 1, &state));
+
+    // Encoding
+    char buf[8];
+    memset(buf, 0, sizeof(buf));
+    printf("wcrtomb(U+E9) = %d: ", (int) wcrtomb(buf, 0xe9, &state));
+    show_bytes(buf);
+    memset(buf, 0, sizeof(buf));
+    printf("wcrtomb(U+1F600) = %d: ", (int) wcrtomb(buf, 0x1f600, &state));
+    show_bytes(buf);
+    errno = 0;
+    rc = wcrtomb(buf, 0xd800, &state);
+    printf("wcrtomb(U+D800) = %d, EILSEQ: %d\n", (int) rc, errno == EILSEQ);
+    errno = 0;
+    rc = wcrtomb(buf, 0x110000, &state);
+    printf("wcrtomb(0x110000) = %d, EILSEQ: %d\n", (int) rc, errno == EILSEQ);
+
+    // Only whole characters fit
+    wchar_t wide[] = { L'a', 0xe9, 0 };
+    memset(buf, 'x', sizeof(buf));
+    printf("wcstombs into 2 bytes = %d\n", (int) wcstombs(buf, wide, 2));
+    return 0;
+}
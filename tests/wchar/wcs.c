@@ -0,0 +1,77 @@
+#include <stdio.h>
+#include <wchar.h>
+
+int sign(int n) {
+    return (n > 0) - (n < 0);
+}
+
+// Printing wide strings needs locale support, so write out the characters by hand
+void show(const wchar_t * ws) {
+    for (; *ws; ws++) {
+        if (*ws < 128) {
+            putchar(*ws);
+        } else {
+            printf("\\u%04x", (unsigned) *ws);
+        }
+    }
+    putchar('\n');
+}
+
+int main(int argc, char* argv[]) {
+    // wcslen
+    printf("%zu %zu %zu\n", wcslen(L"hello"), wcslen(L""), wcslen(L"été"));
+
+    // wcscpy and wcsncpy
+    wchar_t dst[20];
+    wcscpy(dst, L"wcscpy works!");
+    show(dst);
+    wcsncpy(dst, L"wcsncpy works!", 20);
+    show(dst);
+
+    wmemset(dst, L'a', 20);
+    dst[19] = 0;
+    wcsncpy(dst, L"wcsncpy should work here too", 10);
+    show(dst);
+
+    wmemset(dst, L'c', 20);
+    wcsncpy(dst, L"pad", 20);
+    for (int i = 3; i < 20; i++) {
+        if (dst[i] != 0) {
+            printf("wcsncpy didn't pad character %d\n", i);
+            return 1;
+        }
+    }
+    show(dst);
+
+    // wcscat
+    wcscpy(dst, L"hello");
+    wcscat(dst, L", ");
+    wcscat(dst, L"wörld");
+    show(dst);
+
+    // wcscmp and wcsncmp
+    printf("%d %d %d\n", sign(wcscmp(L"abc", L"abc")), sign(wcscmp(L"abc", L"abd")), sign(wcscmp(L"abd", L"abc")));
+    printf("%d %d\n", sign(wcscmp(L"ab", L"abc")), sign(wcscmp(L"abc", L"ab")));
+    printf("%d %d\n", sign(wcscmp(L"", L"a")), sign(wcscmp(L"ÿ", L"a")));
+    printf("%d %d\n", sign(wcsncmp(L"abcX", L"abcY", 3)), sign(wcsncmp(L"abcX", L"abcY", 4)));
+    printf("%d\n", sign(wcsncmp(L"a", L"b", 0)));
+
+    // wcschr and wcsrchr
+    show(wcschr(L"hello", L'e'));
+    show(wcschr(L"wörld", L'ö'));
+    wchar_t * world = L"world";
+    printf("%d %d\n", wcschr(world, 0) == world + 5, wcschr(world, L'a') == NULL);
+    wchar_t s0[] = L"hello, world";
+    printf("%d %d %d\n", wcsrchr(s0, L'l') == &s0[10], wcsrchr(s0, 0) == &s0[12], wcsrchr(L"", L'a') == NULL);
+
+    // wmemcpy, wmemset and wmemcmp
+    wchar_t buf[8];
+    wmemset(buf, L'x', 7);
+    buf[7] = 0;
+    show(buf);
+    wmemcpy(buf + 2, L"abc", 3);
+    show(buf);
+    printf("%d %d %d\n", sign(wmemcmp(L"ab\0c", L"ab\0d", 4)), sign(wmemcmp(L"ab\0c", L"ab\0d", 3)), sign(wmemcmp(L"z", L"a", 1)));
+
+    return 0;
+}